@@ -202,9 +202,7 @@ pub fn Header<G: Html>(ctx: ScopeRef, _: ()) -> View<G> {
     let value = ctx.create_signal(String::new());
     let input_ref = ctx.create_node_ref();
 
-    let handle_submit = |event: Event| {
-        let event: KeyboardEvent = event.unchecked_into();
-
+    let handle_submit = |event: KeyboardEvent| {
         if event.key() == "Enter" {
             let mut task = value.get().as_ref().clone();
             task = task.trim().to_string();
@@ -289,19 +287,16 @@ pub fn Item<G: Html>(ctx: ScopeRef, todo: RcSignal<Todo>) -> View<G> {
         }
     };
 
-    let handle_submit = move |event: Event| {
-        let event: KeyboardEvent = event.unchecked_into();
-        match event.key().as_str() {
-            "Enter" => handle_blur(),
-            "Escape" => {
-                input_ref
-                    .get::<DomNode>()
-                    .unchecked_into::<HtmlInputElement>()
-                    .set_value(&title());
-                editing.set(false);
-            }
-            _ => {}
+    let handle_submit = move |event: KeyboardEvent| match event.key().as_str() {
+        "Enter" => handle_blur(),
+        "Escape" => {
+            input_ref
+                .get::<DomNode>()
+                .unchecked_into::<HtmlInputElement>()
+                .set_value(&title());
+            editing.set(false);
         }
+        _ => {}
     };
 
     let handle_destroy = move |_| {
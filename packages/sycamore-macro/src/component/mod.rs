@@ -3,7 +3,12 @@
 use proc_macro2::TokenStream;
 use quote::quote;
 use syn::parse::{Parse, ParseStream};
-use syn::{FnArg, Item, ItemFn, Result, ReturnType};
+use syn::visit_mut::VisitMut;
+use syn::{
+    parse_quote, FnArg, GenericArgument, GenericParam, Item, ItemFn, Lifetime, LifetimeDef,
+    ParenthesizedGenericArguments, PathArguments, Result, ReturnType, Signature, Type, TypeBareFn,
+    TypeReference,
+};
 
 pub struct ComponentFunction {
     pub f: ItemFn,
@@ -58,8 +63,84 @@ impl Parse for ComponentFunction {
     }
 }
 
+/// Replaces all the elided lifetimes in a type with a given lifetime. Lifetimes inside `fn`
+/// pointers and `Fn` traits are left untouched because elision means something different there.
+struct ReplaceElidedLifetimes<'a>(&'a Lifetime);
+
+impl VisitMut for ReplaceElidedLifetimes<'_> {
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        if lifetime.ident == "_" {
+            *lifetime = self.0.clone();
+        }
+    }
+
+    fn visit_type_reference_mut(&mut self, reference: &mut TypeReference) {
+        if reference.lifetime.is_none() {
+            reference.lifetime = Some(self.0.clone());
+        }
+        syn::visit_mut::visit_type_reference_mut(self, reference);
+    }
+
+    fn visit_type_bare_fn_mut(&mut self, _: &mut TypeBareFn) {}
+
+    fn visit_parenthesized_generic_arguments_mut(&mut self, _: &mut ParenthesizedGenericArguments) {
+    }
+}
+
+/// If the scope argument is written without a lifetime (`ctx: ScopeRef` or `ctx: ScopeRef<'_>`),
+/// adds a lifetime parameter for the scope to the component and uses it for the scope argument and
+/// for all the elided lifetimes in the props type. For example:
+/// ```ignore
+/// #[component]
+/// fn MyComponent<G: Html>(ctx: ScopeRef, props: MyProps<'_>) -> View<G> { ... }
+/// ```
+/// expands to:
+/// ```ignore
+/// fn MyComponent<'a, G: Html>(ctx: ScopeRef<'a>, props: MyProps<'a>) -> View<G> { ... }
+/// ```
+fn add_scope_lifetime(sig: &mut Signature) {
+    let is_taken = |name: &str| {
+        sig.generics
+            .lifetimes()
+            .any(|def| def.lifetime.ident == name)
+    };
+    let name = if !is_taken("a") { "'a" } else { "'__scope" };
+
+    let mut inputs = sig.inputs.iter_mut();
+    let segment = match inputs.next() {
+        Some(FnArg::Typed(ctx)) => match &mut *ctx.ty {
+            Type::Path(ty) => match ty.path.segments.last_mut() {
+                Some(segment) if segment.ident == "ScopeRef" => segment,
+                _ => return,
+            },
+            _ => return,
+        },
+        _ => return,
+    };
+    match &segment.arguments {
+        PathArguments::None => {}
+        PathArguments::AngleBracketed(args)
+            if matches!(
+                args.args.iter().collect::<Vec<_>>().as_slice(),
+                [GenericArgument::Lifetime(lifetime)] if lifetime.ident == "_"
+            ) => {}
+        _ => return,
+    }
+
+    let lifetime = Lifetime::new(name, segment.ident.span());
+
+    segment.arguments = PathArguments::AngleBracketed(parse_quote! { <#lifetime> });
+    if let Some(FnArg::Typed(props)) = inputs.next() {
+        ReplaceElidedLifetimes(&lifetime).visit_type_mut(&mut props.ty);
+    }
+    sig.generics
+        .params
+        .insert(0, GenericParam::Lifetime(LifetimeDef::new(lifetime)));
+}
+
 pub fn component_impl(comp: ComponentFunction) -> Result<TokenStream> {
-    let ComponentFunction { f } = comp;
+    let ComponentFunction { mut f } = comp;
+    add_scope_lifetime(&mut f.sig);
 
     Ok(quote! {
         #[allow(non_snake_case)]
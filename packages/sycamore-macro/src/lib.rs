@@ -11,6 +11,19 @@ mod view;
 ///
 /// To learn more about the template syntax, see the chapter on
 /// [the `view!` macro](https://sycamore-rs.netlify.app/docs/basics/view) in the Sycamore Book.
+///
+/// Interpolated expressions and attribute values are re-evaluated when the signals that they read
+/// change, unless they are literals, paths or field accesses, which are evaluated once. Wrap an
+/// expression in `static(...)` to evaluate it once anyway, or in `dyn(...)` to always make it
+/// reactive.
+///
+/// A reactive `match` or `if` expression is only re-created when a different branch is selected,
+/// or when the signals read by the selected branch change. Branches whose patterns bind variables
+/// are re-created whenever the expression is re-evaluated.
+///
+/// Views inside the braces of a component, e.g. `MyLayout { p { "hi" } }` or
+/// `MyLayout(title="Title") { p { "hi" } }`, are passed to its `children` prop as
+/// `sycamore::component::Children`, which the component creates with `children.call(ctx)`.
 #[proc_macro]
 pub fn view(view: TokenStream) -> TokenStream {
     let view_root = parse_macro_input!(view as view::WithCtxArg<view::ir::ViewRoot>);
@@ -679,7 +679,7 @@ mod struct_info {
         pub fn new(attrs: &[syn::Attribute]) -> Result<TypeBuilderAttr, Error> {
             let mut result = TypeBuilderAttr::default();
             for attr in attrs {
-                if path_to_single_string(&attr.path).as_deref() != Some("builder") {
+                if path_to_single_string(&attr.path).as_deref() != Some("prop") {
                     continue;
                 }
 
@@ -885,7 +885,7 @@ mod field_info {
     impl FieldBuilderAttr {
         pub fn with(mut self, attrs: &[syn::Attribute]) -> Result<Self, Error> {
             for attr in attrs {
-                if path_to_single_string(&attr.path).as_deref() != Some("builder") {
+                if path_to_single_string(&attr.path).as_deref() != Some("prop") {
                     continue;
                 }
 
@@ -1021,7 +1021,7 @@ mod field_info {
             if let (Some(skip), None) = (&self.setter.skip, &self.default) {
                 return Err(Error::new(
                     *skip,
-                    "#[builder(skip)] must be accompanied by default or default_code",
+                    "#[prop(skip)] must be accompanied by default or default_code",
                 ));
             }
 
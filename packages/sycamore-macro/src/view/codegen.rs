@@ -4,9 +4,12 @@
 //! of some internal state during the entire codegen.
 
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{quote, ToTokens};
 use syn::spanned::Spanned;
-use syn::{Expr, ExprLit, Ident, Lit};
+use syn::{
+    Arm, Expr, ExprIf, ExprLet, ExprLit, ExprMatch, Ident, Lit, Pat, PatBox, PatIdent, PatOr,
+    PatReference, PatSlice, PatStruct, PatTuple, PatTupleStruct, PatType,
+};
 
 use crate::view::ir::*;
 
@@ -42,7 +45,6 @@ impl Codegen {
     }
 
     pub fn view_node(&self, view_node: &ViewNode) -> TokenStream {
-        let ctx = &self.ctx;
         match view_node {
             ViewNode::Element(elem) => {
                 let elem = self.element(elem);
@@ -54,17 +56,80 @@ impl Codegen {
             ViewNode::Text(Text { value }) => quote! {
                 ::sycamore::view::View::new_node(::sycamore::generic_node::GenericNode::text_node(#value))
             },
-            ViewNode::Dyn(Dyn { value }) => {
-                quote! {
+            ViewNode::Dyn(Dyn { value, .. }) if view_node.is_dynamic() => self.dyn_view(value),
+            ViewNode::Dyn(Dyn { value, .. }) => quote! {
+                ::sycamore::view::IntoView::create(&(#value))
+            },
+        }
+    }
+
+    /// Returns a dynamic view that is re-created when the signals that `value` reads change.
+    ///
+    /// If `value` is a `match` or an `if` expression, the view is keyed by the selected branch:
+    /// the scrutinee and the conditions are evaluated in a selector, and the view is only
+    /// re-created when a different branch is selected or when the signals read by the body of the
+    /// branch change. Branches whose patterns bind variables need the value of the scrutinee, so
+    /// the whole expression is evaluated again for them.
+    pub fn dyn_view(&self, value: &Expr) -> TokenStream {
+        let ctx = &self.ctx;
+        let branches = match Branches::of(value) {
+            Some(branches) => branches,
+            None => {
+                return quote! {
                     ::sycamore::view::View::new_dyn(#ctx, move ||
                         ::sycamore::view::IntoView::create(&(#value))
                     )
                 }
             }
-        }
+        };
+        let select = branches.select;
+        let bodies = branches
+            .bodies
+            .iter()
+            .enumerate()
+            .map(|(i, body)| match body {
+                Some(body) => quote! { #i => #body, },
+                None => quote! {},
+            });
+        // The captured variables are moved into a single closure that is shared by the selector
+        // and the view, so that they are not moved twice.
+        quote! {{
+            let __branches = ::sycamore::reactive::Scope::create_ref(#ctx,
+                move |__branch: ::std::option::Option<usize>| match __branch {
+                    ::std::option::Option::None => {
+                        #[allow(unused_variables)]
+                        let __index: usize = #select;
+                        ::std::result::Result::Err(__index)
+                    }
+                    ::std::option::Option::Some(__index) => ::std::result::Result::Ok(
+                        ::sycamore::view::IntoView::create(&(match __index {
+                            #(#bodies)*
+                            _ => #value,
+                        }))
+                    ),
+                }
+            );
+            let __branch = ::sycamore::reactive::Scope::create_selector(#ctx, move || {
+                match __branches(::std::option::Option::None) {
+                    ::std::result::Result::Err(__index) => __index,
+                    ::std::result::Result::Ok(_) => ::std::unreachable!(),
+                }
+            });
+            ::sycamore::view::View::new_dyn(#ctx, move || {
+                match __branches(::std::option::Option::Some(*__branch.get())) {
+                    ::std::result::Result::Ok(__view) => __view,
+                    ::std::result::Result::Err(_) => ::std::unreachable!(),
+                }
+            })
+        }}
     }
 
     pub fn element(&self, elem: &Element) -> TokenStream {
+        self.element_in(elem, None)
+    }
+
+    /// Generates the code for `elem` inside of an element with the namespace `parent_ns`.
+    fn element_in(&self, elem: &Element, parent_ns: Option<&str>) -> TokenStream {
         let ctx = &self.ctx;
         let Element {
             tag,
@@ -72,6 +137,8 @@ impl Codegen {
             children,
         } = elem;
 
+        let ns = elem.namespace(parent_ns);
+        let children_ns = elem.children_namespace(ns.as_deref());
         let tag = match tag {
             ElementTag::Builtin(id) => id.to_string(),
             ElementTag::Custom(s) => s.clone(),
@@ -91,7 +158,7 @@ impl Codegen {
                     let marker = if let Some(ViewNode::Element(elem)) =
                         children.next_if(|x| matches!(x, ViewNode::Element(_)))
                     {
-                        let elem = self.element(elem);
+                        let elem = self.element_in(elem, children_ns.as_deref());
                         quote! {
                             let __marker = #elem;
                             ::sycamore::generic_node::GenericNode::append_child(&__el, &__marker);
@@ -159,13 +226,12 @@ impl Codegen {
                                 } else { #quoted }
                             }).unwrap_or(quoted)
                         }
-                        ViewNode::Dyn(Dyn { value}) => {
+                        ViewNode::Dyn(Dyn { value, .. }) => {
+                            let dyn_view = self.dyn_view(value);
                             let quoted = quote! {
                                 #marker
                                 ::sycamore::utils::render::insert(#ctx, &__el,
-                                    ::sycamore::view::View::new_dyn(#ctx, move ||
-                                        ::sycamore::view::IntoView::create(&(#value))
-                                    ),
+                                    #dyn_view,
                                     #initial, __marker, #multi
                                 );
                             };
@@ -173,9 +239,7 @@ impl Codegen {
                                 if ::std::any::Any::type_id(&__el) == ::std::any::TypeId::of::<::sycamore::generic_node::SsrNode>() {
                                     #ssr_markers
                                     ::sycamore::utils::render::insert(#ctx, &__el,
-                                        ::sycamore::view::View::new_dyn(#ctx, move ||
-                                            ::sycamore::view::IntoView::create(&(#value))
-                                        ),
+                                        #dyn_view,
                                         #initial, Some(&__end_marker), #multi
                                     );
                                     #marker_or_none
@@ -190,7 +254,7 @@ impl Codegen {
                 }
                 match child {
                     ViewNode::Element(elem) => quoted.extend({
-                        let elem = self.element(elem);
+                        let elem = self.element_in(elem, children_ns.as_deref());
                         quote! {
                             ::sycamore::generic_node::GenericNode::append_child(&__el, &#elem);
                         }
@@ -217,7 +281,7 @@ impl Codegen {
                             },
                         });
                     }
-                    ViewNode::Dyn(Dyn { value }) => quoted.extend(quote! {
+                    ViewNode::Dyn(Dyn { value, .. }) => quoted.extend(quote! {
                         ::sycamore::utils::render::insert(#ctx, &__el,
                             ::sycamore::view::IntoView::create(&(#value)),
                             None, None, #multi
@@ -228,8 +292,13 @@ impl Codegen {
             quoted
         };
 
+        let create_el = match ns {
+            Some(ns) => quote! { ::sycamore::generic_node::GenericNode::element_ns(#tag, #ns) },
+            None => quote! { ::sycamore::generic_node::GenericNode::element(#tag) },
+        };
+
         quote! {{
-            let __el = ::sycamore::generic_node::GenericNode::element(#tag);
+            let __el = #create_el;
             #quote_attrs
             #quote_children
             __el
@@ -247,7 +316,7 @@ impl Codegen {
                 lit: Lit::Str(_),
                 ..
             })
-        );
+        ) && attr.reactivity.is_reactive(expr);
 
         match &attr.ty {
             AttributeType::Str { name } => {
@@ -323,6 +392,46 @@ impl Codegen {
                     });
                 };
             }
+            AttributeType::Class { name } => {
+                let quoted_set_class = quote! {
+                    if #expr {
+                        ::sycamore::generic_node::GenericNode::add_class(&__el, #name);
+                    } else {
+                        ::sycamore::generic_node::GenericNode::remove_class(&__el, #name);
+                    }
+                };
+
+                if is_dynamic {
+                    tokens.extend(quote! {
+                        ::sycamore::reactive::Scope::create_effect(#ctx, {
+                            let __el = ::std::clone::Clone::clone(&__el);
+                            move || {
+                                #quoted_set_class
+                            }
+                        });
+                    });
+                } else {
+                    tokens.extend(quoted_set_class);
+                };
+            }
+            AttributeType::Style { prop } => {
+                let quoted_set_style = quote! {
+                    ::sycamore::utils::render::set_style_property(&__el, #prop, &(#expr));
+                };
+
+                if is_dynamic {
+                    tokens.extend(quote! {
+                        ::sycamore::reactive::Scope::create_effect(#ctx, {
+                            let __el = ::std::clone::Clone::clone(&__el);
+                            move || {
+                                #quoted_set_style
+                            }
+                        });
+                    });
+                } else {
+                    tokens.extend(quoted_set_style);
+                };
+            }
             AttributeType::DangerouslySetInnerHtml => {
                 if is_dynamic {
                     tokens.extend(quote! {
@@ -345,12 +454,17 @@ impl Codegen {
                     });
                 };
             }
-            AttributeType::Event { event } => {
+            AttributeType::Event { event, modifiers } => {
+                let event_type = event_type(event);
                 tokens.extend(quote! {
-                    ::sycamore::generic_node::GenericNode::event(
+                    ::sycamore::generic_node::GenericNode::typed_event::<#event_type>(
                         &__el,
                         #ctx,
                         #event,
+                        ::sycamore::generic_node::EventOptions {
+                            #(#modifiers: true,)*
+                            ..::std::default::Default::default()
+                        },
                         ::std::boxed::Box::new(#expr),
                     );
                 });
@@ -359,12 +473,16 @@ impl Codegen {
                 #[derive(Clone, Copy)]
                 enum JsPropertyType {
                     Bool,
+                    Number,
                     String,
+                    SelectedOptions,
                 }
 
                 let (event_name, property_ty) = match prop.as_str() {
                     "value" => ("input", JsPropertyType::String),
+                    "valueAsNumber" => ("input", JsPropertyType::Number),
                     "checked" => ("change", JsPropertyType::Bool),
+                    "selectedOptions" => ("change", JsPropertyType::SelectedOptions),
                     _ => {
                         tokens.extend(
                             syn::Error::new(
@@ -377,22 +495,26 @@ impl Codegen {
                     }
                 };
 
-                // let value_ty = match property_ty {
-                //     JsPropertyType::Bool => quote! { ::std::primitive::bool },
-                //     JsPropertyType::String => quote! { ::std::string::String },
-                // };
-
-                let convert_into_jsvalue_fn = match property_ty {
+                let set_property = |value: TokenStream| {
+                    quote! {
+                        ::sycamore::generic_node::GenericNode::set_property(&__el, #prop, &#value)
+                    }
+                };
+                let update_element = match property_ty {
                     JsPropertyType::Bool => {
-                        quote! { ::sycamore::rt::JsValue::from_bool(*#expr.get()) }
+                        set_property(quote! { ::sycamore::rt::JsValue::from_bool(*__signal.get()) })
                     }
-                    JsPropertyType::String => {
-                        quote! {
-                            ::sycamore::rt::JsValue::from_str(
-                                &::std::string::ToString::to_string(&#expr.get())
-                            )
-                        }
+                    JsPropertyType::Number => {
+                        set_property(quote! { ::sycamore::rt::JsValue::from_f64(*__signal.get()) })
                     }
+                    JsPropertyType::String => set_property(quote! {
+                        ::sycamore::rt::JsValue::from_str(
+                            &::std::string::ToString::to_string(&__signal.get())
+                        )
+                    }),
+                    JsPropertyType::SelectedOptions => quote! {
+                        ::sycamore::utils::bind::set_selected_values(&__el, &__signal.get())
+                    },
                 };
 
                 let event_target_prop = quote! {
@@ -406,33 +528,41 @@ impl Codegen {
                     JsPropertyType::Bool => quote! {
                         ::sycamore::rt::JsValue::as_bool(&#event_target_prop).unwrap()
                     },
+                    JsPropertyType::Number => quote! {
+                        ::sycamore::rt::JsValue::as_f64(&#event_target_prop).unwrap()
+                    },
                     JsPropertyType::String => quote! {
                         ::sycamore::rt::JsValue::as_string(&#event_target_prop).unwrap()
                     },
+                    JsPropertyType::SelectedOptions => quote! {
+                        ::sycamore::utils::bind::selected_values(&event.target().unwrap())
+                    },
                 };
 
-                tokens.extend(quote! {
+                tokens.extend(quote! {{
+                    let __signal = #expr;
                     #[cfg(target_arch = "wasm32")]
                     ::sycamore::reactive::Scope::create_effect(#ctx, {
                         let __el = ::std::clone::Clone::clone(&__el);
-                        move ||::sycamore::generic_node::GenericNode::set_property(
-                            &__el,
-                            #prop,
-                            &#convert_into_jsvalue_fn,
-                        )
+                        move || #update_element
                     });
                     ::sycamore::generic_node::GenericNode::event(&__el, #ctx, #event_name,
-                        ::std::boxed::Box::new(|event: ::sycamore::rt::Event| {
-                            #expr.set(#convert_from_jsvalue_fn);
+                        ::std::boxed::Box::new(move |event: ::sycamore::rt::Event| {
+                            __signal.set(#convert_from_jsvalue_fn);
                         }),
                     );
-                });
+                }});
             }
             AttributeType::Ref => {
                 tokens.extend(quote! {{
                     ::sycamore::noderef::NodeRef::set(&#expr, ::std::clone::Clone::clone(&__el));
                 }});
             }
+            AttributeType::Spread => {
+                tokens.extend(quote! {
+                    ::sycamore::utils::render::spread_attributes(#ctx, &__el, #expr);
+                });
+            }
         }
         tokens
     }
@@ -449,13 +579,25 @@ impl Codegen {
                 }
             }
             Component::ElementLike(comp) => {
-                let ElementLikeComponent { ident, props } = comp;
+                let ElementLikeComponent {
+                    ident,
+                    props,
+                    children,
+                } = comp;
                 let mut props_quoted = quote! {
                     ::sycamore::component::element_like_component_builder(__component)
                 };
                 for (field, expr) in props {
                     props_quoted.extend(quote! { .#field(#expr) });
                 }
+                if let Some(children) = children {
+                    // The children are only created when the component calls them, within the
+                    // scope passed by the component.
+                    let children = self.view_root(children);
+                    props_quoted.extend(quote! {
+                        .children(::sycamore::component::Children::new(move |#ctx| #children))
+                    });
+                }
                 props_quoted.extend(quote! { .build() });
 
                 quote! {{
@@ -466,3 +608,125 @@ impl Codegen {
         }
     }
 }
+
+/// Returns the type of the event that is passed to the handlers of the event `name`.
+fn event_type(name: &str) -> TokenStream {
+    match name {
+        "click" | "dblclick" | "auxclick" | "contextmenu" | "mousedown" | "mouseup"
+        | "mousemove" | "mouseenter" | "mouseleave" | "mouseover" | "mouseout" => {
+            quote! { ::sycamore::rt::MouseEvent }
+        }
+        "keydown" | "keyup" | "keypress" => quote! { ::sycamore::rt::KeyboardEvent },
+        // `input` events are only `InputEvent`s for editable elements, not for e.g. `<select>`.
+        "beforeinput" => quote! { ::sycamore::rt::InputEvent },
+        "focus" | "blur" | "focusin" | "focusout" => quote! { ::sycamore::rt::FocusEvent },
+        "wheel" => quote! { ::sycamore::rt::WheelEvent },
+        "drag" | "dragstart" | "dragend" | "dragenter" | "dragleave" | "dragover" | "drop" => {
+            quote! { ::sycamore::rt::DragEvent }
+        }
+        "pointerdown" | "pointerup" | "pointermove" | "pointerenter" | "pointerleave"
+        | "pointerover" | "pointerout" | "pointercancel" | "gotpointercapture"
+        | "lostpointercapture" => quote! { ::sycamore::rt::PointerEvent },
+        "touchstart" | "touchend" | "touchmove" | "touchcancel" => {
+            quote! { ::sycamore::rt::TouchEvent }
+        }
+        _ => quote! { ::sycamore::rt::Event },
+    }
+}
+
+/// The branches of a `match` or `if` expression. See [`Codegen::dyn_view`].
+struct Branches {
+    /// An expression that evaluates to the index of the selected branch.
+    select: TokenStream,
+    /// The body of each branch, or `None` if the pattern of the branch binds variables.
+    bodies: Vec<Option<TokenStream>>,
+}
+
+impl Branches {
+    fn of(expr: &Expr) -> Option<Self> {
+        match expr {
+            Expr::Match(ExprMatch {
+                attrs, expr, arms, ..
+            }) if attrs.is_empty() => {
+                let select_arms = arms.iter().enumerate().map(|(i, arm)| {
+                    let Arm { pat, guard, .. } = arm;
+                    let guard = guard
+                        .as_ref()
+                        .map(|(if_token, guard)| quote! { #if_token #guard });
+                    quote! { #pat #guard => #i, }
+                });
+                Some(Self {
+                    select: quote! { match #expr { #(#select_arms)* } },
+                    bodies: arms
+                        .iter()
+                        .map(|arm| (!binds_variables(&arm.pat)).then(|| arm.body.to_token_stream()))
+                        .collect(),
+                })
+            }
+            Expr::If(_) => {
+                let mut conditions = Vec::new();
+                let mut bodies = Vec::new();
+                let mut next = expr;
+                loop {
+                    match next {
+                        Expr::If(ExprIf {
+                            attrs,
+                            cond,
+                            then_branch,
+                            else_branch,
+                            ..
+                        }) if attrs.is_empty() => {
+                            let binds = match &**cond {
+                                Expr::Let(ExprLet { pat, .. }) => binds_variables(pat),
+                                _ => false,
+                            };
+                            conditions.push(cond);
+                            bodies.push((!binds).then(|| then_branch.to_token_stream()));
+                            // An `if` without `else` cannot be converted into a view.
+                            next = &else_branch.as_ref()?.1;
+                        }
+                        _ => {
+                            bodies.push(Some(next.to_token_stream()));
+                            break;
+                        }
+                    }
+                }
+                let select_branches = conditions.iter().enumerate().map(|(i, cond)| {
+                    quote! { if #cond { #i } else }
+                });
+                let last = conditions.len();
+                Some(Self {
+                    select: quote! { #(#select_branches)* { #last } },
+                    bodies,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Returns `true` if `pat` binds variables. Identifiers that start with an uppercase letter are
+/// assumed to be constants or unit variants.
+fn binds_variables(pat: &Pat) -> bool {
+    match pat {
+        Pat::Ident(PatIdent { ident, subpat, .. }) => {
+            subpat.is_some() || !ident.to_string().starts_with(char::is_uppercase)
+        }
+        Pat::Lit(_) | Pat::Path(_) | Pat::Range(_) | Pat::Rest(_) | Pat::Wild(_) => false,
+        Pat::Or(PatOr { cases, .. }) => cases.iter().any(binds_variables),
+        Pat::Reference(PatReference { pat, .. })
+        | Pat::Box(PatBox { pat, .. })
+        | Pat::Type(PatType { pat, .. }) => binds_variables(pat),
+        Pat::Slice(PatSlice { elems, .. })
+        | Pat::Tuple(PatTuple { elems, .. })
+        | Pat::TupleStruct(PatTupleStruct {
+            pat: PatTuple { elems, .. },
+            ..
+        }) => elems.iter().any(binds_variables),
+        Pat::Struct(PatStruct { fields, .. }) => {
+            fields.iter().any(|field| binds_variables(&field.pat))
+        }
+        // Macros and unknown patterns might bind variables.
+        _ => true,
+    }
+}
@@ -4,7 +4,7 @@ use std::collections::HashSet;
 
 use once_cell::sync::Lazy;
 use syn::punctuated::Punctuated;
-use syn::{Expr, Ident, LitStr, Token};
+use syn::{Expr, ExprLit, Ident, Lit, LitStr, Token};
 
 pub struct ViewRoot(pub Vec<ViewNode>);
 
@@ -16,22 +16,22 @@ pub enum ViewNode {
 }
 
 impl ViewNode {
-    /// Node is dynamic if the node is a component or a splice that is not a simple path.
+    /// Node is dynamic if the node is a component or a splice that might read signals. See
+    /// [`Reactivity`].
     /// # Example
     /// ```ignore
     /// view! { MyComponent() } // is_dynamic = true
     /// view! { (state.get()) } // is_dynamic = true
     /// view! { (state) } // is_dynamic = false
+    /// view! { (format_label()) } // is_dynamic = true
+    /// view! { (static(format_label())) } // is_dynamic = false
     /// ```
     pub fn is_dynamic(&self) -> bool {
         match self {
             ViewNode::Element(_) => false,
             ViewNode::Component(_) => true,
             ViewNode::Text(_) => false,
-            ViewNode::Dyn(Dyn {
-                value: Expr::Lit(_) | Expr::Path(_),
-            }) => false,
-            ViewNode::Dyn(_) => true,
+            ViewNode::Dyn(Dyn { value, reactivity }) => reactivity.is_reactive(value),
         }
     }
 }
@@ -56,6 +56,7 @@ pub enum ElementTag {
 pub struct Attribute {
     pub ty: AttributeType,
     pub value: Expr,
+    pub reactivity: Reactivity,
 }
 
 #[derive(PartialEq, Eq)]
@@ -70,14 +71,36 @@ pub enum AttributeType {
     Bool { name: String },
     /// Syntax: `dangerously_set_inner_html`.
     DangerouslySetInnerHtml,
-    /// Syntax: `on:<event>`.
-    Event { event: String },
-    /// Syntax: `bind:<prop>`.
+    /// Adds the class `name` if the value is `true` and removes it otherwise. Should come after the
+    /// `class` attribute, which replaces all the classes.
+    ///
+    /// Syntax: `class:<name>`.
+    Class { name: String },
+    /// Sets the CSS property `prop` of the inline style to the value.
+    ///
+    /// Syntax: `style:<prop>`.
+    Style { prop: String },
+    /// Syntax: `on:<event>` followed by any number of `|<modifier>`, where `modifier` is one of
+    /// the [`EVENT_MODIFIERS`].
+    Event {
+        event: String,
+        modifiers: Vec<Ident>,
+    },
+    /// Syntax: `bind:<prop>`. `prop` can be `value` (`String`), `valueAsNumber` (`f64`, for
+    /// `number` and `range` inputs), `checked` (`bool`) or `selectedOptions` (`Vec<String>`, for
+    /// `<select multiple>`).
     Bind { prop: String },
     /// Syntax: `ref`.
     Ref,
+    /// Attributes from a reactive map of attribute names to values.
+    ///
+    /// Syntax: `..<expr>`.
+    Spread,
 }
 
+/// The modifiers of the `on:` directive. Each modifier is a field of `EventOptions`.
+pub const EVENT_MODIFIERS: &[&str] = &["prevent_default", "stop_propagation", "once", "passive"];
+
 pub fn is_bool_attr(name: &str) -> bool {
     static BOOLEAN_ATTRIBUTES_SET: Lazy<HashSet<&str>> = Lazy::new(|| {
         vec![
@@ -124,6 +147,168 @@ pub fn is_bool_attr(name: &str) -> bool {
     BOOLEAN_ATTRIBUTES_SET.contains(name)
 }
 
+/// The namespace of SVG elements.
+pub const SVG_NAMESPACE: &str = "http://www.w3.org/2000/svg";
+/// The namespace of MathML elements.
+pub const MATHML_NAMESPACE: &str = "http://www.w3.org/1998/Math/MathML";
+
+/// Returns the namespace of the element `tag` when it is not inside of an element with a
+/// namespace, e.g. when it is the root of a component. Elements that also exist in HTML, such as
+/// `a` or `title`, are created as HTML elements.
+pub fn tag_namespace(tag: &str) -> Option<&'static str> {
+    static SVG_TAGS_SET: Lazy<HashSet<&str>> = Lazy::new(|| {
+        vec![
+            "svg",
+            "animate",
+            "animateMotion",
+            "animateTransform",
+            "circle",
+            "clipPath",
+            "defs",
+            "desc",
+            "ellipse",
+            "feBlend",
+            "feColorMatrix",
+            "feComponentTransfer",
+            "feComposite",
+            "feConvolveMatrix",
+            "feDiffuseLighting",
+            "feDisplacementMap",
+            "feDistantLight",
+            "feDropShadow",
+            "feFlood",
+            "feFuncA",
+            "feFuncB",
+            "feFuncG",
+            "feFuncR",
+            "feGaussianBlur",
+            "feImage",
+            "feMerge",
+            "feMergeNode",
+            "feMorphology",
+            "feOffset",
+            "fePointLight",
+            "feSpecularLighting",
+            "feSpotLight",
+            "feTile",
+            "feTurbulence",
+            "filter",
+            "foreignObject",
+            "g",
+            "image",
+            "line",
+            "linearGradient",
+            "marker",
+            "mask",
+            "metadata",
+            "mpath",
+            "path",
+            "pattern",
+            "polygon",
+            "polyline",
+            "radialGradient",
+            "rect",
+            "set",
+            "stop",
+            "switch",
+            "symbol",
+            "text",
+            "textPath",
+            "tspan",
+            "use",
+            "view",
+        ]
+        .into_iter()
+        .collect()
+    });
+    static MATHML_TAGS_SET: Lazy<HashSet<&str>> = Lazy::new(|| {
+        vec![
+            "math",
+            "annotation",
+            "maction",
+            "menclose",
+            "merror",
+            "mfenced",
+            "mfrac",
+            "mi",
+            "mmultiscripts",
+            "mn",
+            "mo",
+            "mover",
+            "mpadded",
+            "mphantom",
+            "mprescripts",
+            "mroot",
+            "mrow",
+            "ms",
+            "mspace",
+            "msqrt",
+            "mstyle",
+            "msub",
+            "msubsup",
+            "msup",
+            "mtable",
+            "mtd",
+            "mtext",
+            "mtr",
+            "munder",
+            "munderover",
+            "semantics",
+        ]
+        .into_iter()
+        .collect()
+    });
+    if SVG_TAGS_SET.contains(tag) {
+        Some(SVG_NAMESPACE)
+    } else if MATHML_TAGS_SET.contains(tag) {
+        Some(MATHML_NAMESPACE)
+    } else {
+        None
+    }
+}
+
+impl Element {
+    /// Returns the namespace of the element, given the namespace of its parent element. A string
+    /// literal `xmlns` attribute overrides the namespace.
+    pub fn namespace(&self, parent: Option<&str>) -> Option<String> {
+        let xmlns = self
+            .attrs
+            .iter()
+            .find_map(|attr| match (&attr.ty, &attr.value) {
+                (
+                    AttributeType::Str { name },
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(s), ..
+                    }),
+                ) if name == "xmlns" => Some(s.value()),
+                _ => None,
+            });
+        if xmlns.is_some() {
+            return xmlns;
+        }
+        let tag = match &self.tag {
+            ElementTag::Builtin(id) => id.to_string(),
+            ElementTag::Custom(s) => s.clone(),
+        };
+        match tag.as_str() {
+            "svg" => Some(SVG_NAMESPACE.to_string()),
+            "math" => Some(MATHML_NAMESPACE.to_string()),
+            _ => parent
+                .or_else(|| tag_namespace(&tag))
+                .map(ToString::to_string),
+        }
+    }
+
+    /// Returns the namespace of the children of the element, which is the namespace of the
+    /// element itself except for the HTML content of a SVG `foreignObject`.
+    pub fn children_namespace(&self, namespace: Option<&str>) -> Option<String> {
+        match &self.tag {
+            ElementTag::Builtin(id) if id == "foreignObject" => None,
+            _ => namespace.map(ToString::to_string),
+        }
+    }
+}
+
 pub enum Component {
     FnLike(FnLikeComponent),
     ElementLike(ElementLikeComponent),
@@ -137,6 +322,8 @@ pub struct FnLikeComponent {
 pub struct ElementLikeComponent {
     pub ident: Ident,
     pub props: Vec<(Ident, Expr)>,
+    /// The children of the component, passed to the `children` prop.
+    pub children: Option<ViewRoot>,
 }
 
 pub struct Text {
@@ -145,4 +332,37 @@ pub struct Text {
 
 pub struct Dyn {
     pub value: Expr,
+    pub reactivity: Reactivity,
+}
+
+/// Whether an interpolated expression is re-evaluated when the signals that it reads change.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Reactivity {
+    /// The expression is reactive unless it is a literal, a path or a field access.
+    Auto,
+    /// Syntax: `static(<expr>)`. The expression is evaluated once.
+    Static,
+    /// Syntax: `dyn(<expr>)`. The expression is always reactive.
+    Dyn,
+}
+
+impl Reactivity {
+    /// Returns `true` if `expr` should be re-evaluated when the signals that it reads change.
+    pub fn is_reactive(self, expr: &Expr) -> bool {
+        match self {
+            Reactivity::Auto => !is_static(expr),
+            Reactivity::Static => false,
+            Reactivity::Dyn => true,
+        }
+    }
+}
+
+/// Returns `true` if `expr` cannot read signals: literals, paths and field accesses of these. Any
+/// other expression, in particular a function or method call, might read signals.
+fn is_static(expr: &Expr) -> bool {
+    match expr {
+        Expr::Lit(_) | Expr::Path(_) => true,
+        Expr::Field(field) => is_static(&field.base),
+        _ => false,
+    }
 }
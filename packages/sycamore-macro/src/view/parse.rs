@@ -121,10 +121,30 @@ impl Parse for ElementTag {
 
 impl Parse for Attribute {
     fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(Token![..]) {
+            let _dots: Token![..] = input.parse()?;
+            let value = input.parse()?;
+            return Ok(Self {
+                ty: AttributeType::Spread,
+                value,
+                reactivity: Reactivity::Auto,
+            });
+        }
         let ty = input.parse()?;
         let _eqs: Token![=] = input.parse()?;
-        let value = input.parse()?;
-        Ok(Self { ty, value })
+        let (value, reactivity) = match ty {
+            AttributeType::Str { .. }
+            | AttributeType::Bool { .. }
+            | AttributeType::DangerouslySetInnerHtml
+            | AttributeType::Class { .. }
+            | AttributeType::Style { .. } => parse_interpolated(input)?,
+            _ => (input.parse()?, Reactivity::Auto),
+        };
+        Ok(Self {
+            ty,
+            value,
+            reactivity,
+        })
     }
 }
 
@@ -172,8 +192,29 @@ impl Parse for AttributeType {
             match name.as_str() {
                 "on" => {
                     let event = input.call(Ident::parse_any)?;
+                    let mut modifiers = Vec::new();
+                    while input.peek(Token![|]) {
+                        let _pipe: Token![|] = input.parse()?;
+                        let modifier: Ident = input.parse()?;
+                        if !EVENT_MODIFIERS.contains(&modifier.to_string().as_str()) {
+                            return Err(syn::Error::new_spanned(
+                                &modifier,
+                                format!("unknown event modifier `{}`", modifier),
+                            ));
+                        }
+                        modifiers.push(modifier);
+                    }
+                    let has = |name: &str| modifiers.iter().any(|modifier| modifier == name);
+                    if has("passive") && has("prevent_default") {
+                        return Err(syn::Error::new_spanned(
+                            &event,
+                            "event modifiers `passive` and `prevent_default` cannot be used \
+                             together, a passive listener cannot prevent the default action",
+                        ));
+                    }
                     Ok(Self::Event {
                         event: event.to_string(),
+                        modifiers,
                     })
                 }
                 "bind" => {
@@ -182,6 +223,18 @@ impl Parse for AttributeType {
                         prop: prop.to_string(),
                     })
                 }
+                "class" => {
+                    let name: AttributeName = input.parse()?;
+                    Ok(Self::Class {
+                        name: name.to_string(),
+                    })
+                }
+                "style" => {
+                    let prop: AttributeName = input.parse()?;
+                    Ok(Self::Style {
+                        prop: prop.to_string(),
+                    })
+                }
                 _ => Err(syn::Error::new_spanned(
                     ident.tag,
                     format!("unknown directive `{}`", name),
@@ -195,6 +248,20 @@ impl Parse for AttributeType {
     }
 }
 
+/// Returns the name and value of a `name=value` component argument.
+fn named_prop(arg: &Expr) -> Option<(Ident, Expr)> {
+    match arg {
+        Expr::Assign(assign) => match &*assign.left {
+            Expr::Path(path) if path.attrs.is_empty() && path.qself.is_none() => path
+                .path
+                .get_ident()
+                .map(|ident| (ident.clone(), (*assign.right).clone())),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
 impl Parse for Component {
     fn parse(input: ParseStream) -> Result<Self> {
         let ident = input.parse()?;
@@ -202,27 +269,73 @@ impl Parse for Component {
         if input.peek(Paren) {
             parenthesized!(content in input);
             let args = content.parse_terminated(Expr::parse)?;
-            Ok(Self::FnLike(FnLikeComponent { ident, args }))
+            // Arguments of the form `name=value` are named props which are set using the builder
+            // generated by `#[derive(Prop)]`, allowing props with default values to be omitted.
+            let named: Vec<_> = args.iter().filter_map(named_prop).collect();
+            // `MyComponent(name=value) { ... }` passes the view in the braces as children.
+            let children = if input.peek(Brace) && (args.is_empty() || !named.is_empty()) {
+                let content;
+                braced!(content in input);
+                Some(content.parse()?)
+            } else {
+                None
+            };
+            if named.is_empty() && children.is_none() {
+                Ok(Self::FnLike(FnLikeComponent { ident, args }))
+            } else if named.len() == args.len() {
+                Ok(Self::ElementLike(ElementLikeComponent {
+                    ident,
+                    props: named,
+                    children,
+                }))
+            } else {
+                Err(syn::Error::new_spanned(
+                    args,
+                    "cannot mix positional and named props",
+                ))
+            }
         } else if input.peek(Brace) {
             braced!(content in input);
-            let props: Punctuated<FieldValue, Comma> =
-                content.parse_terminated(FieldValue::parse)?;
-            Ok(Self::ElementLike(ElementLikeComponent {
-                ident,
-                props: props
-                    .into_iter()
-                    .map(|x| match x.member {
-                        syn::Member::Named(named) => (named, x.expr),
-                        syn::Member::Unnamed(_) => todo!("implement error handling"),
-                    })
-                    .collect(),
-            }))
+            if is_props(&content) {
+                let props: Punctuated<FieldValue, Comma> =
+                    content.parse_terminated(FieldValue::parse)?;
+                Ok(Self::ElementLike(ElementLikeComponent {
+                    ident,
+                    props: props
+                        .into_iter()
+                        .map(|x| match x.member {
+                            syn::Member::Named(named) => (named, x.expr),
+                            syn::Member::Unnamed(_) => todo!("implement error handling"),
+                        })
+                        .collect(),
+                    children: None,
+                }))
+            } else {
+                Ok(Self::ElementLike(ElementLikeComponent {
+                    ident,
+                    props: Vec::new(),
+                    children: Some(content.parse()?),
+                }))
+            }
         } else {
             Err(input.error("expected either `(` or `{`"))
         }
     }
 }
 
+/// Returns `true` if the content of the braces of `MyComponent { ... }` are props (`name: value`
+/// or the shorthand `name`) rather than a view of children.
+fn is_props(input: ParseStream) -> bool {
+    if input.is_empty() {
+        return true;
+    }
+    let input = input.fork();
+    if input.call(Ident::parse_any).is_err() {
+        return false;
+    }
+    input.is_empty() || input.peek(Token![,]) || (input.peek(Token![:]) && !input.peek(Token![::]))
+}
+
 impl Parse for Text {
     fn parse(input: ParseStream) -> Result<Self> {
         Ok(Self {
@@ -235,8 +348,27 @@ impl Parse for Dyn {
     fn parse(input: ParseStream) -> Result<Self> {
         let content;
         parenthesized!(content in input);
-        Ok(Self {
-            value: content.parse()?,
-        })
+        let (value, reactivity) = parse_interpolated(&content)?;
+        if !content.is_empty() {
+            return Err(content.error("unexpected tokens after interpolated expression"));
+        }
+        Ok(Self { value, reactivity })
     }
 }
+
+/// Parses an interpolated expression, which can be wrapped in `static(...)` or `dyn(...)` to
+/// override the detected [`Reactivity`].
+fn parse_interpolated(input: ParseStream) -> Result<(Expr, Reactivity)> {
+    let reactivity = if input.peek(Token![static]) && input.peek2(Paren) {
+        let _static: Token![static] = input.parse()?;
+        Reactivity::Static
+    } else if input.peek(Token![dyn]) && input.peek2(Paren) {
+        let _dyn: Token![dyn] = input.parse()?;
+        Reactivity::Dyn
+    } else {
+        return Ok((input.parse()?, Reactivity::Auto));
+    };
+    let content;
+    parenthesized!(content in input);
+    Ok((content.parse()?, reactivity))
+}
@@ -10,4 +10,21 @@ fn comp2<G: Html>(_ctx: ScopeRef, _: ()) -> View<G> {
     todo!();
 }
 
+#[derive(Prop)]
+struct Props<'a> {
+    value: &'a Signal<i32>,
+}
+
+#[component]
+fn comp3<G: Html>(ctx: ScopeRef, props: Props<'_>) -> View<G> {
+    ctx.create_effect(move || props.value.track());
+    todo!();
+}
+
+#[component]
+fn comp4<G: Html>(ctx: ScopeRef<'_>, props: &Signal<i32>) -> View<G> {
+    ctx.create_effect(move || props.track());
+    todo!();
+}
+
 fn main() {}
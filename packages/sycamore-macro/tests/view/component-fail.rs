@@ -13,6 +13,7 @@ fn compile_fail<G: Html>() {
 
         let _: View<G> = view! { ctx, C };
         let _: View<G> = view! { ctx, C(1) };
+        let _: View<G> = view! { ctx, C(1, value=2) };
     });
 }
 
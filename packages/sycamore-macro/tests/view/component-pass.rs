@@ -7,9 +7,48 @@ pub fn Component<G: Html>(ctx: ScopeRef, _: ()) -> View<G> {
     }
 }
 
+#[derive(Prop)]
+pub struct PropsWithDefaults {
+    value: i32,
+    #[prop(default)]
+    optional: i32,
+    #[prop(default = 1)]
+    with_default: i32,
+}
+
+#[component]
+pub fn ComponentWithDefaults<G: Html>(ctx: ScopeRef, _: PropsWithDefaults) -> View<G> {
+    view! { ctx,
+        div
+    }
+}
+
+#[derive(Prop)]
+pub struct LayoutProps<'a, G: GenericNode> {
+    children: Children<'a, G>,
+    #[prop(default)]
+    title: &'static str,
+}
+
+#[component]
+pub fn Layout<'a, G: Html>(ctx: ScopeRef<'a>, props: LayoutProps<'a, G>) -> View<G> {
+    let children = props.children.call(ctx);
+    view! { ctx,
+        div { (props.title) (children) }
+    }
+}
+
 fn compile_pass<G: Html>() {
     create_scope_immediate(|ctx| {
         let _: View<G> = view! { ctx, Component() };
+
+        let _: View<G> = view! { ctx, ComponentWithDefaults(value=1) };
+        let _: View<G> = view! { ctx, ComponentWithDefaults(value=1, optional=2, with_default=3) };
+        let _: View<G> = view! { ctx, ComponentWithDefaults { value: 1 } };
+
+        let _: View<G> = view! { ctx, Layout { p { "Hello" } } };
+        let _: View<G> = view! { ctx, Layout(title="Title") { p { "Hello" } "World" } };
+        let _: View<G> = view! { ctx, Layout { Component() } };
     });
 }
 
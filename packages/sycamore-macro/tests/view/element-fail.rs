@@ -7,6 +7,8 @@ fn compile_fail<G: GenericNode>() {
         let _: View<G> = view! { ctx, button(disabled) };
         let _: View<G> = view! { ctx, button(on:click) };
         let _: View<G> = view! { ctx, button(unknown:directive="123") };
+        let _: View<G> = view! { ctx, button(on:click|prevent=|_| {}) };
+        let _: View<G> = view! { ctx, button(on:touchstart|passive|prevent_default=|_| {}) };
 
         let _: View<G> = view! { ctx, button(a.b.c="123") };
 
@@ -13,11 +13,40 @@ fn compile_pass<G: GenericNode>() {
 
         let _: View<G> = view! { ctx, button(class="my-btn", on:click=|_| {}) };
         let _: View<G> = view! { ctx, button(class="my-btn", aria-hidden="true") };
+        let _: View<G> = view! { ctx, button(on:click|prevent_default|once=|_| {}) };
+        let _: View<G> = view! { ctx,
+            input(
+                on:keydown|stop_propagation=|event: sycamore::rt::KeyboardEvent| {
+                    let _ = event.key();
+                },
+                on:touchstart|passive=|_| {},
+                on:change=|_: sycamore::rt::Event| {},
+            )
+        };
 
         let _: View<G> = view! { ctx, p(dangerously_set_inner_html="<span>Test</span>") };
 
+        let node_ref = ctx.create_node_ref();
+        let _: View<G> = view! { ctx, p(ref=node_ref) };
+
+        let attrs = ctx.create_signal(std::collections::HashMap::new());
+        let _: View<G> = view! { ctx, p(..attrs) };
+        let _: View<G> = view! { ctx, p(class="my-class", ..attrs) };
+
         // view! should correctly parenthesize the (1 + 2) when borrowing.
         let _: View<G> = view! { ctx, p { (1 + 2) } };
+
+        let count = ctx.create_signal(0);
+        let _: View<G> = view! { ctx, p(class=static(count.get())) { (static(count.get())) } };
+        let _: View<G> = view! { ctx, p(hidden=dyn(*count.get() == 0)) { (dyn(1 + 2)) } };
+
+        let _: View<G> = view! { ctx, p(class="a", class:is-active=*count.get() > 0, class:b=true) };
+        let _: View<G> = view! { ctx, p(style:color="red", style:font-size=count.get()) };
+
+        let _: View<G> = view! { ctx, svg(viewBox="0 0 10 10") { path(d="M 0 0 L 10 10") } };
+        let _: View<G> = view! { ctx, math { mi { "x" } } };
+        let _: View<G> = view! { ctx, circle(r="5") };
+        let _: View<G> = view! { ctx, div(xmlns="http://www.w3.org/2000/svg") };
     });
 }
 
@@ -6,5 +6,8 @@ fn main() {
         dbg!(data.get());
         let signal_ref = ctx.create_signal(data);
         dbg!(signal_ref.get().get());
+        // Track both the outer and the inner signal.
+        let flattened = signal_ref.flatten();
+        dbg!(flattened.get());
     });
 }
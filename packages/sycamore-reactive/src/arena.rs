@@ -1,6 +1,8 @@
 //! Arena allocator for [`Scope`](crate::Scope).
 
-use std::cell::UnsafeCell;
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, vec::Vec};
+use core::cell::UnsafeCell;
 
 /// A trait that is implemented for everything.
 pub(crate) trait ReallyAny {}
@@ -46,7 +48,7 @@ impl<'a> ScopeArena<'a> {
             drop(boxed);
         }
         // Clear the inner Vec to prevent dangling references.
-        drop(std::mem::take(&mut *self.inner.get()));
+        drop(core::mem::take(&mut *self.inner.get()));
     }
 }
 
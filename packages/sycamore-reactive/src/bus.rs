@@ -0,0 +1,190 @@
+//! Message buses that are shared through contexts.
+
+use alloc::collections::VecDeque;
+use core::fmt;
+
+use crate::*;
+
+struct BusInner<T> {
+    event: Event<T>,
+    /// The messages that were published but not delivered yet.
+    queue: RefCell<VecDeque<T>>,
+    /// Triggers the node that delivers the queued messages.
+    pending: SignalEmitter,
+}
+
+/// A typed message bus, created with [`create_bus`](Scope::create_bus).
+///
+/// The bus is provided as a context, so it can be used anywhere below the scope that created it
+/// (see [`use_bus`](Scope::use_bus)). Messages are not delivered immediately. Instead, they are
+/// delivered by the scheduler together with the effects, so a message that is published inside of
+/// a [`batch`] is only delivered once the batch ends, and listeners always see up-to-date memos.
+///
+/// Cloning the handle does not create a new bus.
+pub struct Bus<T>(Rc<BusInner<T>>);
+
+impl<T: 'static> Bus<T> {
+    /// Calls `listener` with every message that is delivered until `ctx` is disposed. See
+    /// [`Event::on`].
+    pub fn subscribe<'a>(&self, ctx: ScopeRef<'a>, listener: impl FnMut(&T) + 'a) {
+        self.0.event.on(ctx, listener);
+    }
+}
+
+impl<T> Bus<T> {
+    /// Publishes a message to the listeners of the bus. Messages are delivered in the order in
+    /// which they are published.
+    pub fn publish(&self, message: T) {
+        self.0.queue.borrow_mut().push_back(message);
+        self.0.pending.trigger_subscribers();
+    }
+
+    /// Returns the number of listeners of the bus.
+    pub fn listener_count(&self) -> usize {
+        self.0.event.listener_count()
+    }
+}
+
+impl<T> Clone for Bus<T> {
+    fn clone(&self) -> Self {
+        Self(Rc::clone(&self.0))
+    }
+}
+
+impl<T> fmt::Debug for Bus<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Bus")
+            .field("listeners", &self.listener_count())
+            .field("queued", &self.0.queue.borrow().len())
+            .finish()
+    }
+}
+
+impl<'a> Scope<'a> {
+    /// Creates a new [`Bus`] for messages of type `T` and provides it as a context to this scope
+    /// and its child scopes.
+    ///
+    /// # Panics
+    /// This method panics if a bus for `T` exists already in this scope.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// ctx.create_bus::<String>();
+    /// let received = ctx.create_signal(Vec::new());
+    /// let _ = ctx.create_child_scope(|ctx| {
+    ///     ctx.use_bus::<String>().subscribe(&ctx, |message| {
+    ///         received.set([received.get_untracked().as_slice(), &[message.clone()]].concat());
+    ///     });
+    /// });
+    ///
+    /// let bus = ctx.use_bus::<String>();
+    /// batch(|| {
+    ///     bus.publish("Hello".to_string());
+    ///     // Not delivered until the end of the batch.
+    ///     assert!(received.get().is_empty());
+    /// });
+    /// assert_eq!(*received.get(), ["Hello"]);
+    /// # });
+    /// ```
+    pub fn create_bus<T: 'static>(&'a self) -> Bus<T> {
+        let bus = Bus(Rc::new(BusInner {
+            event: create_event(),
+            queue: Default::default(),
+            pending: SignalEmitter::default(),
+        }));
+        self.create_effect({
+            let bus = bus.clone();
+            move || {
+                bus.0.pending.track();
+                // Messages that are published by the listeners are delivered in the same run.
+                loop {
+                    let message = bus.0.queue.borrow_mut().pop_front();
+                    let Some(message) = message else { break };
+                    bus.0.event.emit(message);
+                }
+            }
+        });
+        self.provide_context(bus.clone());
+        bus
+    }
+
+    /// Returns the nearest [`Bus`] for messages of type `T`.
+    ///
+    /// # Panics
+    /// This method panics if there is no bus for `T` in this scope or its parent scopes.
+    #[track_caller]
+    pub fn use_bus<T: 'static>(&'a self) -> Bus<T> {
+        match self.try_use_context::<Bus<T>>() {
+            Some(bus) => bus.clone(),
+            None => panic!("no bus found for type `{}`", core::any::type_name::<T>()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_are_delivered_after_memos() {
+        create_scope_immediate(|ctx| {
+            let bus = ctx.create_bus::<i32>();
+            let state = ctx.create_signal(0);
+            let double = ctx.create_memo(|| *state.get() * 2);
+            let observed = ctx.create_signal(Vec::new());
+            bus.subscribe(ctx, |message| {
+                let value = (*message, *double.get_untracked());
+                observed.set([observed.get_untracked().as_slice(), &[value]].concat());
+            });
+
+            batch(|| {
+                state.set(1);
+                bus.publish(1);
+                bus.publish(2);
+            });
+            assert_eq!(*observed.get(), [(1, 2), (2, 2)]);
+        });
+    }
+
+    #[test]
+    fn messages_published_by_listeners_are_delivered() {
+        create_scope_immediate(|ctx| {
+            let bus = ctx.create_bus::<u32>();
+            let received = ctx.create_signal(Vec::new());
+            bus.subscribe(ctx, {
+                let bus = bus.clone();
+                move |message| {
+                    received.set([received.get_untracked().as_slice(), &[*message]].concat());
+                    if *message > 0 {
+                        bus.publish(message - 1);
+                    }
+                }
+            });
+            bus.publish(2);
+            assert_eq!(*received.get(), [2, 1, 0]);
+        });
+    }
+
+    #[test]
+    fn listeners_of_child_scopes_are_removed() {
+        create_scope_immediate(|ctx| {
+            let bus = ctx.create_bus::<()>();
+            let disposer = ctx.create_child_scope(|ctx| {
+                ctx.use_bus::<()>().subscribe(&ctx, |_| {});
+            });
+            assert_eq!(bus.listener_count(), 1);
+            disposer();
+            assert_eq!(bus.listener_count(), 0);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "no bus found")]
+    fn use_bus_without_bus() {
+        create_scope_immediate(|ctx| {
+            ctx.use_bus::<()>();
+        });
+    }
+}
@@ -0,0 +1,292 @@
+//! Awaiting changes of signals.
+
+use core::fmt;
+use core::future::Future;
+use core::marker::PhantomData;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+
+use crate::*;
+
+#[derive(Default)]
+struct ChangedState {
+    changed: Cell<bool>,
+    waker: RefCell<Option<Waker>>,
+}
+
+/// Subscribes to signals and wakes the task that polls it when one of them is updated.
+struct Watcher {
+    state: Rc<ChangedState>,
+    /// Unsubscribes `_callback` from the signals when dropped. The subscriptions are boxed because
+    /// they must not move while they are linked.
+    #[allow(clippy::vec_box)]
+    _subscriptions: Vec<Box<Subscription>>,
+    _callback: Rc<RefCell<dyn FnMut()>>,
+}
+
+impl Watcher {
+    fn new(emitters: impl IntoIterator<Item = Rc<Subscribers>>) -> Self {
+        let state = Rc::new(ChangedState::default());
+        let callback: Rc<RefCell<dyn FnMut()>> = Rc::new(RefCell::new({
+            let state = Rc::clone(&state);
+            move || {
+                state.changed.set(true);
+                if let Some(waker) = state.waker.take() {
+                    waker.wake();
+                }
+            }
+        }));
+        // The same id is used for all the signals so that the scheduler only runs the callback
+        // once when several of them are updated in a batch.
+        let id = EffectId::next();
+        let runtime = Runtime::current();
+        let subscriptions = emitters
+            .into_iter()
+            .map(|emitter| {
+                emitter.subscribe(
+                    id,
+                    NodeKind::Effect,
+                    Rc::downgrade(&callback),
+                    runtime.clone(),
+                    Default::default(),
+                )
+            })
+            .collect();
+        Self {
+            state,
+            _subscriptions: subscriptions,
+            _callback: callback,
+        }
+    }
+
+    fn poll(&self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.state.changed.get() {
+            Poll::Ready(())
+        } else {
+            *self.state.waker.borrow_mut() = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+/// Future returned by [`ReadSignal::changed`].
+#[must_use = "futures do nothing unless polled"]
+pub struct Changed<'a, T> {
+    _signal: &'a ReadSignal<T>,
+    watcher: Watcher,
+}
+
+impl<T> ReadSignal<T> {
+    /// Returns a future that completes the next time the subscribers of the signal are notified
+    /// (e.g. with [`Signal::set`]) after this method is called.
+    ///
+    /// The future is completed together with the effects, so a change inside a [`batch`] only
+    /// completes it once the batch ends. This is useful for asynchronous tasks that need to react
+    /// to changes without creating an effect.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let state = ctx.create_signal(0);
+    /// let task = async {
+    ///     state.changed().await;
+    ///     println!("state changed to {}", state.get());
+    /// };
+    /// # drop(task);
+    /// # });
+    /// ```
+    pub fn changed(&self) -> Changed<'_, T> {
+        Changed {
+            _signal: self,
+            watcher: Watcher::new([Rc::clone(&self.emitter.0)]),
+        }
+    }
+}
+
+impl<T> Future for Changed<'_, T> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        self.watcher.poll(cx)
+    }
+}
+
+impl<T> fmt::Debug for Changed<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Changed")
+            .field("changed", &self.watcher.state.changed.get())
+            .finish()
+    }
+}
+
+/// Future returned by [`wait_any`].
+#[must_use = "futures do nothing unless polled"]
+pub struct WaitAny<'a> {
+    _signals: PhantomData<&'a ()>,
+    watcher: Watcher,
+}
+
+impl Future for WaitAny<'_> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        self.watcher.poll(cx)
+    }
+}
+
+impl fmt::Debug for WaitAny<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WaitAny")
+            .field("changed", &self.watcher.state.changed.get())
+            .finish()
+    }
+}
+
+/// Returns a future that completes the next time any of the `signals` is updated after this
+/// function is called. See [`ReadSignal::changed`].
+///
+/// # Example
+/// ```
+/// # use sycamore_reactive::*;
+/// # create_scope_immediate(|ctx| {
+/// let name = ctx.create_signal(String::new());
+/// let email = ctx.create_signal(String::new());
+/// let task = async {
+///     wait_any([name, email]).await;
+///     println!("the form was edited");
+/// };
+/// # drop(task);
+/// # });
+/// ```
+pub fn wait_any<'a, const N: usize>(signals: [&'a (dyn AnyReadSignal<'a> + 'a); N]) -> WaitAny<'a> {
+    let ((), emitters) = untrack(|| {
+        collect_dependencies(|| {
+            for signal in signals {
+                signal.track();
+            }
+        })
+    });
+    WaitAny {
+        _signals: PhantomData,
+        watcher: Watcher::new(emitters.iter().filter_map(Weak::upgrade)),
+    }
+}
+
+/// Waits until the value of `signal` satisfies `predicate` and returns that value. If the current
+/// value satisfies `predicate` already, it is returned immediately.
+///
+/// `predicate` is called untracked, once with the current value and then every time the signal is
+/// updated.
+///
+/// # Example
+/// ```
+/// # use sycamore_reactive::*;
+/// # create_scope_immediate(|ctx| {
+/// let step = ctx.create_signal(1);
+/// let task = async {
+///     wait_until(step, |step| *step == 3).await;
+///     println!("the wizard is done");
+/// };
+/// # drop(task);
+/// # });
+/// ```
+pub async fn wait_until<T>(signal: &ReadSignal<T>, mut predicate: impl FnMut(&T) -> bool) -> Rc<T> {
+    loop {
+        // Subscribe before reading so that no update is missed.
+        let changed = signal.changed();
+        let value = signal.get_untracked();
+        if untrack(|| predicate(&value)) {
+            return value;
+        }
+        changed.await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::task::Wake;
+
+    use super::*;
+
+    struct CountWakes(AtomicUsize);
+    impl Wake for CountWakes {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn changed_completes_on_next_update() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(0);
+            let wakes = Arc::new(CountWakes(AtomicUsize::new(0)));
+            let waker = Waker::from(Arc::clone(&wakes));
+            let mut cx = Context::from_waker(&waker);
+
+            let mut changed = state.changed();
+            assert!(Pin::new(&mut changed).poll(&mut cx).is_pending());
+            batch(|| {
+                state.set(1);
+                assert_eq!(wakes.0.load(Ordering::SeqCst), 0);
+            });
+            assert_eq!(wakes.0.load(Ordering::SeqCst), 1);
+            assert!(Pin::new(&mut changed).poll(&mut cx).is_ready());
+        });
+    }
+
+    #[test]
+    fn wait_any_completes_once_per_batch() {
+        create_scope_immediate(|ctx| {
+            let a = ctx.create_signal(0);
+            let b = ctx.create_signal(0);
+            let c = ctx.create_signal(0);
+            let wakes = Arc::new(CountWakes(AtomicUsize::new(0)));
+            let waker = Waker::from(Arc::clone(&wakes));
+            let mut cx = Context::from_waker(&waker);
+
+            let mut any = wait_any([a, b, c]);
+            assert!(Pin::new(&mut any).poll(&mut cx).is_pending());
+            batch(|| {
+                b.set(1);
+                c.set(1);
+            });
+            assert_eq!(wakes.0.load(Ordering::SeqCst), 1);
+            assert!(Pin::new(&mut any).poll(&mut cx).is_ready());
+        });
+    }
+
+    #[test]
+    fn wait_until_checks_every_update() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(0);
+            let waker = Waker::from(Arc::new(CountWakes(AtomicUsize::new(0))));
+            let mut cx = Context::from_waker(&waker);
+
+            let mut done = Box::pin(wait_until(state, |state| *state >= 2));
+            assert!(done.as_mut().poll(&mut cx).is_pending());
+            state.set(1);
+            assert!(done.as_mut().poll(&mut cx).is_pending());
+            state.set(2);
+            assert_eq!(done.as_mut().poll(&mut cx), Poll::Ready(Rc::new(2)));
+        });
+    }
+
+    #[test]
+    fn changed_before_poll() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(0);
+            let waker = Waker::from(Arc::new(CountWakes(AtomicUsize::new(0))));
+            let mut cx = Context::from_waker(&waker);
+
+            let mut changed = state.changed();
+            state.set(1);
+            assert!(Pin::new(&mut changed).poll(&mut cx).is_ready());
+            drop(changed);
+            // The subscription is released with the future.
+            assert_eq!(state.emitter.0.len(), 0);
+        });
+    }
+}
@@ -0,0 +1,337 @@
+//! Bridging non-reactive code into the reactive system.
+
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, ThreadId};
+
+use crate::*;
+
+thread_local! {
+    /// Flush callbacks for all the channels that are received on the current thread, keyed by
+    /// channel id.
+    ///
+    /// The callbacks are only `'static` in name. They are removed from the map when the receiving
+    /// [`Scope`] is disposed and are never called afterwards.
+    static RECEIVERS: RefCell<HashMap<usize, Rc<dyn Fn()>>> = Default::default();
+}
+
+/// Source of unique channel ids.
+static NEXT_CHANNEL_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// State shared between a [`Sender`] and the receiving end of the channel.
+struct Shared<T> {
+    id: usize,
+    /// The thread on which the receiving [`Scope`] lives.
+    owner: ThreadId,
+    queue: Mutex<VecDeque<T>>,
+    /// Set once the receiving [`Scope`] is disposed.
+    disconnected: AtomicBool,
+}
+
+/// The sending half of a channel created with [`Scope::create_channel`].
+///
+/// A [`Sender`] is not bound to a [`Scope`] and can be cloned and moved freely, including to other
+/// threads if `T` is [`Send`].
+pub struct Sender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Sender<T> {
+    /// Sends a value to the receiving [`Scope`].
+    ///
+    /// If called on the thread that owns the receiving [`Scope`], the value is delivered
+    /// immediately. Otherwise, the value is buffered until [`flush_channels`] is called on the
+    /// owning thread.
+    ///
+    /// Returns an error containing `value` if the receiving [`Scope`] has already been disposed.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        if self.shared.disconnected.load(Ordering::Acquire) {
+            return Err(SendError(value));
+        }
+        self.shared.queue.lock().unwrap().push_back(value);
+        if thread::current().id() == self.shared.owner {
+            flush_channel(self.shared.id);
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the receiving [`Scope`] has been disposed. Any further values sent will
+    /// be returned as an error.
+    pub fn is_disconnected(&self) -> bool {
+        self.shared.disconnected.load(Ordering::Acquire)
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender")
+            .field("disconnected", &self.is_disconnected())
+            .finish()
+    }
+}
+
+/// An error returned from [`Sender::send`] when the receiving [`Scope`] has been disposed. Contains
+/// the value that could not be sent.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct SendError<T>(pub T);
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SendError { .. }")
+    }
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("sending on a disconnected channel")
+    }
+}
+
+impl<T> Error for SendError<T> {}
+
+fn flush_channel(id: usize) {
+    let receiver = RECEIVERS.with(|receivers| receivers.borrow().get(&id).cloned());
+    if let Some(receiver) = receiver {
+        receiver();
+    }
+}
+
+/// Delivers all the values that were sent from other threads to channels received on the current
+/// thread.
+///
+/// Values sent on the same thread as the receiving [`Scope`] are delivered immediately and do not
+/// need this. Integrations that receive values from other threads (e.g. a background worker)
+/// should call this on the reactive thread whenever they are notified of new values.
+pub fn flush_channels() {
+    let receivers: Vec<_> =
+        RECEIVERS.with(|receivers| receivers.borrow().values().cloned().collect());
+    for receiver in receivers {
+        receiver();
+    }
+}
+
+impl<'a> Scope<'a> {
+    /// Creates a channel for sending values from non-reactive code into the current [`Scope`].
+    ///
+    /// Returns a [`Sender`] and a [`ReadSignal`] that is updated with every value that is sent.
+    /// The signal is `None` until the first value is received. Values are delivered in the order
+    /// they were sent. If a value is sent while another value is being delivered (e.g. from inside
+    /// an effect), it is queued and delivered once the current value has been processed.
+    ///
+    /// Once the [`Scope`] is disposed, the channel is disconnected and [`Sender::send`] returns an
+    /// error.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let (tx, rx) = ctx.create_channel();
+    /// assert_eq!(*rx.get(), None);
+    ///
+    /// tx.send(1).unwrap();
+    /// assert_eq!(*rx.get(), Some(1));
+    ///
+    /// // Values sent from other threads are buffered until flushed.
+    /// std::thread::spawn(move || tx.send(2).unwrap()).join().unwrap();
+    /// assert_eq!(*rx.get(), Some(1));
+    /// flush_channels();
+    /// assert_eq!(*rx.get(), Some(2));
+    /// # });
+    /// ```
+    pub fn create_channel<T: 'static>(&'a self) -> (Sender<T>, &'a ReadSignal<Option<T>>) {
+        let signal = self.create_signal(None);
+        let sender = self.create_receiver(move |value| signal.set(Some(value)));
+        (sender, signal)
+    }
+
+    /// Creates a sink that merges the values written by multiple producers into a single signal.
+    ///
+    /// Returns a [`Sender`] and a [`ReadSignal`] that starts with `initial` and is set to every
+    /// value that is sent. The [`Sender`] can be cloned to create as many producers as needed (e.g.
+    /// one per websocket or per worker). Writes from all producers are serialized: each value is
+    /// set on the signal and its subscribers are run before the next value is delivered, in the
+    /// order in which the values were sent. This behaves exactly like
+    /// [`create_channel`](Self::create_channel) otherwise, including for values sent from other
+    /// threads.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let (messages, last_message) = ctx.create_write_only_sink("none");
+    /// let (socket, worker) = (messages.clone(), messages);
+    ///
+    /// socket.send("from socket").unwrap();
+    /// assert_eq!(*last_message.get(), "from socket");
+    /// worker.send("from worker").unwrap();
+    /// assert_eq!(*last_message.get(), "from worker");
+    /// # });
+    /// ```
+    pub fn create_write_only_sink<T: 'static>(
+        &'a self,
+        initial: T,
+    ) -> (Sender<T>, &'a ReadSignal<T>) {
+        let signal = self.create_signal(initial);
+        let sender = self.create_receiver(move |value| signal.set(value));
+        (sender, signal)
+    }
+
+    /// Creates a channel whose values are passed to `deliver` on the current thread, one at a
+    /// time.
+    fn create_receiver<T: 'static>(&'a self, deliver: impl Fn(T) + 'a) -> Sender<T> {
+        let shared = Arc::new(Shared {
+            id: NEXT_CHANNEL_ID.fetch_add(1, Ordering::Relaxed),
+            owner: thread::current().id(),
+            queue: Mutex::new(VecDeque::new()),
+            disconnected: AtomicBool::new(false),
+        });
+        let flushing = Cell::new(false);
+        let receiver: Rc<dyn Fn() + 'a> = Rc::new({
+            let shared = Arc::clone(&shared);
+            move || {
+                // Values sent while flushing are picked up by the outermost flush.
+                if flushing.replace(true) {
+                    return;
+                }
+                loop {
+                    // The scope might have been disposed while delivering the previous value.
+                    if shared.disconnected.load(Ordering::Acquire) {
+                        break;
+                    }
+                    let next = shared.queue.lock().unwrap().pop_front();
+                    match next {
+                        Some(value) => deliver(value),
+                        None => break,
+                    }
+                }
+                flushing.set(false);
+            }
+        });
+        // SAFETY: The callback is removed from RECEIVERS when the scope is disposed and the
+        // callback checks whether the scope has been disposed before calling `deliver`.
+        let receiver: Rc<dyn Fn()> = unsafe { std::mem::transmute(receiver) };
+        RECEIVERS.with(|receivers| receivers.borrow_mut().insert(shared.id, receiver));
+
+        self.on_cleanup({
+            let shared = Arc::clone(&shared);
+            move || {
+                shared.disconnected.store(true, Ordering::Release);
+                shared.queue.lock().unwrap().clear();
+                RECEIVERS.with(|receivers| receivers.borrow_mut().remove(&shared.id));
+            }
+        });
+
+        Sender { shared }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channel() {
+        create_scope_immediate(|ctx| {
+            let (tx, rx) = ctx.create_channel();
+            assert_eq!(*rx.get(), None);
+
+            tx.send(1).unwrap();
+            assert_eq!(*rx.get(), Some(1));
+            tx.clone().send(2).unwrap();
+            assert_eq!(*rx.get(), Some(2));
+        });
+    }
+
+    #[test]
+    fn channel_from_other_thread() {
+        create_scope_immediate(|ctx| {
+            let (tx, rx) = ctx.create_channel();
+            std::thread::spawn(move || {
+                tx.send(1).unwrap();
+                tx.send(2).unwrap();
+            })
+            .join()
+            .unwrap();
+            assert_eq!(*rx.get(), None);
+
+            let received = ctx.create_signal(Vec::new());
+            ctx.create_effect(|| {
+                if let Some(value) = *rx.get() {
+                    received.set([received.get_untracked().as_slice(), &[value]].concat());
+                }
+            });
+            flush_channels();
+            assert_eq!(*received.get(), [1, 2]);
+        });
+    }
+
+    #[test]
+    fn channel_send_while_delivering_is_queued() {
+        create_scope_immediate(|ctx| {
+            let (tx, rx) = ctx.create_channel();
+            let received = ctx.create_signal(Vec::new());
+            ctx.create_effect({
+                let tx = tx.clone();
+                move || {
+                    if let Some(value) = *rx.get() {
+                        received.set([received.get_untracked().as_slice(), &[value]].concat());
+                        if value == 1 {
+                            // Delivered after this effect has finished running.
+                            tx.send(2).unwrap();
+                            assert_eq!(*rx.get_untracked(), Some(1));
+                        }
+                    }
+                }
+            });
+            tx.send(1).unwrap();
+            assert_eq!(*received.get(), [1, 2]);
+        });
+    }
+
+    #[test]
+    fn channel_disconnected_on_dispose() {
+        let mut sender = None;
+        create_scope_immediate(|ctx| {
+            let (tx, _rx) = ctx.create_channel::<i32>();
+            sender = Some(tx);
+        });
+        let tx = sender.unwrap();
+        assert!(tx.is_disconnected());
+        assert_eq!(tx.send(1), Err(SendError(1)));
+    }
+
+    #[test]
+    fn write_only_sink_merges_producers() {
+        create_scope_immediate(|ctx| {
+            let (tx, rx) = ctx.create_write_only_sink(0);
+            assert_eq!(*rx.get(), 0);
+
+            let received = ctx.create_signal(Vec::new());
+            ctx.create_effect(|| {
+                let value = *rx.get();
+                received.set([received.get_untracked().as_slice(), &[value]].concat());
+            });
+            let producers: Vec<_> = (0..3).map(|_| tx.clone()).collect();
+            for (i, producer) in producers.iter().enumerate() {
+                producer.send(i as i32 + 1).unwrap();
+            }
+            let other = tx.clone();
+            std::thread::spawn(move || other.send(4).unwrap())
+                .join()
+                .unwrap();
+            flush_channels();
+            assert_eq!(*received.get(), [0, 1, 2, 3, 4]);
+        });
+    }
+}
@@ -1,7 +1,19 @@
 //! Context state management.
 
+use core::any::type_name;
+
 use crate::*;
 
+/// A context value that is owned by a [`Scope`].
+pub(crate) struct ContextEntry {
+    /// The value, which is freed when the scope is disposed.
+    pub(crate) value: *mut dyn Any,
+    /// The name of the type of the value, for the message of [`Scope::use_context`] and for
+    /// [`Scope::debug_contexts`].
+    #[cfg(any(debug_assertions, feature = "debug-tools"))]
+    type_name: &'static str,
+}
+
 impl<'a> Scope<'a> {
     /// Provides a context in the current [`Scope`]. The context can later be accessed by using
     /// [`use_context`](Self::use_context) lower in the scope hierarchy.
@@ -13,26 +25,62 @@ impl<'a> Scope<'a> {
     /// Note that if a context with the same type exists in a parent scope, the new context will
     /// shadow the old context.
     pub fn provide_context<T: 'static>(&'a self, value: T) {
-        let type_id = TypeId::of::<T>();
-        let boxed = Box::new(value);
-        let ptr = Box::into_raw(boxed);
-        if self.contexts.borrow_mut().insert(type_id, ptr).is_some() {
+        if self.insert_context(value).is_some() {
             panic!("existing context with type exists already");
         }
     }
 
+    /// Inserts a context in the current [`Scope`] and returns the context with the same type that
+    /// it replaces, if any.
+    pub(crate) fn insert_context<T: 'static>(&self, value: T) -> Option<ContextEntry> {
+        self.insert_context_named(value, type_name::<T>())
+    }
+
+    /// Like [`insert_context`](Self::insert_context), but with the type name that is shown by the
+    /// diagnostic tools.
+    fn insert_context_named<T: 'static>(
+        &self,
+        value: T,
+        type_name: &'static str,
+    ) -> Option<ContextEntry> {
+        let entry = ContextEntry {
+            value: Box::into_raw(Box::new(value)),
+            #[cfg(any(debug_assertions, feature = "debug-tools"))]
+            type_name,
+        };
+        #[cfg(not(any(debug_assertions, feature = "debug-tools")))]
+        let _ = type_name;
+        self.contexts.borrow_mut().insert(TypeId::of::<T>(), entry)
+    }
+
+    /// Returns the context of type `T` that is provided in the current [`Scope`] itself, without
+    /// looking at the parent scopes.
+    pub(crate) fn own_context<T: 'static>(&'a self) -> Option<&'a T> {
+        let value = self
+            .contexts
+            .borrow()
+            .and_then(|contexts| contexts.get(&TypeId::of::<T>()).map(|entry| entry.value))?;
+        // SAFETY: the value is allocated on the current scope, so it lives as long as 'a.
+        let value = unsafe { &*value };
+        value.downcast_ref::<T>()
+    }
+
     /// Tries to get a context value of the given type. If no context with the right type found,
     /// returns `None`. For a panicking version, see [`use_context`](Self::use_context).
     pub fn try_use_context<T: 'static>(&'a self) -> Option<&'a T> {
         let type_id = TypeId::of::<T>();
         let mut this = Some(self);
         while let Some(current) = this {
-            if let Some(value) = current.contexts.borrow_mut().get(&type_id) {
+            if let Some(value) = current
+                .contexts
+                .borrow()
+                .and_then(|contexts| contexts.get(&type_id).map(|entry| entry.value))
+            {
                 // SAFETY: value lives at least as long as 'a:
                 // - Lifetime of value is 'a if it is allocated on the current scope.
                 // - Lifetime of value is longer than 'a if it is allocated on a parent scope.
                 // - 'a is variant because it is an immutable reference.
-                let value = unsafe { &**value };
+                let value = unsafe { &*value };
                 let value = value.downcast_ref::<T>().unwrap();
                 return Some(value);
             } else {
@@ -48,9 +96,127 @@ impl<'a> Scope<'a> {
     /// # Panics
     /// This method panics if the context cannot be found in the current scope hierarchy.
     /// For a non-panicking version, see [`try_use_context`](Self::try_use_context).
+    ///
+    /// In debug builds, the panic message also shows the path of the scope (see
+    /// [`set_debug_name`](Self::set_debug_name)) and the types of the contexts that are available.
     #[track_caller]
     pub fn use_context<T: 'static>(&'a self) -> &'a T {
-        self.try_use_context().expect("context not found for type")
+        match self.try_use_context() {
+            Some(value) => value,
+            None => panic!("{}", self.missing_context_message::<T>()),
+        }
+    }
+
+    /// Provides a reference as a context in the current [`Scope`]. Unlike
+    /// [`provide_context`](Self::provide_context), the reference only needs to live as long as the
+    /// scope, so values that are allocated on the scope (e.g. a [`Signal`] that was created with
+    /// [`create_signal`](Self::create_signal)) can be provided without wrapping them in an
+    /// [`RcSignal`]. The reference can later be accessed by using
+    /// [`use_context_ref`](Self::use_context_ref) lower in the scope hierarchy.
+    ///
+    /// References and values are separate contexts: a reference to `T` is not found by
+    /// [`use_context`](Self::use_context) and vice versa.
+    ///
+    /// # Panics
+    /// This method panics if a reference to a `T` was provided already in this scope.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let count = ctx.create_signal(0);
+    /// ctx.provide_context_ref(count);
+    /// let _ = ctx.create_child_scope(|ctx| {
+    ///     ctx.use_context_ref::<Signal<i32>>().set(1);
+    /// });
+    /// assert_eq!(*count.get(), 1);
+    /// # });
+    /// ```
+    pub fn provide_context_ref<T: 'static>(&'a self, value: &'a T) {
+        let value = ContextRef(value as *const T);
+        if self
+            .insert_context_named(value, type_name::<&T>())
+            .is_some()
+        {
+            panic!("existing context with type exists already");
+        }
+    }
+
+    /// Tries to get a reference that was provided with
+    /// [`provide_context_ref`](Self::provide_context_ref). If no reference to a `T` is found,
+    /// returns `None`. For a panicking version, see [`use_context_ref`](Self::use_context_ref).
+    pub fn try_use_context_ref<T: 'static>(&'a self) -> Option<&'a T> {
+        let value = self.try_use_context::<ContextRef<T>>()?;
+        // SAFETY: The reference was provided on this scope or on a parent scope, so it lives at
+        // least as long as 'a.
+        Some(unsafe { &*value.0 })
+    }
+
+    /// Gets a reference that was provided with [`provide_context_ref`](Self::provide_context_ref).
+    ///
+    /// # Panics
+    /// This method panics if no reference to a `T` can be found in the current scope hierarchy.
+    /// For a non-panicking version, see [`try_use_context_ref`](Self::try_use_context_ref).
+    #[track_caller]
+    pub fn use_context_ref<T: 'static>(&'a self) -> &'a T {
+        match self.try_use_context_ref() {
+            Some(value) => value,
+            None => panic!("{}", self.missing_context_message::<&T>()),
+        }
+    }
+
+    /// Returns the message of the panic when a context of type `T` is not found.
+    #[cold]
+    fn missing_context_message<T>(&self) -> String {
+        #[cfg(debug_assertions)]
+        {
+            let mut available = self.context_type_names();
+            available.sort_unstable();
+            let available = if available.is_empty() {
+                "none".into()
+            } else {
+                available
+                    .iter()
+                    .map(|name| alloc::format!("`{name}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            };
+            alloc::format!(
+                "context not found for type `{}` in scope `{}`, available contexts: {}",
+                type_name::<T>(),
+                self.debug_path(),
+                available
+            )
+        }
+        #[cfg(not(debug_assertions))]
+        alloc::format!("context not found for type `{}`", type_name::<T>())
+    }
+
+    /// Returns the type names of the contexts of this scope and of its parent scopes, nearest
+    /// scope first and sorted by name within a scope. A type that is provided several times is
+    /// only listed once.
+    #[cfg(any(debug_assertions, feature = "debug-tools"))]
+    pub(crate) fn context_type_names(&self) -> Vec<&'static str> {
+        let mut names = Vec::new();
+        let mut this = Some(self);
+        while let Some(current) = this {
+            let contexts = current.contexts.borrow();
+            let start = names.len();
+            for (type_id, entry) in contexts.iter().flat_map(|contexts| contexts.iter()) {
+                #[cfg(feature = "debug-tools")]
+                if *type_id == TypeId::of::<debug::ScopeName>() {
+                    continue;
+                }
+                let _ = type_id;
+                if !names.contains(&entry.type_name) {
+                    names.push(entry.type_name);
+                }
+            }
+            names[start..].sort_unstable();
+            // SAFETY: `current.parent` necessarily lives longer than `current`.
+            this = current.parent.map(|x| unsafe { &*x });
+        }
+        names
     }
 
     /// Returns the current depth of the scope. If the scope is the root scope, returns `0`.
@@ -66,6 +232,62 @@ impl<'a> Scope<'a> {
     }
 }
 
+/// A reference that is provided with [`Scope::provide_context_ref`].
+struct ContextRef<T>(*const T);
+
+/// Gets a clone of the context value of the given type from the active scope, i.e. the scope
+/// whose body or effect is currently running. If there is no active scope or no context with the
+/// right type is found, returns `None`.
+///
+/// This is useful for hooks that would otherwise need to take a [`ScopeRef`] just to look up a
+/// context. See also [`Scope::try_use_context`].
+pub fn try_use_context<T: Clone + 'static>() -> Option<T> {
+    with_active_scope(|scope| scope?.try_use_context::<T>().cloned())
+}
+
+/// Gets a clone of the context value of the given type from the active scope, i.e. the scope
+/// whose body or effect is currently running.
+///
+/// # Panics
+/// This function panics if there is no active scope or if the context cannot be found in the
+/// scope hierarchy. For a non-panicking version, see [`try_use_context`].
+///
+/// # Example
+/// ```
+/// # use sycamore_reactive::*;
+/// #[derive(Clone)]
+/// struct Theme(&'static str);
+///
+/// fn use_theme() -> Theme {
+///     use_context::<Theme>()
+/// }
+///
+/// create_scope_immediate(|ctx| {
+///     ctx.provide_context(Theme("dark"));
+///     assert_eq!(use_theme().0, "dark");
+/// });
+/// ```
+#[track_caller]
+pub fn use_context<T: Clone + 'static>() -> T {
+    let value = with_active_scope(|scope| {
+        let scope = scope?;
+        Some(
+            scope
+                .try_use_context::<T>()
+                .cloned()
+                .ok_or_else(|| scope.missing_context_message::<T>()),
+        )
+    });
+    match value {
+        Some(Ok(value)) => value,
+        Some(Err(message)) => panic!("{message}"),
+        None => panic!(
+            "no active scope to get the context of type `{}` from",
+            type_name::<T>()
+        ),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -89,4 +311,79 @@ mod tests {
             });
         });
     }
+
+    #[test]
+    fn context_from_active_scope() {
+        assert_eq!(try_use_context::<i32>(), None);
+        create_scope_immediate(|ctx| {
+            ctx.provide_context(1i32);
+            assert_eq!(use_context::<i32>(), 1);
+            let _ = ctx.create_child_scope(|ctx| {
+                ctx.provide_context(2i32);
+                assert_eq!(use_context::<i32>(), 2);
+            });
+            // The parent scope is active again once the child scope body has run.
+            assert_eq!(use_context::<i32>(), 1);
+
+            let trigger = ctx.create_signal(());
+            let seen = ctx.create_signal(Vec::new());
+            let _ = ctx.create_child_scope(|ctx| {
+                ctx.provide_context(3i32);
+                ctx.create_effect(|| {
+                    trigger.track();
+                    seen.set([seen.get_untracked().as_slice(), &[use_context::<i32>()]].concat());
+                });
+            });
+            // Effects that are rerun later see the context of their own scope.
+            trigger.set(());
+            assert_eq!(*seen.get(), [3, 3]);
+        });
+        assert_eq!(try_use_context::<i32>(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "context not found for type `u8`")]
+    fn missing_context_message() {
+        create_scope_immediate(|ctx| {
+            ctx.use_context::<u8>();
+        });
+    }
+
+    #[test]
+    #[cfg(all(debug_assertions, feature = "debug-tools"))]
+    #[should_panic(
+        expected = "context not found for type `u8` in scope `app > _ > row`, available contexts: `&str`, `i32`"
+    )]
+    fn missing_context_message_shows_scope_path() {
+        create_scope_immediate(|ctx| {
+            ctx.set_debug_name("app");
+            ctx.provide_context(1i32);
+            let _ = ctx.create_child_scope(|ctx| {
+                ctx.provide_context("theme");
+                let _ = ctx.create_child_scope(|ctx| {
+                    ctx.set_debug_name("row");
+                    ctx.use_context::<u8>();
+                });
+            });
+        });
+    }
+
+    #[test]
+    fn context_ref_is_separate_from_value() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(1);
+            ctx.provide_context_ref(state);
+            ctx.provide_context(2i32);
+            let _ = ctx.create_child_scope(|ctx| {
+                let inner = ctx.create_signal(3);
+                ctx.provide_context_ref(inner);
+                let _ = ctx.create_child_scope(|ctx| {
+                    assert_eq!(*ctx.use_context_ref::<Signal<i32>>().get(), 3);
+                });
+            });
+            assert!(core::ptr::eq(ctx.use_context_ref::<Signal<i32>>(), state));
+            assert_eq!(*ctx.use_context::<i32>(), 2);
+            assert!(ctx.try_use_context_ref::<i32>().is_none());
+        });
+    }
 }
@@ -0,0 +1,606 @@
+//! Diagnostic tools for debugging reactive code.
+//!
+//! The diagnostic machinery is only compiled in when the `debug-tools` feature is enabled.
+//! Otherwise, everything in this module is a no-op stub that is inlined away so that release
+//! builds do not pay for it.
+
+#[cfg(feature = "debug-tools")]
+use core::fmt::Write;
+
+#[cfg(feature = "debug-tools")]
+use smallvec::SmallVec;
+
+use crate::*;
+
+#[cfg(feature = "debug-tools")]
+thread_local! {
+    /// Names given to signals with [`ReadSignal::set_debug_name`].
+    static SIGNAL_NAMES: RefCell<HashMap<SignalId, String>> = Default::default();
+    /// The trace that is being recorded, if any. See [`start_trace`].
+    static TRACE: RefCell<Option<Trace>> = Default::default();
+    /// See [`Scope::reactive_stats`].
+    static STATS: RefCell<Stats> = Default::default();
+    /// The origin of the timestamps returned by [`now`].
+    #[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+    static ORIGIN: std::time::Instant = std::time::Instant::now();
+}
+
+/// Returns the current time in microseconds.
+#[cfg(all(feature = "debug-tools", feature = "web", target_arch = "wasm32"))]
+fn now() -> f64 {
+    web_sys::window()
+        .and_then(|window| window.performance())
+        .map_or(0.0, |performance| performance.now() * 1000.0)
+}
+
+/// Returns the current time in microseconds.
+#[cfg(all(feature = "debug-tools", feature = "std", not(target_arch = "wasm32")))]
+fn now() -> f64 {
+    ORIGIN.with(|origin| origin.elapsed().as_secs_f64() * 1_000_000.0)
+}
+
+/// Without a clock, no time is measured.
+#[cfg(all(
+    feature = "debug-tools",
+    not(all(feature = "web", target_arch = "wasm32")),
+    not(all(feature = "std", not(target_arch = "wasm32"))),
+))]
+fn now() -> f64 {
+    0.0
+}
+
+/// A trace of the runs of memos and effects that is being recorded.
+#[cfg(feature = "debug-tools")]
+#[derive(Default)]
+struct Trace {
+    events: Vec<TraceEvent>,
+    /// The names of the signals that triggered each queued node since it last ran.
+    triggers: HashMap<EffectId, SmallVec<[String; 1]>>,
+}
+
+/// A run of a memo or effect.
+#[cfg(feature = "debug-tools")]
+struct TraceEvent {
+    node: EffectId,
+    kind: NodeKind,
+    start: f64,
+    duration: f64,
+    triggers: SmallVec<[String; 1]>,
+}
+
+/// The counters of the signals and nodes that are alive.
+#[cfg(feature = "debug-tools")]
+#[derive(Default)]
+struct Stats {
+    signals: HashMap<SignalId, (u64, u64)>,
+    nodes: HashMap<EffectId, (NodeKind, u64, f64)>,
+}
+
+/// Starts recording the runs of memos and effects on the current thread. The trace is retrieved
+/// with [`finish_trace`]. Starting a new trace discards the trace that is being recorded.
+///
+/// Does nothing unless the `debug-tools` feature is enabled.
+#[inline]
+pub fn start_trace() {
+    #[cfg(feature = "debug-tools")]
+    TRACE.with(|trace| *trace.borrow_mut() = Some(Trace::default()));
+}
+
+/// Stops recording the trace that was started with [`start_trace`] and returns it in the
+/// [Chrome trace event format](https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU),
+/// which can be opened in Perfetto or in the performance panel of Chromium based browsers.
+///
+/// Each run of a memo or effect is a complete event with the id of the node and the names of the
+/// signals that triggered the run (see [`ReadSignal::set_debug_name`]). On `wasm32` with the `web`
+/// feature, timestamps are taken from `performance.now()` so that they line up with the frames of
+/// the browser. Without the `std` feature, no timestamps are recorded.
+///
+/// Always returns `None` unless the `debug-tools` feature is enabled and a trace was started.
+#[inline]
+pub fn finish_trace() -> Option<String> {
+    #[cfg(feature = "debug-tools")]
+    return TRACE.with(|trace| trace.borrow_mut().take()).map(|trace| {
+        let mut json = String::from("{\"traceEvents\":[");
+        for (i, event) in trace.events.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            let name = match event.kind {
+                NodeKind::Memo => "memo",
+                NodeKind::Effect => "effect",
+            };
+            let _ = write!(
+                json,
+                "{{\"name\":\"{name}\",\"cat\":\"reactive\",\"ph\":\"X\",\"ts\":{},\"dur\":{},\"pid\":1,\"tid\":1,\"args\":{{\"node\":\"{:?}\",\"triggers\":[",
+                event.start, event.duration, event.node,
+            );
+            for (i, trigger) in event.triggers.iter().enumerate() {
+                if i > 0 {
+                    json.push(',');
+                }
+                write_json_string(&mut json, trigger);
+            }
+            json.push_str("]}}");
+        }
+        json.push_str("]}");
+        json
+    });
+    #[cfg(not(feature = "debug-tools"))]
+    None
+}
+
+/// Writes `s` as a JSON string literal.
+#[cfg(feature = "debug-tools")]
+fn write_json_string(json: &mut String, s: &str) {
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            c if c.is_control() => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+/// Returns the name of the signal with the given id. See [`ReadSignal::set_debug_name`].
+///
+/// Always returns `None` unless the `debug-tools` feature is enabled.
+#[inline]
+pub(crate) fn signal_name(id: SignalId) -> Option<String> {
+    #[cfg(feature = "debug-tools")]
+    return SIGNAL_NAMES.with(|names| names.borrow().get(&id).cloned());
+    #[cfg(not(feature = "debug-tools"))]
+    {
+        let _ = id;
+        None
+    }
+}
+
+/// Called when the subscribers of the signal with the given id are triggered.
+#[inline]
+pub(crate) fn signal_triggered(signal: SignalId) {
+    #[cfg(feature = "debug-tools")]
+    STATS.with(|stats| stats.borrow_mut().signals.entry(signal).or_default().0 += 1);
+    #[cfg(not(feature = "debug-tools"))]
+    let _ = signal;
+}
+
+/// Called when the signal with the given id queues the node with the given id.
+#[inline]
+pub(crate) fn node_triggered(node: EffectId, signal: SignalId) {
+    #[cfg(feature = "debug-tools")]
+    STATS.with(|stats| stats.borrow_mut().signals.entry(signal).or_default().1 += 1);
+    #[cfg(feature = "debug-tools")]
+    TRACE.with(|trace| {
+        if let Some(trace) = trace.borrow_mut().as_mut() {
+            let name = SIGNAL_NAMES
+                .with(|names| names.borrow().get(&signal).cloned())
+                .unwrap_or_else(|| alloc::format!("{signal:?}"));
+            let triggers = trace.triggers.entry(node).or_default();
+            if !triggers.contains(&name) {
+                triggers.push(name);
+            }
+        }
+    });
+    #[cfg(not(feature = "debug-tools"))]
+    let _ = (node, signal);
+}
+
+/// Records a run of a node when dropped. See [`node_started`].
+pub(crate) struct NodeSpan {
+    #[cfg(feature = "debug-tools")]
+    node: EffectId,
+    #[cfg(feature = "debug-tools")]
+    kind: NodeKind,
+    #[cfg(feature = "debug-tools")]
+    start: f64,
+}
+
+/// Called when the node with the given id starts running. The run ends when the returned span is
+/// dropped.
+#[inline]
+pub(crate) fn node_started(node: EffectId, kind: NodeKind) -> NodeSpan {
+    #[cfg(feature = "debug-tools")]
+    return NodeSpan {
+        node,
+        kind,
+        start: now(),
+    };
+    #[cfg(not(feature = "debug-tools"))]
+    {
+        let _ = (node, kind);
+        NodeSpan {}
+    }
+}
+
+#[cfg(feature = "debug-tools")]
+impl Drop for NodeSpan {
+    fn drop(&mut self) {
+        let duration = now() - self.start;
+        // The thread locals might already be destroyed if the node is run during thread teardown.
+        let _ = STATS.try_with(|stats| {
+            let mut stats = stats.borrow_mut();
+            let counters = stats.nodes.entry(self.node).or_insert((self.kind, 0, 0.0));
+            counters.1 += 1;
+            counters.2 += duration;
+        });
+        let _ = TRACE.try_with(|trace| {
+            if let Some(trace) = trace.borrow_mut().as_mut() {
+                let triggers = trace.triggers.remove(&self.node).unwrap_or_default();
+                trace.events.push(TraceEvent {
+                    node: self.node,
+                    kind: self.kind,
+                    start: self.start,
+                    duration,
+                    triggers,
+                });
+            }
+        });
+    }
+}
+
+/// Called when the node with the given id is destroyed to release its diagnostic data.
+#[inline]
+pub(crate) fn node_dropped(id: EffectId) {
+    #[cfg(feature = "debug-tools")]
+    let _ = STATS.try_with(|stats| stats.borrow_mut().nodes.remove(&id));
+    #[cfg(not(feature = "debug-tools"))]
+    let _ = id;
+}
+
+/// Statistics about a signal, see [`ReactiveStats`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignalStats {
+    /// The id of the signal.
+    pub id: SignalId,
+    /// The name of the signal, see [`ReadSignal::set_debug_name`].
+    pub name: Option<String>,
+    /// The number of times that the signal was updated, i.e. that its subscribers were triggered.
+    pub writes: u64,
+    /// The number of memos and effects that were queued by the updates of the signal.
+    pub notifications: u64,
+}
+
+/// Statistics about a memo or an effect, see [`ReactiveStats`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeStats {
+    /// The id of the node.
+    pub id: EffectId,
+    /// Whether the node is a memo (or another node that derives a signal) instead of an effect.
+    pub is_memo: bool,
+    /// The number of times that the node was run, including the initial run.
+    pub runs: u64,
+    /// The total time spent running the node, including the nodes that were run from inside of
+    /// it.
+    pub time: core::time::Duration,
+}
+
+/// A snapshot of the update counters of the signals and nodes of the current thread, returned by
+/// [`Scope::reactive_stats`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReactiveStats {
+    /// The signals that were updated at least once, in no particular order.
+    pub signals: Vec<SignalStats>,
+    /// The memos and effects that were run at least once, in no particular order.
+    pub nodes: Vec<NodeStats>,
+}
+
+impl ReactiveStats {
+    /// Returns the `n` signals that notified the most memos and effects, hottest first.
+    pub fn hottest_signals(&self, n: usize) -> Vec<&SignalStats> {
+        let mut signals: Vec<_> = self.signals.iter().collect();
+        signals.sort_by_key(|signal| core::cmp::Reverse((signal.notifications, signal.writes)));
+        signals.truncate(n);
+        signals
+    }
+
+    /// Returns the `n` memos and effects that spent the most time running, slowest first.
+    pub fn slowest_nodes(&self, n: usize) -> Vec<&NodeStats> {
+        let mut nodes: Vec<_> = self.nodes.iter().collect();
+        nodes.sort_by_key(|node| core::cmp::Reverse((node.time, node.runs)));
+        nodes.truncate(n);
+        nodes
+    }
+}
+
+impl<'a> Scope<'a> {
+    /// Returns the update counters of all the signals and nodes of the current thread that are
+    /// still alive. The counters are kept until [`reset_reactive_stats`](Self::reset_reactive_stats)
+    /// is called.
+    ///
+    /// Always returns empty statistics unless the `debug-tools` feature is enabled. The running
+    /// times are only measured with the `std` feature (or the `web` feature on `wasm32`).
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let count = ctx.create_signal(0);
+    /// count.set_debug_name("count");
+    /// ctx.create_effect(|| {
+    ///     count.track();
+    /// });
+    /// count.set(1);
+    ///
+    /// for signal in ctx.reactive_stats().hottest_signals(5) {
+    ///     println!("{:?}: {} notifications", signal.name, signal.notifications);
+    /// }
+    /// # });
+    /// ```
+    pub fn reactive_stats(&self) -> ReactiveStats {
+        #[cfg(feature = "debug-tools")]
+        return STATS.with(|stats| {
+            let stats = stats.borrow();
+            ReactiveStats {
+                signals: stats
+                    .signals
+                    .iter()
+                    .map(|(&id, &(writes, notifications))| SignalStats {
+                        id,
+                        name: SIGNAL_NAMES.with(|names| names.borrow().get(&id).cloned()),
+                        writes,
+                        notifications,
+                    })
+                    .collect(),
+                nodes: stats
+                    .nodes
+                    .iter()
+                    .map(|(&id, &(kind, runs, time))| NodeStats {
+                        id,
+                        is_memo: kind == NodeKind::Memo,
+                        runs,
+                        time: core::time::Duration::from_nanos((time * 1000.0) as u64),
+                    })
+                    .collect(),
+            }
+        });
+        #[cfg(not(feature = "debug-tools"))]
+        ReactiveStats::default()
+    }
+
+    /// Resets the update counters that are returned by [`reactive_stats`](Self::reactive_stats).
+    pub fn reset_reactive_stats(&self) {
+        #[cfg(feature = "debug-tools")]
+        STATS.with(|stats| *stats.borrow_mut() = Stats::default());
+    }
+}
+
+impl<T> ReadSignal<T> {
+    /// Sets a name for the signal that is shown by the diagnostic tools.
+    ///
+    /// Does nothing unless the `debug-tools` feature is enabled.
+    #[inline]
+    pub fn set_debug_name(&self, name: impl Into<String>) {
+        #[cfg(feature = "debug-tools")]
+        SIGNAL_NAMES.with(|names| names.borrow_mut().insert(self.id(), name.into()));
+        #[cfg(not(feature = "debug-tools"))]
+        let _ = name;
+    }
+
+    /// Returns the name that was set with [`set_debug_name`](Self::set_debug_name).
+    ///
+    /// Always returns `None` unless the `debug-tools` feature is enabled.
+    #[inline]
+    pub fn debug_name(&self) -> Option<String> {
+        signal_name(self.id())
+    }
+}
+
+/// The name of a scope, stored as a context of the scope. See [`Scope::set_debug_name`].
+#[cfg(feature = "debug-tools")]
+pub(crate) struct ScopeName(String);
+
+impl Scope<'_> {
+    /// Sets a name for the scope that is shown by the diagnostic tools, e.g. in the message of
+    /// [`use_context`](Self::use_context) when a context is missing.
+    ///
+    /// Does nothing unless the `debug-tools` feature is enabled.
+    #[inline]
+    pub fn set_debug_name(&self, name: impl Into<String>) {
+        #[cfg(feature = "debug-tools")]
+        if let Some(previous) = self.insert_context(ScopeName(name.into())) {
+            // SAFETY: The pointer was allocated in `insert_context` and is no longer owned by
+            // the scope.
+            drop(unsafe { Box::from_raw(previous.value) });
+        }
+        #[cfg(not(feature = "debug-tools"))]
+        let _ = name;
+    }
+
+    /// Returns the name that was set with [`set_debug_name`](Self::set_debug_name).
+    ///
+    /// Always returns `None` unless the `debug-tools` feature is enabled.
+    #[inline]
+    pub fn debug_name(&self) -> Option<String> {
+        #[cfg(feature = "debug-tools")]
+        return self.contexts.borrow().and_then(|contexts| {
+            let entry = contexts.get(&TypeId::of::<ScopeName>())?;
+            // SAFETY: The value is owned by the scope, which is borrowed.
+            let value = unsafe { &*entry.value };
+            Some(value.downcast_ref::<ScopeName>()?.0.clone())
+        });
+        #[cfg(not(feature = "debug-tools"))]
+        None
+    }
+
+    /// Returns the names of this scope and of its parent scopes, root first, joined by `" > "`.
+    /// Scopes without a name are shown as `_`.
+    #[cfg(any(debug_assertions, feature = "std"))]
+    pub(crate) fn debug_path(&self) -> String {
+        let mut path = Vec::new();
+        let mut this = Some(self);
+        while let Some(current) = this {
+            path.push(current.debug_name().unwrap_or_else(|| "_".into()));
+            // SAFETY: `current.parent` necessarily lives longer than `current`.
+            this = current.parent.map(|x| unsafe { &*x });
+        }
+        path.reverse();
+        path.join(" > ")
+    }
+
+    /// Returns the type names of the contexts that are provided on this scope and on its parent
+    /// scopes (see [`provide_context`](Self::provide_context)), nearest scope first and sorted by
+    /// name within a scope. A type that is provided several times is only listed once.
+    ///
+    /// Always returns an empty list unless the `debug-tools` feature is enabled.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// ctx.provide_context(42i32);
+    /// # #[cfg(feature = "debug-tools")]
+    /// assert_eq!(ctx.debug_contexts(), ["i32"]);
+    /// # });
+    /// ```
+    #[inline]
+    pub fn debug_contexts(&self) -> Vec<&'static str> {
+        #[cfg(feature = "debug-tools")]
+        return self.context_type_names();
+        #[cfg(not(feature = "debug-tools"))]
+        Vec::new()
+    }
+}
+
+/// Called when the signal with the given id is dropped to release its diagnostic data.
+#[inline]
+pub(crate) fn signal_dropped(id: SignalId) {
+    #[cfg(feature = "debug-tools")]
+    // The thread local might already be destroyed if the signal is dropped during thread
+    // teardown.
+    let _ = SIGNAL_NAMES.try_with(|names| names.borrow_mut().remove(&id));
+    #[cfg(feature = "debug-tools")]
+    let _ = STATS.try_with(|stats| stats.borrow_mut().signals.remove(&id));
+    #[cfg(not(feature = "debug-tools"))]
+    let _ = id;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "debug-tools")]
+    fn debug_contexts_lists_ancestors() {
+        create_scope_immediate(|ctx| {
+            ctx.set_debug_name("root");
+            ctx.provide_context(1i32);
+            let _ = ctx.create_child_scope(|ctx| {
+                ctx.provide_context(2i32);
+                ctx.provide_context(true);
+                assert_eq!(ctx.debug_contexts(), ["bool", "i32"]);
+            });
+        });
+    }
+
+    #[test]
+    #[cfg(feature = "debug-tools")]
+    fn debug_name() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(0);
+            assert_eq!(state.debug_name(), None);
+            state.set_debug_name("state");
+            assert_eq!(state.debug_name().as_deref(), Some("state"));
+        });
+    }
+
+    #[test]
+    #[cfg(feature = "debug-tools")]
+    fn debug_name_is_released_on_drop() {
+        let signal = create_rc_signal(0);
+        let id = signal.id();
+        signal.set_debug_name("state");
+        drop(signal);
+        assert!(SIGNAL_NAMES.with(|names| !names.borrow().contains_key(&id)));
+    }
+
+    #[test]
+    #[cfg(feature = "debug-tools")]
+    fn trace_records_runs_with_triggers() {
+        create_scope_immediate(|ctx| {
+            let count = ctx.create_signal(0);
+            count.set_debug_name("count \"a\"");
+            let double = ctx.create_memo(|| *count.get() * 2);
+            ctx.create_effect(|| {
+                double.track();
+            });
+
+            start_trace();
+            count.set(1);
+            let trace = finish_trace().unwrap();
+            assert!(trace.starts_with("{\"traceEvents\":[{\"name\":\"memo\""));
+            assert!(trace.contains("\"triggers\":[\"count \\\"a\\\"\"]"));
+            assert!(trace.contains("\"name\":\"effect\""));
+            assert_eq!(trace.matches("\"ph\":\"X\"").count(), 2);
+
+            // Nothing is recorded once the trace is finished.
+            count.set(2);
+            assert_eq!(finish_trace(), None);
+        });
+    }
+
+    #[test]
+    #[cfg(feature = "debug-tools")]
+    fn reactive_stats_counts_updates() {
+        create_scope_immediate(|ctx| {
+            let hot = ctx.create_signal(0);
+            hot.set_debug_name("hot");
+            let cold = ctx.create_signal(0);
+            let double = ctx.create_memo(|| *hot.get() * 2);
+            let effect = ctx.create_effect(|| {
+                double.track();
+                hot.track();
+                cold.track();
+            });
+
+            ctx.reset_reactive_stats();
+            hot.set(1);
+            hot.set(2);
+            cold.set(1);
+
+            let stats = ctx.reactive_stats();
+            let hottest = stats.hottest_signals(1);
+            assert_eq!(hottest[0].name.as_deref(), Some("hot"));
+            assert_eq!((hottest[0].writes, hottest[0].notifications), (2, 4));
+            let effect = stats.nodes.iter().find(|node| node.id == effect).unwrap();
+            assert!(!effect.is_memo);
+            assert_eq!(effect.runs, 3);
+            let memo = stats
+                .nodes
+                .iter()
+                .find(|node| node.id == double.id())
+                .unwrap();
+            assert!(memo.is_memo);
+            assert_eq!(memo.runs, 2);
+        });
+        // The counters are released with the signals and nodes.
+        create_scope_immediate(|ctx| assert_eq!(ctx.reactive_stats(), ReactiveStats::default()));
+    }
+
+    #[test]
+    #[cfg(not(feature = "debug-tools"))]
+    fn trace_is_noop() {
+        start_trace();
+        create_scope_immediate(|ctx| {
+            ctx.create_effect(|| {});
+            assert_eq!(ctx.reactive_stats(), ReactiveStats::default());
+        });
+        assert_eq!(finish_trace(), None);
+    }
+
+    #[test]
+    #[cfg(not(feature = "debug-tools"))]
+    fn debug_name_is_noop() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(0);
+            state.set_debug_name("state");
+            assert_eq!(state.debug_name(), None);
+            ctx.provide_context(0i32);
+            assert!(ctx.debug_contexts().is_empty());
+        });
+    }
+}
@@ -0,0 +1,173 @@
+//! Derived signals.
+
+use core::fmt;
+
+use crate::*;
+
+/// A signal that is derived from other signals by a function. Created with [`ReadSignal::map`],
+/// [`ReadSignal::zip`] and the other combinators of signals, or with [`Derived::new`].
+///
+/// Unlike a memo (see [`create_memo`](Scope::create_memo)), a derived signal is lazy: it does not
+/// keep its result and is not a node of the reactive graph, so it does not need a [`Scope`] and
+/// can be created from any signal, including a [`RcSignal`]. The function is called every time
+/// that the value is read and the signals that it uses are tracked by the caller, like with a
+/// plain closure. Use [`memo`](Self::memo) to only compute the value when one of the signals
+/// changes.
+///
+/// # Example
+/// ```
+/// # use sycamore_reactive::*;
+/// let count = create_rc_signal(1);
+/// let text = count.map(|&x| x * 2).map(|x| x.to_string());
+/// assert_eq!(text.get(), "2");
+///
+/// count.set(2);
+/// assert_eq!(text.get(), "4");
+/// ```
+#[derive(Clone, Copy)]
+pub struct Derived<F>(F);
+
+impl<U, F: Fn() -> U> Derived<F> {
+    /// Creates a derived signal from a function that reads other signals.
+    pub fn new(f: F) -> Self {
+        Self(f)
+    }
+
+    /// Computes the value of the derived signal. When called inside a reactive scope, the signals
+    /// that the value is derived from are added to the scope's dependencies.
+    #[must_use = "to only subscribe the signal without using the value, use .track() instead"]
+    pub fn get(&self) -> U {
+        (self.0)()
+    }
+
+    /// Computes the value of the derived signal without tracking the signals that it is derived
+    /// from.
+    #[must_use]
+    pub fn get_untracked(&self) -> U {
+        untrack(&self.0)
+    }
+
+    /// Adds the signals that the value is derived from to the dependencies of the current
+    /// reactive scope. This computes the value.
+    pub fn track(&self) {
+        let _ = self.get();
+    }
+
+    /// Creates a derived signal that maps the value of this derived signal with `f`.
+    #[must_use]
+    pub fn map<V>(self, f: impl Fn(&U) -> V) -> Derived<impl Fn() -> V> {
+        Derived(move || f(&(self.0)()))
+    }
+
+    /// Creates a derived signal that combines the values of this derived signal and `other` into
+    /// a tuple.
+    #[must_use]
+    pub fn zip<V>(self, other: Derived<impl Fn() -> V>) -> Derived<impl Fn() -> (U, V)> {
+        Derived(move || ((self.0)(), (other.0)()))
+    }
+
+    /// Flattens a derived signal of signals into a derived signal of the inner value, which
+    /// tracks both the outer and the current inner signal.
+    #[must_use]
+    pub fn flatten<T>(self) -> Derived<impl Fn() -> Rc<T>>
+    where
+        U: AsRef<ReadSignal<T>>,
+    {
+        Derived(move || (self.0)().as_ref().get())
+    }
+
+    /// Memoizes the derived signal in `ctx`. See [`create_memo`](Scope::create_memo).
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let state = ctx.create_signal(1);
+    /// let double = state.map(|&x| x * 2).memo(ctx);
+    /// assert_eq!(*double.get(), 2);
+    ///
+    /// state.set(2);
+    /// assert_eq!(*double.get(), 4);
+    /// # });
+    /// ```
+    pub fn memo<'a>(self, ctx: ScopeRef<'a>) -> &'a Memo<'a, U>
+    where
+        F: 'a,
+        U: 'a,
+    {
+        ctx.create_memo(self.0)
+    }
+}
+
+impl<'a, U, F: Fn() -> U> AnyReadSignal<'a> for Derived<F> {
+    fn track(&self) {
+        self.track();
+    }
+}
+
+impl<F> fmt::Debug for Derived<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Derived").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derived_is_recomputed_on_every_read() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(1);
+            let runs = Cell::new(0);
+            let double = state.map(|&x| {
+                runs.set(runs.get() + 1);
+                x * 2
+            });
+            assert_eq!(runs.get(), 0);
+            assert_eq!(double.get(), 2);
+            assert_eq!(double.get(), 2);
+            assert_eq!(runs.get(), 2);
+        });
+    }
+
+    #[test]
+    fn derived_is_tracked_by_caller() {
+        create_scope_immediate(|ctx| {
+            let a = ctx.create_signal(1);
+            let b = ctx.create_signal(2);
+            let sum = Derived::new(|| *a.get() + *b.get());
+            let result = ctx.create_signal(0);
+            ctx.create_effect(move || result.set(sum.get()));
+            assert_eq!(*result.get(), 3);
+
+            b.set(3);
+            assert_eq!(*result.get(), 4);
+            assert_eq!(sum.get_untracked(), 4);
+        });
+    }
+
+    #[test]
+    fn memoized_derived_is_disposed_with_scope() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(0);
+            let runs = Rc::new(Cell::new(0));
+            let disposer = ctx.create_child_scope(|ctx| {
+                let runs = Rc::clone(&runs);
+                let double = state
+                    .map(move |&x| {
+                        runs.set(runs.get() + 1);
+                        x * 2
+                    })
+                    .memo(*ctx);
+                assert_eq!(*double.get(), 0);
+            });
+            state.set(1);
+            assert_eq!(runs.get(), 2);
+
+            disposer();
+            state.set(2);
+            assert_eq!(runs.get(), 2);
+        });
+    }
+}
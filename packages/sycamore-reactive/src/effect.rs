@@ -1,58 +1,224 @@
 //! Side effects.
 
-use std::collections::HashSet;
+use crate::hash_map::Entry;
+use core::fmt;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use smallvec::SmallVec;
 
 use crate::*;
 
-thread_local! {
-    /// While the [`EffectState`] is inside the Vec, it is owned by [`EFFECTS`].
-    /// Because this is a global variable, the lifetime is necessarily `'static`. However, that does not mean
-    /// that it can last forever. The `EffectState` should only be used the time it is inside [`EFFECTS`].
-    pub(crate) static EFFECTS: RefCell<Vec<*mut EffectState<'static>>> = Default::default();
+/// Source of [`EffectId`]s.
+static NEXT_EFFECT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// An opaque identifier for an effect that is unique for the duration of the program.
+///
+/// Returned by [`Scope::create_effect`] and [`Scope::create_effect_scoped`]. Ids of newer effects
+/// compare greater than the ids of older effects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EffectId(u64);
+
+impl EffectId {
+    pub(crate) fn next() -> Self {
+        Self(NEXT_EFFECT_ID.fetch_add(1, Ordering::Relaxed))
+    }
 }
 
 /// The internal state of an effect. The effect callback and the effect dependencies are stored in
 /// this struct.
 pub(crate) struct EffectState<'a> {
+    /// Subscribers of a signal are called in the order of this id.
+    id: EffectId,
+    kind: NodeKind,
     /// The callback when the effect is re-executed.
     cb: Rc<RefCell<dyn FnMut() + 'a>>,
+    /// The runtime that the effect is run in.
+    runtime: Runtime,
+    /// The state of the node that is shared with its subscriptions.
+    node: Rc<NodeLinks>,
     /// A list of dependencies that can trigger this effect.
-    dependencies: HashSet<EffectDependency<'a>>,
+    dependencies: Dependencies,
+}
+
+/// The state of a node that is read when the signals that it depends on are triggered.
+#[derive(Default)]
+pub(crate) struct NodeLinks {
+    /// The topological rank of the node. See [`Subscribers::rank`].
+    pub rank: Cell<u32>,
+    /// The signals that a memo stores its value in or has written to. The nodes that depend on
+    /// them are marked as [`MaybeDirty`](NodeState::MaybeDirty) when a dependency of the memo
+    /// changes.
+    pub outputs: RefCell<SmallVec<[Weak<Subscribers>; 1]>>,
+}
+
+/// A [`SignalEmitter`] that an effect depends on.
+///
+/// Only a weak reference to the subscriber list is held because the signal can be dropped while
+/// the effect is running (e.g. if the signal was allocated on a child scope that is disposed by
+/// the effect). The weak reference also keeps the allocation alive so that the address of the
+/// subscriber list can be used to identify the emitter.
+pub(crate) struct EffectDependency {
+    emitter: Weak<Subscribers>,
+    /// The link from the emitter to the effect. This is `None` while the effect is running and
+    /// the dependency has only just been tracked. Dropping the subscription unsubscribes the
+    /// effect from the emitter.
+    subscription: Option<Box<Subscription>>,
+}
+
+/// Number of dependencies that are stored inline without a heap allocation. Most effects only
+/// track a handful of signals.
+const INLINE_DEPENDENCIES: usize = 4;
+/// Number of dependencies above which lookups go through a hash index instead of a linear scan.
+const LINEAR_SCAN_THRESHOLD: usize = 16;
+
+/// The set of dependencies of an effect, in the order they were first tracked.
+///
+/// Small sets are stored inline and deduplicated with a linear scan, which is faster than hashing
+/// for the common case. Once a set grows past [`LINEAR_SCAN_THRESHOLD`], a hash index is built
+/// and used for all further lookups.
+#[derive(Default)]
+pub(crate) struct Dependencies {
+    list: SmallVec<[EffectDependency; INLINE_DEPENDENCIES]>,
+    /// Maps emitters to their position in `list`. Only populated once `list` is longer than
+    /// [`LINEAR_SCAN_THRESHOLD`].
+    index: HashMap<*const Subscribers, usize>,
+}
+
+impl Dependencies {
+    /// Adds a dependency on `emitter` to the set if it is not already present.
+    fn insert(&mut self, emitter: Weak<Subscribers>) {
+        if self.list.len() < LINEAR_SCAN_THRESHOLD {
+            if self.position(&emitter).is_some() {
+                return;
+            }
+        } else {
+            if self.index.is_empty() {
+                self.index.extend(
+                    self.list
+                        .iter()
+                        .enumerate()
+                        .map(|(i, dependency)| (dependency.emitter.as_ptr(), i)),
+                );
+            }
+            match self.index.entry(emitter.as_ptr()) {
+                Entry::Occupied(_) => return,
+                Entry::Vacant(entry) => {
+                    entry.insert(self.list.len());
+                }
+            }
+        }
+        self.list.push(EffectDependency {
+            emitter,
+            subscription: None,
+        });
+    }
+
+    /// Returns the position of the dependency on `emitter` in the set.
+    fn position(&self, emitter: &Weak<Subscribers>) -> Option<usize> {
+        if self.index.is_empty() {
+            self.list
+                .iter()
+                .position(|dependency| dependency.emitter.ptr_eq(emitter))
+        } else {
+            self.index.get(&emitter.as_ptr()).copied()
+        }
+    }
 }
 
-/// Implements reference equality for [`AnySignal`]s.
-pub(crate) struct EffectDependency<'a>(&'a SignalEmitter);
-impl<'a> std::cmp::PartialEq for EffectDependency<'a> {
-    fn eq(&self, other: &Self) -> bool {
-        std::ptr::eq(self.0, other.0)
+impl IntoIterator for Dependencies {
+    type Item = EffectDependency;
+    type IntoIter = smallvec::IntoIter<[EffectDependency; INLINE_DEPENDENCIES]>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.list.into_iter()
     }
 }
-impl<'a> std::cmp::Eq for EffectDependency<'a> {}
-impl<'a> std::hash::Hash for EffectDependency<'a> {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        (self.0 as *const SignalEmitter).hash(state);
+
+impl Drop for EffectState<'_> {
+    fn drop(&mut self) {
+        scheduler::remove_order_hint(self.id);
+        debug::node_dropped(self.id);
     }
 }
 
 impl<'a> EffectState<'a> {
-    // Clears the dependencies (both links and backlinks).
-    /// Should be called when re-executing an effect to recreate all dependencies.
-    pub fn clear_dependencies(&mut self) {
-        for dependency in &self.dependencies {
-            // SAFETY: TODO
-            dependency
-                .0
-                .unsubscribe(unsafe { std::mem::transmute(Rc::as_ptr(&self.cb)) });
+    /// Add a dependency to the effect.
+    pub fn add_dependency(&mut self, signal: &SignalEmitter) {
+        let rank = self.node.rank.get().max(signal.0.rank.get() + 1);
+        self.node.rank.set(rank);
+        self.dependencies.insert(signal.downgrade());
+    }
+
+    /// Updates the backlinks from the dependencies to the effect after the effect has been
+    /// re-executed. Only the dependencies that were not tracked in the previous run are subscribed
+    /// and only the dependencies that are no longer tracked are unsubscribed. Dependencies that
+    /// were tracked in both runs keep their existing subscription.
+    fn update_subscriptions(&mut self, old_dependencies: Dependencies) {
+        for old in old_dependencies {
+            if let Some(i) = self.dependencies.position(&old.emitter) {
+                self.dependencies.list[i].subscription = old.subscription;
+            }
+            // Otherwise, the subscription is unlinked when `old` is dropped.
+        }
+        let cb = self.weak_callback();
+        for dependency in &mut self.dependencies.list {
+            if dependency.subscription.is_none() {
+                if let Some(subscribers) = dependency.emitter.upgrade() {
+                    // The callback is only called while the effect is alive because the
+                    // subscription is owned by the effect and is unlinked when dropped.
+                    dependency.subscription = Some(subscribers.subscribe(
+                        self.id,
+                        self.kind,
+                        cb.clone(),
+                        self.runtime.clone(),
+                        Rc::clone(&self.node),
+                    ));
+                }
+            }
         }
-        self.dependencies.clear();
     }
 
-    /// Add a dependency to the effect.
-    pub fn add_dependency(&mut self, signal: &'a SignalEmitter) {
-        self.dependencies.insert(EffectDependency(signal));
+    /// Returns a weak reference to the callback of the effect with the lifetime erased.
+    fn weak_callback(&self) -> WeakEffectCallback {
+        // SAFETY: The weak reference can no longer be upgraded once the effect is dropped.
+        unsafe {
+            core::mem::transmute::<Weak<RefCell<dyn FnMut() + 'a>>, WeakEffectCallback>(
+                Rc::downgrade(&self.cb),
+            )
+        }
     }
 }
 
+/// The callback of an effect created with [`create_effect_scoped`](Scope::create_effect_scoped).
+type ScopedEffectFn<'a> =
+    Box<dyn for<'child_lifetime> FnMut(BoundedScopeRef<'child_lifetime, 'a>) + 'a>;
+
+/// The callback of an effect created with
+/// [`create_effect_scoped_with_previous`](Scope::create_effect_scoped_with_previous).
+type ScopedEffectWithPreviousFn<'a, T> = Box<
+    dyn for<'child_lifetime> FnMut(
+            BoundedScopeRef<'child_lifetime, 'a>,
+            Option<PreviousScope<'a, T>>,
+        ) -> T
+        + 'a,
+>;
+
+/// When the first run of an effect happens. See [`create_effect_with`](Scope::create_effect_with).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InitialRun {
+    /// The effect is run right away when it is created, like with
+    /// [`create_effect`](Scope::create_effect).
+    #[default]
+    Immediate,
+    /// The first run of the effect is queued. If a scope is being constructed (i.e. inside the
+    /// closure passed to [`create_scope`] or
+    /// [`create_child_scope`](Scope::create_child_scope)), the effect is run once the outermost
+    /// scope that is being constructed is finished. Otherwise, it is run on the next flush, e.g.
+    /// at the end of the current [`batch`] or after the effect that is currently
+    /// running.
+    Deferred,
+}
+
 impl<'a> Scope<'a> {
     /// Creates an effect on signals used inside the effect closure.
     ///
@@ -69,14 +235,104 @@ impl<'a> Scope<'a> {
     /// state.set(1); // Prints "State changed. New state value = 1"
     /// # });
     /// ```
-    pub fn create_effect(&self, f: impl FnMut() + 'a) {
-        let f = Rc::new(RefCell::new(f));
+    pub fn create_effect(&'a self, f: impl FnMut() + 'a) -> EffectId {
+        self.create_node(NodeKind::Effect, f)
+    }
+
+    /// Creates an effect like [`create_effect`](Self::create_effect), with control over when the
+    /// effect is run for the first time. See [`InitialRun`].
+    ///
+    /// Deferring the first run is useful when a UI is built top-down, so that the effect only
+    /// observes the signals once all of them have been initialized instead of interleaving with
+    /// the construction. In an SSR scope (see [`create_ssr_scope`]), the
+    /// effect is always run right away.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// let disposer = create_scope(|ctx| {
+    ///     let name = ctx.create_signal("");
+    ///     ctx.create_effect_with(InitialRun::Deferred, || {
+    ///         println!("Hello {}!", name.get()); // Prints "Hello World!" once.
+    ///     });
+    ///     name.set("World");
+    /// });
+    /// # disposer();
+    /// ```
+    pub fn create_effect_with(&'a self, initial_run: InitialRun, f: impl FnMut() + 'a) -> EffectId {
+        self.create_node_dyn(NodeKind::Effect, initial_run, Box::new(f))
+            .0
+    }
+
+    /// Creates a node in the reactive graph that re-executes `f` whenever the signals used inside
+    /// `f` change. See [`NodeKind`].
+    pub(crate) fn create_node(&'a self, kind: NodeKind, f: impl FnMut() + 'a) -> EffectId {
+        self.create_node_dyn(kind, InitialRun::Immediate, Box::new(f))
+            .0
+    }
+
+    /// Like [`create_node`](Self::create_node) but also returns a weak reference to the callback
+    /// of the node so that it can be scheduled manually.
+    pub(crate) fn create_node_with_callback(
+        &'a self,
+        kind: NodeKind,
+        f: impl FnMut() + 'a,
+    ) -> (EffectId, WeakEffectCallback) {
+        self.create_node_dyn(kind, InitialRun::Immediate, Box::new(f))
+    }
+
+    /// The implementation of [`create_node`](Self::create_node). This is not generic so that it is
+    /// only compiled once instead of once for every closure type, which keeps the size of wasm
+    /// binaries down.
+    fn create_node_dyn(
+        &'a self,
+        kind: NodeKind,
+        initial_run: InitialRun,
+        mut f: Box<dyn FnMut() + 'a>,
+    ) -> (EffectId, WeakEffectCallback) {
+        let id = EffectId::next();
+        let error_handlers = ErrorHandlers::of(self);
+        if self.runtime.is_ssr() {
+            // Nothing can trigger the node again, so it is run once and no state is kept for it.
+            let result = self.enter(|| {
+                let _span = debug::node_started(id, kind);
+                catch_unwind(&mut f)
+            });
+            error_handlers.handle(result);
+            return (id, Weak::<RefCell<fn()>>::new());
+        }
+        let group = match kind {
+            NodeKind::Effect => self.try_use_context::<EffectGroup>().cloned(),
+            NodeKind::Memo => None,
+        };
 
         let effect = Rc::new(RefCell::new(None::<EffectState<'a>>));
         let cb = Rc::new(RefCell::new({
             let effect = Rc::downgrade(&effect);
             move || {
-                EFFECTS.with(|effects| {
+                // The node is not run if the maximum update depth is exceeded.
+                if let Some(error) = self.runtime.scheduler().take_depth_error() {
+                    error_handlers.handle_update_depth_exceeded(error);
+                    return;
+                }
+                // Effects of a paused group are run once the group is resumed, and
+                // transition-aware effects once the transition commits.
+                if let Some(group) = &group {
+                    let transition = self.runtime.scheduler().running_transition(id);
+                    let deferred = group.defer(id, transition.as_ref(), || {
+                        let effect = effect.upgrade().unwrap();
+                        let weak_callback = effect.borrow().as_ref().unwrap().weak_callback();
+                        weak_callback
+                    });
+                    if deferred {
+                        return;
+                    }
+                }
+                // The effect is run in its runtime so that it tracks its dependencies there, even
+                // if it was triggered from another runtime. The scope of the effect is the active
+                // scope while it runs.
+                let result = self.enter(|| {
+                    let effects = self.runtime.effects();
                     // Record initial effect stack length to verify that it is the same after.
                     let initial_effect_stack_len = effects.borrow().len();
                     // Upgrade the effect to an Rc now so that it is valid for the rest of the
@@ -85,7 +341,11 @@ impl<'a> Scope<'a> {
 
                     // Take effect out.
                     let mut effect = effect_ref.take().unwrap();
-                    effect.clear_dependencies();
+                    // Dependencies are recollected on each run. The previous dependencies are kept
+                    // around so that only the subscriptions that changed need to be updated.
+                    let old_dependencies = core::mem::take(&mut effect.dependencies);
+                    // The rank is recomputed from the dependencies that are tracked by this run.
+                    effect.node.rank.set(0);
 
                     // Push the effect onto the effect stack.
                     let boxed = Box::new(effect);
@@ -94,44 +354,62 @@ impl<'a> Scope<'a> {
                     effects
                         .borrow_mut()
                         .push(ptr as *mut () as *mut EffectState<'static>);
-                    // Now we can call the user-provided function.
-                    f.borrow_mut()();
+                    // Now we can call the user-provided function. A panic is caught so that the
+                    // effect stack and the effect state can be restored before it is handled.
+                    let result = {
+                        let _span = debug::node_started(id, kind);
+                        catch_unwind(&mut f)
+                    };
                     // Pop the effect from the effect stack.
-                    effects.borrow_mut().pop().unwrap();
+                    effects.borrow_mut().truncate(initial_effect_stack_len);
 
-                    //  SAFETY: Now that the effect has been popped from EFFECTS,
+                    //  SAFETY: Now that the effect has been popped from the effect stack,
                     // get a boxed EffectState with the correct lifetime back.
-                    let boxed = unsafe { Box::from_raw(ptr) };
-
-                    // For all the signals collected by the EffectState,
-                    // we need to add backlinks from the signal to the effect, so that
-                    // updating the signal will trigger the effect.
-                    for emitter in &boxed.dependencies {
-                        // SAFETY: TODO
-                        emitter
-                            .0
-                            .subscribe(unsafe { std::mem::transmute(Rc::downgrade(&boxed.cb)) });
-                    }
+                    let mut boxed = unsafe { Box::from_raw(ptr) };
+
+                    // For all the signals collected by the EffectState, we need to add backlinks
+                    // from the signal to the effect, so that updating the signal will trigger the
+                    // effect. Signals that are no longer tracked need to have their backlinks
+                    // removed.
+                    boxed.update_subscriptions(old_dependencies);
 
                     // Get the effect state back into the Rc
                     *effect_ref.borrow_mut() = Some(*boxed);
 
                     debug_assert_eq!(effects.borrow().len(), initial_effect_stack_len);
+                    result
                 });
+                error_handlers.handle(result);
             }
         }));
 
         // Initialize initial effect state.
         *effect.borrow_mut() = Some(EffectState {
+            id,
+            kind,
             cb: cb.clone(),
-            dependencies: HashSet::new(),
+            runtime: self.runtime.clone(),
+            node: Default::default(),
+            dependencies: Dependencies::default(),
         });
 
+        let weak_callback = effect.borrow().as_ref().unwrap().weak_callback();
+
         // Initial callback call to get everything started.
-        cb.borrow_mut()();
+        if initial_run == InitialRun::Immediate {
+            cb.borrow_mut()();
+        }
 
         // Push Rc to self.effects so that it is not dropped immediately.
         self.effects.borrow_mut().push(effect);
+
+        if initial_run == InitialRun::Deferred {
+            self.runtime
+                .scheduler()
+                .defer_initial_run(id, kind, weak_callback.clone());
+        }
+
+        (id, weak_callback)
     }
 
     /// Creates an effect on signals used inside the effect closure.
@@ -154,10 +432,16 @@ impl<'a> Scope<'a> {
     /// });
     /// # });
     /// ```
-    pub fn create_effect_scoped<F>(&'a self, mut f: F)
+    pub fn create_effect_scoped<F>(&'a self, f: F) -> EffectId
     where
         F: for<'child_lifetime> FnMut(BoundedScopeRef<'child_lifetime, 'a>) + 'a,
     {
+        self.create_effect_scoped_dyn(Box::new(f))
+    }
+
+    /// The implementation of [`create_effect_scoped`](Self::create_effect_scoped). See
+    /// [`create_node_dyn`](Self::create_node_dyn).
+    fn create_effect_scoped_dyn(&'a self, mut f: ScopedEffectFn<'a>) -> EffectId {
         let mut disposer: Option<Box<dyn FnOnce()>> = None;
         self.create_effect(move || {
             if let Some(disposer) = disposer.take() {
@@ -171,12 +455,149 @@ impl<'a> Scope<'a> {
                 Some(Box::new(self.create_child_scope(|ctx| {
                     // SAFETY: f takes the same parameter as the argument to
                     // self.create_child_scope(_).
-                    f(unsafe { std::mem::transmute(ctx) })
+                    f(unsafe { core::mem::transmute(ctx) })
                 })));
             // SAFETY: transmute the lifetime. This is safe because disposer is only used within the
             // effect which is necessarily within the lifetime of self (the Scope).
-            disposer = unsafe { std::mem::transmute(new_disposer) };
-        });
+            disposer = unsafe { core::mem::transmute(new_disposer) };
+        })
+    }
+
+    /// Creates an effect that calls `f` once, the first time that one of the signals that are
+    /// tracked inside `track` changes.
+    ///
+    /// `track` is called right away to collect the dependencies. Once `f` has been called, the
+    /// effect no longer depends on any signal, so the subscriptions are released and `f` is never
+    /// called again. `f` is called untracked.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let clicks = ctx.create_signal(0);
+    /// let initialized = ctx.create_signal(false);
+    /// ctx.create_effect_once(|| clicks.track(), || initialized.set(true));
+    /// assert!(!*initialized.get());
+    ///
+    /// clicks.set(1);
+    /// assert!(*initialized.get());
+    /// # });
+    /// ```
+    pub fn create_effect_once(
+        &'a self,
+        track: impl FnOnce() + 'a,
+        f: impl FnOnce() + 'a,
+    ) -> EffectId {
+        self.create_effect_once_dyn(Box::new(track), Box::new(f))
+    }
+
+    /// The implementation of [`create_effect_once`](Self::create_effect_once). See
+    /// [`create_node_dyn`](Self::create_node_dyn).
+    fn create_effect_once_dyn(
+        &'a self,
+        track: Box<dyn FnOnce() + 'a>,
+        f: Box<dyn FnOnce() + 'a>,
+    ) -> EffectId {
+        let mut track = Some(track);
+        let mut f = Some(f);
+        self.create_effect(move || {
+            if let Some(track) = track.take() {
+                track();
+            } else if let Some(f) = f.take() {
+                untrack(f);
+            }
+        })
+    }
+
+    /// Creates an effect with a new child scope for each run, like
+    /// [`create_effect_scoped`](Self::create_effect_scoped), except that the child scope of the
+    /// previous run is not disposed right away.
+    ///
+    /// Instead, the value that was returned by the previous run is passed to `f` along with the
+    /// child scope of that run as a [`PreviousScope`]. The previous child scope is only disposed
+    /// once the [`PreviousScope`] is finished or dropped. This makes it possible to keep the old
+    /// content alive, e.g. for an exit animation, while the new content is created.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let page = ctx.create_signal("home");
+    /// let leaving = ctx.create_signal(None);
+    /// ctx.create_effect_scoped_with_previous(|ctx, previous| {
+    ///     let page = *page.get();
+    ///     ctx.on_cleanup(move || println!("{page} disposed"));
+    ///     // Keep the previous page until its exit animation is done.
+    ///     leaving.set(previous);
+    ///     page
+    /// });
+    ///
+    /// page.set("settings");
+    /// assert_eq!(leaving.get().as_ref().as_ref().map(|previous| *previous.value()), Some("home"));
+    /// leaving.set(None); // Prints "home disposed"
+    /// # });
+    /// ```
+    pub fn create_effect_scoped_with_previous<T: 'a, F>(&'a self, f: F) -> EffectId
+    where
+        F: for<'child_lifetime> FnMut(
+                BoundedScopeRef<'child_lifetime, 'a>,
+                Option<PreviousScope<'a, T>>,
+            ) -> T
+            + 'a,
+    {
+        self.create_effect_scoped_with_previous_dyn(Box::new(f))
+    }
+
+    /// The implementation of
+    /// [`create_effect_scoped_with_previous`](Self::create_effect_scoped_with_previous). See
+    /// [`create_node_dyn`](Self::create_node_dyn).
+    fn create_effect_scoped_with_previous_dyn<T: 'a>(
+        &'a self,
+        mut f: ScopedEffectWithPreviousFn<'a, T>,
+    ) -> EffectId {
+        let mut previous: Option<PreviousScope<'a, T>> = None;
+        self.create_effect(move || {
+            let mut value = None;
+            let previous_scope = previous.take();
+            let handle = self.create_detached_child_scope(|ctx| {
+                value = Some(f(ctx, previous_scope));
+            });
+            previous = Some(PreviousScope {
+                value: value.unwrap(),
+                handle,
+            });
+        })
+    }
+}
+
+/// The child scope of the previous run of an effect created with
+/// [`create_effect_scoped_with_previous`](Scope::create_effect_scoped_with_previous), along with
+/// the value returned by that run. The child scope is disposed when this is dropped.
+pub struct PreviousScope<'a, T> {
+    value: T,
+    handle: ScopeHandle<'a>,
+}
+
+impl<'a, T> PreviousScope<'a, T> {
+    /// Returns the value that was returned by the previous run.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// Disposes the previous child scope and returns the value that was returned by the previous
+    /// run. This is the same as dropping the [`PreviousScope`], except that the value is kept.
+    pub fn finish(self) -> T {
+        let Self { value, handle } = self;
+        handle.dispose();
+        value
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for PreviousScope<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PreviousScope")
+            .field("value", &self.value)
+            .finish()
     }
 }
 
@@ -200,12 +621,103 @@ impl<'a> Scope<'a> {
 /// # });
 /// ```
 pub fn untrack<T>(f: impl FnOnce() -> T) -> T {
-    EFFECTS.with(|effects| {
-        let tmp = effects.take();
-        let ret = f();
-        *effects.borrow_mut() = tmp;
-        ret
-    })
+    let mut f = Some(f);
+    let mut result = None;
+    untrack_dyn(&mut || result = Some(f.take().unwrap()()));
+    result.unwrap()
+}
+
+/// The implementation of [`untrack`]. This is not generic so that it is only compiled once
+/// instead of once for every closure type.
+fn untrack_dyn(f: &mut dyn FnMut()) {
+    /// Restores the effect stack when dropped, even if `f` panics.
+    struct RestoreOnDrop(Runtime, Vec<*mut EffectState<'static>>);
+    impl Drop for RestoreOnDrop {
+        fn drop(&mut self) {
+            *self.0.effects().borrow_mut() = core::mem::take(&mut self.1);
+        }
+    }
+
+    let runtime = Runtime::current();
+    let effects = runtime.effects().take();
+    let _guard = RestoreOnDrop(runtime, effects);
+    f()
+}
+
+/// Returns the rank of the memo that is currently running, or `0` if the innermost running node is
+/// not a memo. See [`Subscribers::rank`].
+pub(crate) fn running_memo_rank() -> u32 {
+    let runtime = Runtime::current();
+    let effects = runtime.effects().borrow();
+    match effects.last() {
+        // SAFETY: See guarantee on EffectState within the effect stack of the runtime.
+        Some(&last) => match unsafe { &*last } {
+            node if node.kind == NodeKind::Memo => node.node.rank.get(),
+            _ => 0,
+        },
+        None => 0,
+    }
+}
+
+/// Adds a signal to the outputs of the memo that is currently running, if any. See
+/// [`NodeLinks::outputs`].
+pub(crate) fn add_running_memo_output(emitter: &SignalEmitter) {
+    let runtime = Runtime::current();
+    let effects = runtime.effects().borrow();
+    if let Some(&last) = effects.last() {
+        // SAFETY: See guarantee on EffectState within the effect stack of the runtime.
+        let last = unsafe { &*last };
+        if last.kind != NodeKind::Memo {
+            return;
+        }
+        let mut outputs = last.node.outputs.borrow_mut();
+        if !outputs
+            .iter()
+            .any(|output| output.as_ptr() == Rc::as_ptr(&emitter.0))
+        {
+            outputs.retain(|output| output.strong_count() > 0);
+            outputs.push(emitter.downgrade());
+        }
+    }
+}
+
+/// Runs `f` and returns the signals that were tracked inside of it. The signals are also tracked
+/// by the effect that is currently running, if any.
+pub(crate) fn collect_dependencies<T>(f: impl FnOnce() -> T) -> (T, Vec<Weak<Subscribers>>) {
+    /// Pops the collector from the effect stack when dropped, even if `f` panics.
+    struct PopOnDrop(Runtime, usize);
+    impl Drop for PopOnDrop {
+        fn drop(&mut self) {
+            self.0.effects().borrow_mut().truncate(self.1);
+        }
+    }
+
+    let runtime = Runtime::current();
+    let mut collector = EffectState {
+        id: EffectId::next(),
+        kind: NodeKind::Memo,
+        cb: Rc::new(RefCell::new(|| {})),
+        runtime: runtime.clone(),
+        node: Default::default(),
+        dependencies: Dependencies::default(),
+    };
+    let len = runtime.effects().borrow().len();
+    runtime
+        .effects()
+        .borrow_mut()
+        .push(&mut collector as *mut EffectState<'static>);
+    let guard = PopOnDrop(runtime, len);
+    let value = f();
+    drop(guard);
+
+    let dependencies: Vec<_> = core::mem::take(&mut collector.dependencies)
+        .into_iter()
+        .map(|dependency| dependency.emitter)
+        .collect();
+    for emitter in dependencies.iter().filter_map(Weak::upgrade) {
+        SignalEmitter(emitter).track();
+    }
+    (value, dependencies)
 }
 
 #[cfg(test)]
@@ -349,6 +861,28 @@ mod tests {
         });
     }
 
+    #[test]
+    fn effect_tracking_signal_on_disposed_scope() {
+        create_scope_immediate(|ctx| {
+            let trigger = ctx.create_signal(());
+            let counter = ctx.create_signal(0);
+            ctx.create_effect_scoped(|ctx| {
+                trigger.track();
+                counter.set(*counter.get_untracked() + 1);
+                // The signal is dropped when the effect is re-executed while it is still a
+                // dependency of the effect.
+                let inner = ctx.create_signal(0);
+                inner.track();
+            });
+            assert_eq!(*counter.get(), 1);
+
+            trigger.set(());
+            assert_eq!(*counter.get(), 2);
+            trigger.set(());
+            assert_eq!(*counter.get(), 3);
+        });
+    }
+
     #[test]
     fn destroy_effects_on_scope_dispose() {
         create_scope_immediate(|ctx| {
@@ -397,4 +931,138 @@ mod tests {
             );
         });
     }
+
+    #[test]
+    fn effect_with_many_dependencies() {
+        create_scope_immediate(|ctx| {
+            let signals = ctx.create_ref(
+                (0..LINEAR_SCAN_THRESHOLD * 2)
+                    .map(|_| ctx.create_signal(0))
+                    .collect::<Vec<_>>(),
+            );
+            let tracked = ctx.create_signal(signals.len());
+            let counter = ctx.create_signal(0);
+            ctx.create_effect(|| {
+                // Track every signal twice to check that dependencies are deduplicated.
+                for signal in &signals[..*tracked.get()] {
+                    signal.track();
+                    signal.track();
+                }
+                counter.set(*counter.get_untracked() + 1);
+            });
+            assert!(signals.iter().all(|s| s.emitter.0.len() == 1));
+
+            signals.last().unwrap().set(1);
+            assert_eq!(*counter.get(), 2);
+
+            tracked.set(1);
+            assert_eq!(*counter.get(), 3);
+            assert_eq!(signals[0].emitter.0.len(), 1);
+            assert!(signals[1..].iter().all(|s| s.emitter.0.len() == 0));
+        });
+    }
+
+    #[test]
+    fn effect_ids_are_unique_and_ordered() {
+        create_scope_immediate(|ctx| {
+            let a = ctx.create_effect(|| {});
+            let b = ctx.create_effect_scoped(|_| {});
+            assert!(a < b);
+        });
+    }
+
+    #[test]
+    fn effect_once_releases_subscriptions() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(0);
+            let other = ctx.create_signal(0);
+            let runs = ctx.create_signal(0);
+            ctx.create_effect_once(
+                || state.track(),
+                || {
+                    other.track();
+                    runs.set(*runs.get_untracked() + 1);
+                },
+            );
+            assert_eq!(*runs.get(), 0);
+            assert_eq!(state.emitter.0.len(), 1);
+
+            state.set(1);
+            assert_eq!(*runs.get(), 1);
+            assert_eq!(state.emitter.0.len(), 0);
+            assert_eq!(other.emitter.0.len(), 0);
+
+            state.set(2);
+            assert_eq!(*runs.get(), 1);
+        });
+    }
+
+    #[test]
+    fn effect_scoped_with_previous_defers_disposal() {
+        create_scope_immediate(|ctx| {
+            let generation = ctx.create_signal(0);
+            let disposed = ctx.create_signal(Vec::new());
+            let kept = ctx.create_signal(Vec::new());
+            ctx.create_effect_scoped_with_previous(move |ctx, previous| {
+                let generation = *generation.get();
+                ctx.on_cleanup(move || {
+                    disposed.set([disposed.get_untracked().as_slice(), &[generation]].concat());
+                });
+                if let Some(previous) = previous {
+                    if *previous.value() % 2 == 0 {
+                        // Even generations are kept until the next generation is disposed.
+                        ctx.create_ref(previous);
+                    } else {
+                        let value = previous.finish();
+                        kept.set([kept.get_untracked().as_slice(), &[value]].concat());
+                    }
+                }
+                generation
+            });
+
+            generation.set(1);
+            assert!(disposed.get().is_empty());
+            generation.set(2);
+            assert_eq!(*disposed.get(), [1, 0]);
+            assert_eq!(*kept.get(), [1]);
+        });
+    }
+
+    #[test]
+    fn deferred_initial_run() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let disposer = create_scope({
+            let log = log.clone();
+            move |ctx| {
+                let state = ctx.create_signal(0);
+                ctx.create_effect_with(InitialRun::Deferred, {
+                    let log = log.clone();
+                    move || log.borrow_mut().push(*state.get())
+                });
+                ctx.create_effect(move || log.borrow_mut().push(-*state.get()));
+                state.set(1);
+                assert_eq!(state.get_untracked().as_ref(), &1);
+            }
+        });
+        // The deferred effect only observes the value at the end of the construction.
+        assert_eq!(*log.borrow(), [0, -1, 1]);
+        disposer();
+    }
+
+    #[test]
+    fn deferred_initial_run_in_child_scope() {
+        let runs = Rc::new(Cell::new(0));
+        create_scope_immediate({
+            let runs = runs.clone();
+            move |ctx| {
+                let _ = ctx.create_child_scope(|ctx| {
+                    let runs = runs.clone();
+                    ctx.create_effect_with(InitialRun::Deferred, move || runs.set(runs.get() + 1));
+                });
+                // Deferred until the outermost scope is constructed.
+                assert_eq!(runs.get(), 0);
+            }
+        });
+        assert_eq!(runs.get(), 1);
+    }
 }
@@ -0,0 +1,239 @@
+//! Pausing and resuming a set of effects together.
+
+use alloc::collections::BTreeMap;
+use core::fmt;
+
+use crate::*;
+
+struct EffectGroupInner {
+    paused: Cell<bool>,
+    /// The nearest enclosing group. Pausing it also pauses the effects of this group.
+    parent: Option<EffectGroup>,
+    /// The runtime that the effects of the group are run in.
+    runtime: Runtime,
+    /// The effects that were triggered while the group was paused. They are run when the group is
+    /// resumed.
+    dirty: RefCell<BTreeMap<EffectId, WeakEffectCallback>>,
+}
+
+/// A set of effects that can be paused and resumed together, created with
+/// [`create_effect_group`](Scope::create_effect_group).
+///
+/// While the group is paused, its effects are not run when their dependencies change. Instead,
+/// they are marked as dirty and run once the group is resumed. This is useful for content that is
+/// hidden, such as an inactive tab, that should not do any work until it is displayed again.
+///
+/// Only effects are paused. Memos are still recomputed so that reading them always returns an
+/// up-to-date value.
+#[derive(Clone)]
+pub struct EffectGroup(Rc<EffectGroupInner>);
+
+impl EffectGroup {
+    /// Pauses the effects of the group. Effects that are currently queued are still run.
+    pub fn pause(&self) {
+        self.0.paused.set(true);
+    }
+
+    /// Resumes the effects of the group and runs the effects that were triggered while the group
+    /// was paused. Inside a [`batch`], they are run at the end of the batch.
+    pub fn resume(&self) {
+        self.0.paused.set(false);
+        self.run_deferred();
+    }
+
+    /// Runs the effects that were deferred in the group. Effects that are still paused (e.g. by
+    /// an enclosing group) are deferred again.
+    pub(crate) fn run_deferred(&self) {
+        let scheduler = self.0.runtime.scheduler();
+        // The effects are not part of the transition that is running, if any.
+        scheduler.with_transition(None, || {
+            for (id, cb) in self.0.dirty.take() {
+                scheduler.schedule(id, NodeKind::Effect, cb, None, 0, NodeState::Dirty);
+            }
+        });
+        scheduler.flush();
+    }
+
+    /// Returns `true` if the group or one of its enclosing groups is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused_group().is_some()
+    }
+
+    /// Returns the nearest group, starting from this group, that is paused.
+    fn paused_group(&self) -> Option<&EffectGroup> {
+        let mut this = Some(self);
+        while let Some(current) = this {
+            if current.0.paused.get() {
+                return Some(current);
+            }
+            this = current.0.parent.as_ref();
+        }
+        None
+    }
+
+    /// Returns the nearest group, starting from this group, in which the effect with the given `id`
+    /// waits for a transition to commit: the group of `transition` if the effect is
+    /// transition-aware, or the group in which the effect was deferred by a transition that has
+    /// not committed yet.
+    fn transition_group(
+        &self,
+        id: EffectId,
+        transition: Option<&Transition>,
+    ) -> Option<&EffectGroup> {
+        let mut this = Some(self);
+        while let Some(current) = this {
+            if current.0.dirty.borrow().contains_key(&id)
+                || transition.is_some_and(|transition| transition.defers(current, id))
+            {
+                return Some(current);
+            }
+            this = current.0.parent.as_ref();
+        }
+        None
+    }
+
+    /// Returns `true` if both handles refer to the same group.
+    pub(crate) fn ptr_eq(&self, other: &EffectGroup) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// Defers the effect with the given `id` until the group is resumed if the group is paused, or
+    /// if the effect is run by `transition` (see [`Transition`]). Returns `true` if the effect was
+    /// deferred.
+    pub(crate) fn defer(
+        &self,
+        id: EffectId,
+        transition: Option<&Transition>,
+        cb: impl FnOnce() -> WeakEffectCallback,
+    ) -> bool {
+        match self
+            .paused_group()
+            .or_else(|| self.transition_group(id, transition))
+        {
+            Some(group) => {
+                group.0.dirty.borrow_mut().insert(id, cb());
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl fmt::Debug for EffectGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EffectGroup")
+            .field("paused", &self.0.paused.get())
+            .field("dirty", &self.0.dirty.borrow().len())
+            .finish()
+    }
+}
+
+impl<'a> Scope<'a> {
+    /// Creates a new [`EffectGroup`] in the current [`Scope`]. Effects that are created
+    /// afterwards in this scope or in child scopes belong to the group, unless there is another
+    /// group in between. Pausing a group also pauses the groups inside of it.
+    ///
+    /// # Panics
+    /// This method panics if an effect group exists already in this scope.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let state = ctx.create_signal(0);
+    /// let runs = ctx.create_signal(0);
+    /// let group = ctx.create_effect_group();
+    /// ctx.create_effect(|| {
+    ///     state.track();
+    ///     runs.set(*runs.get_untracked() + 1);
+    /// });
+    ///
+    /// group.pause();
+    /// state.set(1);
+    /// state.set(2);
+    /// assert_eq!(*runs.get(), 1);
+    ///
+    /// group.resume();
+    /// assert_eq!(*runs.get(), 2);
+    /// # });
+    /// ```
+    pub fn create_effect_group(&'a self) -> EffectGroup {
+        let group = EffectGroup(Rc::new(EffectGroupInner {
+            paused: Cell::new(false),
+            parent: self.try_use_context::<EffectGroup>().cloned(),
+            runtime: self.runtime.clone(),
+            dirty: Default::default(),
+        }));
+        self.provide_context(group.clone());
+        group
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn effects_created_while_paused_run_on_resume() {
+        create_scope_immediate(|ctx| {
+            let runs = ctx.create_signal(0);
+            let group = ctx.create_effect_group();
+            group.pause();
+            ctx.create_effect(|| runs.set(*runs.get_untracked() + 1));
+            assert_eq!(*runs.get(), 0);
+            group.resume();
+            assert_eq!(*runs.get(), 1);
+            // Nothing is dirty anymore.
+            group.resume();
+            assert_eq!(*runs.get(), 1);
+        });
+    }
+
+    #[test]
+    fn only_effects_of_group_are_paused() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(0);
+            let outside = ctx.create_signal(0);
+            ctx.create_effect(|| outside.set(*state.get()));
+            let inside = ctx.create_signal(0);
+            let _ = ctx.create_child_scope(|ctx| {
+                let group = ctx.create_effect_group();
+                let double = ctx.create_memo(|| *state.get() * 2);
+                ctx.create_effect(|| inside.set(*double.get()));
+
+                group.pause();
+                state.set(1);
+                assert_eq!(*outside.get(), 1);
+                assert_eq!(*double.get(), 2);
+                assert_eq!(*inside.get(), 0);
+                group.resume();
+                assert_eq!(*inside.get(), 2);
+            });
+        });
+    }
+
+    #[test]
+    fn pausing_outer_group_pauses_inner_group() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(0);
+            let mirror = ctx.create_signal(0);
+            let outer = ctx.create_effect_group();
+            let _ = ctx.create_child_scope(|ctx| {
+                let inner = ctx.create_effect_group();
+                ctx.create_effect(|| mirror.set(*state.get()));
+
+                outer.pause();
+                assert!(inner.is_paused());
+                state.set(1);
+                assert_eq!(*mirror.get(), 0);
+
+                // The effect is still paused by the inner group.
+                inner.pause();
+                outer.resume();
+                assert_eq!(*mirror.get(), 0);
+                inner.resume();
+                assert_eq!(*mirror.get(), 1);
+            });
+        });
+    }
+}
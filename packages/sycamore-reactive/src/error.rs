@@ -0,0 +1,475 @@
+//! Catching panics in effects.
+
+use core::fmt;
+
+use crate::*;
+
+thread_local! {
+    /// Whether panics in effects are caught and routed to the nearest error boundary.
+    static CATCH_EFFECT_PANICS: Cell<bool> = const { Cell::new(true) };
+}
+
+/// Sets whether panics in effects and memos are caught and routed to the nearest error boundary
+/// (see [`create_error_boundary`](Scope::create_error_boundary)) or error hook (see
+/// [`set_error_hook`](Scope::set_error_hook)) on the current thread. Enabled by default.
+///
+/// When disabled, or when there is no error boundary or hook, the panic is propagated to the
+/// caller of [`Signal::set`] (or of [`create_effect`](Scope::create_effect) for the initial run).
+/// In both cases the reactive graph is left in a consistent state and the effect runs again once
+/// one of the signals it tracked before the panic is updated.
+///
+/// Note that panics can only be caught if the crate is compiled with `panic = "unwind"` and with
+/// the `std` feature.
+pub fn set_catch_effect_panics(catch: bool) {
+    CATCH_EFFECT_PANICS.with(|c| c.set(catch));
+}
+
+/// Installs a panic hook that also prints the path of the active scope (see
+/// [`Scope::set_debug_name`]), i.e. of the scope whose body or effect panicked, after calling the
+/// previous panic hook. The hook is only installed once per process. See [`create_root`].
+#[cfg(feature = "std")]
+pub(crate) fn install_panic_hook() {
+    static INSTALL: std::sync::Once = std::sync::Once::new();
+    INSTALL.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            previous(info);
+            if let Some(path) = with_active_scope(|scope| scope.map(|scope| scope.debug_path())) {
+                std::eprintln!("panicked in reactive scope `{path}`");
+            }
+        }));
+    });
+}
+
+/// The payload of a caught panic.
+type PanicPayload = Box<dyn Any + Send>;
+
+/// Calls `f`, catching panics if possible.
+#[cfg(feature = "std")]
+pub(crate) fn catch_unwind(f: impl FnOnce()) -> Result<(), PanicPayload> {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(f))
+}
+
+/// Calls `f`. Panics cannot be caught without `std`.
+#[cfg(not(feature = "std"))]
+pub(crate) fn catch_unwind(f: impl FnOnce()) -> Result<(), PanicPayload> {
+    f();
+    Ok(())
+}
+
+#[cfg(feature = "std")]
+fn resume_unwind(payload: PanicPayload) -> ! {
+    std::panic::resume_unwind(payload)
+}
+
+#[cfg(not(feature = "std"))]
+fn resume_unwind(_payload: PanicPayload) -> ! {
+    unreachable!("panics are never caught without the `std` feature")
+}
+
+/// A panic that was caught while running an effect or a memo.
+pub struct EffectPanic {
+    payload: PanicPayload,
+}
+
+impl EffectPanic {
+    /// Returns the panic message if the panic payload is a string, as is the case for
+    /// [`panic!`] with a message.
+    pub fn message(&self) -> Option<&str> {
+        if let Some(s) = self.payload.downcast_ref::<&str>() {
+            Some(s)
+        } else {
+            self.payload.downcast_ref::<String>().map(String::as_str)
+        }
+    }
+
+    /// Returns the panic payload.
+    pub fn payload(&self) -> &(dyn Any + Send) {
+        &*self.payload
+    }
+
+    /// Returns the error if the node was not run because the maximum update depth was exceeded
+    /// (see [`set_max_update_depth`]).
+    pub fn update_depth_exceeded(&self) -> Option<&UpdateDepthExceeded> {
+        self.payload.downcast_ref()
+    }
+}
+
+impl fmt::Display for EffectPanic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.message(), self.update_depth_exceeded()) {
+            (Some(message), _) => f.write_str(message),
+            (None, Some(error)) => error.fmt(f),
+            (None, None) => f.write_str("Box<dyn Any>"),
+        }
+    }
+}
+
+/// The error hook that is installed by [`create_root`]: prints the panics that are not caught by
+/// an error boundary to stderr.
+#[cfg(feature = "std")]
+pub(crate) fn report_uncaught_panic(panic: EffectPanic) {
+    std::eprintln!("uncaught panic in effect or memo: {panic}");
+}
+
+/// The error that is reported when a chain of updates, in which each effect updates a signal that
+/// triggers the next effect, is longer than the maximum update depth (see
+/// [`set_max_update_depth`]). This usually means that effects update each other in a cycle.
+#[derive(Debug, Clone)]
+pub struct UpdateDepthExceeded {
+    /// The maximum update depth that was exceeded.
+    pub max_depth: usize,
+    /// The last steps of the chain of updates, oldest first. The last step is the node that was
+    /// not run.
+    pub chain: Vec<UpdateStep>,
+}
+
+/// A step of the chain of updates of an [`UpdateDepthExceeded`] error: a memo or effect and the
+/// signal whose update triggered it.
+#[derive(Debug, Clone)]
+pub struct UpdateStep {
+    /// The signal that triggered the node, if any.
+    pub signal: Option<SignalId>,
+    /// The name of the signal (see [`ReadSignal::set_debug_name`]), if any.
+    pub signal_name: Option<String>,
+    /// The memo or effect that was triggered.
+    pub node: EffectId,
+    /// Whether the node is a memo rather than an effect.
+    pub is_memo: bool,
+}
+
+impl fmt::Display for UpdateDepthExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "maximum update depth of {} exceeded, effects probably update each other in a cycle",
+            self.max_depth
+        )?;
+        for (i, step) in self.chain.iter().enumerate() {
+            f.write_str(if i == 0 { ": " } else { " -> " })?;
+            match (&step.signal_name, step.signal) {
+                (Some(name), _) => write!(f, "signal `{name}` -> ")?,
+                (None, Some(id)) => write!(f, "signal {id:?} -> ")?,
+                (None, None) => {}
+            }
+            let kind = if step.is_memo { "memo" } else { "effect" };
+            write!(f, "{kind} {:?}", step.node)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UpdateDepthExceeded {}
+
+impl fmt::Debug for EffectPanic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EffectPanic")
+            .field("message", &self.message())
+            .finish_non_exhaustive()
+    }
+}
+
+/// The state of an error boundary, created with
+/// [`create_error_boundary`](Scope::create_error_boundary).
+///
+/// Renderers use this to display a fallback instead of a subtree in which an effect panicked.
+#[derive(Clone)]
+pub struct ErrorBoundary(RcSignal<Option<Rc<EffectPanic>>>);
+
+impl ErrorBoundary {
+    /// Returns the last panic that was caught by this boundary, if any. This method is reactive.
+    pub fn error(&self) -> Option<Rc<EffectPanic>> {
+        self.0.get().as_ref().clone()
+    }
+
+    /// Returns the last panic that was caught by this boundary without tracking it.
+    pub fn error_untracked(&self) -> Option<Rc<EffectPanic>> {
+        self.0.get_untracked().as_ref().clone()
+    }
+
+    /// Clears the caught panic, e.g. to retry rendering the subtree.
+    pub fn reset(&self) {
+        self.0.set(None);
+    }
+}
+
+impl fmt::Debug for ErrorBoundary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ErrorBoundary")
+            .field(&self.error_untracked())
+            .finish()
+    }
+}
+
+/// A handler for panics that are not caught by an error boundary, installed with
+/// [`set_error_hook`](Scope::set_error_hook).
+#[derive(Clone)]
+pub(crate) struct ErrorHook(Rc<dyn Fn(EffectPanic)>);
+
+/// The error boundary and error hook that handle panics in an effect.
+#[derive(Clone, Default)]
+pub(crate) struct ErrorHandlers {
+    boundary: Option<ErrorBoundary>,
+    hook: Option<ErrorHook>,
+}
+
+impl ErrorHandlers {
+    /// Returns the handlers for effects created in `ctx`.
+    pub fn of<'a>(ctx: &'a Scope<'a>) -> Self {
+        Self {
+            boundary: ctx.try_use_context::<ErrorBoundary>().cloned(),
+            hook: ctx.try_use_context::<ErrorHook>().cloned(),
+        }
+    }
+
+    /// Handles the result of running an effect. A panic is routed to the error boundary or else to
+    /// the error hook if possible and propagated otherwise.
+    pub fn handle(&self, result: Result<(), PanicPayload>) {
+        if let Err(payload) = result {
+            if !CATCH_EFFECT_PANICS.with(Cell::get) {
+                resume_unwind(payload);
+            }
+            let panic = EffectPanic { payload };
+            match (&self.boundary, &self.hook) {
+                (Some(boundary), _) => boundary.0.set(Some(Rc::new(panic))),
+                (None, Some(hook)) => hook.0(panic),
+                (None, None) => resume_unwind(panic.payload),
+            }
+        }
+    }
+
+    /// Reports that a node was not run because the maximum update depth was exceeded. The error is
+    /// routed to the error boundary or else to the error hook if possible and raised as a panic
+    /// otherwise.
+    pub fn handle_update_depth_exceeded(&self, error: UpdateDepthExceeded) {
+        if !CATCH_EFFECT_PANICS.with(Cell::get) {
+            panic!("{error}");
+        }
+        let into_panic = |error: UpdateDepthExceeded| EffectPanic {
+            payload: Box::new(error),
+        };
+        match (&self.boundary, &self.hook) {
+            (Some(boundary), _) => boundary.0.set(Some(Rc::new(into_panic(error)))),
+            (None, Some(hook)) => hook.0(into_panic(error)),
+            (None, None) => panic!("{error}"),
+        }
+    }
+}
+
+impl<'a> Scope<'a> {
+    /// Creates a new error boundary in the current [`Scope`]. Panics in effects and memos that are
+    /// created afterwards in this scope or in child scopes are caught and stored in the boundary,
+    /// unless there is another boundary in between.
+    ///
+    /// # Panics
+    /// This method panics if an error boundary exists already in this scope.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let boundary = ctx.create_error_boundary();
+    /// let state = ctx.create_signal(1);
+    /// ctx.create_effect(|| {
+    ///     if *state.get() == 0 {
+    ///         panic!("state is zero");
+    ///     }
+    /// });
+    ///
+    /// state.set(0);
+    /// assert_eq!(boundary.error().unwrap().message(), Some("state is zero"));
+    /// # });
+    /// ```
+    pub fn create_error_boundary(&'a self) -> ErrorBoundary {
+        let boundary = ErrorBoundary(create_rc_signal(None));
+        self.provide_context(boundary.clone());
+        boundary
+    }
+
+    /// Installs a handler that is called with the panics of effects and memos that are not caught
+    /// by an error boundary (see [`create_error_boundary`](Self::create_error_boundary)). Without
+    /// a handler, such panics are propagated to the code that updated the signal.
+    ///
+    /// The handler applies to effects and memos that are created afterwards in this scope or in
+    /// child scopes. It is usually installed on the root scope, e.g. so that a server can log
+    /// errors that happen during SSR instead of aborting the request.
+    ///
+    /// # Panics
+    /// This method panics if an error hook exists already in this scope.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// ctx.set_error_hook(|panic| eprintln!("effect failed: {:?}", panic.message()));
+    /// ctx.create_effect(|| panic!("oops")); // Logged instead of propagated.
+    /// # });
+    /// ```
+    pub fn set_error_hook(&'a self, f: impl Fn(EffectPanic) + 'static) {
+        self.provide_context(ErrorHook(Rc::new(f)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    use super::*;
+
+    #[test]
+    fn panic_is_routed_to_boundary() {
+        create_scope_immediate(|ctx| {
+            let boundary = ctx.create_error_boundary();
+            let state = ctx.create_signal(0);
+            let runs = ctx.create_signal(0);
+            ctx.create_effect(|| {
+                runs.set(*runs.get_untracked() + 1);
+                if *state.get() == 1 {
+                    panic!("effect panicked");
+                }
+            });
+            assert!(boundary.error().is_none());
+
+            state.set(1);
+            assert_eq!(boundary.error().unwrap().message(), Some("effect panicked"));
+
+            // The effect is still subscribed to `state`.
+            boundary.reset();
+            state.set(2);
+            assert_eq!(*runs.get(), 3);
+            assert!(boundary.error().is_none());
+        });
+    }
+
+    #[test]
+    fn nearest_boundary_catches() {
+        create_scope_immediate(|ctx| {
+            let outer = ctx.create_error_boundary();
+            let _ = ctx.create_child_scope(|ctx| {
+                let inner = ctx.create_error_boundary();
+                ctx.create_effect(|| panic!("inner"));
+                assert!(inner.error().is_some());
+                assert!(outer.error().is_none());
+            });
+        });
+    }
+
+    #[test]
+    fn panic_without_boundary_is_propagated_and_graph_recovers() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(0);
+            let double = ctx.create_memo(|| *state.get() * 2);
+            let log = ctx.create_signal(Vec::new());
+            ctx.create_effect(|| {
+                if *state.get() == 1 {
+                    panic!("effect panicked");
+                }
+                let mut next = (*log.get_untracked()).clone();
+                next.push(*double.get());
+                log.set(next);
+            });
+
+            let result = catch_unwind(AssertUnwindSafe(|| state.set(1)));
+            assert!(result.is_err());
+
+            // Signals are still tracked by the right effects after the panic.
+            state.set(2);
+            assert_eq!(*log.get(), [0, 4]);
+            let untracked = ctx.create_signal(0);
+            ctx.create_effect(|| {
+                untracked.track();
+            });
+            state.set(3);
+            assert_eq!(*log.get(), [0, 4, 6]);
+        });
+    }
+
+    #[test]
+    fn effect_panic_display() {
+        let panic = EffectPanic {
+            payload: Box::new("effect panicked"),
+        };
+        assert_eq!(panic.to_string(), "effect panicked");
+        let panic = EffectPanic {
+            payload: Box::new(UpdateDepthExceeded {
+                max_depth: 3,
+                chain: Vec::new(),
+            }),
+        };
+        assert_eq!(
+            panic.to_string(),
+            "maximum update depth of 3 exceeded, effects probably update each other in a cycle"
+        );
+        let panic = EffectPanic {
+            payload: Box::new(1),
+        };
+        assert_eq!(panic.to_string(), "Box<dyn Any>");
+    }
+
+    #[test]
+    fn error_hook_handles_uncaught_panics() {
+        let messages = Rc::new(RefCell::new(Vec::new()));
+        create_scope_immediate(|ctx| {
+            ctx.set_error_hook({
+                let messages = Rc::clone(&messages);
+                move |panic| {
+                    messages
+                        .borrow_mut()
+                        .push(panic.message().unwrap().to_string())
+                }
+            });
+            let state = ctx.create_signal(0);
+            ctx.create_effect(|| {
+                if *state.get() == 1 {
+                    panic!("uncaught");
+                }
+            });
+            let _ = ctx.create_child_scope(|ctx| {
+                let boundary = ctx.create_error_boundary();
+                ctx.create_effect(|| {
+                    if *state.get() == 1 {
+                        panic!("caught");
+                    }
+                });
+                state.set(1);
+                assert_eq!(
+                    boundary.error_untracked().unwrap().message(),
+                    Some("caught")
+                );
+            });
+        });
+        assert_eq!(*messages.borrow(), ["uncaught"]);
+    }
+
+    #[test]
+    fn disabled_catching_propagates() {
+        create_scope_immediate(|ctx| {
+            let boundary = ctx.create_error_boundary();
+            set_catch_effect_panics(false);
+            let result = catch_unwind(AssertUnwindSafe(|| {
+                ctx.create_effect(|| panic!("effect panicked"));
+            }));
+            set_catch_effect_panics(true);
+            assert!(result.is_err());
+            assert!(boundary.error().is_none());
+        });
+    }
+
+    #[test]
+    fn panic_inside_untrack_restores_stack() {
+        create_scope_immediate(|ctx| {
+            let boundary = ctx.create_error_boundary();
+            let state = ctx.create_signal(0);
+            let runs = ctx.create_signal(0);
+            ctx.create_effect(|| {
+                state.track();
+                runs.set(*runs.get_untracked() + 1);
+                untrack(|| panic!("untracked panic"));
+            });
+            assert!(boundary.error().is_some());
+            state.set(1);
+            assert_eq!(*runs.get(), 2);
+        });
+    }
+}
@@ -0,0 +1,210 @@
+//! Events that are emitted to listeners without being stored.
+
+use core::fmt;
+
+use smallvec::SmallVec;
+
+use crate::*;
+
+type Listener<T> = Box<dyn FnMut(&T)>;
+
+struct Listeners<T> {
+    next_id: Cell<u64>,
+    /// The listeners in the order in which they were added, identified by increasing ids. A
+    /// listener is `None` while it is being called.
+    list: RefCell<Vec<(u64, Option<Listener<T>>)>>,
+}
+
+impl<T> Listeners<T> {
+    fn position(&self, id: u64) -> Option<usize> {
+        self.list
+            .borrow()
+            .binary_search_by_key(&id, |(id, _)| *id)
+            .ok()
+    }
+
+    fn remove(&self, id: u64) {
+        let removed = self.position(id).map(|i| self.list.borrow_mut().remove(i));
+        // The listener is dropped after the list is released.
+        drop(removed);
+    }
+}
+
+/// An event that listeners can subscribe to, created with [`create_event`].
+///
+/// Unlike a [`Signal`], an event does not have a current value. Each emitted value is passed to
+/// the listeners that are subscribed at that time and then dropped. This is useful for things
+/// that happen rather than things that are, such as notifications or navigation requests.
+///
+/// Cloning the handle does not create a new event.
+pub struct Event<T>(Rc<Listeners<T>>);
+
+impl<T: 'static> Event<T> {
+    /// Calls `listener` with every value that is emitted until `ctx` is disposed.
+    pub fn on<'a>(&self, ctx: ScopeRef<'a>, listener: impl FnMut(&T) + 'a) {
+        let listener: Box<dyn FnMut(&T) + 'a> = Box::new(listener);
+        // SAFETY: The listener is removed before the scope is disposed, and it is never called
+        // after that.
+        let listener =
+            unsafe { core::mem::transmute::<Box<dyn FnMut(&T) + 'a>, Listener<T>>(listener) };
+        let id = self.0.next_id.get();
+        self.0.next_id.set(id + 1);
+        self.0.list.borrow_mut().push((id, Some(listener)));
+
+        let listeners = Rc::downgrade(&self.0);
+        ctx.on_cleanup(move || {
+            if let Some(listeners) = listeners.upgrade() {
+                listeners.remove(id);
+            }
+        });
+    }
+}
+
+impl<T> Event<T> {
+    /// Calls the listeners of the event with `value` in the order in which they were added.
+    ///
+    /// Listeners that are added while the event is emitted are only called for the following
+    /// values. The listeners are called untracked.
+    pub fn emit(&self, value: T) {
+        let ids: SmallVec<[u64; 4]> = self.0.list.borrow().iter().map(|(id, _)| *id).collect();
+        untrack(|| {
+            for id in ids {
+                let Some(i) = self.0.position(id) else {
+                    // The listener was removed by a previous listener.
+                    continue;
+                };
+                let listener = self.0.list.borrow_mut()[i].1.take();
+                // The listener is `None` if the event is emitted from inside the listener.
+                if let Some(mut listener) = listener {
+                    listener(&value);
+                    if let Some(i) = self.0.position(id) {
+                        self.0.list.borrow_mut()[i].1 = Some(listener);
+                    }
+                }
+            }
+        });
+    }
+
+    /// Returns the number of listeners of the event.
+    pub fn listener_count(&self) -> usize {
+        self.0.list.borrow().len()
+    }
+}
+
+impl<T> Clone for Event<T> {
+    fn clone(&self) -> Self {
+        Self(Rc::clone(&self.0))
+    }
+}
+
+impl<T> Default for Event<T> {
+    fn default() -> Self {
+        Self(Rc::new(Listeners {
+            next_id: Cell::new(0),
+            list: Default::default(),
+        }))
+    }
+}
+
+impl<T> fmt::Debug for Event<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Event")
+            .field("listeners", &self.listener_count())
+            .finish()
+    }
+}
+
+/// Creates a new [`Event`].
+///
+/// # Example
+/// ```
+/// # use sycamore_reactive::*;
+/// let toasts = create_event::<String>();
+/// create_scope_immediate(|ctx| {
+///     let shown = ctx.create_signal(Vec::new());
+///     toasts.on(ctx, |message| {
+///         shown.set([shown.get_untracked().as_slice(), &[message.clone()]].concat());
+///     });
+///
+///     toasts.emit("Saved".to_string());
+///     assert_eq!(*shown.get(), ["Saved"]);
+/// });
+/// // The listener was removed when the scope was disposed.
+/// assert_eq!(toasts.listener_count(), 0);
+/// ```
+pub fn create_event<T>() -> Event<T> {
+    Event::default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn listeners_are_called_once_in_order() {
+        let event = create_event::<i32>();
+        create_scope_immediate(|ctx| {
+            let calls = ctx.create_signal(Vec::new());
+            for listener in 0..3 {
+                event.on(ctx, move |value| {
+                    calls.set([calls.get_untracked().as_slice(), &[(listener, *value)]].concat());
+                });
+            }
+            event.emit(1);
+            assert_eq!(*calls.get(), [(0, 1), (1, 1), (2, 1)]);
+        });
+    }
+
+    #[test]
+    fn listeners_are_removed_with_their_scope() {
+        let event = create_event::<()>();
+        create_scope_immediate(|ctx| {
+            let calls = ctx.create_signal(0);
+            let disposer = ctx.create_child_scope(|ctx| {
+                event.on(&ctx, |_| calls.set(*calls.get_untracked() + 1));
+            });
+            event.on(ctx, |_| calls.set(*calls.get_untracked() + 1));
+            assert_eq!(event.listener_count(), 2);
+
+            disposer();
+            assert_eq!(event.listener_count(), 1);
+            event.emit(());
+            assert_eq!(*calls.get(), 1);
+        });
+        assert_eq!(event.listener_count(), 0);
+    }
+
+    #[test]
+    fn listener_added_while_emitting_is_not_called() {
+        let event = create_event::<i32>();
+        create_scope_immediate(|ctx| {
+            let calls = ctx.create_signal(0);
+            event.on(ctx, {
+                let event = event.clone();
+                move |_| event.on(ctx, |_| calls.set(*calls.get_untracked() + 1))
+            });
+            event.emit(0);
+            assert_eq!(*calls.get(), 0);
+            event.emit(0);
+            assert_eq!(*calls.get(), 1);
+        });
+    }
+
+    #[test]
+    fn listeners_are_untracked() {
+        create_scope_immediate(|ctx| {
+            let event = ctx.create_ref(create_event::<()>());
+            let state = ctx.create_signal(0);
+            let runs = ctx.create_signal(0);
+            event.on(ctx, |_| {
+                state.track();
+            });
+            ctx.create_effect(|| {
+                event.emit(());
+                runs.set(*runs.get_untracked() + 1);
+            });
+            state.set(1);
+            assert_eq!(*runs.get(), 1);
+        });
+    }
+}
@@ -0,0 +1,121 @@
+//! Equality functions for signals and selectors of floating point values.
+//!
+//! With [`PartialEq`], `NaN` is not equal to itself, so a signal that is set to `NaN` notifies its
+//! subscribers on every update, and values that only differ because of rounding errors (e.g. in
+//! animations) are considered to be different. These functions can be passed to
+//! [`create_signal_with_eq`](crate::Scope::create_signal_with_eq) or
+//! [`create_selector_with`](crate::Scope::create_selector_with) instead.
+
+/// Compares two `f64`s with [`f64::total_cmp`]. Unlike `==`, `NaN` is equal to itself, but `0.0`
+/// and `-0.0` are different.
+///
+/// # Example
+/// ```
+/// # use sycamore_reactive::*;
+/// # create_scope_immediate(|ctx| {
+/// let state = ctx.create_signal_with_eq(f64::NAN, f64_total_cmp_eq);
+/// let runs = ctx.create_signal(0);
+/// ctx.create_effect(|| {
+///     state.track();
+///     runs.set(*runs.get_untracked() + 1);
+/// });
+///
+/// state.set(f64::NAN);
+/// assert_eq!(*runs.get(), 1);
+/// # });
+/// ```
+pub fn f64_total_cmp_eq(a: &f64, b: &f64) -> bool {
+    a.total_cmp(b).is_eq()
+}
+
+/// Compares two `f32`s with [`f32::total_cmp`]. See [`f64_total_cmp_eq`].
+pub fn f32_total_cmp_eq(a: &f32, b: &f32) -> bool {
+    a.total_cmp(b).is_eq()
+}
+
+/// Returns a function that considers two `f64`s to be equal if they differ by at most `epsilon`.
+/// `NaN` is equal to itself and infinities are equal to themselves.
+///
+/// # Example
+/// ```
+/// # use sycamore_reactive::*;
+/// # create_scope_immediate(|ctx| {
+/// let progress = ctx.create_signal(0.0);
+/// let rounded = ctx.create_selector_with(|| *progress.get(), f64_approx_eq(0.01));
+///
+/// progress.set(0.001);
+/// assert_eq!(*rounded.get(), 0.0);
+///
+/// progress.set(0.5);
+/// assert_eq!(*rounded.get(), 0.5);
+/// # });
+/// ```
+pub fn f64_approx_eq(epsilon: f64) -> impl Fn(&f64, &f64) -> bool + Copy {
+    move |a, b| {
+        if a == b || (a.is_nan() && b.is_nan()) {
+            return true;
+        }
+        let diff = a - b;
+        diff <= epsilon && -diff <= epsilon
+    }
+}
+
+/// Returns a function that considers two `f32`s to be equal if they differ by at most `epsilon`.
+/// See [`f64_approx_eq`].
+pub fn f32_approx_eq(epsilon: f32) -> impl Fn(&f32, &f32) -> bool + Copy {
+    move |a, b| {
+        if a == b || (a.is_nan() && b.is_nan()) {
+            return true;
+        }
+        let diff = a - b;
+        diff <= epsilon && -diff <= epsilon
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+
+    #[test]
+    fn total_cmp_eq() {
+        assert!(f64_total_cmp_eq(&f64::NAN, &f64::NAN));
+        assert!(f64_total_cmp_eq(&1.0, &1.0));
+        assert!(!f64_total_cmp_eq(&0.0, &-0.0));
+        assert!(f32_total_cmp_eq(&f32::NAN, &f32::NAN));
+        assert!(!f32_total_cmp_eq(&1.0, &2.0));
+    }
+
+    #[test]
+    fn approx_eq() {
+        let eq = f64_approx_eq(0.1);
+        assert!(eq(&1.0, &1.05));
+        assert!(eq(&1.05, &1.0));
+        assert!(!eq(&1.0, &1.2));
+        assert!(eq(&f64::NAN, &f64::NAN));
+        assert!(!eq(&f64::NAN, &1.0));
+        assert!(eq(&f64::INFINITY, &f64::INFINITY));
+        assert!(!eq(&f64::INFINITY, &f64::NEG_INFINITY));
+
+        let eq = f32_approx_eq(0.1);
+        assert!(eq(&1.0, &1.05));
+        assert!(!eq(&1.0, &f32::NAN));
+    }
+
+    #[test]
+    fn signal_with_approx_eq() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal_with_eq(0.0, f64_approx_eq(0.001));
+            let runs = ctx.create_signal(0);
+            ctx.create_effect(|| {
+                state.track();
+                runs.set(*runs.get_untracked() + 1);
+            });
+
+            state.set(0.0001);
+            assert_eq!(*runs.get(), 1);
+            state.set(1.0);
+            assert_eq!(*runs.get(), 2);
+        });
+    }
+}
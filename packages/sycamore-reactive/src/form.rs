@@ -0,0 +1,336 @@
+//! Form state.
+
+use core::fmt;
+
+use crate::*;
+
+/// The state of a form, created with [`create_form`](Scope::create_form).
+///
+/// A form is a collection of [`Field`]s. It keeps track of whether the fields are valid and
+/// whether they were edited, and validates all of them when the form is submitted.
+pub struct Form<'a> {
+    ctx: ScopeRef<'a>,
+    fields: RefCell<Vec<&'a dyn AnyField>>,
+    /// Notified when a field is added.
+    fields_changed: SignalEmitter,
+}
+
+impl<'a> Form<'a> {
+    /// Adds a field without validation to the form. See [`field_with`](Self::field_with).
+    pub fn field<T: PartialEq + 'a>(&'a self, name: &'static str, initial: T) -> &'a Field<'a, T> {
+        self.field_with(name, initial, |_| Ok(()))
+    }
+
+    /// Adds a field to the form. `validate` returns the error message of a value, if any.
+    ///
+    /// `validate` is reactive: it is called again when the value changes, and also when the
+    /// signals that it uses change. Fields that depend on other fields (e.g. a password
+    /// confirmation) can simply read them.
+    pub fn field_with<T: PartialEq + 'a>(
+        &'a self,
+        name: &'static str,
+        initial: T,
+        validate: impl Fn(&T) -> Result<(), String> + 'a,
+    ) -> &'a Field<'a, T> {
+        let initial = Rc::new(initial);
+        let value = self.ctx.create_ref(Signal::new_rc(Rc::clone(&initial)));
+        let error = self.ctx.create_memo(move || validate(&value.get()).err());
+        let dirty = self.ctx.create_selector({
+            let initial = Rc::clone(&initial);
+            move || *value.get() != *initial
+        });
+        let field = self.ctx.create_ref(Field {
+            name,
+            initial,
+            value,
+            error,
+            dirty,
+            touched: self.ctx.create_signal(false),
+        });
+        self.fields.borrow_mut().push(field);
+        self.fields_changed.trigger_subscribers();
+        field
+    }
+
+    /// Returns `true` if none of the fields has an error. This method is reactive.
+    pub fn is_valid(&self) -> bool {
+        self.fields_changed.track();
+        self.fields
+            .borrow()
+            .iter()
+            .all(|field| field.error().is_none())
+    }
+
+    /// Returns `true` if any of the fields differs from its initial value. This method is
+    /// reactive.
+    pub fn is_dirty(&self) -> bool {
+        self.fields_changed.track();
+        self.fields.borrow().iter().any(|field| field.is_dirty())
+    }
+
+    /// Resets all the fields. See [`Field::reset`].
+    pub fn reset(&self) {
+        batch(|| self.fields.borrow().iter().for_each(|field| field.reset()));
+    }
+
+    /// Submits the form. All the fields are marked as touched so that their errors can be shown.
+    /// If no field has an error, `gather` is called to collect the values of the form. Otherwise,
+    /// the errors are returned in the order in which the fields were added.
+    ///
+    /// `gather` is called untracked.
+    pub fn submit<R>(&self, gather: impl FnOnce() -> R) -> Result<R, Vec<FieldError>> {
+        let fields = self.fields.borrow().clone();
+        batch(|| fields.iter().for_each(|field| field.touch()));
+        let errors: Vec<_> = untrack(|| {
+            fields
+                .iter()
+                .filter_map(|field| {
+                    Some(FieldError {
+                        field: field.name(),
+                        message: field.error()?,
+                    })
+                })
+                .collect()
+        });
+        if errors.is_empty() {
+            Ok(untrack(gather))
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl fmt::Debug for Form<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fields = self.fields.borrow();
+        f.debug_struct("Form")
+            .field(
+                "fields",
+                &fields.iter().map(|field| field.name()).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+/// A field of a [`Form`], created with [`Form::field`] or [`Form::field_with`].
+///
+/// The field dereferences to the [`Signal`] that holds its value.
+pub struct Field<'a, T> {
+    name: &'static str,
+    initial: Rc<T>,
+    value: &'a Signal<T>,
+    error: &'a ReadSignal<Option<String>>,
+    dirty: &'a ReadSignal<bool>,
+    touched: &'a Signal<bool>,
+}
+
+impl<'a, T> Field<'a, T> {
+    /// Returns the name of the field.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the signal that holds the value of the field.
+    pub fn value(&self) -> &'a Signal<T> {
+        self.value
+    }
+
+    /// Returns the error message of the current value, if any.
+    pub fn error(&self) -> &'a ReadSignal<Option<String>> {
+        self.error
+    }
+
+    /// Returns the error message of the current value once the field is touched. This is usually
+    /// what should be displayed, so that users are not shown errors before they had a chance to
+    /// fill in the field. This method is reactive.
+    pub fn visible_error(&self) -> Option<String> {
+        if *self.touched.get() {
+            (*self.error.get()).clone()
+        } else {
+            None
+        }
+    }
+
+    /// Returns a signal that is `true` if the value differs from the initial value.
+    pub fn is_dirty(&self) -> &'a ReadSignal<bool> {
+        self.dirty
+    }
+
+    /// Returns a signal that is `true` once the field is touched, i.e. once [`touch`](Self::touch)
+    /// is called (typically when the input loses focus) or the form is submitted.
+    pub fn is_touched(&self) -> &'a ReadSignal<bool> {
+        self.touched
+    }
+
+    /// Marks the field as touched.
+    pub fn touch(&self) {
+        if !*self.touched.get_untracked() {
+            self.touched.set(true);
+        }
+    }
+
+    /// Restores the initial value and marks the field as not touched.
+    pub fn reset(&self) {
+        batch(|| {
+            self.value.set_rc(Rc::clone(&self.initial));
+            self.touched.set(false);
+        });
+    }
+}
+
+impl<T> Deref for Field<'_, T> {
+    type Target = Signal<T>;
+
+    fn deref(&self) -> &Self::Target {
+        self.value
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Field<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Field")
+            .field("name", &self.name)
+            .field("value", &self.value.get_untracked())
+            .field("error", &self.error.get_untracked())
+            .finish()
+    }
+}
+
+/// The fields of a [`Form`] with their type erased.
+trait AnyField {
+    fn name(&self) -> &'static str;
+    fn error(&self) -> Option<String>;
+    fn is_dirty(&self) -> bool;
+    fn touch(&self);
+    fn reset(&self);
+}
+
+impl<T> AnyField for Field<'_, T> {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn error(&self) -> Option<String> {
+        (*self.error.get()).clone()
+    }
+
+    fn is_dirty(&self) -> bool {
+        *self.dirty.get()
+    }
+
+    fn touch(&self) {
+        Field::touch(self);
+    }
+
+    fn reset(&self) {
+        Field::reset(self);
+    }
+}
+
+/// The error of a field, returned by [`Form::submit`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    /// The name of the field.
+    pub field: &'static str,
+    /// The error message.
+    pub message: String,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+impl<'a> Scope<'a> {
+    /// Creates a new [`Form`]. The fields of the form are disposed with the scope.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let form = ctx.create_form();
+    /// let email = form.field_with("email", String::new(), |email| {
+    ///     if email.contains('@') {
+    ///         Ok(())
+    ///     } else {
+    ///         Err("invalid email address".to_string())
+    ///     }
+    /// });
+    /// let newsletter = form.field("newsletter", false);
+    ///
+    /// // Errors are only shown once the field is touched.
+    /// assert_eq!(email.visible_error(), None);
+    /// assert!(form.submit(|| ()).is_err());
+    /// assert_eq!(email.visible_error().as_deref(), Some("invalid email address"));
+    ///
+    /// email.set("me@example.com".to_string());
+    /// newsletter.set(true);
+    /// let values = form.submit(|| (email.get().to_string(), *newsletter.get()));
+    /// assert_eq!(values, Ok(("me@example.com".to_string(), true)));
+    /// # });
+    /// ```
+    pub fn create_form(&'a self) -> &'a Form<'a> {
+        self.create_ref(Form {
+            ctx: self,
+            fields: RefCell::new(Vec::new()),
+            fields_changed: SignalEmitter::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dirty_and_reset() {
+        create_scope_immediate(|ctx| {
+            let form = ctx.create_form();
+            let name = form.field("name", "Ada");
+            assert!(!form.is_dirty());
+
+            name.set("Grace");
+            assert!(*name.is_dirty().get());
+            assert!(form.is_dirty());
+            name.set("Ada");
+            assert!(!form.is_dirty());
+
+            name.set("Grace");
+            name.touch();
+            form.reset();
+            assert_eq!(*name.get(), "Ada");
+            assert!(!*name.is_touched().get());
+        });
+    }
+
+    #[test]
+    fn validation_is_reactive() {
+        create_scope_immediate(|ctx| {
+            let form = ctx.create_form();
+            let valid = ctx.create_memo(|| form.is_valid());
+            let password = form.field("password", String::from("secret"));
+            let confirm = form.field_with("confirm", String::new(), |confirm| {
+                if *confirm == *password.get() {
+                    Ok(())
+                } else {
+                    Err("passwords do not match".to_string())
+                }
+            });
+            // The memo is notified when fields are added.
+            assert!(!*valid.get());
+
+            confirm.set("secret".to_string());
+            assert!(*valid.get());
+            password.set("hunter2".to_string());
+            assert!(!*valid.get());
+            assert_eq!(
+                form.submit(|| ()),
+                Err(vec![FieldError {
+                    field: "confirm",
+                    message: "passwords do not match".to_string(),
+                }])
+            );
+        });
+    }
+}
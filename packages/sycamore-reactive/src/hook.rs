@@ -0,0 +1,214 @@
+//! Values that are cached per scope and call site, for building reusable hooks.
+//!
+//! A hook is a function that takes a [`ScopeRef`] and sets up some reactive state, e.g. a
+//! `use_local_storage` function that returns a signal that is persisted. With
+//! [`use_hook`](Scope::use_hook), the state of a hook is created the first time the hook is called
+//! on a scope and reused on later calls from the same call site, e.g. when an effect on the scope
+//! is re-executed. Hooks from different call sites never share state.
+
+use core::hash::Hash;
+use core::panic::Location;
+
+use crate::*;
+
+/// The values cached by [`Scope::use_hook`], keyed by call site and by the types of the key and
+/// the value. Each entry is a `HashMap<K, *const T>` of pointers into the arena of the scope.
+pub(crate) type HookMap = HashMap<(&'static Location<'static>, TypeId), Box<dyn Any>>;
+
+impl<'a> Scope<'a> {
+    /// Returns the value that was created by `init` the first time this method was called on this
+    /// scope from the same call site and with the same `key`, calling `init` if there is no such
+    /// value yet. The value lasts as long as the scope.
+    ///
+    /// The call site is determined with `#[track_caller]`, so hooks that are themselves marked
+    /// with `#[track_caller]` get separate state for each place where they are called. Use `()`
+    /// as the key if a call site only ever needs a single value, or a distinguishing key (e.g. an
+    /// index) if it is called in a loop.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// #[track_caller]
+    /// fn use_counter<'a>(ctx: ScopeRef<'a>) -> &'a Signal<i32> {
+    ///     ctx.use_hook((), || create_rc_signal(0))
+    /// }
+    ///
+    /// # create_scope_immediate(|ctx| {
+    /// let trigger = ctx.create_signal(());
+    /// let counts = ctx.create_signal(Vec::new());
+    /// ctx.create_effect(move || {
+    ///     trigger.track();
+    ///     // The same signal is returned every time the effect is run.
+    ///     let counter = use_counter(ctx);
+    ///     counter.set(*counter.get_untracked() + 1);
+    ///     counts.set([counts.get_untracked().as_slice(), &[*counter.get_untracked()]].concat());
+    /// });
+    /// trigger.set(());
+    /// assert_eq!(*counts.get(), [1, 2]);
+    /// # });
+    /// ```
+    #[track_caller]
+    pub fn use_hook<K, T>(&'a self, key: K, init: impl FnOnce() -> T) -> &'a T
+    where
+        K: Hash + Eq + 'static,
+        T: 'static,
+    {
+        self.use_hook_at(Location::caller(), key, || self.create_ref(init()))
+    }
+
+    /// Returns the memo that was created the first time this method was called on this scope from
+    /// the same call site and with the same `key`, creating it with `f` if there is no such memo
+    /// yet. See [`use_hook`](Self::use_hook).
+    ///
+    /// This is useful when memos are created for each item of a list (e.g. in the template of a
+    /// `Keyed` component) but some of them only depend on shared signals. Calling this method on
+    /// the outer scope instead of the scope of the item computes the shared value once for all
+    /// the items. Only the first `f` for a given call site and key is used.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let rows = ctx.create_signal(vec![1, 2, 3]);
+    /// let currency = ctx.create_signal("EUR");
+    /// let computed = ctx.create_signal(0);
+    /// let mapped = ctx.map_keyed(rows, move |_row_ctx, row| {
+    ///     // Computed once instead of once per row.
+    ///     let symbol = ctx.create_shared_memo((), || {
+    ///         computed.set(*computed.get_untracked() + 1);
+    ///         if *currency.get() == "EUR" { '€' } else { '$' }
+    ///     });
+    ///     format!("{row}{}", symbol.get())
+    /// }, |row| *row);
+    /// assert_eq!(*mapped.get(), ["1€", "2€", "3€"]);
+    /// assert_eq!(*computed.get(), 1);
+    /// # });
+    /// ```
+    #[track_caller]
+    pub fn create_shared_memo<K, U>(
+        &'a self,
+        key: K,
+        f: impl FnMut() -> U + 'a,
+    ) -> &'a ReadSignal<U>
+    where
+        K: Hash + Eq + 'static,
+        U: 'static,
+    {
+        self.use_hook_at(Location::caller(), key, || &**self.create_memo(f))
+    }
+
+    /// Implementation of [`use_hook`](Self::use_hook) for the given call site. `init` must return
+    /// a value that lives as long as the scope.
+    fn use_hook_at<K, T>(
+        &'a self,
+        location: &'static Location<'static>,
+        key: K,
+        init: impl FnOnce() -> &'a T,
+    ) -> &'a T
+    where
+        K: Hash + Eq + 'static,
+        T: 'static,
+    {
+        let slot = (location, TypeId::of::<(K, T)>());
+        let cached = self.hooks.borrow().and_then(|hooks| {
+            let values = hooks.get(&slot)?.downcast_ref::<HashMap<K, *const T>>();
+            values.unwrap().get(&key).copied()
+        });
+        if let Some(ptr) = cached {
+            // SAFETY: The value is allocated on the arena of this scope, so it lives as long as
+            // 'a.
+            return unsafe { &*ptr };
+        }
+
+        // The map is not borrowed while `init` runs because `init` can call other hooks.
+        let value = init();
+        self.hooks
+            .borrow_mut()
+            .entry(slot)
+            .or_insert_with(|| Box::new(HashMap::<K, *const T>::new()))
+            .downcast_mut::<HashMap<K, *const T>>()
+            .unwrap()
+            .insert(key, value);
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[track_caller]
+    fn use_counter<'a>(ctx: ScopeRef<'a>) -> &'a Signal<i32> {
+        ctx.use_hook((), || create_rc_signal(0))
+    }
+
+    #[test]
+    fn hook_state_is_reused() {
+        create_scope_immediate(|ctx| {
+            let mut counters = Vec::new();
+            for _ in 0..2 {
+                counters.push(use_counter(ctx) as *const _);
+            }
+            assert_eq!(counters[0], counters[1]);
+        });
+    }
+
+    #[test]
+    fn call_sites_and_keys_are_separate() {
+        create_scope_immediate(|ctx| {
+            let first = use_counter(ctx);
+            let second = use_counter(ctx);
+            assert!(!core::ptr::eq(first, second));
+
+            let a = ctx.use_hook("a", || 1);
+            let b = ctx.use_hook("b", || 2);
+            assert_eq!((*a, *b), (1, 2));
+        });
+    }
+
+    #[test]
+    fn shared_memo_is_computed_once() {
+        create_scope_immediate(|ctx| {
+            let factor = ctx.create_signal(2);
+            let runs = ctx.create_signal(0);
+            let rows = ctx.create_signal(vec![1, 2, 3]);
+            let mapped = ctx.map_keyed(
+                rows,
+                move |_, row| {
+                    let shared = ctx.create_shared_memo((), || {
+                        runs.set(*runs.get_untracked() + 1);
+                        *factor.get()
+                    });
+                    row * *shared.get()
+                },
+                |row| *row,
+            );
+            assert_eq!(*mapped.get(), [2, 4, 6]);
+            assert_eq!(*runs.get(), 1);
+
+            factor.set(3);
+            assert_eq!(*runs.get(), 2);
+            // New rows use the existing memo.
+            rows.set(vec![1, 2, 3, 4]);
+            assert_eq!(mapped.get()[3], 12);
+            assert_eq!(*runs.get(), 2);
+        });
+    }
+
+    #[test]
+    fn hook_state_is_per_scope() {
+        create_scope_immediate(|ctx| {
+            let values = ctx.create_signal(Vec::new());
+            let trigger = ctx.create_signal(());
+            ctx.create_effect_scoped(|ctx| {
+                trigger.track();
+                let value = use_counter(*ctx);
+                value.set(*value.get_untracked() + 1);
+                values.set([values.get_untracked().as_slice(), &[*value.get_untracked()]].concat());
+            });
+            trigger.set(());
+            // Each run of the effect has a new scope and therefore new hook state.
+            assert_eq!(*values.get(), [1, 1]);
+        });
+    }
+}
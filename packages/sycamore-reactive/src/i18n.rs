@@ -0,0 +1,297 @@
+//! Reactive formatting of messages for internationalization.
+
+use core::fmt::{self, Display, Write};
+
+use crate::*;
+
+/// Formats a message template with arguments. Implement this trait to integrate an i18n library
+/// (e.g. to look up the template in a translation catalog or to apply plural rules) and provide
+/// it with a [`Locale`]. See [`create_formatted`](Scope::create_formatted).
+pub trait MessageFormatter {
+    /// Formats `template` with `args` for `locale`.
+    fn format(&self, locale: &str, template: &str, args: &[Rc<dyn Display>]) -> String;
+}
+
+/// The [`MessageFormatter`] that is used when no other formatter is provided. It ignores the
+/// locale and replaces the placeholders in the template with the arguments:
+/// * `{0}`, `{1}`, ... are replaced with the argument at that index.
+/// * `{}` is replaced with the argument after the previous placeholder.
+/// * `{{` and `}}` are replaced with `{` and `}`.
+///
+/// Placeholders without a matching argument are left as is.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PositionalFormatter;
+
+impl MessageFormatter for PositionalFormatter {
+    fn format(&self, _locale: &str, template: &str, args: &[Rc<dyn Display>]) -> String {
+        let mut out = String::with_capacity(template.len());
+        let mut next = 0;
+        let mut rest = template;
+        while let Some(i) = rest.find(['{', '}']) {
+            out.push_str(&rest[..i]);
+            rest = &rest[i..];
+            if rest.starts_with("{{") || rest.starts_with("}}") {
+                out.push_str(&rest[..1]);
+                rest = &rest[2..];
+                continue;
+            }
+            let placeholder = rest
+                .find('}')
+                .filter(|_| rest.starts_with('{'))
+                .map(|end| &rest[1..end]);
+            let index = match placeholder {
+                Some("") => Some(next),
+                Some(index) => index.parse().ok(),
+                None => None,
+            };
+            match index.and_then(|index| args.get(index).map(|arg| (index, arg))) {
+                Some((index, arg)) => {
+                    let _ = write!(out, "{arg}");
+                    next = index + 1;
+                    rest = &rest[placeholder.unwrap().len() + 2..];
+                }
+                None => {
+                    out.push_str(&rest[..1]);
+                    rest = &rest[1..];
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+/// The current locale and the [`MessageFormatter`] of an app, provided as a context with
+/// [`provide_context`](Scope::provide_context). Messages created with
+/// [`create_formatted`](Scope::create_formatted) are formatted again when the locale is changed.
+#[derive(Clone)]
+pub struct Locale {
+    locale: RcSignal<String>,
+    formatter: Rc<dyn MessageFormatter>,
+}
+
+impl Locale {
+    /// Creates a locale that formats messages with the [`PositionalFormatter`].
+    pub fn new(locale: impl Into<String>) -> Self {
+        Self::with_formatter(locale, PositionalFormatter)
+    }
+
+    /// Creates a locale that formats messages with `formatter`.
+    pub fn with_formatter(
+        locale: impl Into<String>,
+        formatter: impl MessageFormatter + 'static,
+    ) -> Self {
+        Self {
+            locale: create_rc_signal(locale.into()),
+            formatter: Rc::new(formatter),
+        }
+    }
+
+    /// Returns the current locale. This method is reactive.
+    pub fn get(&self) -> Rc<String> {
+        self.locale.get()
+    }
+
+    /// Changes the locale.
+    pub fn set(&self, locale: impl Into<String>) {
+        self.locale.set(locale.into());
+    }
+
+    /// Formats `template` with `args` for the current locale. This method is reactive.
+    pub fn format(&self, template: &str, args: &[Rc<dyn Display>]) -> String {
+        self.formatter.format(&self.get(), template, args)
+    }
+}
+
+impl fmt::Debug for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Locale")
+            .field(&self.locale.get_untracked())
+            .finish_non_exhaustive()
+    }
+}
+
+/// An argument of [`create_formatted`](Scope::create_formatted). Implemented for signals of
+/// values that implement [`Display`].
+pub trait FormatArg {
+    /// Returns the current value of the argument and tracks it.
+    fn get_arg(&self) -> Rc<dyn Display>;
+}
+
+impl<T: Display + 'static> FormatArg for ReadSignal<T> {
+    fn get_arg(&self) -> Rc<dyn Display> {
+        self.get()
+    }
+}
+
+impl<T: Display + 'static> FormatArg for Signal<T> {
+    fn get_arg(&self) -> Rc<dyn Display> {
+        self.get()
+    }
+}
+
+impl<T: Display + 'static> FormatArg for RcSignal<T> {
+    fn get_arg(&self) -> Rc<dyn Display> {
+        self.get()
+    }
+}
+
+impl<T: Display + 'static> FormatArg for Memo<'_, T> {
+    fn get_arg(&self) -> Rc<dyn Display> {
+        self.get()
+    }
+}
+
+impl<T: Display + 'static> FormatArg for MaybeDyn<'_, T> {
+    fn get_arg(&self) -> Rc<dyn Display> {
+        self.get()
+    }
+}
+
+impl<'a> Scope<'a> {
+    /// Creates a string signal that formats `template` with `args`. The string is formatted again
+    /// whenever one of the arguments or the [`Locale`] changes. For a template that can change,
+    /// see [`create_formatted_dyn`](Self::create_formatted_dyn).
+    ///
+    /// The message is formatted by the [`MessageFormatter`] of the [`Locale`] context, or by the
+    /// [`PositionalFormatter`] if there is no [`Locale`] in the scope hierarchy.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// use std::fmt::Display;
+    /// use std::rc::Rc;
+    ///
+    /// struct Greeter;
+    ///
+    /// impl MessageFormatter for Greeter {
+    ///     fn format(&self, locale: &str, _template: &str, args: &[Rc<dyn Display>]) -> String {
+    ///         match locale {
+    ///             "fr" => format!("Bonjour {} !", args[0]),
+    ///             _ => format!("Hello {}!", args[0]),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// # create_scope_immediate(|ctx| {
+    /// let locale = Locale::with_formatter("en", Greeter);
+    /// ctx.provide_context(locale.clone());
+    ///
+    /// let name = ctx.create_signal("Alice");
+    /// let greeting = ctx.create_formatted("greeting", [name]);
+    /// assert_eq!(*greeting.get(), "Hello Alice!");
+    ///
+    /// name.set("Bob");
+    /// locale.set("fr");
+    /// assert_eq!(*greeting.get(), "Bonjour Bob !");
+    /// # });
+    /// ```
+    pub fn create_formatted<const N: usize>(
+        &'a self,
+        template: impl Into<String>,
+        args: [&'a (dyn FormatArg + 'a); N],
+    ) -> &'a Memo<'a, String> {
+        self.create_formatted_dyn(MaybeDyn::Static(Rc::new(template.into())), args)
+    }
+
+    /// Like [`create_formatted`](Self::create_formatted), with a template that can be a signal.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let template = ctx.create_signal("{} items".to_string());
+    /// let count = ctx.create_signal(2);
+    /// let message = ctx.create_formatted_dyn(template, [count]);
+    /// assert_eq!(*message.get(), "2 items");
+    ///
+    /// template.set("{} éléments".to_string());
+    /// assert_eq!(*message.get(), "2 éléments");
+    /// # });
+    /// ```
+    pub fn create_formatted_dyn<const N: usize>(
+        &'a self,
+        template: impl Into<MaybeDyn<'a, String>>,
+        args: [&'a (dyn FormatArg + 'a); N],
+    ) -> &'a Memo<'a, String> {
+        let template = template.into();
+        let locale = self.try_use_context::<Locale>();
+        self.create_selector(move || {
+            let template = template.get();
+            let args = args.map(FormatArg::get_arg);
+            match locale {
+                Some(locale) => locale.format(&template, &args),
+                None => PositionalFormatter.format("", &template, &args),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(template: &str, args: &[&'static str]) -> String {
+        let args: Vec<Rc<dyn Display>> = args
+            .iter()
+            .map(|&arg| Rc::new(arg) as Rc<dyn Display>)
+            .collect();
+        PositionalFormatter.format("", template, &args)
+    }
+
+    #[test]
+    fn positional_formatter() {
+        assert_eq!(format("{} and {}", &["a", "b"]), "a and b");
+        assert_eq!(format("{1} before {0}", &["a", "b"]), "b before a");
+        assert_eq!(format("{0}, {}", &["a", "b"]), "a, b");
+        assert_eq!(format("{{{0}}}", &["a"]), "{a}");
+        assert_eq!(format("{2} {name} {", &["a"]), "{2} {name} {");
+        assert_eq!(format("héllo {}", &["wörld"]), "héllo wörld");
+    }
+
+    #[test]
+    fn formatted_tracks_template_args_and_locale() {
+        create_scope_immediate(|ctx| {
+            let template = ctx.create_signal("{} items".to_string());
+            let count = ctx.create_signal(1);
+            let message = ctx.create_formatted_dyn(template, [count]);
+            assert_eq!(*message.get(), "1 items");
+
+            count.set(2);
+            assert_eq!(*message.get(), "2 items");
+            template.set("{} éléments".to_string());
+            assert_eq!(*message.get(), "2 éléments");
+        });
+
+        create_scope_immediate(|ctx| {
+            struct Upper;
+            impl MessageFormatter for Upper {
+                fn format(&self, locale: &str, template: &str, args: &[Rc<dyn Display>]) -> String {
+                    let message = PositionalFormatter.format(locale, template, args);
+                    if locale.eq_ignore_ascii_case("upper") {
+                        message.to_uppercase()
+                    } else {
+                        message
+                    }
+                }
+            }
+
+            let locale = Locale::with_formatter("en", Upper);
+            ctx.provide_context(locale.clone());
+            let name = ctx.create_signal("world");
+            let runs = ctx.create_signal(0);
+            let message = ctx.create_formatted("hello {}", [name]);
+            ctx.create_effect(|| {
+                message.track();
+                runs.set(*runs.get_untracked() + 1);
+            });
+            assert_eq!(*message.get(), "hello world");
+
+            locale.set("upper");
+            assert_eq!(*message.get(), "HELLO WORLD");
+            // Unchanged messages do not notify their subscribers.
+            locale.set("UPPER");
+            assert_eq!(*runs.get(), 2);
+        });
+    }
+}
@@ -1,13 +1,51 @@
 //! Reactive utilities for dealing with lists and iterables.
 
-use std::cell::RefCell;
-use std::collections::HashMap;
-use std::hash::Hash;
-use std::mem::MaybeUninit;
-use std::rc::Rc;
+use alloc::rc::Rc;
+use core::cell::{Cell, RefCell};
+use core::cmp::Ordering;
+use core::fmt::Debug;
+use core::hash::Hash;
 
 use crate::*;
 
+/// A change to a mapped list, passed to the callback of [`map_keyed_with`](Scope::map_keyed_with)
+/// and [`map_indexed_with`](Scope::map_indexed_with).
+///
+/// Applying the operations of an update in order to a copy of the previous mapped list (e.g. a
+/// list of DOM nodes) results in the new mapped list. Indexes refer to the list as it is after the
+/// previous operations have been applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffOp<U> {
+    /// `value` was inserted at `index`.
+    Insert {
+        /// The index of the new value.
+        index: usize,
+        /// The new value.
+        value: U,
+    },
+    /// The value at `index` was removed.
+    Remove {
+        /// The index of the removed value.
+        index: usize,
+    },
+    /// The value at `index` was replaced by `value`. Only emitted by
+    /// [`map_indexed_with`](Scope::map_indexed_with).
+    Replace {
+        /// The index of the replaced value.
+        index: usize,
+        /// The new value.
+        value: U,
+    },
+    /// The value at `from` was removed and inserted again at `to`. `to` is an index in the list
+    /// without the moved value.
+    Move {
+        /// The index of the value before the move.
+        from: usize,
+        /// The index of the value after the move.
+        to: usize,
+    },
+}
+
 impl<'a> Scope<'a> {
     /// Function that maps a `Vec` to another `Vec` via a map function. The mapped `Vec` is lazy
     /// computed, meaning that it's value will only be updated when requested. Modifications to the
@@ -19,7 +57,14 @@ impl<'a> Scope<'a> {
     /// * `list` - The list to be mapped. The list must be a [`ReadSignal`] (obtained from a
     ///   [`Signal`]) and therefore reactive.
     /// * `map_fn` - A closure that maps from the input type to the output type.
-    /// * `key_fn` - A closure that returns an _unique_ key to each entry.
+    /// * `key_fn` - A closure that returns an _unique_ key to each entry. The key function can
+    ///   read signals: the entries are re-keyed when they change, and the entries whose key
+    ///   changed are mapped again.
+    ///
+    /// # Panics
+    /// In debug builds, this panics if two entries have the same key, since the diff would not be
+    /// correct otherwise. The message names the indices of the entries and the type of the key but
+    /// not the key itself, since keys are not required to implement [`Debug`](core::fmt::Debug).
     ///
     ///  _Credits: Based on TypeScript implementation in <https://github.com/solidjs/solid>_
     pub fn map_keyed<T, K, U>(
@@ -30,27 +75,104 @@ impl<'a> Scope<'a> {
     ) -> &'a ReadSignal<Vec<U>>
     where
         T: Eq + Clone + 'a,
-        K: Eq + Hash,
+        K: Eq + Hash + 'a,
+        U: Clone + 'a,
+    {
+        self.map_keyed_inner(list, map_fn, key_fn, None::<fn(DiffOp<U>)>)
+    }
+
+    /// Like [`map_keyed`](Self::map_keyed), but also calls `diff` with the operations that turn
+    /// the previous mapped `Vec` into the new one each time `list` is updated. This allows
+    /// renderers to apply minimal mutations directly instead of diffing the mapped `Vec` again.
+    ///
+    /// `diff` is first called with an [`Insert`](DiffOp::Insert) for each initial item. Existing
+    /// items are never recomputed: they are either kept in place, [moved](DiffOp::Move) or
+    /// [removed](DiffOp::Remove). Items that keep their relative order are not moved.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let list = ctx.create_signal(vec![1, 2, 3]);
+    /// let ops = ctx.create_ref(std::cell::RefCell::new(Vec::new()));
+    /// ctx.map_keyed_with(list, |_, x| x * 2, |x| *x, |op| ops.borrow_mut().push(op));
+    /// ops.borrow_mut().clear();
+    ///
+    /// list.set(vec![3, 1, 2]);
+    /// assert_eq!(*ops.borrow(), [DiffOp::Move { from: 2, to: 0 }]);
+    /// # });
+    /// ```
+    pub fn map_keyed_with<T, K, U>(
+        &'a self,
+        list: &'a ReadSignal<Vec<T>>,
+        map_fn: impl for<'child_lifetime> Fn(BoundedScopeRef<'child_lifetime, 'a>, T) -> U + 'a,
+        key_fn: impl Fn(&T) -> K + 'a,
+        diff: impl FnMut(DiffOp<U>) + 'a,
+    ) -> &'a ReadSignal<Vec<U>>
+    where
+        T: Eq + Clone + 'a,
+        K: Eq + Hash + 'a,
+        U: Clone + 'a,
+    {
+        self.map_keyed_inner(list, map_fn, key_fn, Some(diff))
+    }
+
+    fn map_keyed_inner<T, K, U>(
+        &'a self,
+        list: &'a ReadSignal<Vec<T>>,
+        map_fn: impl for<'child_lifetime> Fn(BoundedScopeRef<'child_lifetime, 'a>, T) -> U + 'a,
+        key_fn: impl Fn(&T) -> K + 'a,
+        mut diff: Option<impl FnMut(DiffOp<U>) + 'a>,
+    ) -> &'a ReadSignal<Vec<U>>
+    where
+        T: Eq + Clone + 'a,
+        K: Eq + Hash + 'a,
         U: Clone + 'a,
     {
         let map_fn = Rc::new(map_fn);
 
         // Previous state used for diffing.
         let mut items = Rc::new(Vec::new());
+        let mut keys: Vec<K> = Vec::new();
         let mut mapped: Vec<U> = Vec::new();
         let mut disposers: Vec<Option<Rc<dyn FnOnce() + 'a>>> = Vec::new();
+        // Watches the signals that the key function reads. Created before the memo so that it is
+        // notified first.
+        let mut watcher = ChangeWatcher::new(self.runtime.clone());
 
         let signal = self.create_signal(Vec::new());
 
         // Diff and update signal each time list is updated.
-        self.create_effect(move || {
+        self.create_node(NodeKind::Memo, move || {
             let new_items = list.get();
+            // The keys are only computed for the items that changed and are stored for the next
+            // update. When a signal that the key function read changes, all the keys are
+            // computed again instead and no entries are skipped.
+            let rekey = watcher.take_changed();
+            if rekey {
+                watcher.clear();
+            }
+            let mut compute_keys = |items: &[T]| {
+                let (new_keys, dependencies) =
+                    collect_dependencies(|| items.iter().map(&key_fn).collect::<Vec<_>>());
+                watcher.watch(dependencies);
+                watcher.track();
+                new_keys
+            };
+            // Keep the previous entries alive so that their identities are not reused by new
+            // entries before the diff is computed.
+            let old_disposers = diff.as_ref().map(|_| disposers.clone());
             if new_items.is_empty() {
                 // Fast path for removing all items.
                 disposers = Vec::new();
                 mapped = Vec::new();
+                keys = Vec::new();
+                watcher.clear();
             } else if items.is_empty() {
                 // Fast path for new create.
+                keys = compute_keys(&new_items);
+                #[cfg(debug_assertions)]
+                check_unique_keys(&new_items, &keys);
                 // TODO: do not clone T
                 #[allow(clippy::unnecessary_to_owned)] // Clippy false positive.
                 for new_item in new_items.iter().cloned() {
@@ -61,7 +183,7 @@ impl<'a> Scope<'a> {
                         move |ctx| {
                             // SAFETY: f takes the same parameter as the argument to
                             // self.create_child_scope(_).
-                            *tmp.borrow_mut() = Some(map_fn(unsafe { std::mem::transmute(ctx) }, new_item));
+                            *tmp.borrow_mut() = Some(map_fn(unsafe { core::mem::transmute(ctx) }, new_item));
                         }
                     });
                     mapped.push(tmp.borrow().clone().unwrap());
@@ -76,15 +198,21 @@ impl<'a> Scope<'a> {
                 let mut temp = vec![None; new_items.len()];
                 let mut temp_disposers = vec![None; new_items.len()];
 
-                // Skip common prefix.
+                // Skip common prefix. Nothing is skipped when re-keying since the keys of equal
+                // items might have changed.
                 let min_len = usize::min(items.len(), new_items.len());
-                let start = items
-                    .iter()
-                    .zip(new_items.iter())
-                    .position(|(a, b)| a != b)
-                    .unwrap_or(min_len);
+                let start = if rekey {
+                    0
+                } else {
+                    items
+                        .iter()
+                        .zip(new_items.iter())
+                        .position(|(a, b)| a != b)
+                        .unwrap_or(min_len)
+                };
                 debug_assert!(
-                    (items.get(start).is_none() && new_items.get(start).is_none())
+                    rekey
+                        || (items.get(start).is_none() && new_items.get(start).is_none())
                         || (items.get(start) != new_items.get(start)),
                     "start is the first index where items[start] != new_items[start]"
                 );
@@ -94,14 +222,18 @@ impl<'a> Scope<'a> {
                 let mut new_end = new_items.len();
                 #[allow(clippy::suspicious_operation_groupings)]
                 // FIXME: make code clearer so that clippy won't complain
-                while end > start && new_end > start && items[end - 1] == new_items[new_end - 1] {
+                while !rekey
+                    && end > start
+                    && new_end > start
+                    && items[end - 1] == new_items[new_end - 1]
+                {
                     end -= 1;
                     new_end -= 1;
                     temp[new_end] = Some(mapped[end].clone());
                     temp_disposers[new_end] = disposers[end].clone();
                 }
                 debug_assert!(
-                    if end != 0 && new_end != 0 {
+                    if !rekey && end != 0 && new_end != 0 {
                         (end == items.len() && new_end == new_items.len())
                             || (items[end - 1] != new_items[new_end - 1])
                     } else {
@@ -110,6 +242,13 @@ impl<'a> Scope<'a> {
                     "end and new_end are the last indexes where items[end - 1] != new_items[new_end - 1]"
                 );
 
+                // The keys of the skipped items did not change.
+                let old_keys: Vec<K> = keys
+                    .splice(start..end, compute_keys(&new_items[start..new_end]))
+                    .collect();
+                #[cfg(debug_assertions)]
+                check_unique_keys(&new_items, &keys);
+
                 // 0) Prepare a map of indices in newItems. Scan backwards so we encounter them in
                 // natural order.
                 let mut new_indices = HashMap::with_capacity(new_end - start);
@@ -118,22 +257,22 @@ impl<'a> Scope<'a> {
                 // always None.
                 let mut new_indices_next = vec![None; new_end - start];
                 for j in (start..new_end).rev() {
-                    let item = &new_items[j];
-                    let i = new_indices.get(&key_fn(item));
+                    let key = &keys[j];
+                    let i = new_indices.get(key);
                     new_indices_next[j - start] = i.copied();
-                    new_indices.insert(key_fn(item), j);
+                    new_indices.insert(key, j);
                 }
 
                 // 1) Step through old items and see if they can be found in new set; if so, mark
                 // them as moved.
                 for i in start..end {
-                    let item = &items[i];
-                    if let Some(j) = new_indices.get(&key_fn(item)).copied() {
+                    let key = &old_keys[i - start];
+                    if let Some(j) = new_indices.get(key).copied() {
                         // Moved. j is index of item in new_items.
                         temp[j] = Some(mapped[i].clone());
                         temp_disposers[j] = disposers[i].clone();
                         new_indices_next[j - start]
-                            .and_then(|j| new_indices.insert(key_fn(item), j));
+                            .and_then(|j| new_indices.insert(key, j));
                     } else {
                         // Create new.
                         disposers[i] = None;
@@ -163,7 +302,7 @@ impl<'a> Scope<'a> {
                             move |ctx| {
                                 // SAFETY: f takes the same parameter as the argument to
                                 // self.create_child_scope(_).
-                                *tmp.borrow_mut() = Some(map_fn(unsafe { std::mem::transmute(ctx) }, new_item));
+                                *tmp.borrow_mut() = Some(map_fn(unsafe { core::mem::transmute(ctx) }, new_item));
                             }
                         });
 
@@ -184,11 +323,21 @@ impl<'a> Scope<'a> {
 
             // 4) Save a copy of the mapped items for the next update.
             items = Rc::clone(&new_items);
-            debug_assert!([items.len(), mapped.len(), disposers.len()]
+            debug_assert!([items.len(), keys.len(), mapped.len(), disposers.len()]
                 .iter()
                 .all(|l| *l == new_items.len()));
 
-            // 5) Update signal to trigger updates.
+            // 5) Report the changes to the mapped items.
+            if let (Some(diff), Some(old_disposers)) = (&mut diff, old_disposers) {
+                diff_keyed(
+                    &entry_ids(&old_disposers),
+                    &entry_ids(&disposers),
+                    &mapped,
+                    diff,
+                );
+            }
+
+            // 6) Update signal to trigger updates.
             signal.set(mapped.clone());
         });
 
@@ -213,6 +362,38 @@ impl<'a> Scope<'a> {
         list: &'a ReadSignal<Vec<T>>,
         map_fn: impl for<'child_lifetime> Fn(BoundedScopeRef<'child_lifetime, 'a>, T) -> U + 'a,
     ) -> &'a ReadSignal<Vec<U>>
+    where
+        T: PartialEq + Clone,
+        U: Clone + 'a,
+    {
+        self.map_indexed_inner(list, map_fn, None::<fn(DiffOp<U>)>)
+    }
+
+    /// Like [`map_indexed`](Self::map_indexed), but also calls `diff` with the operations that
+    /// turn the previous mapped `Vec` into the new one each time `list` is updated.
+    ///
+    /// `diff` is first called with an [`Insert`](DiffOp::Insert) for each initial item. After
+    /// that, items that changed are [replaced](DiffOp::Replace), new items are inserted at the end
+    /// and extra items are [removed](DiffOp::Remove) from the end.
+    pub fn map_indexed_with<T, U>(
+        &'a self,
+        list: &'a ReadSignal<Vec<T>>,
+        map_fn: impl for<'child_lifetime> Fn(BoundedScopeRef<'child_lifetime, 'a>, T) -> U + 'a,
+        diff: impl FnMut(DiffOp<U>) + 'a,
+    ) -> &'a ReadSignal<Vec<U>>
+    where
+        T: PartialEq + Clone,
+        U: Clone + 'a,
+    {
+        self.map_indexed_inner(list, map_fn, Some(diff))
+    }
+
+    fn map_indexed_inner<T, U>(
+        &'a self,
+        list: &'a ReadSignal<Vec<T>>,
+        map_fn: impl for<'child_lifetime> Fn(BoundedScopeRef<'child_lifetime, 'a>, T) -> U + 'a,
+        mut diff: Option<impl FnMut(DiffOp<U>) + 'a>,
+    ) -> &'a ReadSignal<Vec<U>>
     where
         T: PartialEq + Clone,
         U: Clone + 'a,
@@ -227,65 +408,138 @@ impl<'a> Scope<'a> {
         let signal = self.create_signal(Vec::new());
 
         // Diff and update signal each time list is updated.
-        self.create_effect(move || {
+        self.create_node(NodeKind::Memo, move || {
             let new_items = list.get();
 
             if new_items.is_empty() {
                 // Fast path for removing all items.
+                if let Some(diff) = &mut diff {
+                    for index in (0..items.len()).rev() {
+                        diff(DiffOp::Remove { index });
+                    }
+                }
                 disposers = Vec::new();
                 items = Rc::new(Vec::new());
                 mapped = Vec::new();
             } else {
-                // Pre-allocate space needed
-                if new_items.len() > items.len() {
-                    let new_count = new_items.len() - items.len();
-                    mapped.reserve(new_count);
-                    disposers.reserve(new_count);
-                }
+                // Maps a single item in a new child scope.
+                let map_item = |item: T| {
+                    let tmp = RefCell::new(None);
+                    let new_disposer = self.create_child_scope(|ctx| {
+                        // SAFETY: f takes the same parameter as the argument to
+                        // self.create_child_scope(_).
+                        *tmp.borrow_mut() =
+                            Some(map_fn(unsafe { core::mem::transmute(ctx) }, item));
+                    });
+                    let disposer: Box<dyn FnOnce()> = Box::new(new_disposer);
+                    (tmp.into_inner().unwrap(), disposer)
+                };
+                let (old_len, new_len) = (items.len(), new_items.len());
 
-                for (i, new_item) in new_items.iter().enumerate() {
-                    let new_item = new_item.clone();
-                    let item = items.get(i);
-                    // We lift the equality out of the else if branch to satisfy borrow checker.
-                    let eqs = item != Some(&new_item);
+                // The length of the common prefix. The scan stops at the first item that changed
+                // and the general path below continues from there, so every shared item is compared
+                // only once.
+                let prefix = new_items
+                    .iter()
+                    .zip(items.iter())
+                    .take_while(|(new_item, item)| new_item == item)
+                    .count();
 
-                    let mut tmp = MaybeUninit::<U>::zeroed();
-                    let ptr = &mut tmp as *mut MaybeUninit<U>;
-                    if item.is_none() || eqs {
-                        let new_disposer = self.create_child_scope({
-                            let map_fn = Rc::clone(&map_fn);
-                            move |ctx| unsafe {
-                                // SAFETY: callback is called immediately in
-                                // self.create_child_scope.
-                                // ptr is still accessible after self.create_child_scope and
-                                // therefore lives long enough.
+                // The fast paths below only clone and map the items that were added.
+                if prefix == old_len {
+                    // Fast path for appending items.
+                    mapped.reserve(new_len - old_len);
+                    disposers.reserve(new_len - old_len);
+                    for (index, new_item) in new_items.iter().enumerate().skip(old_len) {
+                        let (value, disposer) = map_item(new_item.clone());
+                        mapped.push(value);
+                        disposers.push(disposer);
+                        if let Some(diff) = &mut diff {
+                            diff(DiffOp::Insert {
+                                index,
+                                value: mapped[index].clone(),
+                            });
+                        }
+                    }
+                } else if prefix == new_len {
+                    // Fast path for truncating the list.
+                    if let Some(diff) = &mut diff {
+                        for index in (new_len..old_len).rev() {
+                            diff(DiffOp::Remove { index });
+                        }
+                    }
+                } else if prefix == 0
+                    && new_len > old_len
+                    // Compare the last items first so that most other updates are not scanned twice.
+                    && new_items.last() == items.last()
+                    && new_items[new_len - old_len..] == items[..]
+                {
+                    // Fast path for prepending items. Since an item is mapped independently of its
+                    // index, the existing mapped items can be reused at their new indices.
+                    let added = new_len - old_len;
+                    let (new_mapped, new_disposers): (Vec<_>, Vec<_>) = new_items[..added]
+                        .iter()
+                        .map(|new_item| map_item(new_item.clone()))
+                        .unzip();
+                    mapped.splice(0..0, new_mapped);
+                    disposers.splice(0..0, new_disposers);
+                    if let Some(diff) = &mut diff {
+                        for (index, value) in mapped[..added].iter().enumerate() {
+                            diff(DiffOp::Insert {
+                                index,
+                                value: value.clone(),
+                            });
+                        }
+                    }
+                } else {
+                    // Pre-allocate space needed
+                    if new_len > old_len {
+                        let new_count = new_len - old_len;
+                        mapped.reserve(new_count);
+                        disposers.reserve(new_count);
+                    }
 
-                                // SAFETY: f takes the same parameter as the argument to
-                                // self.create_child_scope(_).
-                                (*ptr).write(map_fn(std::mem::transmute(ctx), new_item));
+                    for (i, new_item) in new_items.iter().enumerate().skip(prefix) {
+                        match items.get(i) {
+                            // The item right after the common prefix is known to have changed.
+                            Some(item) if i > prefix && item == new_item => {}
+                            Some(_) => {
+                                let (value, disposer) = map_item(new_item.clone());
+                                mapped[i] = value;
+                                disposers[i] = disposer;
+                                if let Some(diff) = &mut diff {
+                                    diff(DiffOp::Replace {
+                                        index: i,
+                                        value: mapped[i].clone(),
+                                    });
+                                }
+                            }
+                            None => {
+                                let (value, disposer) = map_item(new_item.clone());
+                                mapped.push(value);
+                                disposers.push(disposer);
+                                if let Some(diff) = &mut diff {
+                                    diff(DiffOp::Insert {
+                                        index: i,
+                                        value: mapped[i].clone(),
+                                    });
+                                }
                             }
-                        });
-                        if item.is_none() {
-                            // SAFETY: tmp is written in self.create_child_scope
-                            mapped.push(unsafe { tmp.assume_init() });
-                            disposers.push(Box::new(new_disposer));
-                        } else if eqs {
-                            // SAFETY: tmp is written in self.create_child_scope
-                            mapped[i] = unsafe { tmp.assume_init() };
-                            disposers[i] = Box::new(new_disposer);
                         }
                     }
-                }
 
-                if new_items.len() < items.len() {
-                    for _i in new_items.len()..items.len() {
-                        disposers.pop();
+                    if new_len < old_len {
+                        if let Some(diff) = &mut diff {
+                            for index in (new_len..old_len).rev() {
+                                diff(DiffOp::Remove { index });
+                            }
+                        }
                     }
                 }
 
                 // In case the new set is shorter than the old, set the length of the mapped array.
-                mapped.truncate(new_items.len());
-                disposers.truncate(new_items.len());
+                mapped.truncate(new_len);
+                disposers.truncate(new_len);
 
                 // save a copy of the mapped items for the next update.
                 items = Rc::clone(&new_items);
@@ -300,6 +554,633 @@ impl<'a> Scope<'a> {
 
         signal
     }
+
+    /// Creates a list with the items of `source` for which `predicate` returns `true`, in the same
+    /// order.
+    ///
+    /// The predicate is reactive, so it can depend on other signals (e.g. a search query).
+    /// Dependents are only notified if the filtered list actually changes, so that a list that is
+    /// rendered with `Keyed` is not diffed again when, for example, typing another character into
+    /// the query does not change the result.
+    ///
+    /// The list is updated incrementally: when `source` changes, the predicate is only called for
+    /// the items between the unchanged items at the start and at the end of the list. The whole
+    /// list is only filtered again when a signal that the predicate depends on changes.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let names = ctx.create_signal(vec!["Alice", "Bob", "Carol"]);
+    /// let query = ctx.create_signal("o".to_string());
+    /// let matches = ctx.create_filtered(names, |name| name.to_lowercase().contains(&*query.get()));
+    /// assert_eq!(*matches.get(), ["Bob", "Carol"]);
+    ///
+    /// query.set("al".to_string());
+    /// assert_eq!(*matches.get(), ["Alice"]);
+    /// # });
+    /// ```
+    pub fn create_filtered<T>(
+        &'a self,
+        source: &'a ReadSignal<Vec<T>>,
+        predicate: impl Fn(&T) -> bool + 'a,
+    ) -> &'a ReadSignal<Vec<T>>
+    where
+        T: Clone + PartialEq + 'a,
+    {
+        let mut items = Rc::new(Vec::new());
+        // Whether each item of `items` is in the filtered list.
+        let mut kept: Vec<bool> = Vec::new();
+        let mut filtered = Vec::new();
+        // Created before the memo so that it is notified first.
+        let mut watcher = ChangeWatcher::new(self.runtime.clone());
+        self.create_selector(move || {
+            let new_items = source.get();
+            let (prefix, suffix) = if watcher.take_changed() {
+                watcher.clear();
+                (0, 0)
+            } else {
+                common_affixes(&items, &new_items)
+            };
+            let (old_end, new_end) = (items.len() - suffix, new_items.len() - suffix);
+
+            let (new_kept, dependencies) = collect_dependencies(|| {
+                new_items[prefix..new_end]
+                    .iter()
+                    .map(&predicate)
+                    .collect::<Vec<_>>()
+            });
+            // The predicate is not called again for the other items, but still depends on the
+            // signals that it read for them.
+            watcher.watch(dependencies);
+            watcher.track();
+
+            let start = kept[..prefix].iter().filter(|kept| **kept).count();
+            let removed = kept[prefix..old_end].iter().filter(|kept| **kept).count();
+            filtered.splice(
+                start..start + removed,
+                new_items[prefix..new_end]
+                    .iter()
+                    .zip(&new_kept)
+                    .filter(|(_, kept)| **kept)
+                    .map(|(item, _)| item.clone()),
+            );
+            kept.splice(prefix..old_end, new_kept);
+            items = new_items;
+            filtered.clone()
+        })
+    }
+
+    /// Creates a list with the items of `source` sorted with `compare`. The sort is stable.
+    ///
+    /// The list is updated incrementally: when `source` changes, only the items between the
+    /// unchanged items at the start and at the end of the list are removed from and inserted into
+    /// the sorted list, with a binary search. The list is only sorted from scratch when most of it
+    /// changed. Dependents are only notified if the sorted list actually changes.
+    ///
+    /// `compare` is not reactive. To sort by a criterion that is stored in a signal, create the
+    /// sorted list inside a scoped effect that tracks the signal instead.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let scores = ctx.create_signal(vec![3, 1, 2]);
+    /// let sorted = ctx.create_sorted(scores, |a, b| b.cmp(a));
+    /// assert_eq!(*sorted.get(), [3, 2, 1]);
+    ///
+    /// scores.set(vec![3, 1, 2, 5]);
+    /// assert_eq!(*sorted.get(), [5, 3, 2, 1]);
+    /// # });
+    /// ```
+    pub fn create_sorted<T>(
+        &'a self,
+        source: &'a ReadSignal<Vec<T>>,
+        compare: impl Fn(&T, &T) -> Ordering + 'a,
+    ) -> &'a ReadSignal<Vec<T>>
+    where
+        T: Clone + PartialEq + 'a,
+    {
+        let mut items = Rc::new(Vec::new());
+        // The sorted items with their index in `items`. Ties are broken by the index, which makes
+        // the sort stable and the position of every item unique.
+        let mut sorted: Vec<(usize, T)> = Vec::new();
+        self.create_selector(move || {
+            let new_items = source.get();
+            untrack(|| {
+                let cmp = |(a_index, a): &(usize, T), (b_index, b): (usize, &T)| {
+                    compare(a, b).then(a_index.cmp(&b_index))
+                };
+                let (prefix, suffix) = common_affixes(&items, &new_items);
+                let (old_end, new_end) = (items.len() - suffix, new_items.len() - suffix);
+                let changed = (old_end - prefix) + (new_end - prefix);
+
+                if changed * 2 > new_items.len().max(items.len()) {
+                    sorted = new_items.iter().cloned().enumerate().collect();
+                    sorted.sort_by(|a, b| cmp(a, (b.0, &b.1)));
+                    return;
+                }
+                for index in prefix..old_end {
+                    let position =
+                        sorted.partition_point(|x| cmp(x, (index, &items[index])).is_lt());
+                    sorted.remove(position);
+                }
+                if new_end != old_end {
+                    // Shift the indexes of the unchanged items at the end.
+                    for (index, _) in &mut sorted {
+                        if *index >= old_end {
+                            *index = *index - old_end + new_end;
+                        }
+                    }
+                }
+                for (index, item) in new_items.iter().enumerate().take(new_end).skip(prefix) {
+                    let position = sorted.partition_point(|x| cmp(x, (index, item)).is_lt());
+                    sorted.insert(position, (index, item.clone()));
+                }
+            });
+            items = new_items;
+            sorted.iter().map(|(_, item)| item.clone()).collect()
+        })
+    }
+
+    /// Groups the items of `source` by the key that is returned by `group_fn`. The groups are in
+    /// the order in which their first item appears in `source`, and the items of a group keep
+    /// their order.
+    ///
+    /// Each group has its own signal for its items, so that a grouped list can be rendered with
+    /// nested `Keyed` components: the outer list only changes when a group is added, removed or
+    /// moved, and the items of a group only change when that group changes.
+    ///
+    /// The groups are updated incrementally: when `source` changes, only the items between the
+    /// unchanged items at the start and at the end of the list are removed from and added to
+    /// their groups, and only the signals of these groups are updated.
+    ///
+    /// `group_fn` is not reactive. When a group is removed, its signal is cleared and reused by the
+    /// next group that is added, so the signal of a group should only be used while the group
+    /// exists.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let contacts = ctx.create_signal(vec!["Alice", "Bob", "Anna"]);
+    /// let by_letter = ctx.create_grouped(contacts, |name| name.chars().next());
+    /// let groups = by_letter.get();
+    /// assert_eq!(*groups[0].key(), Some('A'));
+    /// assert_eq!(*groups[0].items().get(), ["Alice", "Anna"]);
+    /// assert_eq!(*groups[1].items().get(), ["Bob"]);
+    /// # });
+    /// ```
+    pub fn create_grouped<T, K>(
+        &'a self,
+        source: &'a ReadSignal<Vec<T>>,
+        group_fn: impl Fn(&T) -> K + 'a,
+    ) -> &'a ReadSignal<Vec<Group<'a, K, T>>>
+    where
+        T: Clone + PartialEq + 'a,
+        K: Clone + Eq + Hash + 'a,
+    {
+        let mut items = Rc::new(Vec::new());
+        // The key of the group of each item of `items`.
+        let mut keys: Vec<K> = Vec::new();
+        let mut groups: HashMap<K, GroupState<'a, T>> = HashMap::new();
+        // The signals of the groups that were removed, which are reused by new groups.
+        let mut spare: Vec<&'a Signal<Vec<T>>> = Vec::new();
+        let mut order: Vec<Group<'a, K, T>> = Vec::new();
+        self.create_selector(move || {
+            let new_items = source.get();
+            untrack(|| {
+                let (prefix, suffix) = common_affixes(&items, &new_items);
+                let (old_end, new_end) = (items.len() - suffix, new_items.len() - suffix);
+                let mut touched = Vec::new();
+                let mut touch = |key: &K, group: &mut GroupState<'a, T>| {
+                    if !group.touched {
+                        group.touched = true;
+                        touched.push(key.clone());
+                    }
+                };
+
+                let new_keys = new_items[prefix..new_end].iter().map(&group_fn);
+                let old_keys: Vec<K> = keys.splice(prefix..old_end, new_keys).collect();
+                for (index, key) in (prefix..old_end).zip(old_keys) {
+                    let group = groups.get_mut(&key).unwrap();
+                    let position = group.entries.partition_point(|(i, _)| *i < index);
+                    group.entries.remove(position);
+                    touch(&key, group);
+                }
+                if new_end != old_end {
+                    // Shift the indexes of the unchanged items at the end.
+                    for group in groups.values_mut() {
+                        let start = group.entries.partition_point(|(i, _)| *i < old_end);
+                        for (index, _) in &mut group.entries[start..] {
+                            *index = *index - old_end + new_end;
+                        }
+                    }
+                }
+                for index in prefix..new_end {
+                    let key = &keys[index];
+                    let group = groups.entry(key.clone()).or_insert_with(|| GroupState {
+                        entries: Vec::new(),
+                        signal: spare
+                            .pop()
+                            .unwrap_or_else(|| self.create_signal(Vec::new())),
+                        touched: false,
+                    });
+                    let position = group.entries.partition_point(|(i, _)| *i < index);
+                    group
+                        .entries
+                        .insert(position, (index, new_items[index].clone()));
+                    touch(key, group);
+                }
+
+                if touched.is_empty() {
+                    return;
+                }
+                for key in touched {
+                    let group = groups.get_mut(&key).unwrap();
+                    group.touched = false;
+                    if group.entries.is_empty() {
+                        let group = groups.remove(&key).unwrap();
+                        group.signal.set(Vec::new());
+                        spare.push(group.signal);
+                    } else {
+                        group
+                            .signal
+                            .set(group.entries.iter().map(|(_, item)| item.clone()).collect());
+                    }
+                }
+                let mut first_indexes: Vec<_> = groups
+                    .iter()
+                    .map(|(key, group)| (group.entries[0].0, key))
+                    .collect();
+                first_indexes.sort_unstable_by_key(|(index, _)| *index);
+                order = first_indexes
+                    .into_iter()
+                    .map(|(_, key)| Group {
+                        key: key.clone(),
+                        items: groups[key].signal,
+                    })
+                    .collect();
+            });
+            items = new_items;
+            order.clone()
+        })
+    }
+}
+
+/// A group of items, created with [`create_grouped`](Scope::create_grouped).
+pub struct Group<'a, K, T> {
+    key: K,
+    items: &'a ReadSignal<Vec<T>>,
+}
+
+impl<'a, K, T> Group<'a, K, T> {
+    /// Returns the key of the group.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Returns the items of the group.
+    pub fn items(&self) -> &'a ReadSignal<Vec<T>> {
+        self.items
+    }
+}
+
+impl<K: Clone, T> Clone for Group<'_, K, T> {
+    fn clone(&self) -> Self {
+        Self {
+            key: self.key.clone(),
+            items: self.items,
+        }
+    }
+}
+
+impl<K: PartialEq, T> PartialEq for Group<'_, K, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key == other.key && core::ptr::eq(self.items, other.items)
+    }
+}
+
+impl<K: Eq, T> Eq for Group<'_, K, T> {}
+
+impl<K: Debug, T: Debug> Debug for Group<'_, K, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Group")
+            .field("key", &self.key)
+            .field("items", &self.items.get_untracked())
+            .finish()
+    }
+}
+
+/// A group of [`create_grouped`](Scope::create_grouped).
+struct GroupState<'a, T> {
+    /// The items of the group with their index in the source list, in order.
+    entries: Vec<(usize, T)>,
+    signal: &'a Signal<Vec<T>>,
+    /// Whether the group changed in the update that is being made.
+    touched: bool,
+}
+
+/// Returns the number of items that are the same at the start and at the end of `old` and `new`.
+/// The two counts never add up to more than the length of either list.
+fn common_affixes<T: PartialEq>(old: &[T], new: &[T]) -> (usize, usize) {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    (prefix, suffix)
+}
+
+/// Watches a set of signals without depending on them, to know whether a reactive callback (e.g.
+/// the predicate of [`create_filtered`](Scope::create_filtered)) has to be called again for all
+/// the items of a list.
+///
+/// The watcher is notified like a memo, before the memos with the same rank. The memo that uses it
+/// must be created after the watcher and track the same signals (see [`track`](Self::track)).
+struct ChangeWatcher {
+    id: EffectId,
+    runtime: Runtime,
+    changed: Rc<Cell<bool>>,
+    callback: Rc<RefCell<dyn FnMut()>>,
+    emitters: Vec<Weak<Subscribers>>,
+    /// The subscriptions are boxed because they must not move while they are linked.
+    #[allow(clippy::vec_box)]
+    subscriptions: Vec<Box<Subscription>>,
+}
+
+impl ChangeWatcher {
+    fn new(runtime: Runtime) -> Self {
+        let id = EffectId::next();
+        id.set_order_hint(i32::MIN);
+        let changed = Rc::new(Cell::new(false));
+        let callback = Rc::new(RefCell::new({
+            let changed = Rc::clone(&changed);
+            move || changed.set(true)
+        }));
+        Self {
+            id,
+            runtime,
+            changed,
+            callback,
+            emitters: Vec::new(),
+            subscriptions: Vec::new(),
+        }
+    }
+
+    /// Returns `true` if one of the watched signals changed since the last call.
+    fn take_changed(&self) -> bool {
+        self.changed.replace(false)
+    }
+
+    /// Watches `emitters` in addition to the signals that are already watched.
+    fn watch(&mut self, emitters: Vec<Weak<Subscribers>>) {
+        let callback: WeakEffectCallback = Rc::downgrade(&self.callback);
+        for emitter in emitters {
+            if self.emitters.iter().any(|watched| watched.ptr_eq(&emitter)) {
+                continue;
+            }
+            if let Some(subscribers) = emitter.upgrade() {
+                self.subscriptions.push(subscribers.subscribe(
+                    self.id,
+                    NodeKind::Memo,
+                    callback.clone(),
+                    self.runtime.clone(),
+                    Default::default(),
+                ));
+            }
+            self.emitters.push(emitter);
+        }
+    }
+
+    /// Stops watching all the signals.
+    fn clear(&mut self) {
+        self.subscriptions.clear();
+        self.emitters.clear();
+    }
+
+    /// Tracks the watched signals in the memo or effect that is currently running.
+    fn track(&self) {
+        for emitter in self.emitters.iter().filter_map(Weak::upgrade) {
+            SignalEmitter(emitter).track();
+        }
+    }
+}
+
+impl Drop for ChangeWatcher {
+    fn drop(&mut self) {
+        scheduler::remove_order_hint(self.id);
+    }
+}
+
+/// Panics if two different `items` have the same key. Equal items can share a key since their
+/// entries are interchangeable. The key is not printed because `K` is not required to implement
+/// [`Debug`](core::fmt::Debug), so the message only has the indices and the type of the key.
+#[cfg(debug_assertions)]
+fn check_unique_keys<T: Eq, K: Eq + Hash>(items: &[T], keys: &[K]) {
+    let mut seen = HashMap::with_capacity(keys.len());
+    for (j, key) in keys.iter().enumerate() {
+        if let Some(i) = seen.insert(key, j) {
+            if items[i] != items[j] {
+                panic!(
+                    "entries {i} and {j} of keyed list have the same key of type `{}`, keys must \
+                     be unique",
+                    core::any::type_name::<K>()
+                );
+            }
+        }
+    }
+}
+
+/// Returns the identities of the entries of a keyed list. Entries are identified by their
+/// disposer, which is shared when an entry is moved.
+fn entry_ids(disposers: &[Option<Rc<dyn FnOnce() + '_>>]) -> Vec<*const ()> {
+    disposers
+        .iter()
+        .map(|disposer| {
+            disposer.as_ref().map_or(core::ptr::null(), |disposer| {
+                Rc::as_ptr(disposer) as *const ()
+            })
+        })
+        .collect()
+}
+
+/// Calls `diff` with the operations that turn the entries `old` into the entries `new`. `mapped`
+/// are the values of the entries in `new`.
+fn diff_keyed<U: Clone>(
+    old: &[*const ()],
+    new: &[*const ()],
+    mapped: &[U],
+    diff: &mut impl FnMut(DiffOp<U>),
+) {
+    let new_indices: HashMap<_, _> = new.iter().enumerate().map(|(j, id)| (*id, j)).collect();
+
+    // 1) Remove the entries that are not in the new list, from the back so that indexes do not
+    // shift.
+    for (index, id) in old.iter().enumerate().rev() {
+        if !new_indices.contains_key(id) {
+            diff(DiffOp::Remove { index });
+        }
+    }
+    let current: Vec<_> = old
+        .iter()
+        .copied()
+        .filter(|id| new_indices.contains_key(id))
+        .collect();
+
+    if current.is_empty() {
+        // Fast path for new create.
+        for (index, value) in mapped.iter().enumerate() {
+            diff(DiffOp::Insert {
+                index,
+                value: value.clone(),
+            });
+        }
+        return;
+    }
+
+    // 2) The remaining entries in the longest subsequence that is already in the right order stay
+    // in place.
+    let targets: Vec<_> = current.iter().map(|id| new_indices[id]).collect();
+    let mut existing = vec![false; new.len()];
+    for &target in &targets {
+        existing[target] = true;
+    }
+    let mut stable = vec![false; new.len()];
+    for i in longest_increasing_subsequence(&targets) {
+        stable[targets[i]] = true;
+    }
+
+    // 3) Every other entry is inserted or moved in front of the entry after it in the new list,
+    // stepping backwards so that the entry after it is already in its final position. The
+    // entries in front of a stable entry therefore end up right before it, and the entries after
+    // the last stable entry at the end. Lay out a slot for each of these final positions next to
+    // the slots of the current entries, so that the index of an entry is the number of occupied
+    // slots before its slot.
+    let mut slots = Vec::with_capacity(current.len() + new.len());
+    let mut old_slot = vec![0; new.len()];
+    let mut new_slot = vec![0; new.len()];
+    let mut group = 0;
+    for &target in &targets {
+        if stable[target] {
+            while group < target {
+                if !stable[group] {
+                    new_slot[group] = slots.len();
+                    slots.push(false);
+                }
+                group += 1;
+            }
+            group += 1;
+        }
+        old_slot[target] = slots.len();
+        slots.push(true);
+    }
+    for (j, slot) in new_slot.iter_mut().enumerate().skip(group) {
+        if !stable[j] {
+            *slot = slots.len();
+            slots.push(false);
+        }
+    }
+    let mut slots = OccupiedSlots::new(&slots);
+
+    for j in (0..new.len()).rev() {
+        if stable[j] {
+            continue;
+        }
+        if existing[j] {
+            let from = slots.count_before(old_slot[j]);
+            slots.toggle(old_slot[j]);
+            let to = slots.count_before(new_slot[j]);
+            slots.toggle(new_slot[j]);
+            diff(DiffOp::Move { from, to });
+        } else {
+            let index = slots.count_before(new_slot[j]);
+            slots.toggle(new_slot[j]);
+            diff(DiffOp::Insert {
+                index,
+                value: mapped[j].clone(),
+            });
+        }
+    }
+}
+
+/// A set of slots that are either occupied or free, which counts the occupied slots before a slot
+/// in `O(log n)` (a Fenwick tree).
+struct OccupiedSlots {
+    occupied: Vec<bool>,
+    tree: Vec<usize>,
+}
+
+impl OccupiedSlots {
+    fn new(occupied: &[bool]) -> Self {
+        let mut tree = vec![0; occupied.len() + 1];
+        for (i, &slot) in occupied.iter().enumerate() {
+            let i = i + 1;
+            tree[i] += usize::from(slot);
+            let parent = i + (i & i.wrapping_neg());
+            if parent < tree.len() {
+                tree[parent] += tree[i];
+            }
+        }
+        Self {
+            occupied: occupied.to_vec(),
+            tree,
+        }
+    }
+
+    /// Returns the number of occupied slots before `slot`.
+    fn count_before(&self, slot: usize) -> usize {
+        let mut count = 0;
+        let mut i = slot;
+        while i > 0 {
+            count += self.tree[i];
+            i &= i - 1;
+        }
+        count
+    }
+
+    /// Frees `slot` if it is occupied and occupies it otherwise.
+    fn toggle(&mut self, slot: usize) {
+        let occupied = !self.occupied[slot];
+        self.occupied[slot] = occupied;
+        let mut i = slot + 1;
+        while i < self.tree.len() {
+            if occupied {
+                self.tree[i] += 1;
+            } else {
+                self.tree[i] -= 1;
+            }
+            i += i & i.wrapping_neg();
+        }
+    }
+}
+
+/// Returns the indexes of a longest strictly increasing subsequence of `seq`.
+fn longest_increasing_subsequence(seq: &[usize]) -> Vec<usize> {
+    // tails[k] is the index of the smallest tail of an increasing subsequence of length k + 1.
+    let mut tails: Vec<usize> = Vec::new();
+    let mut prev = vec![None; seq.len()];
+    for (i, x) in seq.iter().enumerate() {
+        let k = tails.partition_point(|&t| seq[t] < *x);
+        if k > 0 {
+            prev[i] = Some(tails[k - 1]);
+        }
+        if k == tails.len() {
+            tails.push(i);
+        } else {
+            tails[k] = i;
+        }
+    }
+    let mut lis = Vec::with_capacity(tails.len());
+    let mut i = tails.last().copied();
+    while let Some(index) = i {
+        lis.push(index);
+        i = prev[index];
+    }
+    lis.reverse();
+    lis
 }
 
 #[cfg(test)]
@@ -377,6 +1258,64 @@ mod tests {
         });
     }
 
+    #[test]
+    fn keyed_rekeys_when_key_signals_change() {
+        create_scope_immediate(|ctx| {
+            let a = ctx.create_signal(vec![1, 2, 3]);
+            let modulo = ctx.create_signal(10);
+            let counter = Rc::new(Cell::new(0));
+            let mapped = ctx.map_keyed(
+                a,
+                {
+                    let counter = Rc::clone(&counter);
+                    move |_, _| {
+                        counter.set(counter.get() + 1);
+                        counter.get()
+                    }
+                },
+                |x| *x % *modulo.get(),
+            );
+            assert_eq!(*mapped.get(), vec![1, 2, 3]);
+
+            // Only the key of 3 changes.
+            modulo.set(3);
+            assert_eq!(*mapped.get(), vec![1, 2, 4]);
+        });
+    }
+
+    #[test]
+    fn keyed_only_computes_keys_of_changed_items() {
+        create_scope_immediate(|ctx| {
+            let a = ctx.create_signal(vec![1, 2, 3, 4, 5]);
+            let calls = Rc::new(Cell::new(0));
+            let mapped = ctx.map_keyed(a, |_, x| x * 2, {
+                let calls = Rc::clone(&calls);
+                move |x| {
+                    calls.set(calls.get() + 1);
+                    *x
+                }
+            });
+            assert_eq!(*mapped.get(), vec![2, 4, 6, 8, 10]);
+            assert_eq!(calls.get(), 5);
+
+            calls.set(0);
+            a.set(vec![1, 2, 4, 3, 5]);
+            assert_eq!(*mapped.get(), vec![2, 4, 8, 6, 10]);
+            assert_eq!(calls.get(), 2);
+        });
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "entries 0 and 2 of keyed list have the same key of type `i32`")]
+    fn keyed_duplicate_keys_panic() {
+        create_scope_immediate(|ctx| {
+            let a = ctx.create_signal(vec![1, 2, 3]);
+            let mapped = ctx.map_keyed(a, |_, x| x, |x| *x % 2);
+            mapped.track();
+        });
+    }
+
     #[test]
     fn indexed() {
         create_scope_immediate(|ctx| {
@@ -448,4 +1387,422 @@ mod tests {
             assert_eq!(*mapped.get(), vec![1, 5, 4]);
         });
     }
+
+    #[test]
+    fn indexed_append_prepend_truncate_only_map_added_items() {
+        create_scope_immediate(|ctx| {
+            let a = ctx.create_signal(vec![1, 2]);
+            let counter = Rc::new(Cell::new(0));
+            let ops = Rc::new(RefCell::new(Vec::new()));
+            let mapped = ctx.map_indexed_with(
+                a,
+                {
+                    let counter = Rc::clone(&counter);
+                    move |_, x| {
+                        counter.set(counter.get() + 1);
+                        (x, counter.get())
+                    }
+                },
+                {
+                    let ops = Rc::clone(&ops);
+                    move |op| ops.borrow_mut().push(op)
+                },
+            );
+            let mut mirror = Vec::new();
+            apply(&mut mirror, ops.take());
+
+            a.set(vec![1, 2, 3]);
+            assert_eq!(*mapped.get(), [(1, 1), (2, 2), (3, 3)]);
+
+            a.set(vec![-1, 0, 1, 2, 3]);
+            assert_eq!(*mapped.get(), [(-1, 4), (0, 5), (1, 1), (2, 2), (3, 3)]);
+
+            a.set(vec![-1, 0]);
+            assert_eq!(*mapped.get(), [(-1, 4), (0, 5)]);
+            assert_eq!(counter.get(), 5);
+
+            apply(&mut mirror, ops.take());
+            assert_eq!(mirror, *mapped.get());
+        });
+    }
+
+    #[test]
+    fn indexed_compares_each_item_once() {
+        /// An item that counts how often it is compared.
+        #[derive(Clone)]
+        struct Item(i32, Rc<Cell<usize>>);
+        impl PartialEq for Item {
+            fn eq(&self, other: &Self) -> bool {
+                self.1.set(self.1.get() + 1);
+                self.0 == other.0
+            }
+        }
+
+        create_scope_immediate(|ctx| {
+            let comparisons = Rc::new(Cell::new(0));
+            let items = |values: &[i32]| {
+                values
+                    .iter()
+                    .map(|&value| Item(value, Rc::clone(&comparisons)))
+                    .collect::<Vec<_>>()
+            };
+            let a = ctx.create_signal(items(&[1, 2, 3, 4]));
+            let mapped = ctx.map_indexed(a, |_, item| item.0);
+
+            a.set(items(&[1, 2, 3, 4, 5, 6]));
+            assert_eq!(*mapped.get(), [1, 2, 3, 4, 5, 6]);
+            assert_eq!(comparisons.take(), 4);
+
+            a.set(items(&[1, 2, 0, 4, 5, 6]));
+            assert_eq!(*mapped.get(), [1, 2, 0, 4, 5, 6]);
+            assert_eq!(comparisons.take(), 6);
+        });
+    }
+
+    #[test]
+    fn filtered_only_notifies_on_change() {
+        create_scope_immediate(|ctx| {
+            let source = ctx.create_signal(vec![1, 2, 3, 4]);
+            let min = ctx.create_signal(2);
+            let filtered = ctx.create_filtered(source, |x| *x >= *min.get());
+            let runs = ctx.create_signal(0);
+            ctx.create_effect(|| {
+                filtered.track();
+                runs.set(*runs.get_untracked() + 1);
+            });
+            assert_eq!(*filtered.get(), [2, 3, 4]);
+
+            min.set(1);
+            assert_eq!(*filtered.get(), [1, 2, 3, 4]);
+            source.set(vec![1, 2, 3, 4, 0]);
+            // The result did not change.
+            assert_eq!(*runs.get(), 2);
+        });
+    }
+
+    #[test]
+    fn filtered_only_calls_predicate_for_changed_items() {
+        create_scope_immediate(|ctx| {
+            let source = ctx.create_signal(vec![1, 2, 3, 4, 5]);
+            let min = ctx.create_signal(2);
+            let calls = Rc::new(Cell::new(0));
+            let filtered = ctx.create_filtered(source, {
+                let calls = Rc::clone(&calls);
+                move |x| {
+                    calls.set(calls.get() + 1);
+                    *x >= *min.get()
+                }
+            });
+            assert_eq!(*filtered.get(), [2, 3, 4, 5]);
+
+            calls.set(0);
+            source.set(vec![1, 2, 6, 0, 4, 5]);
+            assert_eq!(*filtered.get(), [2, 6, 4, 5]);
+            assert_eq!(calls.get(), 2);
+
+            calls.set(0);
+            source.set(vec![1, 2, 6, 4, 5]);
+            assert_eq!(*filtered.get(), [2, 6, 4, 5]);
+            assert_eq!(calls.get(), 0);
+
+            // Changing the signal that the predicate depends on filters all the items again.
+            calls.set(0);
+            min.set(5);
+            assert_eq!(*filtered.get(), [6, 5]);
+            assert_eq!(calls.get(), 5);
+        });
+    }
+
+    #[test]
+    fn filtered_and_sorted_match_full_recomputation() {
+        create_scope_immediate(|ctx| {
+            let source = ctx.create_signal(Vec::<u32>::new());
+            let filtered = ctx.create_filtered(source, |x| x % 3 != 0);
+            let sorted = ctx.create_sorted(source, |a, b| (a % 7).cmp(&(b % 7)));
+            let mut seed = 7u32;
+            let mut next = move || {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                seed >> 16
+            };
+            let mut list = Vec::new();
+            for _ in 0..300 {
+                // Insert or remove a few items, like a user editing the list.
+                for _ in 0..next() % 4 {
+                    let index = next() as usize % (list.len() + 1);
+                    if next() % 2 == 0 || list.is_empty() {
+                        list.insert(index, next() % 50);
+                    } else {
+                        list.remove(index.min(list.len() - 1));
+                    }
+                }
+                source.set(list.clone());
+
+                let expected: Vec<_> = list.iter().copied().filter(|x| x % 3 != 0).collect();
+                assert_eq!(*filtered.get(), expected);
+                let mut expected = list.clone();
+                expected.sort_by_key(|x| x % 7);
+                assert_eq!(*sorted.get(), expected);
+            }
+        });
+    }
+
+    #[test]
+    fn grouped_only_updates_changed_groups() {
+        create_scope_immediate(|ctx| {
+            let source = ctx.create_signal(vec![1, 2, 3]);
+            let groups = ctx.create_grouped(source, |x| x % 2);
+            let odd = groups.get()[0].items();
+            let even = groups.get()[1].items();
+            let even_updates = ctx.create_signal(0);
+            ctx.create_effect(|| {
+                even.track();
+                even_updates.set(*even_updates.get_untracked() + 1);
+            });
+            let group_updates = ctx.create_signal(0);
+            ctx.create_effect(|| {
+                groups.track();
+                group_updates.set(*group_updates.get_untracked() + 1);
+            });
+
+            source.set(vec![1, 2, 3, 5]);
+            assert_eq!(*odd.get(), [1, 3, 5]);
+            assert_eq!(*even_updates.get(), 1);
+            assert_eq!(*group_updates.get(), 1);
+
+            source.set(vec![2, 4]);
+            assert_eq!(
+                *groups.get(),
+                [Group {
+                    key: 0,
+                    items: even
+                }]
+            );
+            assert_eq!(*even.get(), [2, 4]);
+            assert!(odd.get().is_empty());
+            assert_eq!(*group_updates.get(), 2);
+
+            // The signal of a group is reused.
+            source.set(vec![2, 4, 7]);
+            assert!(core::ptr::eq(groups.get()[1].items(), odd));
+            assert_eq!(*odd.get(), [7]);
+        });
+    }
+
+    #[test]
+    fn grouped_matches_full_regrouping() {
+        create_scope_immediate(|ctx| {
+            let source = ctx.create_signal(Vec::<u32>::new());
+            let grouped = ctx.create_grouped(source, |x| x % 5);
+            let mut seed = 11u32;
+            let mut next = move || {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                seed >> 16
+            };
+            let mut list = Vec::new();
+            for _ in 0..300 {
+                for _ in 0..next() % 4 {
+                    let index = next() as usize % (list.len() + 1);
+                    if next() % 2 == 0 || list.is_empty() {
+                        list.insert(index, next() % 50);
+                    } else {
+                        list.remove(index.min(list.len() - 1));
+                    }
+                }
+                source.set(list.clone());
+
+                let mut expected: Vec<(u32, Vec<u32>)> = Vec::new();
+                for item in &list {
+                    match expected.iter_mut().find(|(key, _)| *key == item % 5) {
+                        Some((_, items)) => items.push(*item),
+                        None => expected.push((item % 5, vec![*item])),
+                    }
+                }
+                let actual: Vec<_> = grouped
+                    .get()
+                    .iter()
+                    .map(|group| (*group.key(), group.items().get().as_ref().clone()))
+                    .collect();
+                assert_eq!(actual, expected);
+            }
+        });
+    }
+
+    #[test]
+    fn sorted_only_moves_changed_items() {
+        create_scope_immediate(|ctx| {
+            let source = ctx.create_signal(vec![(2, 'a'), (1, 'b'), (2, 'c')]);
+            let comparisons = Rc::new(Cell::new(0));
+            let sorted = ctx.create_sorted(source, {
+                let comparisons = Rc::clone(&comparisons);
+                move |a, b| {
+                    comparisons.set(comparisons.get() + 1);
+                    a.0.cmp(&b.0)
+                }
+            });
+            assert_eq!(*sorted.get(), [(1, 'b'), (2, 'a'), (2, 'c')]);
+
+            comparisons.set(0);
+            source.set(vec![(2, 'a'), (1, 'b'), (2, 'c'), (2, 'd'), (0, 'e')]);
+            assert_eq!(
+                *sorted.get(),
+                [(0, 'e'), (1, 'b'), (2, 'a'), (2, 'c'), (2, 'd')]
+            );
+            // Two binary searches instead of sorting all the items again.
+            assert!(comparisons.get() <= 6);
+
+            source.set(vec![(3, 'f'), (1, 'b')]);
+            assert_eq!(*sorted.get(), [(1, 'b'), (3, 'f')]);
+
+            // Removing an item in the middle is a single binary search as well.
+            comparisons.set(0);
+            source.set(vec![(3, 'f'), (2, 'g'), (1, 'b'), (0, 'h'), (4, 'i')]);
+            comparisons.set(0);
+            source.set(vec![(3, 'f'), (2, 'g'), (0, 'h'), (4, 'i')]);
+            assert_eq!(*sorted.get(), [(0, 'h'), (2, 'g'), (3, 'f'), (4, 'i')]);
+            assert!(comparisons.get() <= 4);
+        });
+    }
+
+    /// Applies `ops` to `list`.
+    fn apply<U>(list: &mut Vec<U>, ops: impl IntoIterator<Item = DiffOp<U>>) {
+        for op in ops {
+            match op {
+                DiffOp::Insert { index, value } => list.insert(index, value),
+                DiffOp::Remove { index } => {
+                    list.remove(index);
+                }
+                DiffOp::Replace { index, value } => list[index] = value,
+                DiffOp::Move { from, to } => {
+                    let value = list.remove(from);
+                    list.insert(to, value);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn keyed_with_diff() {
+        create_scope_immediate(|ctx| {
+            let a = ctx.create_signal(vec![1, 2, 3, 4, 5]);
+            let ops = Rc::new(RefCell::new(Vec::new()));
+            let mapped = ctx.map_keyed_with(a, |_, x| x * 2, |x| *x, {
+                let ops = Rc::clone(&ops);
+                move |op| ops.borrow_mut().push(op)
+            });
+            let mut mirror = Vec::new();
+            apply(&mut mirror, ops.take());
+            assert_eq!(mirror, *mapped.get());
+
+            for list in [
+                vec![5, 4, 3, 2, 1],
+                vec![1, 2, 3, 4, 5],
+                vec![1, 3, 5],
+                vec![0, 1, 2, 3, 4, 5, 6],
+                vec![6, 0, 3, 4, 2, 7],
+                vec![],
+                vec![3, 1, 2],
+                vec![2, 5, 1, 4, 3, 0],
+                vec![4, 0, 6, 2, 1, 5, 3],
+                vec![3, 1, 5, 2, 6],
+            ] {
+                a.set(list);
+                apply(&mut mirror, ops.take());
+                assert_eq!(mirror, *mapped.get());
+            }
+        });
+    }
+
+    #[test]
+    fn keyed_with_diff_shuffled() {
+        create_scope_immediate(|ctx| {
+            let a = ctx.create_signal(Vec::<i32>::new());
+            let ops = Rc::new(RefCell::new(Vec::new()));
+            let mapped = ctx.map_keyed_with(a, |_, x| x, |x| *x, {
+                let ops = Rc::clone(&ops);
+                move |op| ops.borrow_mut().push(op)
+            });
+            let mut mirror = Vec::new();
+            let mut seed = 1u32;
+            let mut random = move |n: usize| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                (seed >> 16) as usize % n
+            };
+            for _ in 0..500 {
+                let mut list: Vec<_> = (0..12).collect();
+                for i in (1..list.len()).rev() {
+                    list.swap(i, random(i + 1));
+                }
+                list.truncate(random(13));
+                a.set(list);
+                apply(&mut mirror, ops.take());
+                assert_eq!(mirror, *mapped.get());
+            }
+        });
+    }
+
+    #[test]
+    fn keyed_with_diff_moves_minimal() {
+        create_scope_immediate(|ctx| {
+            let a = ctx.create_signal(vec![1, 2, 3, 4, 5]);
+            let ops = Rc::new(RefCell::new(Vec::new()));
+            ctx.map_keyed_with(a, |_, x| x, |x| *x, {
+                let ops = Rc::clone(&ops);
+                move |op| ops.borrow_mut().push(op)
+            });
+            ops.borrow_mut().clear();
+
+            a.set(vec![1, 2, 5, 3, 4]);
+            assert_eq!(*ops.borrow(), [DiffOp::Move { from: 4, to: 2 }]);
+            ops.borrow_mut().clear();
+
+            a.set(vec![1, 2, 6, 5, 3, 4]);
+            assert_eq!(*ops.borrow(), [DiffOp::Insert { index: 2, value: 6 }]);
+            ops.borrow_mut().clear();
+
+            a.set(vec![1, 2, 6, 5, 3, 4]);
+            assert!(ops.borrow().is_empty());
+        });
+    }
+
+    #[test]
+    fn indexed_with_diff() {
+        create_scope_immediate(|ctx| {
+            let a = ctx.create_signal(vec![1, 2, 3]);
+            let ops = Rc::new(RefCell::new(Vec::new()));
+            let mapped = ctx.map_indexed_with(a, |_, x| x * 2, {
+                let ops = Rc::clone(&ops);
+                move |op| ops.borrow_mut().push(op)
+            });
+            let mut mirror = Vec::new();
+            apply(&mut mirror, ops.take());
+            assert_eq!(mirror, *mapped.get());
+
+            a.set(vec![1, 4, 3, 5]);
+            assert_eq!(
+                *ops.borrow(),
+                [
+                    DiffOp::Replace { index: 1, value: 8 },
+                    DiffOp::Insert {
+                        index: 3,
+                        value: 10
+                    },
+                ]
+            );
+            for list in [vec![1, 4, 3, 5], vec![2], vec![], vec![3, 2, 1]] {
+                a.set(list);
+                apply(&mut mirror, ops.take());
+                assert_eq!(mirror, *mapped.get());
+            }
+        });
+    }
+
+    #[test]
+    fn longest_increasing_subsequence_indexes() {
+        assert_eq!(longest_increasing_subsequence(&[]), Vec::<usize>::new());
+        assert_eq!(longest_increasing_subsequence(&[3, 2, 1]).len(), 1);
+        assert_eq!(
+            longest_increasing_subsequence(&[0, 8, 4, 12, 2, 10, 6, 14]),
+            [0, 4, 6, 7]
+        );
+    }
 }
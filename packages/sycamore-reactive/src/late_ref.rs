@@ -0,0 +1,151 @@
+//! References that are set after they are created.
+
+use core::cell::OnceCell;
+use core::fmt;
+
+use crate::*;
+
+/// A callback registered with [`LateRef::on_set`].
+type OnSet<'a, T> = Box<dyn FnOnce(&T) + 'a>;
+
+/// A reference that starts empty and can be set once, e.g. to an element after it is mounted.
+/// Created with [`create_late_ref`](Scope::create_late_ref).
+///
+/// Unlike a `Signal<Option<T>>`, the value can be borrowed for as long as the reference lives
+/// once it is set, and callbacks can be registered to be called when it becomes available.
+pub struct LateRef<'a, T> {
+    value: OnceCell<T>,
+    available: Signal<bool>,
+    callbacks: RefCell<Vec<OnSet<'a, T>>>,
+}
+
+impl<'a, T> LateRef<'a, T> {
+    /// Returns the value if it has been set. This method is not reactive. Use
+    /// [`is_set`](Self::is_set) to track when the value becomes available.
+    pub fn get(&self) -> Option<&T> {
+        self.value.get()
+    }
+
+    /// Returns a signal that is `true` once the value has been set.
+    pub fn is_set(&self) -> &ReadSignal<bool> {
+        &self.available
+    }
+
+    /// Sets the value, calls the callbacks that were registered with [`on_set`](Self::on_set) and
+    /// then notifies the subscribers of [`is_set`](Self::is_set).
+    ///
+    /// # Panics
+    /// This method panics if the value has already been set. For a non-panicking version, see
+    /// [`try_set`](Self::try_set).
+    #[track_caller]
+    pub fn set(&self, value: T) {
+        if self.try_set(value).is_err() {
+            panic!("late ref has already been set");
+        }
+    }
+
+    /// Sets the value like [`set`](Self::set), or returns it back if the value has already been
+    /// set.
+    pub fn try_set(&self, value: T) -> Result<(), T> {
+        self.value.set(value)?;
+        let value = self.value.get().unwrap();
+        untrack(|| {
+            for f in self.callbacks.take() {
+                f(value);
+            }
+        });
+        self.available.set(true);
+        Ok(())
+    }
+
+    /// Calls `f` with the value once it is set, or immediately if it has been set already.
+    pub fn on_set(&self, f: impl FnOnce(&T) + 'a) {
+        match self.value.get() {
+            Some(value) => untrack(|| f(value)),
+            None => self.callbacks.borrow_mut().push(Box::new(f)),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for LateRef<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("LateRef").field(&self.value.get()).finish()
+    }
+}
+
+impl<'a> Scope<'a> {
+    /// Creates an empty [`LateRef`] that can be set once later, e.g. after an element is mounted.
+    /// Effects can wait for the value by tracking [`LateRef::is_set`] instead of polling.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let width = ctx.create_late_ref::<u32>();
+    /// let doubled = ctx.create_memo(|| width.is_set().get().then(|| width.get().unwrap() * 2));
+    /// assert_eq!(*doubled.get(), None);
+    ///
+    /// width.set(100);
+    /// assert_eq!(*doubled.get(), Some(200));
+    /// # });
+    /// ```
+    pub fn create_late_ref<T: 'a>(&'a self) -> &'a LateRef<'a, T> {
+        self.create_ref(LateRef {
+            value: OnceCell::new(),
+            available: Signal::new(false),
+            callbacks: Default::default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn late_ref_can_only_be_set_once() {
+        create_scope_immediate(|ctx| {
+            let late = ctx.create_late_ref();
+            assert_eq!(late.get(), None);
+            assert_eq!(late.try_set(1), Ok(()));
+            assert_eq!(late.try_set(2), Err(2));
+            assert_eq!(late.get(), Some(&1));
+        });
+    }
+
+    #[test]
+    fn on_set_callbacks() {
+        create_scope_immediate(|ctx| {
+            let late = ctx.create_late_ref();
+            let seen = ctx.create_signal(Vec::new());
+            late.on_set(|value: &i32| {
+                seen.set([seen.get_untracked().as_slice(), &[*value]].concat())
+            });
+            assert!(seen.get().is_empty());
+
+            late.set(1);
+            assert_eq!(*seen.get(), [1]);
+            // Callbacks that are registered later are called immediately.
+            late.on_set(|value| {
+                seen.set([seen.get_untracked().as_slice(), &[*value + 1]].concat())
+            });
+            assert_eq!(*seen.get(), [1, 2]);
+        });
+    }
+
+    #[test]
+    fn effects_wait_for_late_ref() {
+        create_scope_immediate(|ctx| {
+            let late = ctx.create_late_ref::<&str>();
+            let runs = ctx.create_signal(0);
+            ctx.create_effect(|| {
+                if *late.is_set().get() {
+                    assert_eq!(late.get(), Some(&"ready"));
+                }
+                runs.set(*runs.get_untracked() + 1);
+            });
+            late.set("ready");
+            assert_eq!(*runs.get(), 2);
+        });
+    }
+}
@@ -1,32 +1,170 @@
 //! Reactive primitives for Sycamore.
+//!
+//! ## Feature Flags
+//! - `debug-tools` - Enables diagnostic tools such as naming signals and tracing the runs of memos
+//!   and effects. When disabled, the diagnostic APIs are no-ops that are compiled away.
+//! - `futures` - Enables awaiting the next change of a signal with `ReadSignal::changed`.
+//! - `persist` - Enables persisting signals to a `StorageBackend` using `serde`.
+//! - `serde` - Enables serializing and deserializing `Signal`s and other wrapper types using
+//!   `serde`.
+//! - `std` _(enabled by default)_ - Enables the parts that need the standard library: channels
+//!   and catching panics in effects (see [`set_catch_effect_panics`]). Without it, the crate only
+//!   depends on `core` and `alloc` so that it can be used on embedded targets. Since the reactive
+//!   runtime is stored in globals instead of thread locals in that case, it must only be used
+//!   from a single thread (and not from interrupt handlers), which the application declares by
+//!   calling `assume_single_threaded` before creating a scope.
+//! - `web` - Enables browser-specific utilities such as the `LocalStorage` and `SessionStorage`
+//!   backends, reactive media queries and signals that are synced across tabs. Also enables
+//!   `persist`.
+//! - `worker-sync` - Enables mirroring signals between the main thread and web workers with
+//!   `SignalSync`. Also enables `web`.
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
 
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+#[macro_use]
+mod local;
+
 mod arena;
+mod bus;
+#[cfg(feature = "futures")]
+mod changed;
+#[cfg(feature = "std")]
+mod channel;
 mod context;
+mod debug;
+mod derived;
 mod effect;
+mod effect_group;
+mod error;
+mod event;
+mod float_eq;
+mod form;
+mod hook;
+mod i18n;
 mod iter;
+mod late_ref;
+mod maybe_dyn;
 mod memo;
+mod memo_cache;
+mod output;
+mod pagination;
+#[cfg(feature = "persist")]
+mod persist;
+mod raw_node;
+mod runtime;
+mod scheduler;
+mod scope_map;
+mod service;
 mod signal;
+mod signal_cell;
+mod suspense;
+mod transition;
+mod trigger;
+mod validate;
+#[cfg(feature = "web")]
+mod web;
+#[cfg(feature = "worker-sync")]
+mod worker_sync;
 
+pub use bus::*;
+#[cfg(feature = "futures")]
+pub use changed::*;
+#[cfg(feature = "std")]
+pub use channel::*;
+pub use context::*;
+pub use debug::{finish_trace, start_trace, NodeStats, ReactiveStats, SignalStats};
+pub use derived::*;
 pub use effect::*;
+pub use effect_group::*;
+pub use error::*;
+pub use event::*;
+pub use float_eq::*;
+pub use form::*;
+pub use i18n::*;
+pub use iter::*;
+pub use late_ref::*;
+#[cfg(not(feature = "std"))]
+pub use local::assume_single_threaded;
+pub use maybe_dyn::*;
+pub use memo::*;
+pub use memo_cache::*;
+pub use output::*;
+pub use pagination::*;
+#[cfg(feature = "persist")]
+pub use persist::*;
+pub use raw_node::*;
+pub use runtime::*;
+pub use scheduler::{batch, set_max_update_depth, NodeState};
+pub use scope_map::*;
 pub use signal::*;
+pub use signal_cell::*;
+pub use suspense::*;
+pub use transition::*;
+pub use trigger::*;
+pub use validate::*;
+#[cfg(feature = "web")]
+pub use web::*;
+#[cfg(feature = "worker-sync")]
+pub use worker_sync::*;
 
-use std::any::{Any, TypeId};
-use std::cell::RefCell;
-use std::collections::HashMap;
-use std::marker::PhantomData;
-use std::ops::Deref;
-use std::rc::{Rc, Weak};
+use alloc::rc::{Rc, Weak};
+#[cfg(not(feature = "std"))]
+use alloc::{boxed::Box, string::String, vec, vec::Vec};
+use core::any::{Any, TypeId};
+use core::cell::{Cell, Ref, RefCell, RefMut};
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::ops::Deref;
+#[cfg(not(feature = "std"))]
+use hashbrown::{hash_map, HashMap};
+#[cfg(feature = "std")]
+use std::collections::{hash_map, HashMap};
 
 use arena::*;
-use indexmap::IndexMap;
+use hook::HookMap;
+use runtime::with_active_scope;
+use scheduler::{NodeKind, Scheduler};
 use slotmap::{DefaultKey, SlotMap};
 
 /// A wrapper type around a lifetime that forces the lifetime to be invariant.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 struct InvariantLifetime<'id>(PhantomData<&'id mut &'id ()>);
 
+/// A [`RefCell`] that only allocates its value on the heap the first time it is mutably borrowed.
+///
+/// Most scopes (e.g. the scope of every row in a keyed list) never use some of their fields, so
+/// these are kept to a single pointer until they are actually needed.
+struct LazyCell<T>(RefCell<Option<Box<T>>>);
+
+impl<T> Default for LazyCell<T> {
+    fn default() -> Self {
+        Self(RefCell::new(None))
+    }
+}
+
+impl<T: Default> LazyCell<T> {
+    /// Immutably borrows the value, or returns `None` if it has not been allocated yet.
+    fn borrow(&self) -> Option<Ref<'_, T>> {
+        Ref::filter_map(self.0.borrow(), |value| value.as_deref()).ok()
+    }
+
+    /// Mutably borrows the value, allocating it first if necessary.
+    fn borrow_mut(&self) -> RefMut<'_, T> {
+        RefMut::map(self.0.borrow_mut(), |value| {
+            &mut **value.get_or_insert_with(Default::default)
+        })
+    }
+
+    /// Takes the value out of the cell, or returns `None` if it has not been allocated yet.
+    fn take(&self) -> Option<T> {
+        self.0.take().map(|value| *value)
+    }
+}
+
 /// A reactive scope.
 ///
 /// The only way to ever use a scope should be behind a reference.
@@ -45,22 +183,28 @@ pub struct Scope<'a> {
     /// Effect functions created on the [`Scope`].
     effects: RefCell<Vec<Rc<RefCell<Option<EffectState<'a>>>>>>,
     /// Cleanup functions.
-    cleanups: RefCell<Vec<Box<dyn FnOnce() + 'a>>>,
+    cleanups: LazyCell<Vec<Box<dyn FnOnce() + 'a>>>,
     /// Child scopes.
     ///
     /// The raw pointer is owned by this field.
-    child_scopes: RefCell<SlotMap<DefaultKey, *mut Scope<'a>>>,
+    child_scopes: LazyCell<SlotMap<DefaultKey, *mut Scope<'a>>>,
     /// An arena allocator for allocating refs and signals.
     arena: ScopeArena<'a>,
     /// Contexts that are allocated on the current [`Scope`].
     /// See the [`mod@context`] module.
     ///
     /// The raw pointer is owned by this field.
-    contexts: RefCell<HashMap<TypeId, *mut (dyn Any)>>,
+    contexts: LazyCell<HashMap<TypeId, ContextEntry>>,
+    /// Values cached by [`use_hook`](Self::use_hook). See the [`mod@hook`] module.
+    hooks: LazyCell<HookMap>,
+    /// The runtime that the effects of the scope are run in.
+    runtime: Runtime,
     /// A pointer to the parent scope.
     /// # Safety
     /// The parent scope does not actually have the right lifetime.
     parent: Option<*const Scope<'a>>,
+    /// Whether [`dispose`](Self::dispose) has been called.
+    disposed: Cell<bool>,
     // Make sure that 'a is invariant.
     _phantom: InvariantLifetime<'a>,
 }
@@ -68,7 +212,7 @@ pub struct Scope<'a> {
 impl<'a> Scope<'a> {
     /// Create a new [`Scope`]. This function is deliberately not `pub` because it should not be
     /// possible to access a [`Scope`] directly on the stack.
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(runtime: Runtime) -> Self {
         // Even though the initialization code below is same as deriving Default::default(), we
         // can't do that because accessing a raw Scope outside of a scope closure breaks
         // safety contracts.
@@ -80,10 +224,57 @@ impl<'a> Scope<'a> {
             child_scopes: Default::default(),
             arena: Default::default(),
             contexts: Default::default(),
+            hooks: Default::default(),
+            runtime,
             parent: None,
+            disposed: Cell::new(false),
             _phantom: Default::default(),
         }
     }
+
+    /// Allocates a new [`Scope`] on the heap, reusing the allocation of a previously disposed
+    /// scope if one is available. This avoids hitting the allocator every time a scoped effect
+    /// re-executes or a row of a keyed list is recreated.
+    ///
+    /// The returned pointer should be released with [`free_pooled`](Self::free_pooled).
+    pub(crate) fn new_pooled(runtime: Runtime) -> *mut Self {
+        let slot = SCOPE_POOL
+            .with(|pool| pool.borrow_mut().pop())
+            .unwrap_or_else(|| Box::new(MaybeUninit::uninit()));
+        let ptr = Box::into_raw(slot) as *mut Self;
+        // SAFETY: ptr points to an allocation for a Scope that does not contain a value.
+        unsafe { ptr.write(Self::new(runtime)) };
+        ptr
+    }
+
+    /// Disposes and drops the [`Scope`] and returns its allocation to the pool.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been created with [`new_pooled`](Self::new_pooled) and must not be used
+    /// afterwards. The same restrictions as for [`dispose`](Self::dispose) apply.
+    pub(crate) unsafe fn free_pooled(ptr: *mut Self) {
+        (*ptr).dispose();
+        core::ptr::drop_in_place(ptr);
+        // SAFETY: The value has been dropped, so only the allocation remains. The lifetime is
+        // never observed because the allocation does not contain a value until it is reused.
+        let slot = Box::from_raw(ptr as *mut MaybeUninit<Scope<'static>>);
+        SCOPE_POOL.with(|pool| {
+            let mut pool = pool.borrow_mut();
+            if pool.len() < SCOPE_POOL_CAPACITY {
+                pool.push(slot);
+            }
+        });
+    }
+}
+
+/// Maximum number of scope allocations that are kept around for reuse on each thread.
+const SCOPE_POOL_CAPACITY: usize = 64;
+
+thread_local! {
+    /// Allocations of disposed scopes that can be reused by [`Scope::new_pooled`].
+    #[allow(clippy::vec_box)] // The boxed allocations are what is being reused.
+    static SCOPE_POOL: RefCell<Vec<Box<MaybeUninit<Scope<'static>>>>> = Default::default();
 }
 
 /// A reference to a [`Scope`].
@@ -116,6 +307,8 @@ impl<'a, 'bound> Deref for BoundedScopeRef<'a, 'bound> {
 ///
 /// The callback closure is called in an [untracked](untrack) scope.
 ///
+/// The scope and all of its child scopes use the current [`Runtime`] (see [`Runtime::enter`]).
+///
 /// # Scope lifetime
 ///
 /// The lifetime of the child scope is arbitrary. As such, it is impossible for anything allocated
@@ -143,25 +336,125 @@ impl<'a, 'bound> Deref for BoundedScopeRef<'a, 'bound> {
 /// ```
 #[must_use = "not calling the disposer function will result in a memory leak"]
 pub fn create_scope(f: impl for<'a> FnOnce(ScopeRef<'a>)) -> impl FnOnce() {
-    let ctx = Scope::new();
-    let boxed = Box::new(ctx);
-    let ptr = Box::into_raw(boxed);
+    let ptr = Scope::new_pooled(Runtime::current());
     // SAFETY: Safe because heap allocated value has stable address.
     // The reference passed to f cannot possible escape the closure. We know however, that ptr
     // necessary outlives the closure call because it is only dropped in the returned disposer
     // closure.
-    untrack(|| f(unsafe { &*ptr }));
-    //                      ^^^ -> `ptr` is still accessible here after the call to f.
+    untrack(|| unsafe {
+        let scheduler = (*ptr).runtime.scheduler();
+        scheduler.construct(|| (*ptr).enter(|| f(&*ptr)))
+    });
+    //                                      ^^^ -> `ptr` is still accessible here
+    // after the call to f.
 
     // Ownership of `ptr` is passed into the closure.
     move || unsafe {
-        // SAFETY: Safe because ptr created using Box::into_raw.
-        let boxed = Box::from_raw(ptr);
-        // SAFETY: Outside of call to f.
-        boxed.dispose();
+        // SAFETY: Safe because ptr created using Scope::new_pooled and outside of call to f.
+        Scope::free_pooled(ptr);
+    }
+}
+
+/// Creates a reactive scope for server side rendering. See [`create_scope`].
+///
+/// The scope and all of its child scopes use a new [`Runtime`] in which effects and memos are run
+/// once when they are created but never subscribe to the signals that they use. Updating a signal
+/// afterwards does not re-run anything. Effects and memos do not keep any state after their first
+/// run, which saves work when a view is only rendered once. Signals are still created as usual.
+///
+/// # Example
+/// ```
+/// # use sycamore_reactive::*;
+/// let disposer = create_ssr_scope(|ctx| {
+///     let state = ctx.create_signal(1);
+///     let double = ctx.create_memo(|| *state.get() * 2);
+///     assert_eq!(*double.get(), 2);
+///
+///     state.set(2);
+///     assert_eq!(*double.get(), 2);
+/// });
+/// disposer();
+/// ```
+#[must_use = "not calling the disposer function will result in a memory leak"]
+pub fn create_ssr_scope(f: impl for<'a> FnOnce(ScopeRef<'a>)) -> impl FnOnce() {
+    Runtime::new_ssr().enter(|| create_scope(f))
+}
+
+/// Options for [`create_root_with`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RootOptions {
+    /// Whether panics in effects and memos that are not caught by an error boundary (see
+    /// [`Scope::create_error_boundary`]) are printed to stderr instead of being propagated to the
+    /// code that updated a signal (see [`Scope::set_error_hook`]). This also applies to nodes that
+    /// are not run because the maximum update depth was exceeded (see [`set_max_update_depth`]).
+    /// Only has an effect with the `std` feature. Defaults to `true`.
+    pub error_hook: bool,
+    /// Whether to install a panic hook that prints the path of the scope (see
+    /// [`Scope::set_debug_name`]) in which a panic happened, after calling the previous panic
+    /// hook. Only has an effect with the `std` feature. Defaults to `false`.
+    pub panic_hook: bool,
+}
+
+impl Default for RootOptions {
+    fn default() -> Self {
+        Self {
+            error_hook: true,
+            panic_hook: false,
+        }
     }
 }
 
+/// Creates a root scope like [`create_scope`], set up as the entry point of an app that uses the
+/// reactive primitives directly:
+/// * Panics in effects and memos that are not caught by an error boundary (see
+///   [`Scope::create_error_boundary`]) are printed to stderr by an error hook instead of being
+///   propagated to the code that updated a signal (see [`Scope::set_error_hook`]), so that the app
+///   keeps running. This requires the `std` feature.
+///
+/// Use [`create_root_with`] to change this, or to install a panic hook that also prints the path
+/// of the scope in which a panic happened.
+///
+/// # Example
+/// ```
+/// # use sycamore_reactive::*;
+/// let disposer = create_root(|ctx| {
+///     ctx.set_debug_name("app");
+///     let state = ctx.create_signal(0);
+///     ctx.create_effect(|| {
+///         if *state.get() == 1 {
+///             panic!("state is one");
+///         }
+///     });
+///     state.set(1); // Printed by the error hook instead of propagated.
+/// });
+/// disposer();
+/// ```
+#[must_use = "not calling the disposer function will result in a memory leak"]
+pub fn create_root(f: impl for<'a> FnOnce(ScopeRef<'a>)) -> impl FnOnce() {
+    create_root_with(RootOptions::default(), f)
+}
+
+/// Like [`create_root`], with the [`RootOptions`] `options`.
+#[must_use = "not calling the disposer function will result in a memory leak"]
+pub fn create_root_with(
+    options: RootOptions,
+    f: impl for<'a> FnOnce(ScopeRef<'a>),
+) -> impl FnOnce() {
+    #[cfg(feature = "std")]
+    if options.panic_hook {
+        install_panic_hook();
+    }
+    #[cfg(not(feature = "std"))]
+    let _ = options;
+    create_scope(move |ctx| {
+        #[cfg(feature = "std")]
+        if options.error_hook {
+            ctx.set_error_hook(report_uncaught_panic);
+        }
+        f(ctx);
+    })
+}
+
 /// Creates a reactive scope, runs the callback, and disposes the scope immediately.
 ///
 /// Calling this is equivalent to writing:
@@ -176,6 +469,20 @@ pub fn create_scope_immediate(f: impl for<'a> FnOnce(ScopeRef<'a>)) {
     disposer();
 }
 
+/// Adds a callback that is called when the active scope, i.e. the scope whose body or effect is
+/// currently running, is destroyed. See also [`Scope::on_cleanup`].
+///
+/// # Panics
+/// This function panics if there is no active scope.
+#[track_caller]
+pub fn on_cleanup(f: impl FnOnce() + 'static) {
+    with_active_scope(|scope| {
+        scope
+            .expect("on_cleanup called outside of a reactive scope")
+            .on_cleanup(f)
+    });
+}
+
 impl<'a> Scope<'a> {
     /// Create a new [`Signal`] under the current [`Scope`].
     /// The created signal lasts as long as the scope and cannot be used outside of the scope.
@@ -198,6 +505,50 @@ impl<'a> Scope<'a> {
         self.arena.alloc(signal)
     }
 
+    /// Create a new [`Signal`] under the current [`Scope`] like
+    /// [`create_signal`](Self::create_signal), that only notifies its subscribers when it is set
+    /// to a value that is not equal to the current value according to `eq`.
+    ///
+    /// Unlike a selector (see [`create_selector_with`](Self::create_selector_with)), all the
+    /// effects and memos that depend on the signal benefit from the comparison, which is only
+    /// done once per update.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// // Only notify when the rounded value changes.
+    /// let state = ctx.create_signal_with_eq(1.0_f64, |a, b| a.round() == b.round());
+    /// let runs = ctx.create_signal(0);
+    /// ctx.create_effect(|| {
+    ///     state.track();
+    ///     runs.set(*runs.get_untracked() + 1);
+    /// });
+    ///
+    /// state.set(1.2);
+    /// assert_eq!(*runs.get(), 1);
+    /// assert_eq!(*state.get(), 1.0); // The value is not updated either.
+    ///
+    /// state.set(2.0);
+    /// assert_eq!(*runs.get(), 2);
+    /// # });
+    /// ```
+    pub fn create_signal_with_eq<T>(
+        &'a self,
+        value: T,
+        eq: impl Fn(&T, &T) -> bool + 'a,
+    ) -> &'a Signal<T> {
+        let signal = Signal::new_with_eq(value, eq);
+        self.arena.alloc(signal)
+    }
+
+    /// Create a new [`Signal`] under the current [`Scope`] that only notifies its subscribers
+    /// when it is set to a different value. Shorthand for
+    /// [`create_signal_with_eq`](Self::create_signal_with_eq) with [`PartialEq::eq`].
+    pub fn create_signal_eq<T: PartialEq>(&'a self, value: T) -> &'a Signal<T> {
+        self.create_signal_with_eq(value, T::eq)
+    }
+
     /// Allocate a new arbitrary value under the current [`Scope`].
     /// The allocated value lasts as long as the scope and cannot be used outside of the scope.
     ///
@@ -277,37 +628,66 @@ impl<'a> Scope<'a> {
     where
         F: for<'child_lifetime> FnOnce(BoundedScopeRef<'child_lifetime, 'a>),
     {
-        let mut child: Scope = Scope::new();
+        let ptr: *mut Scope = Scope::new_pooled(self.runtime.clone());
         // SAFETY: The only fields that are accessed on self from child is `context` which does not
         // have any lifetime annotations.
-        child.parent = Some(unsafe { std::mem::transmute(self as *const _) });
-        let boxed = Box::new(child);
-        let ptr = Box::into_raw(boxed);
+        unsafe { (*ptr).parent = Some(core::mem::transmute(self as *const _)) };
 
         let key = self
             .child_scopes
             .borrow_mut()
             // SAFETY: None of the fields of ptr are accessed through child_scopes therefore we can
             // safely transmute the lifetime.
-            .insert(unsafe { std::mem::transmute(ptr) });
+            .insert(unsafe { core::mem::transmute(ptr) });
 
         // SAFETY: the address of the Ctx lives as long as 'a because:
         // - It is allocated on the heap and therefore has a stable address.
         // - self.child_ctx is append only. That means that the Box<Ctx> will not be dropped until
         //   Self is dropped.
-        f(BoundedScopeRef::new(unsafe { &*ptr }));
-        //                                    ^^^ -> `ptr` is still accessible here after
-        // the call to f.
+        self.runtime
+            .scheduler()
+            .construct(|| unsafe { (*ptr).enter(|| f(BoundedScopeRef::new(&*ptr))) });
+        //                                                ^^^ -> `ptr` is still accessible here
+        // after the call to f.
         move || unsafe {
-            let ctx = self.child_scopes.borrow_mut().remove(key).unwrap();
-            // SAFETY: Safe because ptr created using Box::into_raw and closure cannot live longer
-            // than 'a.
-            let ctx = Box::from_raw(ctx);
-            // SAFETY: Outside of call to f.
-            ctx.dispose();
+            // The child scope has already been disposed if the disposer is called while the
+            // parent scope is being disposed (e.g. if the disposer is stored in a signal).
+            let ctx = self.child_scopes.borrow_mut().remove(key);
+            if let Some(ctx) = ctx {
+                // SAFETY: Safe because ptr created using Scope::new_pooled, closure cannot live
+                // longer than 'a, and outside of call to f.
+                Scope::free_pooled(ctx);
+            }
         }
     }
 
+    /// Create a child scope that is disposed when the returned [`ScopeHandle`] is dropped.
+    ///
+    /// This is an alternative to [`create_child_scope`](Self::create_child_scope) for when the
+    /// lifetime of the child scope is tied to a value, e.g. an entry in a cache. The handle can be
+    /// stored anywhere in the parent scope, including in a signal. If the handle is still alive
+    /// when the parent scope is disposed, the child scope is disposed along with the parent scope.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let current = ctx.create_signal(None);
+    /// let handle = ctx.create_detached_child_scope(|ctx| {
+    ///     ctx.on_cleanup(|| println!("disposed"));
+    /// });
+    /// current.set(Some(handle));
+    /// current.set(None); // Prints "disposed"
+    /// # });
+    /// ```
+    #[must_use = "dropping the handle disposes the child scope"]
+    pub fn create_detached_child_scope<F>(&'a self, f: F) -> ScopeHandle<'a>
+    where
+        F: for<'child_lifetime> FnOnce(BoundedScopeRef<'child_lifetime, 'a>),
+    {
+        ScopeHandle(Some(Box::new(self.create_child_scope(f))))
+    }
+
     /// Cleanup the resources owned by the [`Scope`]. This is automatically called in [`Drop`]
     /// However, [`dispose`](Self::dispose) only needs to take `&self` instead of `&mut self`.
     /// Dropping a [`Scope`] will automatically call [`dispose`](Self::dispose).
@@ -327,30 +707,32 @@ impl<'a> Scope<'a> {
     /// * `child_scopes` - Run child scope drop first.
     /// * `effects`
     /// * `cleanups`
+    /// * `hooks` - Only holds pointers to values that are owned by the arena.
     /// * `contexts` - Contexts can be refereed to inside a cleanup callback so they are dropped
     ///   after cleanups.
     /// * `arena` - Signals and refs are dropped last because they can be refereed to in the other
     ///   fields (e.g. inside a cleanup callback).
     pub(crate) unsafe fn dispose(&self) {
+        self.disposed.set(true);
         // Drop child contexts.
-        for &i in self.child_scopes.take().values() {
+        for &i in self.child_scopes.take().iter().flat_map(SlotMap::values) {
             // SAFETY: These pointers were allocated in Self::create_child_scope.
-            let ctx = Box::from_raw(i);
-            // Dispose of ctx if it has not already been disposed.
-            ctx.dispose()
+            Scope::free_pooled(i);
         }
         // Drop effects.
         drop(self.effects.take());
         // Call cleanup functions in an untracked scope.
         untrack(|| {
-            for cb in self.cleanups.take() {
+            for cb in self.cleanups.take().into_iter().flatten() {
                 cb();
             }
         });
+        // The hook values are owned by the arena.
+        drop(self.hooks.take());
         // Cleanup context values.
-        for &i in self.contexts.take().values() {
-            // SAFETY: These pointers were allocated in Self::provide_context.
-            drop(Box::from_raw(i));
+        for entry in self.contexts.take().iter().flat_map(HashMap::values) {
+            // SAFETY: These pointers were allocated in Self::insert_context.
+            drop(Box::from_raw(entry.value));
         }
         // Cleanup signals and refs allocated on the arena.
         self.arena.dispose();
@@ -366,6 +748,37 @@ impl<'a> Scope<'a> {
         });
         status
     }
+
+    /// Runs `f` with this scope as the reactive context, or returns `None` if the scope is being
+    /// disposed.
+    ///
+    /// This is meant for callbacks that are called from outside of the reactive system, such as
+    /// JS event listeners or channel receivers, and that create signals or effects on an existing
+    /// scope. The closure is run in the [`Runtime`] of the scope with the scope as the active scope
+    /// (see [`use_context`](crate::use_context())) and is [untracked](untrack), so that the effects
+    /// it creates belong to the scope even if the callback happens to be called while another
+    /// effect is running.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let count = ctx.create_signal(0);
+    /// let on_message = |message: i32| {
+    ///     ctx.run_in(|ctx| {
+    ///         let doubled = ctx.create_memo(move || *count.get() * message);
+    ///         assert_eq!(*doubled.get(), 0);
+    ///     })
+    /// };
+    /// assert!(on_message(2).is_some());
+    /// # });
+    /// ```
+    pub fn run_in<T>(&'a self, f: impl FnOnce(ScopeRef<'a>) -> T) -> Option<T> {
+        if self.disposed.get() {
+            return None;
+        }
+        Some(self.enter(|| untrack(|| f(self))))
+    }
 }
 
 impl Drop for Scope<'_> {
@@ -375,6 +788,38 @@ impl Drop for Scope<'_> {
     }
 }
 
+/// A handle to a child scope created with
+/// [`create_detached_child_scope`](Scope::create_detached_child_scope). The child scope is
+/// disposed when the handle is dropped.
+pub struct ScopeHandle<'a>(Option<Box<dyn FnOnce() + 'a>>);
+
+impl ScopeHandle<'_> {
+    /// Disposes the child scope now. This is the same as dropping the handle.
+    pub fn dispose(self) {
+        drop(self);
+    }
+
+    /// Releases the handle without disposing the child scope. The child scope is disposed along
+    /// with the parent scope instead.
+    pub fn detach(mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for ScopeHandle<'_> {
+    fn drop(&mut self) {
+        if let Some(disposer) = self.0.take() {
+            disposer();
+        }
+    }
+}
+
+impl core::fmt::Debug for ScopeHandle<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("ScopeHandle").finish()
+    }
+}
+
 /// A helper function for making it explicit to define dependencies for an effect.
 ///
 /// # Params
@@ -409,7 +854,7 @@ pub fn on<'a, const N: usize>(
 
 #[cfg(test)]
 mod tests {
-    use crate::{create_scope, create_scope_immediate};
+    use crate::{create_rc_signal, create_scope, create_scope_immediate};
 
     #[test]
     fn refs() {
@@ -423,6 +868,51 @@ mod tests {
         disposer();
     }
 
+    #[test]
+    fn root_catches_effect_panics() {
+        let disposer = crate::create_root(|ctx| {
+            let state = ctx.create_signal(0);
+            let runs = ctx.create_signal(0);
+            ctx.create_effect(|| {
+                runs.set(*runs.get_untracked() + 1);
+                if *state.get() == 1 {
+                    panic!("state is one");
+                }
+            });
+            state.set(1);
+            // The effect is still subscribed to `state`.
+            state.set(2);
+            assert_eq!(*runs.get(), 3);
+
+            // Error boundaries still take precedence.
+            let _ = ctx.create_child_scope(|ctx| {
+                let boundary = ctx.create_error_boundary();
+                ctx.create_effect(|| panic!("caught by boundary"));
+                assert!(boundary.error().is_some());
+            });
+        });
+        disposer();
+    }
+
+    #[test]
+    fn root_without_error_hook_propagates_effect_panics() {
+        let options = crate::RootOptions {
+            error_hook: false,
+            ..Default::default()
+        };
+        let disposer = crate::create_root_with(options, |ctx| {
+            let state = ctx.create_signal(0);
+            ctx.create_effect(|| {
+                if *state.get() == 1 {
+                    panic!("state is one");
+                }
+            });
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| state.set(1)));
+            assert!(result.is_err());
+        });
+        disposer();
+    }
+
     #[test]
     fn cleanup() {
         create_scope_immediate(|ctx| {
@@ -493,4 +983,121 @@ mod tests {
             signal.set(Some(disposer));
         });
     }
+
+    #[test]
+    fn scope_handle_disposes_on_drop() {
+        create_scope_immediate(|ctx| {
+            let disposed = ctx.create_signal(false);
+            let handle = ctx.create_detached_child_scope(|ctx| {
+                ctx.on_cleanup(|| disposed.set(true));
+            });
+            assert!(!*disposed.get());
+            drop(handle);
+            assert!(*disposed.get());
+
+            let handle = ctx.create_detached_child_scope(|ctx| {
+                ctx.on_cleanup(|| disposed.set(false));
+            });
+            handle.detach();
+            assert!(*disposed.get());
+        });
+    }
+
+    #[test]
+    fn can_store_scope_handle_in_own_signal() {
+        let disposed = create_rc_signal(false);
+        create_scope_immediate(|ctx| {
+            let signal = ctx.create_signal(None);
+            let handle = ctx.create_detached_child_scope(|ctx| {
+                let disposed = disposed.clone();
+                ctx.on_cleanup(move || disposed.set(true));
+            });
+            signal.set(Some(handle));
+        });
+        assert!(*disposed.get());
+    }
+
+    #[test]
+    fn unused_scope_fields_are_not_allocated() {
+        create_scope_immediate(|ctx| {
+            let _ = ctx.create_child_scope(|ctx| {
+                let _ = ctx.create_signal(0);
+                assert!(ctx.cleanups.borrow().is_none());
+                assert!(ctx.child_scopes.borrow().is_none());
+                assert!(ctx.contexts.borrow().is_none());
+                assert!(ctx.try_use_context::<i32>().is_none());
+
+                ctx.provide_context(0);
+                assert!(ctx.contexts.borrow().is_some());
+            });
+        });
+    }
+
+    #[test]
+    fn disposed_scopes_are_reused() {
+        create_scope_immediate(|ctx| {
+            let trigger = ctx.create_signal(());
+            let scopes = ctx.create_signal(Vec::new());
+            ctx.create_effect_scoped(|ctx| {
+                trigger.track();
+                let _ = ctx.create_signal(0);
+                let ptr = *ctx as *const _ as *const ();
+                scopes.set([scopes.get_untracked().as_slice(), &[ptr]].concat());
+            });
+            trigger.set(());
+            trigger.set(());
+
+            let scopes = scopes.get();
+            assert_eq!(scopes.len(), 3);
+            assert!(scopes.iter().all(|&ptr| ptr == scopes[0]));
+        });
+    }
+
+    #[test]
+    fn run_in_is_untracked() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(0);
+            let outer_runs = ctx.create_signal(0);
+            let inner_runs = ctx.create_signal(0);
+            let _ = ctx.create_child_scope(|other| {
+                other.create_effect(|| {
+                    outer_runs.set(*outer_runs.get_untracked() + 1);
+                    // Simulate a callback that is called while an unrelated effect is running.
+                    ctx.run_in(|ctx| {
+                        ctx.create_effect(|| {
+                            state.track();
+                            inner_runs.set(*inner_runs.get_untracked() + 1);
+                        });
+                    });
+                });
+            });
+            state.set(1);
+            assert_eq!(*outer_runs.get(), 1);
+            assert_eq!(*inner_runs.get(), 2);
+        });
+    }
+
+    #[test]
+    fn run_in_disposed_scope() {
+        let ran = create_rc_signal(None);
+        create_scope_immediate(|ctx| {
+            let ran = ran.clone();
+            let _ = ctx.create_child_scope(|child| {
+                child.on_cleanup(move || ran.set(Some(child.run_in(|_| ()).is_some())));
+            });
+        });
+        assert_eq!(*ran.get(), Some(false));
+    }
+
+    #[test]
+    fn on_cleanup_of_active_scope() {
+        let cleaned_up = create_rc_signal(false);
+        let disposer = create_scope(|_| {
+            let cleaned_up = cleaned_up.clone();
+            crate::on_cleanup(move || cleaned_up.set(true));
+        });
+        assert!(!*cleaned_up.get());
+        disposer();
+        assert!(*cleaned_up.get());
+    }
 }
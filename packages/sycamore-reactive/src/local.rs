@@ -0,0 +1,83 @@
+//! A replacement for [`thread_local!`](https://doc.rust-lang.org/std/macro.thread_local.html)
+//! when the `std` feature is disabled.
+//!
+//! Without `std` there is no way to tell threads apart, so the values are stored in statics
+//! instead. The values (e.g. the current runtime) are not thread safe, so they are only accessed
+//! once the application has promised to use the reactive runtime from a single thread by calling
+//! [`assume_single_threaded`]. Until then, accessing a value panics.
+
+use core::cell::OnceCell;
+use core::convert::Infallible;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// See [`assume_single_threaded`].
+static SINGLE_THREADED: AtomicBool = AtomicBool::new(false);
+
+/// Declares that the reactive runtime is only used from a single thread. This is only needed
+/// without the `std` feature, in which case it must be called before any [`Scope`](crate::Scope)
+/// is created.
+///
+/// # Safety
+/// From now on, all the reactive primitives of this crate must only be used from the same thread,
+/// and never from an interrupt handler or any other context that can preempt that thread.
+pub unsafe fn assume_single_threaded() {
+    SINGLE_THREADED.store(true, Ordering::Release);
+}
+
+/// A lazily initialized global with the same interface as `std::thread::LocalKey`.
+///
+/// The value is stored in a `static mut` that is only reachable through `storage`, so the key
+/// itself is `Sync` without giving access to the value from several threads.
+pub(crate) struct LocalKey<T: 'static> {
+    storage: unsafe fn() -> &'static OnceCell<T>,
+    init: fn() -> T,
+}
+
+impl<T: 'static> LocalKey<T> {
+    #[doc(hidden)]
+    pub const fn new(storage: unsafe fn() -> &'static OnceCell<T>, init: fn() -> T) -> Self {
+        Self { storage, init }
+    }
+
+    /// Calls `f` with a reference to the value, initializing it first if necessary.
+    ///
+    /// # Panics
+    /// Panics if [`assume_single_threaded`] has not been called.
+    #[track_caller]
+    pub fn with<R>(&'static self, f: impl FnOnce(&T) -> R) -> R {
+        assert!(
+            SINGLE_THREADED.load(Ordering::Acquire),
+            "`assume_single_threaded` must be called before using the reactive runtime without the \
+             `std` feature"
+        );
+        // SAFETY: The value is only accessed from a single thread. See `assume_single_threaded`.
+        let value = unsafe { (self.storage)() };
+        f(value.get_or_init(self.init))
+    }
+
+    /// Same as [`with`](Self::with). Never fails because statics are never destroyed.
+    #[cfg_attr(not(feature = "debug-tools"), allow(dead_code))]
+    pub fn try_with<R>(&'static self, f: impl FnOnce(&T) -> R) -> Result<R, Infallible> {
+        Ok(self.with(f))
+    }
+}
+
+macro_rules! thread_local {
+    () => {};
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $ty:ty = const { $init:expr }; $($rest:tt)*) => {
+        thread_local!($(#[$attr])* $vis static $name: $ty = $init; $($rest)*);
+    };
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $ty:ty = $init:expr; $($rest:tt)*) => {
+        $(#[$attr])*
+        $vis static $name: $crate::local::LocalKey<$ty> = {
+            /// # Safety
+            /// Must only be called from a single thread.
+            unsafe fn storage() -> &'static ::core::cell::OnceCell<$ty> {
+                static mut VALUE: ::core::cell::OnceCell<$ty> = ::core::cell::OnceCell::new();
+                &*::core::ptr::addr_of!(VALUE)
+            }
+            $crate::local::LocalKey::new(storage, || $init)
+        };
+        thread_local!($($rest)*);
+    };
+}
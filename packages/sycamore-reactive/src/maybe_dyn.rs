@@ -0,0 +1,135 @@
+//! Values that are either static or read from a signal.
+
+use core::fmt;
+
+use crate::*;
+
+/// A value that is either static or read from a signal.
+///
+/// This is useful for component props that accept both a constant and a reactive value, since
+/// constants, [`ReadSignal`]s, [`Signal`]s, [`RcSignal`]s and [`Memo`]s can all be converted into
+/// a [`MaybeDyn`] with [`Into`]. When deriving `Prop` in `sycamore`, add `#[prop(setter(into))]` to
+/// the field so that the conversion is done by the builder.
+///
+/// # Example
+/// ```
+/// # use sycamore_reactive::*;
+/// fn label<'a>(value: impl Into<MaybeDyn<'a, i32>>) -> String {
+///     format!("value: {}", value.into().get())
+/// }
+///
+/// # create_scope_immediate(|ctx| {
+/// assert_eq!(label(1), "value: 1");
+///
+/// let state = ctx.create_signal(2);
+/// assert_eq!(label(state), "value: 2");
+/// # });
+/// ```
+pub enum MaybeDyn<'a, T> {
+    /// A value that never changes.
+    Static(Rc<T>),
+    /// A value that is read from a signal.
+    Dyn(&'a ReadSignal<T>),
+}
+
+impl<'a, T> MaybeDyn<'a, T> {
+    /// Returns the current value. If the value is read from a signal, the signal is tracked like
+    /// with [`ReadSignal::get`].
+    pub fn get(&self) -> Rc<T> {
+        match self {
+            Self::Static(value) => Rc::clone(value),
+            Self::Dyn(signal) => signal.get(),
+        }
+    }
+
+    /// Returns the current value without tracking the signal that it is read from.
+    pub fn get_untracked(&self) -> Rc<T> {
+        match self {
+            Self::Static(value) => Rc::clone(value),
+            Self::Dyn(signal) => signal.get_untracked(),
+        }
+    }
+
+    /// Returns `true` if the value is read from a signal and can therefore change.
+    pub fn is_dyn(&self) -> bool {
+        matches!(self, Self::Dyn(_))
+    }
+}
+
+impl<T> Clone for MaybeDyn<'_, T> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Static(value) => Self::Static(Rc::clone(value)),
+            Self::Dyn(signal) => Self::Dyn(signal),
+        }
+    }
+}
+
+impl<T: Default> Default for MaybeDyn<'_, T> {
+    fn default() -> Self {
+        Self::Static(Rc::new(T::default()))
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for MaybeDyn<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Static(value) => f.debug_tuple("Static").field(value).finish(),
+            Self::Dyn(signal) => f.debug_tuple("Dyn").field(signal).finish(),
+        }
+    }
+}
+
+impl<T> From<T> for MaybeDyn<'_, T> {
+    fn from(value: T) -> Self {
+        Self::Static(Rc::new(value))
+    }
+}
+
+impl<'a, T> From<&'a ReadSignal<T>> for MaybeDyn<'a, T> {
+    fn from(signal: &'a ReadSignal<T>) -> Self {
+        Self::Dyn(signal)
+    }
+}
+
+impl<'a, T> From<&'a Signal<T>> for MaybeDyn<'a, T> {
+    fn from(signal: &'a Signal<T>) -> Self {
+        Self::Dyn(signal)
+    }
+}
+
+impl<'a, T> From<&'a RcSignal<T>> for MaybeDyn<'a, T> {
+    fn from(signal: &'a RcSignal<T>) -> Self {
+        Self::Dyn(signal)
+    }
+}
+
+impl<'a, T> From<&'a Memo<'_, T>> for MaybeDyn<'a, T> {
+    fn from(memo: &'a Memo<'_, T>) -> Self {
+        Self::Dyn(memo)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maybe_dyn_is_tracked() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(1);
+            let value: MaybeDyn<'_, i32> = state.into();
+            assert!(value.is_dyn());
+            let double = ctx.create_memo(move || *value.get() * 2);
+            state.set(2);
+            assert_eq!(*double.get(), 4);
+
+            let value: MaybeDyn<'_, i32> = 3.into();
+            assert!(!value.is_dyn());
+            assert_eq!(*value.get(), 3);
+
+            let memo: MaybeDyn<'_, i32> = double.into();
+            assert_eq!(*memo.get_untracked(), 4);
+        });
+    }
+}
@@ -1,9 +1,133 @@
 //! Derived and computed data.
 
-use std::cell::Cell;
+use core::cell::Cell;
+use core::fmt;
 
 use crate::*;
 
+/// A handle that reruns a memo created with
+/// [`create_memo_with_invalidate`](Scope::create_memo_with_invalidate).
+///
+/// The handle is not bound to a [`Scope`] and can be cloned and moved into callbacks (e.g. an event
+/// listener). Invalidating a memo whose [`Scope`] has been disposed does nothing.
+#[derive(Clone)]
+pub struct Invalidator(Rc<SignalEmitter>);
+
+impl Invalidator {
+    /// Marks the memo as dirty. The memo is rerun through the scheduler like any other memo whose
+    /// dependencies changed, so this is batched inside [`batch`].
+    pub fn invalidate(&self) {
+        self.0.trigger_subscribers();
+    }
+}
+
+impl fmt::Debug for Invalidator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Invalidator").field(&self.0.id()).finish()
+    }
+}
+
+/// A memoized value, created with [`create_memo`](Scope::create_memo) or one of the selector
+/// functions.
+///
+/// A memo derefs to a [`ReadSignal`] with its current value, so it can be used like any other
+/// signal. In addition, it can be inspected and recomputed manually.
+pub struct Memo<'a, U: ?Sized> {
+    signal: &'a ReadSignal<U>,
+    id: EffectId,
+    callback: WeakEffectCallback,
+    runtime: Runtime,
+}
+
+impl<'a, U: ?Sized> Memo<'a, U> {
+    /// Returns the last computed value without tracking the memo. This is the same as
+    /// [`get_untracked`](ReadSignal::get_untracked).
+    pub fn peek(&self) -> Rc<U> {
+        self.signal.get_untracked()
+    }
+
+    /// Returns `true` if a dependency of the memo changed but the memo has not been recomputed yet.
+    /// This can only happen inside of a [`batch`].
+    pub fn is_dirty(&self) -> bool {
+        self.state() == NodeState::Dirty
+    }
+
+    /// Returns whether the memo is up to date. Inside of a [`batch`], a memo whose dependency
+    /// changed is [`Dirty`](NodeState::Dirty) and a memo that depends on it through other memos is
+    /// [`MaybeDirty`](NodeState::MaybeDirty) until the end of the batch.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let state = ctx.create_signal(1);
+    /// let is_even = ctx.create_selector(|| *state.get() % 2 == 0);
+    /// let label = ctx.create_memo(|| if *is_even.get() { "even" } else { "odd" });
+    /// batch(|| {
+    ///     state.set(3);
+    ///     assert_eq!(is_even.state(), NodeState::Dirty);
+    ///     assert_eq!(label.state(), NodeState::MaybeDirty);
+    /// });
+    /// // `is_even` did not change, so `label` was not recomputed.
+    /// assert_eq!(label.state(), NodeState::Clean);
+    /// # });
+    /// ```
+    pub fn state(&self) -> NodeState {
+        self.runtime.scheduler().state(self.id, NodeKind::Memo)
+    }
+
+    /// Recomputes the memo right away, even inside of a [`batch`] and even if none of its
+    /// dependencies changed. This does nothing if the [`Scope`] of the memo has been disposed or if
+    /// called from inside the memo itself.
+    pub fn force_recompute(&self) {
+        let scheduler = self.runtime.scheduler();
+        scheduler.unschedule(self.id, NodeKind::Memo);
+        if let Some(callback) = self.callback.upgrade() {
+            if let Ok(mut callback) = callback.try_borrow_mut() {
+                callback();
+            }
+        }
+        scheduler.flush();
+    }
+
+    /// Returns the id of the node that computes the memo.
+    pub fn id(&self) -> EffectId {
+        self.id
+    }
+}
+
+impl<'a, U: ?Sized> Deref for Memo<'a, U> {
+    type Target = ReadSignal<U>;
+
+    fn deref(&self) -> &ReadSignal<U> {
+        self.signal
+    }
+}
+
+impl<U: ?Sized> AsRef<ReadSignal<U>> for Memo<'_, U> {
+    fn as_ref(&self) -> &ReadSignal<U> {
+        self.signal
+    }
+}
+
+impl<'a, U: ?Sized> AnyReadSignal<'a> for Memo<'_, U> {
+    fn track(&self) {
+        self.signal.track();
+    }
+}
+
+impl<U: fmt::Debug + ?Sized> fmt::Debug for Memo<'_, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Memo").field(&self.peek()).finish()
+    }
+}
+
+impl<U: fmt::Display + ?Sized> fmt::Display for Memo<'_, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.peek().fmt(f)
+    }
+}
+
 impl<'a> Scope<'a> {
     /// Creates a memoized computation from some signals.
     /// The output is derived from all the signals that are used within the memo closure.
@@ -11,10 +135,10 @@ impl<'a> Scope<'a> {
     ///
     /// # Difference from derived signals
     ///
-    /// Derived signals (functions referencing signals) are lazy and do not keep track of the result
-    /// of the computation. This means that the computation will not be executed until needed.
-    /// This also means that calling the derived signal twice will result in the same computation
-    /// twice.
+    /// Derived signals (functions referencing signals, see [`Derived`]) are lazy and do not keep
+    /// track of the result of the computation. This means that the computation will not be
+    /// executed until needed. This also means that calling the derived signal twice will result
+    /// in the same computation twice.
     ///
     /// ```
     /// # use sycamore_reactive::*;
@@ -46,8 +170,8 @@ impl<'a> Scope<'a> {
     /// assert_eq!(*double.get(), 2);
     /// # });
     /// ```
-    pub fn create_memo<U: 'a>(&'a self, f: impl FnMut() -> U + 'a) -> &'a ReadSignal<U> {
-        self.create_selector_with(f, |_, _| false)
+    pub fn create_memo<U: 'a>(&'a self, f: impl FnMut() -> U + 'a) -> &'a Memo<'a, U> {
+        self.create_memo_dyn(Box::new(f), None)
     }
 
     /// Creates a memoized value from some signals.
@@ -73,7 +197,7 @@ impl<'a> Scope<'a> {
     pub fn create_selector<U: PartialEq + 'a>(
         &'a self,
         f: impl FnMut() -> U + 'a,
-    ) -> &'a ReadSignal<U> {
+    ) -> &'a Memo<'a, U> {
         self.create_selector_with(f, PartialEq::eq)
     }
 
@@ -88,27 +212,231 @@ impl<'a> Scope<'a> {
     /// [`create_selector`](Self::create_selector).
     pub fn create_selector_with<U: 'a>(
         &'a self,
-        mut f: impl FnMut() -> U + 'a,
+        f: impl FnMut() -> U + 'a,
         eq_f: impl Fn(&U, &U) -> bool + 'a,
-    ) -> &'a ReadSignal<U> {
+    ) -> &'a Memo<'a, U> {
+        self.create_memo_dyn(Box::new(f), Some(Box::new(eq_f)))
+    }
+
+    /// The implementation of [`create_memo`](Self::create_memo) and
+    /// [`create_selector_with`](Self::create_selector_with). The closures are boxed so that this
+    /// is only compiled once for every type of value instead of once for every closure type. The
+    /// dependents are always notified if there is no `eq_f`.
+    fn create_memo_dyn<U: 'a>(
+        &'a self,
+        mut f: Box<dyn FnMut() -> U + 'a>,
+        eq_f: Option<EqFn<'a, U>>,
+    ) -> &'a Memo<'a, U> {
         let signal: Rc<Cell<Option<&Signal<U>>>> = Default::default();
 
-        self.create_effect({
+        let (id, callback) = self.create_node_with_callback(NodeKind::Memo, {
             let signal = signal.clone();
             move || {
                 let new = f();
                 if let Some(signal) = signal.get() {
                     // Check if new value is different from old value.
-                    if !eq_f(&new, &*signal.get_untracked()) {
+                    let unchanged = eq_f
+                        .as_ref()
+                        .is_some_and(|eq_f| eq_f(&new, &signal.get_untracked()));
+                    if !unchanged {
                         signal.set(new)
                     }
                 } else {
-                    signal.set(Some(self.create_signal(new)))
+                    let output = self.create_signal(new);
+                    add_running_memo_output(&output.emitter);
+                    signal.set(Some(output))
                 }
             }
         });
 
-        signal.get().unwrap()
+        self.create_ref(Memo {
+            signal: signal.get().unwrap(),
+            id,
+            callback,
+            runtime: self.runtime.clone(),
+        })
+    }
+
+    /// Creates a memoized string, e.g. for a text node that is derived from several signals.
+    ///
+    /// Instead of returning a new [`String`], `f` writes the string into a buffer that is cleared
+    /// before each run and reused across runs, so the buffer is only reallocated when the string
+    /// grows beyond its capacity. The memo publishes the string as an [`Rc<str>`]:
+    /// * If the string is unchanged, nothing is allocated and the dependents are not notified,
+    ///   like with [`create_selector`](Self::create_selector).
+    /// * If the string changed but has the same length, it is copied into the allocation of the
+    ///   previous [`Rc<str>`], unless the previous string is still held somewhere (e.g. by an
+    ///   effect that keeps the result of [`get`](ReadSignal::get) around).
+    /// * Otherwise, the string is copied into a new [`Rc<str>`].
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// use std::fmt::Write;
+    ///
+    /// # create_scope_immediate(|ctx| {
+    /// let done = ctx.create_signal(1);
+    /// let total = ctx.create_signal(3);
+    /// let progress = ctx.create_memo_str(|buf| {
+    ///     write!(buf, "{} of {} done", done.get(), total.get()).unwrap();
+    /// });
+    /// assert_eq!(&*progress.get(), "1 of 3 done");
+    ///
+    /// done.set(2);
+    /// assert_eq!(&*progress.get(), "2 of 3 done");
+    /// # });
+    /// ```
+    pub fn create_memo_str(&'a self, mut f: impl FnMut(&mut String) + 'a) -> &'a Memo<'a, str> {
+        let signal: Rc<Cell<Option<&Signal<str>>>> = Default::default();
+        let mut buf = String::new();
+
+        let (id, callback) = self.create_node_with_callback(NodeKind::Memo, {
+            let signal = signal.clone();
+            move || {
+                buf.clear();
+                f(&mut buf);
+                if let Some(signal) = signal.get() {
+                    if *signal.get_untracked() != *buf {
+                        signal.set_str(&buf);
+                    }
+                } else {
+                    let output = self.create_ref(Signal::new_rc(Rc::from(buf.as_str())));
+                    add_running_memo_output(&output.emitter);
+                    signal.set(Some(output))
+                }
+            }
+        });
+
+        self.create_ref(Memo {
+            signal: signal.get().unwrap(),
+            id,
+            callback,
+            runtime: self.runtime.clone(),
+        })
+    }
+
+    /// Creates a memoized computation that can also be rerun manually with the returned
+    /// [`Invalidator`]. This is useful for memos that depend on data that is not stored in a
+    /// signal, such as the size of the window or the current time.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # use std::cell::Cell;
+    /// # create_scope_immediate(|ctx| {
+    /// let external = ctx.create_ref(Cell::new(1));
+    /// let (double, invalidator) = ctx.create_memo_with_invalidate(|| external.get() * 2);
+    /// assert_eq!(*double.get(), 2);
+    ///
+    /// external.set(2);
+    /// assert_eq!(*double.get(), 2);
+    /// invalidator.invalidate();
+    /// assert_eq!(*double.get(), 4);
+    /// # });
+    /// ```
+    pub fn create_memo_with_invalidate<U: 'a>(
+        &'a self,
+        f: impl FnMut() -> U + 'a,
+    ) -> (&'a Memo<'a, U>, Invalidator) {
+        self.create_memo_with_invalidate_dyn(Box::new(f))
+    }
+
+    /// The implementation of [`create_memo_with_invalidate`](Self::create_memo_with_invalidate).
+    /// See [`create_memo_dyn`](Self::create_memo_dyn).
+    fn create_memo_with_invalidate_dyn<U: 'a>(
+        &'a self,
+        mut f: Box<dyn FnMut() -> U + 'a>,
+    ) -> (&'a Memo<'a, U>, Invalidator) {
+        let invalidator = Invalidator(Default::default());
+        let memo = self.create_memo({
+            let emitter = Rc::clone(&invalidator.0);
+            move || {
+                emitter.track();
+                f()
+            }
+        });
+        (memo, invalidator)
+    }
+
+    /// Creates a memoized computation that reads the signals used inside `f` without tracking them.
+    /// The memo is therefore only rerun when the returned [`Invalidator`] is invalidated, which
+    /// makes it possible to initialize a value from other signals without following them.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let saved = ctx.create_signal("draft".to_string());
+    /// let (initial, reset) = ctx.create_memo_untracked(|| saved.get().to_uppercase());
+    ///
+    /// saved.set("published".to_string());
+    /// assert_eq!(*initial.get(), "DRAFT");
+    /// reset.invalidate();
+    /// assert_eq!(*initial.get(), "PUBLISHED");
+    /// # });
+    /// ```
+    pub fn create_memo_untracked<U: 'a>(
+        &'a self,
+        mut f: impl FnMut() -> U + 'a,
+    ) -> (&'a Memo<'a, U>, Invalidator) {
+        self.create_memo_with_invalidate_dyn(Box::new(move || untrack(&mut f)))
+    }
+
+    /// Creates a memoized computation from a fallible function.
+    ///
+    /// Returns two signals: the value of the last successful computation and the error of the
+    /// last computation. The value is `None` until the computation succeeds for the first time and
+    /// keeps its last good value while the computation fails. The error is `None` whenever the
+    /// last computation succeeded.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let input = ctx.create_signal("1".to_string());
+    /// let (number, error) = ctx.create_try_memo(|| input.get().parse::<i32>());
+    /// assert_eq!(*number.get(), Some(1));
+    /// assert!(error.get().is_none());
+    ///
+    /// input.set("a".to_string());
+    /// assert_eq!(*number.get(), Some(1)); // Last good value is kept.
+    /// assert!(error.get().is_some());
+    ///
+    /// input.set("2".to_string());
+    /// assert_eq!(*number.get(), Some(2));
+    /// assert!(error.get().is_none());
+    /// # });
+    /// ```
+    pub fn create_try_memo<U: 'a, E: 'a>(
+        &'a self,
+        f: impl FnMut() -> Result<U, E> + 'a,
+    ) -> (&'a ReadSignal<Option<U>>, &'a ReadSignal<Option<E>>) {
+        self.create_try_memo_dyn(Box::new(f))
+    }
+
+    /// The implementation of [`create_try_memo`](Self::create_try_memo). See
+    /// [`create_memo_dyn`](Self::create_memo_dyn).
+    fn create_try_memo_dyn<U: 'a, E: 'a>(
+        &'a self,
+        mut f: Box<dyn FnMut() -> Result<U, E> + 'a>,
+    ) -> (&'a ReadSignal<Option<U>>, &'a ReadSignal<Option<E>>) {
+        let value = self.create_signal(None);
+        let error = self.create_signal(None);
+
+        self.create_node(NodeKind::Memo, move || {
+            let result = f();
+            batch(|| match result {
+                Ok(new) => {
+                    if error.get_untracked().is_some() {
+                        error.set(None);
+                    }
+                    value.set(Some(new));
+                }
+                Err(err) => error.set(Some(err)),
+            });
+        });
+
+        (value, error)
     }
 
     /// An alternative to [`create_signal`](Self::create_signal) that uses a reducer to get the next
@@ -117,7 +445,9 @@ impl<'a> Scope<'a> {
     /// It uses a reducer function that takes the previous value and a message and returns the next
     /// value.
     ///
-    /// Returns a [`ReadSignal`] and a dispatch function to send messages to the reducer.
+    /// Returns a [`ReadSignal`] and a dispatch function to send messages to the reducer. The
+    /// dispatch function is allocated on the [`Scope`], so the returned reference is `Copy` and can
+    /// be used in any number of closures without cloning.
     ///
     /// # Params
     /// * `initial` - The initial value of the state.
@@ -149,21 +479,194 @@ impl<'a> Scope<'a> {
         &'a self,
         initial: U,
         reduce: impl Fn(&U, Msg) -> U + 'a,
-    ) -> (&'a ReadSignal<U>, impl Fn(Msg) + 'a) {
+    ) -> (&'a ReadSignal<U>, &'a (impl Fn(Msg) + 'a)) {
         let memo = self.create_signal(initial);
 
-        let dispatcher = move |msg| {
+        let dispatcher = self.create_ref(move |msg| {
             memo.set(reduce(&memo.get_untracked(), msg));
-        };
+        });
 
         (&*memo, dispatcher)
     }
 }
 
+/// Creates a selector (see [`Scope::create_selector`]) that only depends on the listed signals.
+///
+/// Each signal is read once, and inside the expression its name refers to the value of the signal
+/// (an [`Rc`](alloc::rc::Rc) of the value) instead of the signal itself. The expression is
+/// evaluated with [`untrack`], so reading any other signal inside of it does not add a dependency.
+/// This makes the dependencies of the selector explicit.
+///
+/// The signals are moved into the selector. Use references to signals or clones of
+/// [`RcSignal`]s.
+///
+/// # Example
+/// ```
+/// # use sycamore_reactive::*;
+/// # create_scope_immediate(|ctx| {
+/// let first = ctx.create_signal("Jane");
+/// let last = ctx.create_signal("Doe");
+/// let separator = ctx.create_signal(" ");
+/// let full = selector!(ctx, (first, last) => format!("{first}{}{last}", separator.get()));
+/// assert_eq!(*full.get(), "Jane Doe");
+///
+/// last.set("Smith");
+/// assert_eq!(*full.get(), "Jane Smith");
+/// // `separator` is not a dependency.
+/// separator.set(", ");
+/// assert_eq!(*full.get(), "Jane Smith");
+/// # });
+/// ```
+#[macro_export]
+macro_rules! selector {
+    ($ctx:expr, ($($signal:ident),* $(,)?) => $body:expr) => {
+        $ctx.create_selector(move || {
+            $(let $signal = $signal.get();)*
+            $crate::untrack(|| $body)
+        })
+    };
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn selector_macro_only_tracks_listed_signals() {
+        create_scope_immediate(|ctx| {
+            let a = ctx.create_signal(1);
+            let b = ctx.create_signal(2);
+            let c = ctx.create_signal(3);
+            let sum = selector!(ctx, (a, b) => *a + *b + *c.get());
+            assert_eq!(*sum.get(), 6);
+
+            a.set(2);
+            assert_eq!(*sum.get(), 7);
+            c.set(4);
+            assert_eq!(*sum.get(), 7);
+            b.set(3);
+            assert_eq!(*sum.get(), 9);
+
+            let constant = selector!(ctx, () => 0);
+            assert_eq!(*constant.get(), 0);
+        });
+    }
+
+    #[test]
+    fn memo_str_reuses_unchanged_string() {
+        create_scope_immediate(|ctx| {
+            let first = ctx.create_signal("Jane");
+            let last = ctx.create_signal("Doe");
+            let name = ctx.create_memo_str(|buf| {
+                buf.push_str(&first.get());
+                buf.push(' ');
+                buf.push_str(&last.get());
+            });
+            let runs = ctx.create_signal(0);
+            ctx.create_effect(|| {
+                name.track();
+                runs.set(*runs.get_untracked() + 1);
+            });
+            let initial = name.get_untracked();
+            assert_eq!(&*initial, "Jane Doe");
+
+            batch(|| {
+                first.set("John");
+                first.set("Jane");
+            });
+            assert!(Rc::ptr_eq(&name.get(), &initial));
+            assert_eq!(*runs.get(), 1);
+
+            last.set("Smith");
+            assert_eq!(&*name.get(), "Jane Smith");
+            assert_eq!(*runs.get(), 2);
+        });
+    }
+
+    #[test]
+    fn memo_str_reuses_allocation_of_same_length() {
+        create_scope_immediate(|ctx| {
+            let last = ctx.create_signal("Doe");
+            let name = ctx.create_memo_str(|buf| {
+                buf.push_str("Jane ");
+                buf.push_str(&last.get());
+            });
+            let ptr = name.get_untracked().as_ptr();
+
+            last.set("Roe");
+            let current = name.get_untracked();
+            assert_eq!(&*current, "Jane Roe");
+            assert_eq!(current.as_ptr(), ptr);
+
+            // The previous string is still held, so it is not overwritten.
+            last.set("Moe");
+            assert_eq!(&*current, "Jane Roe");
+            assert_eq!(&*name.get_untracked(), "Jane Moe");
+            assert_ne!(name.get_untracked().as_ptr(), ptr);
+        });
+    }
+
+    #[test]
+    fn memo_with_invalidate() {
+        let external = Rc::new(Cell::new(0));
+        let mut invalidator = None;
+        create_scope_immediate(|ctx| {
+            let runs = ctx.create_signal(0);
+            let (value, handle) = ctx.create_memo_with_invalidate({
+                let external = Rc::clone(&external);
+                move || {
+                    runs.set(*runs.get_untracked() + 1);
+                    external.get()
+                }
+            });
+            external.set(1);
+            assert_eq!(*value.get(), 0);
+
+            batch(|| {
+                handle.invalidate();
+                handle.clone().invalidate();
+            });
+            assert_eq!(*value.get(), 1);
+            assert_eq!(*runs.get(), 2);
+            invalidator = Some(handle);
+        });
+        // Does nothing once the scope is disposed.
+        invalidator.unwrap().invalidate();
+    }
+
+    #[test]
+    fn try_memo_keeps_last_good_value() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(-1);
+            let (value, error) = ctx.create_try_memo(|| {
+                let state = *state.get();
+                if state >= 0 {
+                    Ok(state * 2)
+                } else {
+                    Err(format!("{} is negative", state))
+                }
+            });
+            let runs = ctx.create_signal(0);
+            ctx.create_effect(|| {
+                value.track();
+                error.track();
+                runs.set(*runs.get_untracked() + 1);
+            });
+            assert_eq!(*value.get(), None);
+            assert_eq!(*error.get(), Some("-1 is negative".to_string()));
+
+            state.set(1);
+            assert_eq!(*value.get(), Some(2));
+            assert_eq!(*error.get(), None);
+            // Both signals were updated, but dependents only run once.
+            assert_eq!(*runs.get(), 2);
+
+            state.set(-2);
+            assert_eq!(*value.get(), Some(2));
+            assert_eq!(*error.get(), Some("-2 is negative".to_string()));
+        });
+    }
+
     #[test]
     fn memo() {
         create_scope_immediate(|ctx| {
@@ -288,6 +791,13 @@ mod tests {
             });
             let doubled = ctx.create_memo(|| *state.get() * 2);
 
+            assert_eq!(*doubled.get(), 0);
+            // The dispatcher can be copied into closures.
+            let increment = move || dispatch(Msg::Increment);
+            let decrement = move || dispatch(Msg::Decrement);
+            increment();
+            assert_eq!(*doubled.get(), 2);
+            decrement();
             assert_eq!(*doubled.get(), 0);
             dispatch(Msg::Increment);
             assert_eq!(*doubled.get(), 2);
@@ -295,4 +805,54 @@ mod tests {
             assert_eq!(*doubled.get(), 0);
         });
     }
+
+    #[test]
+    fn memo_peek_is_untracked() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(0);
+            let double = ctx.create_memo(|| *state.get() * 2);
+            let runs = ctx.create_signal(0);
+            ctx.create_effect(|| {
+                double.peek();
+                runs.set(*runs.get_untracked() + 1);
+            });
+            state.set(1);
+            assert_eq!(*double.peek(), 2);
+            assert_eq!(*runs.get(), 1);
+        });
+    }
+
+    #[test]
+    fn memo_is_dirty_inside_batch() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(0);
+            let double = ctx.create_memo(|| *state.get() * 2);
+            assert!(!double.is_dirty());
+            batch(|| {
+                state.set(1);
+                assert!(double.is_dirty());
+                assert_eq!(*double.peek(), 0);
+
+                double.force_recompute();
+                assert!(!double.is_dirty());
+                assert_eq!(*double.peek(), 2);
+            });
+            assert!(!double.is_dirty());
+        });
+    }
+
+    #[test]
+    fn memo_force_recompute() {
+        create_scope_immediate(|ctx| {
+            let external = ctx.create_ref(Cell::new(1));
+            let memo = ctx.create_memo(|| external.get());
+            let observed = ctx.create_signal(0);
+            ctx.create_effect(|| observed.set(*memo.get()));
+
+            external.set(2);
+            assert_eq!(*observed.get(), 1);
+            memo.force_recompute();
+            assert_eq!(*observed.get(), 2);
+        });
+    }
 }
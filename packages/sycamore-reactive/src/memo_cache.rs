@@ -0,0 +1,300 @@
+//! Keeping the values of memos while their scope is recreated.
+
+use core::fmt;
+
+use crate::*;
+
+/// The value of a memo whose scope has been disposed.
+struct ParkedMemo {
+    /// The `Rc<U>` of the memo.
+    value: Box<dyn Any>,
+    dependencies: Vec<Weak<Subscribers>>,
+    /// Set by `watcher` once one of the dependencies changes.
+    changed: Rc<Cell<bool>>,
+    /// Unsubscribes `watcher` from the dependencies when dropped. The subscriptions are boxed
+    /// because they must not move while they are linked.
+    #[allow(clippy::vec_box)]
+    _subscriptions: Vec<Box<Subscription>>,
+    _watcher: Rc<RefCell<dyn FnMut()>>,
+}
+
+/// A cache for the values of persistent memos, created with
+/// [`create_memo_cache`](Scope::create_memo_cache).
+///
+/// When the scope of a memo that was created with
+/// [`create_persistent_memo`](Scope::create_persistent_memo) is disposed, the value of the memo is
+/// kept in the nearest cache. A persistent memo with the same name that is created afterwards
+/// starts with the kept value instead of computing it again, unless one of the dependencies of
+/// the memo changed in the meantime.
+#[derive(Clone, Default)]
+pub struct MemoCache(Rc<RefCell<HashMap<String, ParkedMemo>>>);
+
+impl MemoCache {
+    /// Returns the number of memo values in the cache.
+    pub fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    /// Returns `true` if the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.borrow().is_empty()
+    }
+
+    /// Removes the value of the memo with the given `name` from the cache.
+    pub fn remove(&self, name: &str) {
+        let parked = self.0.borrow_mut().remove(name);
+        drop(parked);
+    }
+
+    /// Removes all the values from the cache.
+    pub fn clear(&self) {
+        let parked = self.0.take();
+        drop(parked);
+    }
+
+    /// Keeps `value` in the cache until one of the `dependencies` changes.
+    fn park<U: 'static>(
+        &self,
+        name: String,
+        value: Rc<U>,
+        dependencies: Vec<Weak<Subscribers>>,
+        runtime: &Runtime,
+    ) {
+        let changed = Rc::new(Cell::new(false));
+        let watcher: Rc<RefCell<dyn FnMut()>> = Rc::new(RefCell::new({
+            let changed = Rc::clone(&changed);
+            move || changed.set(true)
+        }));
+        let id = EffectId::next();
+        let subscriptions = dependencies
+            .iter()
+            .filter_map(Weak::upgrade)
+            .map(|emitter| {
+                emitter.subscribe(
+                    id,
+                    NodeKind::Memo,
+                    Rc::downgrade(&watcher),
+                    runtime.clone(),
+                    Default::default(),
+                )
+            })
+            .collect();
+        let parked = ParkedMemo {
+            value: Box::new(value),
+            dependencies,
+            changed,
+            _subscriptions: subscriptions,
+            _watcher: watcher,
+        };
+        // A previous value with the same name is dropped after the cache is released.
+        let previous = self.0.borrow_mut().insert(name, parked);
+        drop(previous);
+    }
+
+    /// Takes the value of the memo with the given `name` out of the cache if its dependencies did
+    /// not change.
+    fn take<U: 'static>(&self, name: &str) -> Option<(Rc<U>, Vec<Weak<Subscribers>>)> {
+        let parked = self.0.borrow_mut().remove(name)?;
+        // A dependency that was dropped (e.g. a signal of the disposed scope) cannot be tracked
+        // anymore.
+        let outdated = parked.changed.get()
+            || parked
+                .dependencies
+                .iter()
+                .any(|dependency| dependency.strong_count() == 0);
+        if outdated {
+            return None;
+        }
+        let value = parked.value.downcast::<Rc<U>>().ok()?;
+        Some((*value, parked.dependencies))
+    }
+}
+
+impl fmt::Debug for MemoCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoCache")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+impl<'a> Scope<'a> {
+    /// Creates a new [`MemoCache`] in the current [`Scope`]. Persistent memos in this scope or in
+    /// child scopes keep their value in this cache, unless there is another cache in between.
+    ///
+    /// # Panics
+    /// This method panics if a memo cache exists already in this scope.
+    pub fn create_memo_cache(&'a self) -> MemoCache {
+        let cache = MemoCache::default();
+        self.provide_context(cache.clone());
+        cache
+    }
+
+    /// Creates a memo (see [`create_memo`](Self::create_memo)) whose value is kept in the nearest
+    /// [`MemoCache`] under `name` when this scope is disposed.
+    ///
+    /// If a value for `name` is in the cache and none of the signals that the memo depended on
+    /// changed since, the memo starts with that value and `f` is only called once a dependency
+    /// changes. This avoids recomputing expensive values when, for example, the scope of a route
+    /// is disposed and created again. Without a cache, this is the same as
+    /// [`create_memo`](Self::create_memo).
+    ///
+    /// If several persistent memos with the same name are alive at the same time, the value of the
+    /// memo that is disposed last is kept.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// fn sorted<'a>(ctx: ScopeRef<'a>, items: &'a Signal<Vec<i32>>) -> &'a ReadSignal<Vec<i32>> {
+    ///     ctx.create_persistent_memo("sorted", || {
+    ///         println!("sorting");
+    ///         let mut items = items.get().to_vec();
+    ///         items.sort();
+    ///         items
+    ///     })
+    /// }
+    ///
+    /// # create_scope_immediate(|ctx| {
+    /// ctx.create_memo_cache();
+    /// let items = ctx.create_signal(vec![3, 1, 2]);
+    /// let disposer = ctx.create_child_scope(|ctx| {
+    ///     sorted(&ctx, items); // Prints "sorting"
+    /// });
+    /// disposer();
+    /// let disposer = ctx.create_child_scope(|ctx| {
+    ///     // Uses the kept value.
+    ///     assert_eq!(*sorted(&ctx, items).get(), [1, 2, 3]);
+    /// });
+    /// disposer();
+    /// # });
+    /// ```
+    pub fn create_persistent_memo<U: 'static>(
+        &'a self,
+        name: impl Into<String>,
+        mut f: impl FnMut() -> U + 'a,
+    ) -> &'a ReadSignal<U> {
+        let cache = match self.try_use_context::<MemoCache>() {
+            Some(cache) => cache.clone(),
+            None => return self.create_memo(f),
+        };
+        let name = name.into();
+        let mut parked = cache.take::<U>(&name);
+        let dependencies: Rc<RefCell<Vec<Weak<Subscribers>>>> = Default::default();
+        let signal: Rc<Cell<Option<&Signal<U>>>> = Default::default();
+
+        self.create_node(NodeKind::Memo, {
+            let signal = Rc::clone(&signal);
+            let dependencies = Rc::clone(&dependencies);
+            move || {
+                let new = match parked.take() {
+                    Some((value, parked_dependencies)) => {
+                        // The dependencies of the kept value are tracked instead of calling `f`.
+                        for emitter in parked_dependencies.iter().filter_map(Weak::upgrade) {
+                            SignalEmitter(emitter).track();
+                        }
+                        *dependencies.borrow_mut() = parked_dependencies;
+                        value
+                    }
+                    None => {
+                        let (value, new_dependencies) = collect_dependencies(&mut f);
+                        *dependencies.borrow_mut() = new_dependencies;
+                        Rc::new(value)
+                    }
+                };
+                match signal.get() {
+                    Some(signal) => signal.set_rc(new),
+                    None => signal.set(Some(self.arena.alloc(Signal::new_rc(new)))),
+                }
+            }
+        });
+        let signal = signal.get().unwrap();
+
+        self.on_cleanup(move || {
+            cache.park(
+                name,
+                signal.get_untracked(),
+                dependencies.take(),
+                &self.runtime,
+            )
+        });
+        signal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn double<'a>(
+        ctx: ScopeRef<'a>,
+        state: &'a Signal<i32>,
+        runs: &'a Signal<i32>,
+    ) -> &'a ReadSignal<i32> {
+        ctx.create_persistent_memo("double", || {
+            runs.set(*runs.get_untracked() + 1);
+            *state.get() * 2
+        })
+    }
+
+    #[test]
+    fn persistent_memo_is_only_recomputed_if_dependencies_changed() {
+        create_scope_immediate(|ctx| {
+            let cache = ctx.create_memo_cache();
+            let state = ctx.create_signal(1);
+            let runs = ctx.create_signal(0);
+
+            let disposer = ctx.create_child_scope(|ctx| {
+                assert_eq!(*double(&ctx, state, runs).get(), 2);
+            });
+            disposer();
+            assert_eq!(cache.len(), 1);
+            let disposer = ctx.create_child_scope(|ctx| {
+                let double = double(&ctx, state, runs);
+                assert_eq!(*double.get(), 2);
+                assert_eq!(*runs.get(), 1);
+                // The dependencies of the kept value are still tracked.
+                state.set(2);
+                assert_eq!(*double.get(), 4);
+            });
+            disposer();
+            assert_eq!(*runs.get(), 2);
+
+            state.set(3);
+            let disposer = ctx.create_child_scope(|ctx| {
+                assert_eq!(*double(&ctx, state, runs).get(), 6);
+            });
+            disposer();
+            assert_eq!(*runs.get(), 3);
+        });
+    }
+
+    #[test]
+    fn persistent_memo_depending_on_disposed_signal_is_recomputed() {
+        create_scope_immediate(|ctx| {
+            ctx.create_memo_cache();
+            let runs = ctx.create_signal(0);
+            for _ in 0..2 {
+                let disposer = ctx.create_child_scope(|ctx| {
+                    let local = ctx.create_signal(1);
+                    let memo = ctx.create_persistent_memo("local", || {
+                        runs.set(*runs.get_untracked() + 1);
+                        *local.get()
+                    });
+                    assert_eq!(*memo.get(), 1);
+                });
+                disposer();
+            }
+            assert_eq!(*runs.get(), 2);
+        });
+    }
+
+    #[test]
+    fn persistent_memo_without_cache() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(1);
+            let memo = ctx.create_persistent_memo("memo", || *state.get() + 1);
+            state.set(2);
+            assert_eq!(*memo.get(), 3);
+        });
+    }
+}
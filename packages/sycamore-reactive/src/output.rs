@@ -0,0 +1,148 @@
+//! Plugging the output of a renderer into the reactive system.
+
+use core::hash::Hash;
+
+use crate::*;
+
+/// The output of a renderer (e.g. a DOM node, a terminal widget or a canvas element) that can be
+/// updated dynamically and combined into lists.
+///
+/// Implementing this trait for the node type of a renderer gives access to
+/// [`create_dyn_output`](Scope::create_dyn_output),
+/// [`map_keyed_output`](Scope::map_keyed_output) and
+/// [`map_indexed_output`](Scope::map_indexed_output) without depending on a specific renderer. In
+/// `sycamore`, this trait is implemented by `View`.
+pub trait ReactiveOutput: Clone {
+    /// Creates an output that always reflects the latest value of `signal`.
+    fn from_dyn(signal: RcSignal<Self>) -> Self;
+
+    /// Creates an output from a list of outputs that are rendered one after the other.
+    fn from_list(list: Vec<Self>) -> Self;
+}
+
+impl<'a> Scope<'a> {
+    /// Creates an output that is re-computed with `f` whenever the signals used inside `f` change.
+    ///
+    /// This is the generic version of `View::new_dyn` in `sycamore`.
+    pub fn create_dyn_output<O: ReactiveOutput + 'a>(&'a self, f: impl FnMut() -> O + 'a) -> O {
+        self.create_dyn_output_dyn(Box::new(f))
+    }
+
+    /// The implementation of [`create_dyn_output`](Self::create_dyn_output). The closure is boxed
+    /// so that this is only compiled once for every type of output instead of once for every
+    /// closure type.
+    fn create_dyn_output_dyn<O: ReactiveOutput + 'a>(&'a self, f: Box<dyn FnMut() -> O + 'a>) -> O {
+        let memo = self.create_memo(f);
+        let signal = create_rc_signal(memo.get().as_ref().clone());
+        self.create_effect({
+            let signal = signal.clone();
+            move || signal.set(memo.get().as_ref().clone())
+        });
+        O::from_dyn(signal)
+    }
+
+    /// Maps each item of `list` to an output with [`map_keyed`](Self::map_keyed) and combines the
+    /// outputs into a single dynamic output.
+    ///
+    /// This is the utility behind the `Keyed` component in `sycamore`.
+    pub fn map_keyed_output<T, K, O>(
+        &'a self,
+        list: &'a ReadSignal<Vec<T>>,
+        map_fn: impl for<'child_lifetime> Fn(BoundedScopeRef<'child_lifetime, 'a>, T) -> O + 'a,
+        key_fn: impl Fn(&T) -> K + 'a,
+    ) -> O
+    where
+        T: Eq + Clone + 'a,
+        K: Eq + Hash + 'a,
+        O: ReactiveOutput + 'a,
+    {
+        let mapped = self.map_keyed(list, map_fn, key_fn);
+        self.create_dyn_output(|| O::from_list(mapped.get().as_ref().clone()))
+    }
+
+    /// Maps each item of `list` to an output with [`map_indexed`](Self::map_indexed) and combines
+    /// the outputs into a single dynamic output.
+    ///
+    /// This is the utility behind the `Indexed` component in `sycamore`.
+    pub fn map_indexed_output<T, O>(
+        &'a self,
+        list: &'a ReadSignal<Vec<T>>,
+        map_fn: impl for<'child_lifetime> Fn(BoundedScopeRef<'child_lifetime, 'a>, T) -> O + 'a,
+    ) -> O
+    where
+        T: PartialEq + Clone,
+        O: ReactiveOutput + 'a,
+    {
+        let mapped = self.map_indexed(list, map_fn);
+        self.create_dyn_output(|| O::from_list(mapped.get().as_ref().clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A minimal renderer that renders to a string.
+    #[derive(Clone)]
+    enum Text {
+        Leaf(String),
+        Dyn(RcSignal<Text>),
+        List(Vec<Text>),
+    }
+
+    impl Text {
+        fn render(&self) -> String {
+            match self {
+                Text::Leaf(text) => text.clone(),
+                Text::Dyn(signal) => signal.get().render(),
+                Text::List(list) => list.iter().map(Text::render).collect(),
+            }
+        }
+    }
+
+    impl ReactiveOutput for Text {
+        fn from_dyn(signal: RcSignal<Self>) -> Self {
+            Text::Dyn(signal)
+        }
+
+        fn from_list(list: Vec<Self>) -> Self {
+            Text::List(list)
+        }
+    }
+
+    #[test]
+    fn dyn_output() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(0);
+            let output = ctx.create_dyn_output(|| Text::Leaf(state.get().to_string()));
+            assert_eq!(output.render(), "0");
+
+            state.set(1);
+            assert_eq!(output.render(), "1");
+        });
+    }
+
+    #[test]
+    fn keyed_output() {
+        create_scope_immediate(|ctx| {
+            let list = ctx.create_signal(vec![1, 2, 3]);
+            let output = ctx.map_keyed_output(list, |_, x| Text::Leaf(x.to_string()), |&x| x);
+            assert_eq!(output.render(), "123");
+
+            list.set(vec![3, 1]);
+            assert_eq!(output.render(), "31");
+        });
+    }
+
+    #[test]
+    fn indexed_output() {
+        create_scope_immediate(|ctx| {
+            let list = ctx.create_signal(vec![1, 2, 3]);
+            let output = ctx.map_indexed_output(list, |_, x| Text::Leaf(x.to_string()));
+            assert_eq!(output.render(), "123");
+
+            list.set(vec![4]);
+            assert_eq!(output.render(), "4");
+        });
+    }
+}
@@ -0,0 +1,165 @@
+//! Splitting a list into pages.
+
+use crate::*;
+
+/// A list that is split into pages, created with
+/// [`create_paginated`](Scope::create_paginated).
+///
+/// Pages are zero-based. The current page is kept in range when the source list shrinks or the
+/// page size changes.
+pub struct Paginated<'a, T> {
+    requested_page: &'a Signal<usize>,
+    page: &'a ReadSignal<usize>,
+    page_count: &'a ReadSignal<usize>,
+    items: &'a ReadSignal<Vec<T>>,
+}
+
+impl<'a, T> Paginated<'a, T> {
+    /// Returns the items of the current page.
+    pub fn items(&self) -> &'a ReadSignal<Vec<T>> {
+        self.items
+    }
+
+    /// Returns the index of the current page.
+    pub fn page(&self) -> &'a ReadSignal<usize> {
+        self.page
+    }
+
+    /// Returns the number of pages. An empty list has a single, empty page.
+    pub fn page_count(&self) -> &'a ReadSignal<usize> {
+        self.page_count
+    }
+
+    /// Goes to the page with the given index, or to the last page if there is no such page.
+    pub fn set_page(&self, page: usize) {
+        let last = self.page_count.get_untracked().saturating_sub(1);
+        self.requested_page.set(page.min(last));
+    }
+
+    /// Goes to the next page, if any.
+    pub fn next(&self) {
+        self.set_page(*self.page.get_untracked() + 1);
+    }
+
+    /// Goes to the previous page, if any.
+    pub fn prev(&self) {
+        self.set_page(self.page.get_untracked().saturating_sub(1));
+    }
+
+    /// Returns `true` if there is a page after the current page. This method is reactive.
+    pub fn has_next(&self) -> bool {
+        *self.page.get() + 1 < *self.page_count.get()
+    }
+
+    /// Returns `true` if there is a page before the current page. This method is reactive.
+    pub fn has_prev(&self) -> bool {
+        *self.page.get() > 0
+    }
+}
+
+impl<T> Clone for Paginated<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Paginated<'_, T> {}
+
+impl<'a> Scope<'a> {
+    /// Splits `source` into pages of `page_size` items. A page size of `0` is treated as `1`.
+    ///
+    /// Only the items of the current page are cloned when the source list, the page size or the
+    /// current page changes.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let source = ctx.create_signal((1..=5).collect::<Vec<_>>());
+    /// let page_size = ctx.create_signal(2);
+    /// let pages = ctx.create_paginated(source, page_size);
+    /// assert_eq!(*pages.items().get(), [1, 2]);
+    /// assert_eq!(*pages.page_count().get(), 3);
+    ///
+    /// pages.next();
+    /// assert_eq!(*pages.items().get(), [3, 4]);
+    ///
+    /// page_size.set(4);
+    /// assert_eq!(*pages.items().get(), [5]);
+    /// # });
+    /// ```
+    pub fn create_paginated<T: Clone + 'a>(
+        &'a self,
+        source: &'a ReadSignal<Vec<T>>,
+        page_size: &'a ReadSignal<usize>,
+    ) -> Paginated<'a, T> {
+        let page_count = self.create_selector(move || {
+            let page_size = (*page_size.get()).max(1);
+            source.get().len().div_ceil(page_size).max(1)
+        });
+        let requested_page = self.create_signal(0);
+        let page = self.create_selector(move || (*requested_page.get()).min(*page_count.get() - 1));
+        let items = self.create_memo(move || {
+            let source = source.get();
+            let page_size = (*page_size.get()).max(1);
+            let start = (*page.get() * page_size).min(source.len());
+            let end = (start + page_size).min(source.len());
+            source[start..end].to_vec()
+        });
+        Paginated {
+            requested_page,
+            page,
+            page_count,
+            items,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn navigation_is_clamped() {
+        create_scope_immediate(|ctx| {
+            let source = ctx.create_signal((0..10).collect::<Vec<_>>());
+            let page_size = ctx.create_signal(3);
+            let pages = ctx.create_paginated(source, page_size);
+            assert_eq!(*pages.page_count().get(), 4);
+            assert!(!pages.has_prev());
+
+            pages.prev();
+            assert_eq!(*pages.page().get(), 0);
+            pages.set_page(10);
+            assert_eq!(*pages.page().get(), 3);
+            assert_eq!(*pages.items().get(), [9]);
+            assert!(!pages.has_next());
+            pages.next();
+            assert_eq!(*pages.page().get(), 3);
+        });
+    }
+
+    #[test]
+    fn page_follows_source() {
+        create_scope_immediate(|ctx| {
+            let source = ctx.create_signal((0..10).collect::<Vec<_>>());
+            let page_size = ctx.create_signal(4);
+            let pages = ctx.create_paginated(source, page_size);
+            pages.set_page(2);
+            assert_eq!(*pages.items().get(), [8, 9]);
+
+            // The current page is clamped when the source shrinks.
+            source.set((0..5).collect());
+            assert_eq!(*pages.page().get(), 1);
+            assert_eq!(*pages.items().get(), [4]);
+
+            source.set(Vec::new());
+            assert_eq!(*pages.page_count().get(), 1);
+            assert!(pages.items().get().is_empty());
+
+            page_size.set(0);
+            source.set(vec![1, 2]);
+            assert_eq!(*pages.page_count().get(), 2);
+        });
+    }
+}
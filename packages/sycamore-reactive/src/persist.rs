@@ -0,0 +1,205 @@
+//! Persisting signals to a storage backend.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::*;
+
+/// A key-value store that a persistent signal can be synced with.
+///
+/// Values are stored as serialized JSON strings. When the `web` feature is enabled,
+/// [`LocalStorage`] and [`SessionStorage`] are provided for use in the browser. Other platforms
+/// can implement this trait themselves (e.g. to persist values to files).
+pub trait StorageBackend {
+    /// Gets the value stored under `key`, or `None` if there is no such value.
+    fn get(&self, key: &str) -> Option<String>;
+
+    /// Stores `value` under `key`, replacing any existing value.
+    fn set(&self, key: &str, value: &str);
+}
+
+impl<B: StorageBackend + ?Sized> StorageBackend for &B {
+    fn get(&self, key: &str) -> Option<String> {
+        (**self).get(key)
+    }
+
+    fn set(&self, key: &str, value: &str) {
+        (**self).set(key, value)
+    }
+}
+
+impl<B: StorageBackend + ?Sized> StorageBackend for Rc<B> {
+    fn get(&self, key: &str) -> Option<String> {
+        (**self).get(key)
+    }
+
+    fn set(&self, key: &str, value: &str) {
+        (**self).set(key, value)
+    }
+}
+
+/// A [`StorageBackend`] backed by the browser's `window.localStorage`.
+///
+/// If `localStorage` is not available, reads return `None` and writes are ignored.
+#[cfg(feature = "web")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LocalStorage;
+
+#[cfg(feature = "web")]
+impl StorageBackend for LocalStorage {
+    fn get(&self, key: &str) -> Option<String> {
+        web_sys::window()?
+            .local_storage()
+            .ok()??
+            .get_item(key)
+            .ok()?
+    }
+
+    fn set(&self, key: &str, value: &str) {
+        if let Some(Ok(Some(storage))) = web_sys::window().map(|w| w.local_storage()) {
+            let _ = storage.set_item(key, value);
+        }
+    }
+}
+
+/// A [`StorageBackend`] backed by the browser's `window.sessionStorage`.
+///
+/// If `sessionStorage` is not available, reads return `None` and writes are ignored.
+#[cfg(feature = "web")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SessionStorage;
+
+#[cfg(feature = "web")]
+impl StorageBackend for SessionStorage {
+    fn get(&self, key: &str) -> Option<String> {
+        web_sys::window()?
+            .session_storage()
+            .ok()??
+            .get_item(key)
+            .ok()?
+    }
+
+    fn set(&self, key: &str, value: &str) {
+        if let Some(Ok(Some(storage))) = web_sys::window().map(|w| w.session_storage()) {
+            let _ = storage.set_item(key, value);
+        }
+    }
+}
+
+impl<'a> Scope<'a> {
+    /// Create a new [`Signal`] that is synced with a [`StorageBackend`] under `key`.
+    ///
+    /// The initial value of the signal is loaded from `backend`. If there is no value stored under
+    /// `key`, or if the stored value cannot be deserialized, `initial` is used instead. Every time
+    /// the signal is updated, the new value is serialized and written back to `backend`.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::cell::RefCell;
+    /// # use std::collections::HashMap;
+    /// # use std::rc::Rc;
+    /// # use sycamore_reactive::*;
+    /// #[derive(Default)]
+    /// struct MemoryStorage(RefCell<HashMap<String, String>>);
+    ///
+    /// impl StorageBackend for MemoryStorage {
+    ///     fn get(&self, key: &str) -> Option<String> {
+    ///         self.0.borrow().get(key).cloned()
+    ///     }
+    ///     fn set(&self, key: &str, value: &str) {
+    ///         self.0.borrow_mut().insert(key.to_string(), value.to_string());
+    ///     }
+    /// }
+    ///
+    /// let storage = Rc::new(MemoryStorage::default());
+    /// create_scope_immediate(|ctx| {
+    ///     let count = ctx.create_persistent_signal("count", 0, storage.clone());
+    ///     count.set(1);
+    /// });
+    /// create_scope_immediate(|ctx| {
+    ///     // The value is restored from the storage.
+    ///     let count = ctx.create_persistent_signal("count", 0, storage.clone());
+    ///     assert_eq!(*count.get(), 1);
+    /// });
+    /// ```
+    pub fn create_persistent_signal<T, B>(
+        &'a self,
+        key: impl Into<String>,
+        initial: T,
+        backend: B,
+    ) -> &'a Signal<T>
+    where
+        T: Serialize + DeserializeOwned + 'a,
+        B: StorageBackend + 'a,
+    {
+        let key = key.into();
+        let value = backend
+            .get(&key)
+            .and_then(|stored| serde_json::from_str(&stored).ok())
+            .unwrap_or(initial);
+        let signal = self.create_signal(value);
+
+        self.create_effect(move || {
+            if let Ok(serialized) = serde_json::to_string(&*signal.get()) {
+                backend.set(&key, &serialized);
+            }
+        });
+
+        signal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct MemoryStorage(RefCell<HashMap<String, String>>);
+
+    impl StorageBackend for MemoryStorage {
+        fn get(&self, key: &str) -> Option<String> {
+            self.0.borrow().get(key).cloned()
+        }
+
+        fn set(&self, key: &str, value: &str) {
+            self.0
+                .borrow_mut()
+                .insert(key.to_string(), value.to_string());
+        }
+    }
+
+    #[test]
+    fn persistent_signal_writes_to_storage() {
+        let storage = Rc::new(MemoryStorage::default());
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_persistent_signal("state", 1, storage.clone());
+            assert_eq!(storage.get("state").as_deref(), Some("1"));
+
+            state.set(2);
+            assert_eq!(storage.get("state").as_deref(), Some("2"));
+        });
+    }
+
+    #[test]
+    fn persistent_signal_loads_from_storage() {
+        let storage = Rc::new(MemoryStorage::default());
+        storage.set("state", "\"hello\"");
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_persistent_signal("state", String::new(), storage.clone());
+            assert_eq!(*state.get(), "hello");
+        });
+    }
+
+    #[test]
+    fn persistent_signal_invalid_value_uses_initial() {
+        let storage = Rc::new(MemoryStorage::default());
+        storage.set("state", "not json");
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_persistent_signal("state", 123, storage.clone());
+            assert_eq!(*state.get(), 123);
+            assert_eq!(storage.get("state").as_deref(), Some("123"));
+        });
+    }
+}
@@ -0,0 +1,185 @@
+//! Low-level nodes with explicit dependencies.
+
+use core::fmt;
+
+use crate::*;
+
+/// A handle to a node in the reactive graph that depends on an explicit list of
+/// [`SignalEmitter`]s, created with [`create_raw_node`](Scope::create_raw_node).
+///
+/// Raw nodes are the building block for custom reactive primitives. A raw node is run by the
+/// scheduler in the same phase as memos, so a primitive that notifies its own [`SignalEmitter`]
+/// from inside the node is seen as up to date by the effects that depend on it.
+#[derive(Clone)]
+pub struct RawNode {
+    id: EffectId,
+    dependencies: Rc<RefCell<Vec<Weak<Subscribers>>>>,
+    /// Tracked by the node so that it can be rerun to track new dependencies.
+    retrack: Rc<SignalEmitter>,
+}
+
+impl RawNode {
+    /// Returns the id of the node, e.g. to set its order hint with
+    /// [`EffectId::set_order_hint`].
+    pub fn id(&self) -> EffectId {
+        self.id
+    }
+
+    /// Replaces the dependencies of the node. Because the inputs of the node changed, the update
+    /// callback is run once afterwards.
+    pub fn set_dependencies(&self, dependencies: &[&SignalEmitter]) {
+        *self.dependencies.borrow_mut() = downgrade_all(dependencies);
+        self.retrack.trigger_subscribers();
+    }
+}
+
+impl fmt::Debug for RawNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawNode")
+            .field("id", &self.id)
+            .field("dependencies", &self.dependencies.borrow().len())
+            .finish()
+    }
+}
+
+fn downgrade_all(dependencies: &[&SignalEmitter]) -> Vec<Weak<Subscribers>> {
+    dependencies
+        .iter()
+        .map(|emitter| emitter.downgrade())
+        .collect()
+}
+
+impl<'a> Scope<'a> {
+    /// Creates a node in the reactive graph that calls `on_update` whenever one of the
+    /// `dependencies` notifies its subscribers. Unlike memos and effects, `on_update` is not
+    /// called when the node is created and the signals that are read inside of it are not
+    /// tracked. The dependencies of a signal can be obtained with [`ReadSignal::emitter`].
+    ///
+    /// To let other nodes depend on the result of the node, store the result outside of a signal
+    /// along with a [`SignalEmitter`] and call [`SignalEmitter::trigger_subscribers`] when the
+    /// result changes and [`SignalEmitter::track`] when it is read.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::cell::Cell;
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let items = ctx.create_signal(vec![1, 2, 3]);
+    /// // A running total that is only updated with the items that were added.
+    /// let total = ctx.create_ref(Cell::new(6));
+    /// let counted = ctx.create_ref(Cell::new(3));
+    /// let changed = ctx.create_ref(SignalEmitter::default());
+    /// ctx.create_raw_node(
+    ///     || {
+    ///         let items = items.get_untracked();
+    ///         let added: i32 = items[counted.get()..].iter().sum();
+    ///         total.set(total.get() + added);
+    ///         counted.set(items.len());
+    ///         changed.trigger_subscribers();
+    ///     },
+    ///     &[items.emitter()],
+    /// );
+    ///
+    /// items.set(vec![1, 2, 3, 4]);
+    /// assert_eq!(total.get(), 10);
+    /// # });
+    /// ```
+    pub fn create_raw_node(
+        &'a self,
+        mut on_update: impl FnMut() + 'a,
+        dependencies: &[&SignalEmitter],
+    ) -> RawNode {
+        let dependencies = Rc::new(RefCell::new(downgrade_all(dependencies)));
+        let retrack = Rc::new(SignalEmitter::default());
+        let mut initialized = false;
+        let id = self.create_node(NodeKind::Memo, {
+            let dependencies = Rc::clone(&dependencies);
+            let retrack = Rc::clone(&retrack);
+            move || {
+                retrack.track();
+                for emitter in dependencies.borrow().iter().filter_map(Weak::upgrade) {
+                    SignalEmitter(emitter).track();
+                }
+                if initialized {
+                    untrack(&mut on_update);
+                }
+                initialized = true;
+            }
+        });
+        RawNode {
+            id,
+            dependencies,
+            retrack,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raw_node_only_tracks_explicit_dependencies() {
+        create_scope_immediate(|ctx| {
+            let a = ctx.create_signal(0);
+            let b = ctx.create_signal(0);
+            let runs = ctx.create_signal(0);
+            ctx.create_raw_node(
+                || {
+                    b.track();
+                    runs.set(*runs.get_untracked() + 1);
+                },
+                &[a.emitter()],
+            );
+            assert_eq!(*runs.get(), 0);
+
+            a.set(1);
+            assert_eq!(*runs.get(), 1);
+            b.set(1);
+            assert_eq!(*runs.get(), 1);
+        });
+    }
+
+    #[test]
+    fn raw_node_set_dependencies() {
+        create_scope_immediate(|ctx| {
+            let a = ctx.create_signal(0);
+            let b = ctx.create_signal(0);
+            let runs = ctx.create_signal(0);
+            let node = ctx.create_raw_node(|| runs.set(*runs.get_untracked() + 1), &[a.emitter()]);
+
+            node.set_dependencies(&[b.emitter()]);
+            assert_eq!(*runs.get(), 1);
+            a.set(1);
+            assert_eq!(*runs.get(), 1);
+            b.set(1);
+            assert_eq!(*runs.get(), 2);
+        });
+    }
+
+    #[test]
+    fn raw_node_runs_before_effects() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(1);
+            let observed = ctx.create_signal(Vec::new());
+            let result = ctx.create_ref(Cell::new(2));
+            let changed = ctx.create_ref(SignalEmitter::default());
+            ctx.create_effect(|| {
+                changed.track();
+                state.track();
+                observed.set([observed.get_untracked().as_slice(), &[result.get()]].concat());
+            });
+            ctx.create_raw_node(
+                || {
+                    result.set(*state.get_untracked() * 2);
+                    changed.trigger_subscribers();
+                },
+                &[state.emitter()],
+            );
+
+            state.set(2);
+            // The effect was created before the node but only sees the updated result.
+            assert_eq!(*observed.get(), [2, 4]);
+        });
+    }
+}
@@ -0,0 +1,283 @@
+//! Reactive runtimes.
+//!
+//! A [`Runtime`] holds the state that is shared by all the scopes of a reactive tree: the stack
+//! of effects that are currently running (which is used to track dependencies), the scheduler
+//! that runs memos and effects after their dependencies change and the active scope (see
+//! [`use_context`](crate::use_context())).
+//!
+//! Every thread has a default runtime that is used unless another runtime is entered with
+//! [`Runtime::enter`]. Root scopes use the runtime that is entered when they are created, and
+//! child scopes use the runtime of their parent. This allows several independent reactive trees
+//! (e.g. from two libraries that both embed `sycamore-reactive`) to live on the same thread
+//! without batching or flushing each other's effects.
+
+use core::fmt;
+
+use crate::*;
+
+#[derive(Default)]
+struct RuntimeState {
+    /// While the [`EffectState`] is inside the Vec, it is owned by the stack. Because this is
+    /// shared state, the lifetime is necessarily `'static`. However, that does not mean that it can
+    /// last forever. The `EffectState` should only be used the time it is inside the stack.
+    effects: RefCell<Vec<*mut EffectState<'static>>>,
+    scheduler: Scheduler,
+    /// The scope whose body or effect is currently running.
+    ///
+    /// # Safety
+    /// The scope does not actually have the `'static` lifetime. It is only set for the duration of
+    /// a call that borrows the scope. See [`Scope::enter`].
+    owner: Cell<Option<*const Scope<'static>>>,
+    /// Whether the runtime is used for server side rendering. See [`create_ssr_scope`].
+    ssr: bool,
+}
+
+/// A handle to a reactive runtime. See the [module-level documentation](self) for more
+/// information.
+///
+/// Cloning the handle does not create a new runtime.
+#[derive(Clone, Default)]
+pub struct Runtime(Rc<RuntimeState>);
+
+thread_local! {
+    /// The runtime that is entered on the current thread.
+    static CURRENT_RUNTIME: RefCell<Runtime> = Default::default();
+}
+
+impl Runtime {
+    /// Creates a new runtime that is independent of all the other runtimes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new runtime in which effects and memos are run once without tracking their
+    /// dependencies. See [`create_ssr_scope`].
+    pub(crate) fn new_ssr() -> Self {
+        Self(Rc::new(RuntimeState {
+            ssr: true,
+            ..Default::default()
+        }))
+    }
+
+    /// Returns the runtime that is entered on the current thread, or the default runtime of the
+    /// thread if no runtime is entered.
+    pub fn current() -> Self {
+        CURRENT_RUNTIME.with(|current| current.borrow().clone())
+    }
+
+    /// Runs `f` with this runtime entered. Root scopes that are created inside `f` and calls to
+    /// [`batch`] use this runtime.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// let runtime = Runtime::new();
+    /// runtime.enter(|| {
+    ///     create_scope_immediate(|ctx| {
+    ///         assert!(ctx.runtime().ptr_eq(&runtime));
+    ///     });
+    /// });
+    /// ```
+    pub fn enter<T>(&self, f: impl FnOnce() -> T) -> T {
+        let mut f = Some(f);
+        let mut result = None;
+        self.enter_dyn(&mut || result = Some(f.take().unwrap()()));
+        result.unwrap()
+    }
+
+    /// The implementation of [`enter`](Self::enter). This is not generic so that it is only
+    /// compiled once instead of once for every closure type.
+    fn enter_dyn(&self, f: &mut dyn FnMut()) {
+        /// Restores the previous runtime when dropped, even if `f` panics.
+        struct RestoreOnDrop(Option<Runtime>);
+        impl Drop for RestoreOnDrop {
+            fn drop(&mut self) {
+                let previous = self.0.take().unwrap();
+                let _ = CURRENT_RUNTIME.with(|current| current.replace(previous));
+            }
+        }
+
+        let previous = CURRENT_RUNTIME.with(|current| current.replace(self.clone()));
+        let _guard = RestoreOnDrop(Some(previous));
+        f()
+    }
+
+    /// Returns `true` if both handles refer to the same runtime.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+
+    /// Returns `true` if the runtime was created by [`create_ssr_scope`]. Effects and memos of
+    /// such a runtime are run once and never subscribe to signals.
+    pub fn is_ssr(&self) -> bool {
+        self.0.ssr
+    }
+
+    /// Returns the stack of effects that are currently running in this runtime.
+    pub(crate) fn effects(&self) -> &RefCell<Vec<*mut EffectState<'static>>> {
+        &self.0.effects
+    }
+
+    pub(crate) fn scheduler(&self) -> &Scheduler {
+        &self.0.scheduler
+    }
+}
+
+impl<'a> Scope<'a> {
+    /// Returns the [`Runtime`] that the effects and memos of this scope are run in.
+    pub fn runtime(&self) -> &Runtime {
+        &self.runtime
+    }
+
+    /// Runs `f` in the runtime of the scope with the scope as the active scope.
+    pub(crate) fn enter<T>(&self, f: impl FnOnce() -> T) -> T {
+        let mut f = Some(f);
+        let mut result = None;
+        self.enter_dyn(&mut || result = Some(f.take().unwrap()()));
+        result.unwrap()
+    }
+
+    /// The implementation of [`enter`](Self::enter). See [`Runtime::enter_dyn`].
+    fn enter_dyn(&self, f: &mut dyn FnMut()) {
+        /// Restores the previous active scope when dropped, even if `f` panics.
+        struct RestoreOnDrop<'a>(&'a Runtime, Option<*const Scope<'static>>);
+        impl Drop for RestoreOnDrop<'_> {
+            fn drop(&mut self) {
+                self.0 .0.owner.set(self.1);
+            }
+        }
+
+        self.runtime.enter_dyn(&mut || {
+            // SAFETY: The active scope is only used while `self` is borrowed.
+            let owner =
+                unsafe { core::mem::transmute::<*const Scope<'_>, *const Scope<'static>>(self) };
+            let previous = self.runtime.0.owner.replace(Some(owner));
+            let _guard = RestoreOnDrop(&self.runtime, previous);
+            f()
+        })
+    }
+}
+
+/// Calls `f` with the scope whose body or effect is currently running in the current runtime, if
+/// any.
+pub(crate) fn with_active_scope<T>(f: impl for<'s> FnOnce(Option<ScopeRef<'s>>) -> T) -> T {
+    let owner = Runtime::current().0.owner.get();
+    // SAFETY: The active scope is alive while it is set. See `Scope::enter`.
+    f(owner.map(|owner| unsafe { &*owner }))
+}
+
+impl fmt::Debug for Runtime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Runtime")
+            .field(&Rc::as_ptr(&self.0))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runtimes_are_isolated() {
+        let runtime = Runtime::new();
+        let state = create_rc_signal(0);
+        let outer_runs = create_rc_signal(0);
+        let inner_runs = create_rc_signal(0);
+        create_scope_immediate(|ctx| {
+            ctx.create_effect({
+                let state = state.clone();
+                let outer_runs = outer_runs.clone();
+                move || {
+                    state.track();
+                    outer_runs.set(*outer_runs.get_untracked() + 1);
+                }
+            });
+
+            runtime.enter(|| {
+                create_scope_immediate(|ctx| {
+                    ctx.create_effect({
+                        let state = state.clone();
+                        let inner_runs = inner_runs.clone();
+                        move || {
+                            state.track();
+                            inner_runs.set(*inner_runs.get_untracked() + 1);
+                        }
+                    });
+
+                    // A batch in one runtime does not defer the effects of the other runtime.
+                    batch(|| {
+                        state.set(1);
+                        assert_eq!(*outer_runs.get(), 2);
+                        assert_eq!(*inner_runs.get(), 1);
+                    });
+                    assert_eq!(*inner_runs.get(), 2);
+                });
+            });
+        });
+    }
+
+    #[test]
+    fn child_scopes_use_runtime_of_parent() {
+        let runtime = Runtime::new();
+        let disposer = runtime.enter(|| {
+            create_scope(|ctx| {
+                let _ = ctx.create_child_scope(|child| {
+                    assert!(child.runtime().ptr_eq(&runtime));
+                });
+            })
+        });
+        assert!(!Runtime::current().ptr_eq(&runtime));
+        disposer();
+    }
+
+    #[test]
+    fn effects_run_in_their_runtime() {
+        let runtime = Runtime::new();
+        let trigger = create_rc_signal(());
+        let observed = Rc::new(RefCell::new(Vec::new()));
+        let disposer = runtime.enter(|| {
+            create_scope(|ctx| {
+                let trigger = trigger.clone();
+                let observed = Rc::clone(&observed);
+                ctx.create_effect(move || {
+                    trigger.track();
+                    observed.borrow_mut().push(Runtime::current());
+                });
+            })
+        });
+        // Triggered from outside of the runtime.
+        trigger.set(());
+        assert!(observed.borrow().iter().all(|r| r.ptr_eq(&runtime)));
+        assert_eq!(observed.borrow().len(), 2);
+        disposer();
+    }
+
+    #[test]
+    fn ssr_scope_runs_effects_once() {
+        let state = create_rc_signal(0);
+        let runs = Rc::new(Cell::new(0));
+        let disposer = create_ssr_scope(|ctx| {
+            assert!(ctx.runtime().is_ssr());
+            let _ = ctx.create_child_scope(|child| {
+                let state = state.clone();
+                let runs = Rc::clone(&runs);
+                child.create_effect(move || {
+                    state.track();
+                    runs.set(runs.get() + 1);
+                });
+            });
+            let double = ctx.create_memo({
+                let state = state.clone();
+                move || *state.get() * 2
+            });
+            assert_eq!(*double.get(), 0);
+            state.set(1);
+            assert_eq!(*double.get(), 0);
+        });
+        assert!(!Runtime::current().is_ssr());
+        state.set(2);
+        assert_eq!(runs.get(), 1);
+        disposer();
+    }
+}
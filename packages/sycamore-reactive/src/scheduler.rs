@@ -0,0 +1,893 @@
+//! Scheduling of effects and memos after signals are updated.
+//!
+//! Updating a signal does not run its subscribers directly. Instead, the subscribers are queued
+//! and the queue is flushed in two phases:
+//! 1. All the queued memos are recomputed in topological order: every memo has a rank that is one
+//!    more than the highest rank of the signals it depends on, and the signals written by a memo
+//!    have the rank of the memo. Memos are run in ascending order of rank, so every memo is
+//!    recomputed at most once per update, after all its dependencies, even if several of them
+//!    changed (e.g. in a diamond) and even if it was created before the memos it depends on.
+//! 2. The queued effects are run in the order in which they were created. Effects therefore never
+//!    observe a memo that has not been updated yet.
+//!
+//! Queuing the subscribers of a signal is itself done in two phases, like in MobX or Reactively:
+//! the subscribers of the signal are marked as [`Dirty`](NodeState::Dirty) and the nodes that
+//! depend on them through memos, transitively, as [`MaybeDirty`](NodeState::MaybeDirty). A memo
+//! that is recomputed and whose value changed (by equality for selectors) marks its own
+//! subscribers as dirty. When the queue is run, dirty nodes are run and maybe dirty nodes that
+//! were not marked as dirty in the meantime are skipped because none of their dependencies
+//! changed. A long chain of memos below a selector whose value did not change is therefore never
+//! recomputed.
+//!
+//! Memos are recomputed as soon as a signal they depend on is updated so that reading a memo
+//! right after updating one of its dependencies returns the new value. Inside a [`batch`], both
+//! memos and effects are deferred until the end of the batch.
+//!
+//! Within each phase (and within each rank for memos), nodes are run in ascending order of their
+//! order hint (`0` unless set with [`EffectId::set_order_hint`]) and then in creation order. This
+//! order is part of the public contract and does not depend on the order in which the nodes
+//! subscribed to a signal or were queued.
+//!
+//! Flushes never nest: a signal that is updated while the queue is being run (e.g. by a memo that
+//! updates its own signal, or by an effect) only queues its subscribers, and they are picked up by
+//! the loop of the outermost flush. This keeps the call stack shallow regardless of the depth of
+//! the graph and ensures that an effect always finishes before the effects it triggers are run.
+//!
+//! An effect that updates a signal which (directly or through memos) triggers another effect
+//! increases the update depth of the triggered effect by one. When the depth exceeds the maximum
+//! (see [`set_max_update_depth`]), which usually means that effects update each other in a cycle,
+//! the node is not run and an [`UpdateDepthExceeded`] error is reported instead of looping forever.
+//!
+//! Every [`Runtime`] has its own queues. A node is always queued on the runtime it was created in,
+//! so flushing or batching one runtime never runs or defers the nodes of another runtime.
+
+use alloc::collections::BTreeMap;
+
+use crate::*;
+
+/// The kind of a node in the reactive graph. Memos are recomputed before any effects are run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum NodeKind {
+    /// A node that derives a signal from other signals, such as a memo or a selector.
+    Memo,
+    /// A node that performs side effects.
+    Effect,
+}
+
+/// The state of a memo or an effect in the queues of its [`Runtime`]. See [`Memo::state`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum NodeState {
+    /// The node is up to date.
+    Clean,
+    /// A signal that the node depends on through memos changed. The node is only run if one of
+    /// these memos changes when it is recomputed.
+    MaybeDirty,
+    /// A signal that the node depends on directly changed. The node is run on the next flush.
+    Dirty,
+}
+
+/// The queues of a [`Runtime`].
+#[derive(Default)]
+pub(crate) struct Scheduler {
+    /// Number of [`batch`] calls that are currently running.
+    batch_depth: Cell<usize>,
+    /// Whether queued memos are currently being run. Memos that are queued while running another
+    /// memo are picked up by the outermost flush.
+    running_memos: Cell<bool>,
+    /// Whether queued effects are currently being run. Effects that are queued while running
+    /// another effect are picked up by the outermost flush.
+    running_effects: Cell<bool>,
+    memos: RefCell<BTreeMap<QueueKey, Queued>>,
+    effects: RefCell<BTreeMap<QueueKey, Queued>>,
+    /// The ranks of the queued memos, which are part of their key in the queue.
+    memo_ranks: RefCell<HashMap<EffectId, u32>>,
+    /// The update depth and the node that is currently being run by a flush, if any.
+    running: Cell<(usize, Option<(EffectId, NodeKind)>)>,
+    /// The transition that the signal updates that are currently being made belong to, if any.
+    /// The nodes that they queue are part of the transition as well, and so are the signal
+    /// updates that these nodes make.
+    transition: RefCell<Option<Transition>>,
+    /// The runs of the nodes whose update depth is close to the maximum, used to report the chain
+    /// of updates when the maximum is exceeded. Cleared after each flush.
+    history: RefCell<Vec<Run>>,
+    /// The error that is reported by the node that is about to be run instead of running.
+    depth_error: Cell<Option<Box<UpdateDepthExceeded>>>,
+    /// Number of scopes that are currently being constructed. See [`InitialRun::Deferred`].
+    constructing: Cell<usize>,
+    /// The nodes whose first run is deferred until the outermost scope that is being constructed
+    /// is finished.
+    deferred: RefCell<Vec<(EffectId, NodeKind, WeakEffectCallback)>>,
+}
+
+/// Nodes are run in ascending order of their rank (always `0` for effects), of their order hint and
+/// then of their id.
+type QueueKey = (u32, i32, EffectId);
+
+/// A node in a queue of the [`Scheduler`].
+struct Queued {
+    cb: WeakEffectCallback,
+    /// Either [`NodeState::Dirty`] or [`NodeState::MaybeDirty`].
+    state: NodeState,
+    /// The number of effects in the chain of updates that queued the node.
+    depth: usize,
+    /// The signal that queued the node, if any.
+    signal: Option<SignalId>,
+    /// The node that was running when the node was queued, if any.
+    parent: Option<(EffectId, NodeKind)>,
+    /// The transition that queued the node, if any.
+    transition: Option<Transition>,
+}
+
+/// A run of a node whose update depth is close to the maximum.
+struct Run {
+    node: EffectId,
+    kind: NodeKind,
+    signal: Option<SignalId>,
+    parent: Option<(EffectId, NodeKind)>,
+}
+
+/// The number of steps of the chain of updates that are reported in an [`UpdateDepthExceeded`].
+const REPORTED_STEPS: usize = 16;
+
+thread_local! {
+    /// See [`set_max_update_depth`].
+    static MAX_UPDATE_DEPTH: Cell<usize> = const { Cell::new(100) };
+
+    /// The order hints of the nodes that have a hint other than `0`. These are shared by all the
+    /// runtimes of the thread because effect ids are unique.
+    static ORDER_HINTS: RefCell<HashMap<EffectId, i32>> = Default::default();
+}
+
+/// Sets the maximum update depth on the current thread, i.e. the maximum number of effects in a
+/// chain of updates in which each effect updates a signal that triggers the next effect. Defaults
+/// to `100`.
+///
+/// When the maximum is exceeded, the next node is not run. Instead, an [`UpdateDepthExceeded`]
+/// error with the last steps of the chain is routed to the error boundary or error hook of the
+/// node (see [`EffectPanic::update_depth_exceeded`]), or raised as a panic if there is none. This
+/// usually means that effects update each other in a cycle, which would otherwise loop forever.
+pub fn set_max_update_depth(depth: usize) {
+    MAX_UPDATE_DEPTH.with(|max| max.set(depth));
+}
+
+impl Scheduler {
+    /// Queues an effect or memo to be run on the next flush, or to be skipped if it is still
+    /// [`MaybeDirty`](NodeState::MaybeDirty) by then. A node that is already queued is only ever
+    /// marked as more dirty. `signal` is the signal whose update queued the node, if any. `rank` is
+    /// the rank of the node, which is ignored for effects. See [`Subscribers::rank`].
+    ///
+    /// Returns `true` if the node was not queued yet.
+    pub fn schedule(
+        &self,
+        id: EffectId,
+        kind: NodeKind,
+        cb: WeakEffectCallback,
+        signal: Option<SignalId>,
+        rank: u32,
+        state: NodeState,
+    ) -> bool {
+        match cb.upgrade() {
+            // An effect that is currently running stays subscribed to its dependencies but should
+            // not be triggered by its own updates.
+            Some(callback) if callback.try_borrow_mut().is_ok() => {}
+            // The effect might have already been destroyed in the case of nested effects.
+            _ => return false,
+        }
+        let (depth, parent) = self.running.get();
+        // Only effects increase the depth so that long chains of memos are not limited.
+        let depth = match parent {
+            Some((_, NodeKind::Effect)) => depth + 1,
+            _ => depth,
+        };
+        let mut queue = self.queue(kind).borrow_mut();
+        let mut key = queue_key(id);
+        if kind == NodeKind::Memo {
+            let mut ranks = self.memo_ranks.borrow_mut();
+            let queued_rank = ranks.entry(id).or_insert(rank);
+            key.0 = *queued_rank;
+            if rank > key.0 {
+                // Move the memo after the signal that queued it.
+                if let Some(queued) = queue.remove(&key) {
+                    queue.insert((rank, key.1, id), queued);
+                }
+                *queued_rank = rank;
+                key.0 = rank;
+            }
+        }
+        let mut inserted = false;
+        let queued = queue.entry(key).or_insert_with(|| {
+            inserted = true;
+            Queued {
+                cb,
+                state,
+                depth,
+                signal,
+                parent,
+                transition: None,
+            }
+        });
+        if state == NodeState::MaybeDirty {
+            return inserted;
+        }
+        // A node that is queued both by a transition and by an urgent update waits for the
+        // transition.
+        if queued.transition.is_none() {
+            queued.transition = self.transition.borrow().clone();
+        }
+        if queued.state == NodeState::MaybeDirty || depth > queued.depth {
+            queued.state = NodeState::Dirty;
+            queued.depth = depth;
+            queued.signal = signal;
+            queued.parent = parent;
+        }
+        inserted
+    }
+
+    /// Removes a node from its queue. Returns `true` if the node was queued.
+    pub fn unschedule(&self, id: EffectId, kind: NodeKind) -> bool {
+        match self.queued_key(id, kind) {
+            Some(key) => {
+                self.memo_ranks.borrow_mut().remove(&id);
+                self.queue(kind).borrow_mut().remove(&key).is_some()
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the state of a node, which is [`NodeState::Clean`] if the node is not queued.
+    pub fn state(&self, id: EffectId, kind: NodeKind) -> NodeState {
+        self.queued_key(id, kind)
+            .and_then(|key| Some(self.queue(kind).borrow().get(&key)?.state))
+            .unwrap_or(NodeState::Clean)
+    }
+
+    /// Returns the key of a node in its queue, or `None` if it is a memo that is not queued.
+    fn queued_key(&self, id: EffectId, kind: NodeKind) -> Option<QueueKey> {
+        let mut key = queue_key(id);
+        if kind == NodeKind::Memo {
+            key.0 = *self.memo_ranks.borrow().get(&id)?;
+        }
+        Some(key)
+    }
+
+    fn queue(&self, kind: NodeKind) -> &RefCell<BTreeMap<QueueKey, Queued>> {
+        match kind {
+            NodeKind::Memo => &self.memos,
+            NodeKind::Effect => &self.effects,
+        }
+    }
+
+    /// Runs all the queued memos and effects, unless inside a [`batch`].
+    pub fn flush(&self) {
+        if self.batch_depth.get() > 0 {
+            return;
+        }
+        if self.running_memos.replace(true) {
+            return;
+        }
+        let guard = ResetOnDrop(&self.running_memos);
+        self.run_queue(NodeKind::Memo);
+        drop(guard);
+        if self.running_effects.replace(true) {
+            return;
+        }
+        let _guard = ResetOnDrop(&self.running_effects);
+        self.run_queue(NodeKind::Effect);
+        self.history.borrow_mut().clear();
+    }
+
+    /// Runs the callbacks in the queue of `kind` in creation order until it is empty.
+    fn run_queue(&self, kind: NodeKind) {
+        let max_depth = MAX_UPDATE_DEPTH.with(Cell::get);
+        loop {
+            let next = self.queue(kind).borrow_mut().pop_first();
+            let Some(((_, _, id), queued)) = next else {
+                break;
+            };
+            if kind == NodeKind::Memo {
+                self.memo_ranks.borrow_mut().remove(&id);
+            }
+            // None of the memos that the node depends on changed.
+            if queued.state == NodeState::MaybeDirty {
+                continue;
+            }
+            let Some(callback) = queued.cb.upgrade() else {
+                continue;
+            };
+            let Ok(mut callback) = callback.try_borrow_mut() else {
+                continue;
+            };
+            if queued.depth + REPORTED_STEPS > max_depth {
+                self.history.borrow_mut().push(Run {
+                    node: id,
+                    kind,
+                    signal: queued.signal,
+                    parent: queued.parent,
+                });
+            }
+            if queued.depth > max_depth {
+                // The callback reports the error instead of running the node.
+                self.depth_error
+                    .set(Some(Box::new(self.update_depth_exceeded(max_depth))));
+            }
+            let previous = self.running.replace((queued.depth, Some((id, kind))));
+            let _guard = RestoreOnDrop(&self.running, previous);
+            self.with_transition(queued.transition, &mut *callback);
+            self.depth_error.take();
+        }
+    }
+
+    /// Returns the error for the last run in the history, with the chain of updates that led to
+    /// it.
+    fn update_depth_exceeded(&self, max_depth: usize) -> UpdateDepthExceeded {
+        let history = self.history.borrow();
+        let mut chain = Vec::new();
+        let mut next = history.last();
+        while let Some(run) = next {
+            chain.push(UpdateStep {
+                signal: run.signal,
+                signal_name: run.signal.and_then(debug::signal_name),
+                node: run.node,
+                is_memo: run.kind == NodeKind::Memo,
+            });
+            if chain.len() == REPORTED_STEPS {
+                break;
+            }
+            next = run
+                .parent
+                .and_then(|(parent, _)| history.iter().rev().find(|run| run.node == parent));
+        }
+        chain.reverse();
+        UpdateDepthExceeded { max_depth, chain }
+    }
+
+    /// Queues the first run of a node. See [`InitialRun::Deferred`].
+    pub fn defer_initial_run(&self, id: EffectId, kind: NodeKind, cb: WeakEffectCallback) {
+        if self.constructing.get() > 0 {
+            self.deferred.borrow_mut().push((id, kind, cb));
+        } else {
+            self.schedule(id, kind, cb, None, 0, NodeState::Dirty);
+            self.flush();
+        }
+    }
+
+    /// Calls `f`, which constructs a scope. Once the outermost scope that is being constructed is
+    /// finished, the nodes whose first run was deferred are queued and run.
+    pub fn construct<T>(&self, f: impl FnOnce() -> T) -> T {
+        self.constructing.set(self.constructing.get() + 1);
+        let guard = DecrementOnDrop(&self.constructing);
+        let ret = f();
+        drop(guard);
+        if self.constructing.get() == 0 && !self.deferred.borrow().is_empty() {
+            for (id, kind, cb) in self.deferred.take() {
+                self.schedule(id, kind, cb, None, 0, NodeState::Dirty);
+            }
+            self.flush();
+        }
+        ret
+    }
+
+    /// Calls `f` with the signal updates that it makes belonging to `transition`.
+    pub fn with_transition<T>(&self, transition: Option<Transition>, f: impl FnOnce() -> T) -> T {
+        let previous = self.transition.replace(transition);
+        let _guard = RestoreTransitionOnDrop(&self.transition, previous);
+        f()
+    }
+
+    /// Returns the transition that queued the node with the given `id` if the node is currently
+    /// being run by a flush.
+    pub fn running_transition(&self, id: EffectId) -> Option<Transition> {
+        match self.running.get().1 {
+            Some((running, _)) if running == id => self.transition.borrow().clone(),
+            _ => None,
+        }
+    }
+
+    /// Returns the error that the node that is about to be run should report instead of running,
+    /// if any. See [`set_max_update_depth`].
+    pub fn take_depth_error(&self) -> Option<UpdateDepthExceeded> {
+        self.depth_error.take().map(|error| *error)
+    }
+}
+
+/// Returns the key of a node in its queue, with a rank of `0`.
+fn queue_key(id: EffectId) -> QueueKey {
+    let hint = ORDER_HINTS.with(|hints| hints.borrow().get(&id).copied().unwrap_or_default());
+    (0, hint, id)
+}
+
+/// Removes the order hint of a node that is being destroyed.
+pub(crate) fn remove_order_hint(id: EffectId) {
+    let _ = ORDER_HINTS.try_with(|hints| {
+        let mut hints = hints.borrow_mut();
+        if !hints.is_empty() {
+            hints.remove(&id);
+        }
+    });
+}
+
+impl EffectId {
+    /// Sets the order hint of the effect or memo. Within each phase of a flush (memos first, then
+    /// effects), nodes with a lower hint are run before nodes with a higher hint, regardless of
+    /// when they were created. Nodes with the same hint are run in creation order. The default
+    /// hint is `0`. Memos are always run after the memos they depend on, so the hint only orders
+    /// memos of the same rank.
+    ///
+    /// This is only needed in rare cases. The creation order is usually the right order because a
+    /// node can only depend on nodes that were created before it.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let state = ctx.create_signal(0);
+    /// let log = ctx.create_signal(Vec::new());
+    /// ctx.create_effect(|| {
+    ///     state.track();
+    ///     log.set([log.get_untracked().as_slice(), &["first"]].concat());
+    /// });
+    /// let last = ctx.create_effect(|| {
+    ///     state.track();
+    ///     log.set([log.get_untracked().as_slice(), &["second"]].concat());
+    /// });
+    /// last.set_order_hint(-1);
+    ///
+    /// log.set(Vec::new());
+    /// state.set(1);
+    /// assert_eq!(*log.get(), ["second", "first"]);
+    /// # });
+    /// ```
+    pub fn set_order_hint(self, hint: i32) {
+        ORDER_HINTS.with(|hints| {
+            let mut hints = hints.borrow_mut();
+            if hint == 0 {
+                hints.remove(&self);
+            } else {
+                hints.insert(self, hint);
+            }
+        });
+    }
+}
+
+/// Resets a flag when dropped, even if a callback panics.
+struct ResetOnDrop<'a>(&'a Cell<bool>);
+
+impl Drop for ResetOnDrop<'_> {
+    fn drop(&mut self) {
+        self.0.set(false);
+    }
+}
+
+/// Restores the running node when dropped, even if a callback panics.
+struct RestoreOnDrop<'a>(
+    &'a Cell<(usize, Option<(EffectId, NodeKind)>)>,
+    (usize, Option<(EffectId, NodeKind)>),
+);
+
+impl Drop for RestoreOnDrop<'_> {
+    fn drop(&mut self) {
+        self.0.set(self.1);
+    }
+}
+
+/// Restores the transition of the [`Scheduler`] when dropped, even if the code that changed it
+/// panics.
+struct RestoreTransitionOnDrop<'a>(&'a RefCell<Option<Transition>>, Option<Transition>);
+
+impl Drop for RestoreTransitionOnDrop<'_> {
+    fn drop(&mut self) {
+        *self.0.borrow_mut() = self.1.take();
+    }
+}
+
+/// Decrements a counter when dropped, even if the code that incremented it panics.
+struct DecrementOnDrop<'a>(&'a Cell<usize>);
+
+impl Drop for DecrementOnDrop<'_> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() - 1);
+    }
+}
+
+/// Batches all the signal updates inside the closure. Memos and effects that depend on the
+/// updated signals are only run once, after the closure returns.
+///
+/// Only the memos and effects of the current [`Runtime`] are batched.
+///
+/// Note that memos are not updated until the end of the batch, so reading a memo inside the batch
+/// returns the value from before the batch.
+///
+/// # Example
+/// ```
+/// # use sycamore_reactive::*;
+/// # create_scope_immediate(|ctx| {
+/// let first = ctx.create_signal("Jane");
+/// let last = ctx.create_signal("Doe");
+/// let runs = ctx.create_signal(0);
+/// ctx.create_effect(|| {
+///     first.track();
+///     last.track();
+///     runs.set(*runs.get_untracked() + 1);
+/// });
+/// assert_eq!(*runs.get(), 1);
+///
+/// batch(|| {
+///     first.set("John");
+///     last.set("Smith");
+///     assert_eq!(*runs.get(), 1); // Not run yet.
+/// });
+/// assert_eq!(*runs.get(), 2); // Only run once for both updates.
+/// # });
+/// ```
+pub fn batch<T>(f: impl FnOnce() -> T) -> T {
+    let runtime = Runtime::current();
+    let scheduler = runtime.scheduler();
+    scheduler.batch_depth.set(scheduler.batch_depth.get() + 1);
+    let guard = DecrementOnDrop(&scheduler.batch_depth);
+    let ret = f();
+    drop(guard);
+    scheduler.flush();
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_runs_effects_once() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(0);
+            let counter = ctx.create_signal(0);
+            ctx.create_effect(|| {
+                state.track();
+                counter.set(*counter.get_untracked() + 1);
+            });
+            assert_eq!(*counter.get(), 1);
+
+            batch(|| {
+                state.set(1);
+                state.set(2);
+                assert_eq!(*counter.get(), 1);
+            });
+            assert_eq!(*counter.get(), 2);
+        });
+    }
+
+    #[test]
+    fn nested_batch() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(0);
+            let double = ctx.create_memo(|| *state.get() * 2);
+            batch(|| {
+                batch(|| state.set(1));
+                assert_eq!(*double.get(), 0);
+            });
+            assert_eq!(*double.get(), 2);
+        });
+    }
+
+    #[test]
+    fn memo_diamond_is_glitch_free() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(1);
+            let double = ctx.create_memo(|| *state.get() * 2);
+            let sum = ctx.create_memo(|| *state.get() + *double.get());
+            let observed = ctx.create_signal(Vec::new());
+            ctx.create_effect(|| {
+                let value = (*state.get(), *double.get(), *sum.get());
+                observed.set([observed.get_untracked().as_slice(), &[value]].concat());
+            });
+
+            state.set(2);
+            // The effect only sees consistent values and only runs once per update.
+            assert_eq!(*observed.get(), [(1, 2, 3), (2, 4, 6)]);
+        });
+    }
+
+    #[test]
+    fn memo_is_recomputed_once_per_update() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(1);
+            let double = ctx.create_memo(|| *state.get() * 2);
+            let runs = ctx.create_signal(0);
+            let sum = ctx.create_memo(|| {
+                runs.set(*runs.get_untracked() + 1);
+                *state.get() + *double.get()
+            });
+            assert_eq!(*runs.get(), 1);
+
+            state.set(2);
+            assert_eq!(*sum.get(), 6);
+            assert_eq!(*runs.get(), 2);
+        });
+    }
+
+    #[test]
+    fn memo_created_before_dependency_is_recomputed_once() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(1);
+            let placeholder: &ReadSignal<i32> = ctx.create_signal(0);
+            let selected = ctx.create_signal(placeholder);
+            let runs = Rc::new(Cell::new(0));
+            let sum = ctx.create_memo({
+                let runs = Rc::clone(&runs);
+                move || {
+                    runs.set(runs.get() + 1);
+                    *state.get() + *selected.get().get()
+                }
+            });
+            // `double` is created after `sum` but `sum` depends on it.
+            let double = ctx.create_memo(|| *state.get() * 2);
+            selected.set(double);
+            assert_eq!(*sum.get(), 3);
+            assert_eq!(runs.get(), 2);
+
+            state.set(2);
+            assert_eq!(*sum.get(), 6);
+            assert_eq!(runs.get(), 3);
+        });
+    }
+
+    #[test]
+    fn memo_is_fresh_inside_effect() {
+        create_scope_immediate(|ctx| {
+            let trigger = ctx.create_signal(());
+            let state = ctx.create_signal(0);
+            let double = ctx.create_memo(|| *state.get() * 2);
+            let observed = ctx.create_signal(0);
+            ctx.create_effect(|| {
+                trigger.track();
+                state.set(*state.get_untracked() + 1);
+                observed.set(*double.get_untracked());
+            });
+            assert_eq!(*observed.get(), 2);
+            trigger.set(());
+            assert_eq!(*observed.get(), 4);
+        });
+    }
+
+    #[test]
+    fn memo_chain_below_unchanged_selector_is_not_recomputed() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(1);
+            let is_even = ctx.create_selector(|| *state.get() % 2 == 0);
+            let runs = Rc::new(Cell::new(0));
+            let mut memos = Vec::new();
+            let mut last: &ReadSignal<bool> = is_even;
+            for _ in 0..10 {
+                let prev = last;
+                let runs = Rc::clone(&runs);
+                let memo = ctx.create_memo(move || {
+                    runs.set(runs.get() + 1);
+                    *prev.get()
+                });
+                memos.push(memo);
+                last = memo;
+            }
+            let effect_runs = ctx.create_signal(0);
+            ctx.create_effect(|| {
+                last.track();
+                effect_runs.set(*effect_runs.get_untracked() + 1);
+            });
+            assert_eq!(runs.get(), 10);
+
+            batch(|| {
+                state.set(3);
+                assert_eq!(is_even.state(), NodeState::Dirty);
+                assert!(memos
+                    .iter()
+                    .all(|memo| memo.state() == NodeState::MaybeDirty));
+            });
+            assert!(memos.iter().all(|memo| memo.state() == NodeState::Clean));
+            assert_eq!(runs.get(), 10);
+            assert_eq!(*effect_runs.get(), 1);
+
+            state.set(4);
+            assert!(*last.get());
+            assert_eq!(runs.get(), 20);
+            assert_eq!(*effect_runs.get(), 2);
+        });
+    }
+
+    #[test]
+    fn maybe_dirty_memo_is_marked_dirty_by_changed_dependency() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(1);
+            let double = ctx.create_memo(|| *state.get() * 2);
+            let quadruple = ctx.create_memo(|| *double.get() * 2);
+            batch(|| {
+                state.set(2);
+                assert_eq!(quadruple.state(), NodeState::MaybeDirty);
+                double.force_recompute();
+                assert_eq!(double.state(), NodeState::Clean);
+                assert_eq!(quadruple.state(), NodeState::Dirty);
+                assert_eq!(*quadruple.get(), 4);
+            });
+            assert_eq!(*quadruple.get(), 8);
+        });
+    }
+
+    #[test]
+    fn memo_written_signal_marks_dependents_maybe_dirty() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(1);
+            let written = ctx.create_signal(0);
+            ctx.create_memo(|| written.set(*state.get() * 2));
+            let plus_one = ctx.create_memo(|| *written.get() + 1);
+            batch(|| {
+                state.set(2);
+                assert_eq!(plus_one.state(), NodeState::MaybeDirty);
+            });
+            assert_eq!(*plus_one.get(), 5);
+        });
+    }
+
+    #[test]
+    fn long_memo_chain_does_not_recurse() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(0);
+            let mut last: &ReadSignal<i32> = state;
+            for _ in 0..10_000 {
+                let prev = last;
+                last = ctx.create_memo(move || *prev.get() + 1);
+            }
+            state.set(1);
+            assert_eq!(*last.get(), 10_001);
+        });
+    }
+
+    #[test]
+    fn nested_updates_are_queued() {
+        create_scope_immediate(|ctx| {
+            let a = ctx.create_signal(0);
+            let b = ctx.create_signal(0);
+            let log = ctx.create_signal(Vec::new());
+            let push = move |entry: &'static str| {
+                log.set([log.get_untracked().as_slice(), &[entry]].concat());
+            };
+            ctx.create_effect(move || {
+                a.track();
+                push("a start");
+                b.set(*a.get_untracked());
+                push("a end");
+            });
+            ctx.create_effect(move || {
+                b.track();
+                push("b");
+            });
+            log.set(Vec::new());
+
+            a.set(1);
+            // The effect on `b` only runs once the effect on `a` has finished.
+            assert_eq!(*log.get(), ["a start", "a end", "b"]);
+        });
+    }
+
+    #[test]
+    fn effects_run_in_creation_order() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(0);
+            let log = ctx.create_signal(Vec::new());
+            let push = move |entry: i32| {
+                log.set([log.get_untracked().as_slice(), &[entry]].concat());
+            };
+            // Subscribe to `state` in a different order than the creation order.
+            let other = ctx.create_signal(0);
+            ctx.create_effect(move || {
+                other.track();
+                if *other.get_untracked() > 0 {
+                    state.track();
+                }
+                push(1);
+            });
+            ctx.create_effect(move || {
+                state.track();
+                push(2);
+            });
+            other.set(1);
+            log.set(Vec::new());
+
+            state.set(1);
+            assert_eq!(*log.get(), [1, 2]);
+        });
+    }
+
+    #[test]
+    fn order_hint() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(0);
+            let log = ctx.create_signal(Vec::new());
+            let push = move |entry: i32| {
+                log.set([log.get_untracked().as_slice(), &[entry]].concat());
+            };
+            let first = ctx.create_effect(move || {
+                state.track();
+                push(1);
+            });
+            ctx.create_effect(move || {
+                state.track();
+                push(2);
+            });
+            let third = ctx.create_effect(move || {
+                state.track();
+                push(3);
+            });
+            first.set_order_hint(1);
+            third.set_order_hint(-1);
+            log.set(Vec::new());
+
+            state.set(1);
+            assert_eq!(*log.get(), [3, 2, 1]);
+
+            first.set_order_hint(0);
+            third.set_order_hint(0);
+            log.set(Vec::new());
+            state.set(2);
+            assert_eq!(*log.get(), [1, 2, 3]);
+        });
+    }
+
+    #[test]
+    fn effect_cycle_exceeds_max_update_depth() {
+        create_scope_immediate(|ctx| {
+            let boundary = ctx.create_error_boundary();
+            let a = ctx.create_signal(0);
+            let b = ctx.create_signal(0);
+            ctx.create_effect(|| b.set(*a.get() + 1));
+            ctx.create_effect(|| a.set(*b.get() + 1));
+            assert!(boundary.error().is_none());
+
+            a.set(0);
+            let error = boundary.error().unwrap();
+            let error = error.update_depth_exceeded().unwrap();
+            assert_eq!(error.max_depth, 100);
+            assert_eq!(error.chain.len(), REPORTED_STEPS);
+            assert!(error.chain.iter().all(|step| !step.is_memo));
+            // The steps alternate between the two effects.
+            assert_ne!(error.chain[0].node, error.chain[1].node);
+            assert_eq!(error.chain[0].node, error.chain[2].node);
+        });
+    }
+
+    #[test]
+    fn set_max_update_depth_limits_effect_chain() {
+        set_max_update_depth(2);
+        create_scope_immediate(|ctx| {
+            let errors = Rc::new(Cell::new(0));
+            ctx.set_error_hook({
+                let errors = errors.clone();
+                move |panic| {
+                    assert!(panic.update_depth_exceeded().is_some());
+                    errors.set(errors.get() + 1);
+                }
+            });
+            // Each effect copies a signal into the next one, so the last effect has depth 3.
+            let signals: Vec<_> = (0..5).map(|_| ctx.create_signal(0)).collect();
+            for pair in signals.windows(2) {
+                let (from, to) = (pair[0], pair[1]);
+                ctx.create_effect(move || to.set(*from.get()));
+            }
+
+            signals[0].set(1);
+            assert_eq!(errors.get(), 1);
+            assert_eq!(*signals[3].get(), 1);
+            assert_eq!(*signals[4].get(), 0);
+        });
+        set_max_update_depth(100);
+    }
+
+    #[test]
+    fn memo_chain_is_not_limited() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(0);
+            let mut last: &ReadSignal<i32> = state;
+            for _ in 0..200 {
+                let prev = last;
+                last = ctx.create_memo(move || *prev.get() + 1);
+            }
+            state.set(1);
+            assert_eq!(*last.get(), 201);
+        });
+    }
+}
@@ -0,0 +1,187 @@
+//! Child scopes that are identified by a key.
+
+use crate::hash_map::Entry;
+use core::fmt;
+use core::hash::Hash;
+
+use crate::*;
+
+/// A map of child scopes identified by keys of type `K`, created with
+/// [`create_scope_map`](Scope::create_scope_map).
+///
+/// Each key owns at most one child scope. The child scope is disposed when its key is removed or
+/// when the parent scope is disposed. This is useful for routers and tab views that keep the
+/// scopes of the pages that have been visited.
+pub struct ScopeMap<'a, K> {
+    ctx: ScopeRef<'a>,
+    scopes: RefCell<HashMap<K, ScopeHandle<'a>>>,
+}
+
+impl<'a, K: Hash + Eq> ScopeMap<'a, K> {
+    /// Creates a child scope for `key` with `init` if there is none yet. Returns `true` if the
+    /// child scope was created and `false` if it existed already.
+    pub fn ensure<F>(&self, key: K, init: F) -> bool
+    where
+        F: for<'child_lifetime> FnOnce(BoundedScopeRef<'child_lifetime, 'a>),
+    {
+        if self.contains_key(&key) {
+            return false;
+        }
+        // The map is not borrowed while the child scope is created so that `init` can access it.
+        let handle = self.ctx.create_detached_child_scope(init);
+        match self.scopes.borrow_mut().entry(key) {
+            Entry::Occupied(_) => panic!("key was added inside of `init`"),
+            Entry::Vacant(entry) => {
+                entry.insert(handle);
+            }
+        }
+        true
+    }
+
+    /// Disposes the child scope of `key`. Returns `true` if there was a child scope for `key`.
+    pub fn remove(&self, key: &K) -> bool {
+        let handle = self.scopes.borrow_mut().remove(key);
+        // The child scope is disposed after the map is released.
+        handle.is_some()
+    }
+
+    /// Disposes the child scopes of the keys for which `f` returns `false`.
+    pub fn retain(&self, mut f: impl FnMut(&K) -> bool) {
+        let mut removed = Vec::new();
+        let mut scopes = self.scopes.borrow_mut();
+        *scopes = core::mem::take(&mut *scopes)
+            .into_iter()
+            .filter_map(|(key, handle)| {
+                if f(&key) {
+                    Some((key, handle))
+                } else {
+                    removed.push(handle);
+                    None
+                }
+            })
+            .collect();
+        drop(scopes);
+        // The child scopes are disposed after the map is released.
+        drop(removed);
+    }
+
+    /// Disposes all the child scopes.
+    pub fn clear(&self) {
+        let scopes = self.scopes.take();
+        drop(scopes);
+    }
+
+    /// Returns `true` if there is a child scope for `key`.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.scopes.borrow().contains_key(key)
+    }
+
+    /// Returns the number of child scopes.
+    pub fn len(&self) -> usize {
+        self.scopes.borrow().len()
+    }
+
+    /// Returns `true` if there are no child scopes.
+    pub fn is_empty(&self) -> bool {
+        self.scopes.borrow().is_empty()
+    }
+}
+
+impl<K> fmt::Debug for ScopeMap<'_, K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScopeMap")
+            .field("len", &self.scopes.borrow().len())
+            .finish()
+    }
+}
+
+impl<'a> Scope<'a> {
+    /// Creates a [`ScopeMap`] that owns child scopes of this scope, identified by keys of type `K`.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let tabs = ctx.create_scope_map();
+    /// let tab = ctx.create_signal("home");
+    /// ctx.create_effect(|| {
+    ///     let tab = *tab.get();
+    ///     // The scope of a tab is only created the first time the tab is opened.
+    ///     tabs.ensure(tab, |ctx| {
+    ///         ctx.on_cleanup(move || println!("closed {tab}"));
+    ///     });
+    /// });
+    ///
+    /// tab.set("settings");
+    /// assert_eq!(tabs.len(), 2);
+    /// tabs.remove(&"settings"); // Prints "closed settings"
+    /// # });
+    /// ```
+    pub fn create_scope_map<K: Hash + Eq + 'a>(&'a self) -> &'a ScopeMap<'a, K> {
+        self.create_ref(ScopeMap {
+            ctx: self,
+            scopes: Default::default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ensure_creates_scope_once() {
+        create_scope_immediate(|ctx| {
+            let map = ctx.create_scope_map();
+            let created = ctx.create_signal(0);
+            assert!(map.ensure(1, |_| created.set(*created.get() + 1)));
+            assert!(!map.ensure(1, |_| created.set(*created.get() + 1)));
+            assert_eq!(*created.get(), 1);
+            assert!(map.contains_key(&1));
+            assert_eq!(map.len(), 1);
+        });
+    }
+
+    #[test]
+    fn removed_scopes_are_disposed() {
+        create_scope_immediate(|ctx| {
+            let map = ctx.create_scope_map();
+            let disposed = ctx.create_signal(Vec::new());
+            for key in 0..4 {
+                map.ensure(key, |ctx| {
+                    ctx.on_cleanup(move || {
+                        disposed.set([disposed.get_untracked().as_slice(), &[key]].concat());
+                    })
+                });
+            }
+
+            assert!(map.remove(&0));
+            assert!(!map.remove(&0));
+            assert_eq!(*disposed.get(), [0]);
+
+            map.retain(|key| *key != 2);
+            assert_eq!(*disposed.get(), [0, 2]);
+
+            map.clear();
+            assert!(map.is_empty());
+            let mut disposed = disposed.get().to_vec();
+            disposed.sort_unstable();
+            assert_eq!(disposed, [0, 1, 2, 3]);
+        });
+    }
+
+    #[test]
+    fn scopes_are_disposed_with_parent() {
+        let disposed = create_rc_signal(0);
+        create_scope_immediate(|ctx| {
+            let map = ctx.create_scope_map();
+            for key in 0..2 {
+                map.ensure(key, |ctx| {
+                    let disposed = disposed.clone();
+                    ctx.on_cleanup(move || disposed.set(*disposed.get_untracked() + 1));
+                });
+            }
+        });
+        assert_eq!(*disposed.get(), 2);
+    }
+}
@@ -0,0 +1,206 @@
+//! Lazily constructed services that are provided through contexts.
+
+use core::any::type_name;
+use core::cell::OnceCell;
+
+use crate::*;
+
+/// The factory of a service. Called at most once, with the scope that registered the service.
+type Factory<T> = Box<dyn for<'s> FnOnce(ScopeRef<'s>) -> T>;
+
+/// A service that is registered with [`Scope::register_service`]. Stored as a context of the
+/// scope that registered it.
+struct Service<T> {
+    factory: Cell<Option<Factory<T>>>,
+    value: OnceCell<T>,
+    /// Whether the factory is currently running, to tell a cyclic dependency apart from a factory
+    /// that panicked.
+    constructing: Cell<bool>,
+    /// The scope that registered the service.
+    ///
+    /// # Safety
+    /// The scope does not actually have the `'static` lifetime. It outlives the service because
+    /// the service is a context of the scope.
+    scope: *const Scope<'static>,
+}
+
+impl<T> Service<T> {
+    /// Returns the value of the service, constructing it first if necessary.
+    fn get(&self) -> &T {
+        if let Some(value) = self.value.get() {
+            return value;
+        }
+        if self.constructing.get() {
+            panic!(
+                "cyclic dependency while constructing service `{}`",
+                type_name::<T>()
+            );
+        }
+        let factory = self.factory.take().unwrap_or_else(|| {
+            panic!(
+                "factory of service `{}` panicked previously",
+                type_name::<T>()
+            )
+        });
+
+        /// Resets `constructing` even if the factory panics.
+        struct ResetOnDrop<'a>(&'a Cell<bool>);
+        impl Drop for ResetOnDrop<'_> {
+            fn drop(&mut self) {
+                self.0.set(false);
+            }
+        }
+        self.constructing.set(true);
+        let guard = ResetOnDrop(&self.constructing);
+        // SAFETY: The scope outlives the service. See `Service::scope`.
+        let value = untrack(|| factory(unsafe { &*self.scope }));
+        drop(guard);
+        // Cannot be initialized already: the factory is only taken once.
+        let _ = self.value.set(value);
+        self.value.get().unwrap()
+    }
+}
+
+impl<'a> Scope<'a> {
+    /// Registers a service of type `T` in the current [`Scope`]. The service can later be
+    /// accessed with [`resolve`](Self::resolve) lower in the scope hierarchy, like a context.
+    ///
+    /// Unlike [`provide_context`](Self::provide_context), the value of the service is only
+    /// constructed the first time that it is resolved, by calling `factory` with the scope that
+    /// registered the service. The factory can therefore resolve the other services that the
+    /// service depends on. The value is shared by all the scopes that resolve it and is dropped
+    /// when the registering scope is disposed.
+    ///
+    /// This is useful for non-reactive parts of an app that are expensive to create or that
+    /// depend on each other, such as API clients and caches.
+    ///
+    /// # Panics
+    /// This method panics if a service of type `T` is registered already in this scope. A service
+    /// of the same type that is registered in a parent scope is shadowed.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// struct Config {
+    ///     base_url: String,
+    /// }
+    ///
+    /// struct ApiClient {
+    ///     base_url: String,
+    /// }
+    ///
+    /// # create_scope_immediate(|ctx| {
+    /// ctx.register_service(|_| Config { base_url: "https://example.com".to_string() });
+    /// ctx.register_service(|ctx| ApiClient {
+    ///     base_url: ctx.resolve::<Config>().base_url.clone(),
+    /// });
+    ///
+    /// let _ = ctx.create_child_scope(|ctx| {
+    ///     // Both services are constructed here.
+    ///     assert_eq!(ctx.resolve::<ApiClient>().base_url, "https://example.com");
+    /// });
+    /// # });
+    /// ```
+    pub fn register_service<T: 'static>(
+        &'a self,
+        factory: impl for<'s> FnOnce(ScopeRef<'s>) -> T + 'static,
+    ) {
+        self.provide_context(Service {
+            factory: Cell::new(Some(Box::new(factory) as Factory<T>)),
+            value: OnceCell::new(),
+            constructing: Cell::new(false),
+            // SAFETY: See `Service::scope`.
+            scope: unsafe { core::mem::transmute::<ScopeRef<'_>, ScopeRef<'static>>(self) },
+        });
+    }
+
+    /// Tries to get the service of type `T`, constructing it if this is the first time that it is
+    /// resolved. If no service of type `T` is registered in the current scope hierarchy, returns
+    /// `None`. For a panicking version, see [`resolve`](Self::resolve).
+    ///
+    /// # Panics
+    /// This method panics if the factory of the service (indirectly) resolves the service itself,
+    /// or if the factory panicked while constructing the service before.
+    pub fn try_resolve<T: 'static>(&'a self) -> Option<&'a T> {
+        self.try_use_context::<Service<T>>().map(Service::get)
+    }
+
+    /// Gets the service of type `T`, constructing it if this is the first time that it is
+    /// resolved. See [`register_service`](Self::register_service).
+    ///
+    /// # Panics
+    /// This method panics if no service of type `T` is registered in the current scope hierarchy
+    /// or if the factory of the service (indirectly) resolves the service itself or panicked
+    /// before. For a version that does not panic if the service is missing, see
+    /// [`try_resolve`](Self::try_resolve).
+    #[track_caller]
+    pub fn resolve<T: 'static>(&'a self) -> &'a T {
+        match self.try_resolve() {
+            Some(value) => value,
+            None => panic!("service `{}` is not registered", type_name::<T>()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn service_is_constructed_once() {
+        create_scope_immediate(|ctx| {
+            let constructed = Rc::new(Cell::new(0));
+            ctx.register_service({
+                let constructed = Rc::clone(&constructed);
+                move |_| {
+                    constructed.set(constructed.get() + 1);
+                    String::from("service")
+                }
+            });
+            assert_eq!(constructed.get(), 0);
+
+            let _ = ctx.create_child_scope(|ctx| {
+                assert_eq!(ctx.resolve::<String>(), "service");
+            });
+            assert_eq!(ctx.resolve::<String>(), "service");
+            assert_eq!(constructed.get(), 1);
+            assert!(ctx.try_resolve::<i32>().is_none());
+        });
+    }
+
+    #[test]
+    fn service_is_shadowed_in_child_scope() {
+        create_scope_immediate(|ctx| {
+            ctx.register_service(|_| 1);
+            ctx.register_service(|ctx| *ctx.resolve::<i32>() as u8 + 1);
+            let _ = ctx.create_child_scope(|ctx| {
+                ctx.register_service(|_| 10);
+                assert_eq!(*ctx.resolve::<i32>(), 10);
+                // Dependencies are resolved from the scope that registered the service.
+                assert_eq!(*ctx.resolve::<u8>(), 2);
+            });
+        });
+    }
+
+    #[test]
+    #[should_panic = "cyclic dependency while constructing service `i32`"]
+    fn cyclic_service_panics() {
+        create_scope_immediate(|ctx| {
+            ctx.register_service(|ctx| *ctx.resolve::<i32>());
+            ctx.resolve::<i32>();
+        });
+    }
+
+    #[test]
+    #[should_panic = "factory of service `i32` panicked previously"]
+    fn service_after_panicking_factory_panics() {
+        create_scope_immediate(|ctx| {
+            ctx.register_service::<i32>(|_| panic!("factory failed"));
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                ctx.resolve::<i32>();
+            }));
+            assert!(result.is_err());
+            ctx.resolve::<i32>();
+        });
+    }
+}
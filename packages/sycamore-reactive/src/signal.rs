@@ -1,71 +1,336 @@
 //! Signals - The building blocks of reactivity.
 
-use std::fmt::{Debug, Display, Formatter};
-use std::hash::Hash;
-use std::ops::Deref;
+use core::fmt::{Debug, Display, Formatter};
+use core::hash::Hash;
+use core::ops::Deref;
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use smallvec::SmallVec;
 
-use crate::effect::EFFECTS;
 use crate::*;
 
-type WeakEffectCallback = Weak<RefCell<dyn FnMut()>>;
-type EffectCallbackPtr = *const RefCell<dyn FnMut()>;
+/// Source of [`SignalId`]s.
+static NEXT_SIGNAL_ID: AtomicU64 = AtomicU64::new(0);
+
+/// An opaque identifier for a signal that is unique for the duration of the program.
+///
+/// Unlike the address of a signal, the id of a signal is never reused after the signal is
+/// dropped, so it can be used to key data about signals (e.g. in a map).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SignalId(u64);
+
+pub(crate) type WeakEffectCallback = Weak<RefCell<dyn FnMut()>>;
+
+/// A link from a [`SignalEmitter`] to an effect that depends on it.
+///
+/// Subscriptions are owned by the effect and form an intrusive doubly-linked list that is headed
+/// by the [`Subscribers`] of the emitter. Dropping a subscription unlinks it from the list in
+/// constant time without touching any of the other subscribers.
+pub(crate) struct Subscription {
+    /// See [`SignalEmitter::trigger_subscribers`].
+    id: EffectId,
+    kind: NodeKind,
+    cb: WeakEffectCallback,
+    /// The runtime whose scheduler runs the effect.
+    runtime: Runtime,
+    /// The rank and the output of the node.
+    node: Rc<NodeLinks>,
+    /// The list that this subscription is linked into, or null if it has been unlinked (e.g.
+    /// because the emitter was dropped).
+    list: Cell<*const Subscribers>,
+    prev: Cell<*const Subscription>,
+    next: Cell<*const Subscription>,
+}
+
+impl Subscription {
+    fn unlink(&self) {
+        let list = self.list.replace(core::ptr::null());
+        if list.is_null() {
+            return;
+        }
+        let prev = self.prev.replace(core::ptr::null());
+        let next = self.next.replace(core::ptr::null());
+        // SAFETY: While the subscription is linked, the list and the neighbouring subscriptions
+        // are alive because they unlink themselves from this subscription when dropped.
+        unsafe {
+            match prev.as_ref() {
+                Some(prev) => prev.next.set(next),
+                None => (*list).first.set(next),
+            }
+            if let Some(next) = next.as_ref() {
+                next.prev.set(prev);
+            }
+            (*list).len.set((*list).len.get() - 1);
+        }
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        self.unlink();
+    }
+}
+
+/// The head of the list of [`Subscription`]s of a [`SignalEmitter`].
+pub(crate) struct Subscribers {
+    id: SignalId,
+    /// The topological rank of the signal: `0` for signals that are only written outside of
+    /// memos, and otherwise the highest rank of the memos that wrote the signal. The rank of a memo
+    /// is one more than the highest rank of its dependencies, so queued memos that are run in
+    /// ascending order of rank are run after all their dependencies.
+    pub(crate) rank: Cell<u32>,
+    first: Cell<*const Subscription>,
+    len: Cell<usize>,
+}
+
+impl Subscribers {
+    /// Links a new subscription for the effect with the given `id`, `kind`, callback, runtime and
+    /// shared state into the list.
+    ///
+    /// The list must not be moved while the subscription is linked. This is guaranteed by only
+    /// ever creating subscriptions on lists inside an [`Rc`].
+    pub(crate) fn subscribe(
+        &self,
+        id: EffectId,
+        kind: NodeKind,
+        cb: WeakEffectCallback,
+        runtime: Runtime,
+        node: Rc<NodeLinks>,
+    ) -> Box<Subscription> {
+        let subscription = Box::new(Subscription {
+            id,
+            kind,
+            cb,
+            runtime,
+            node,
+            list: Cell::new(self),
+            prev: Cell::new(core::ptr::null()),
+            next: Cell::new(self.first.get()),
+        });
+        // SAFETY: Subscriptions in the list are alive. See `Subscription::unlink`.
+        if let Some(first) = unsafe { self.first.get().as_ref() } {
+            first.prev.set(&*subscription);
+        }
+        self.first.set(&*subscription);
+        self.len.set(self.len.get() + 1);
+        subscription
+    }
+
+    /// Queues the subscribers with `state`. `rank` is the rank of the signal. The outputs of the
+    /// memos that were not queued yet are pushed onto `outputs` with the rank of the memo, and the
+    /// runtimes of the subscribers onto `runtimes`.
+    fn schedule_subscribers(
+        &self,
+        state: NodeState,
+        rank: u32,
+        outputs: &mut SmallVec<[(Rc<Subscribers>, u32); 4]>,
+        runtimes: &mut SmallVec<[Runtime; 1]>,
+    ) {
+        let mut next = self.first.get();
+        // SAFETY: Subscriptions in the list are alive. See `Subscription::unlink`.
+        while let Some(subscription) = unsafe { next.as_ref() } {
+            let runtime = &subscription.runtime;
+            if state == NodeState::Dirty {
+                debug::node_triggered(subscription.id, self.id);
+            }
+            // The rank of the subscriber is only updated when it runs again, so it can be outdated
+            // if the rank of the signal increased since.
+            let node_rank = subscription.node.rank.get().max(rank + 1);
+            let queued = runtime.scheduler().schedule(
+                subscription.id,
+                subscription.kind,
+                subscription.cb.clone(),
+                Some(self.id),
+                node_rank,
+                state,
+            );
+            if queued && subscription.kind == NodeKind::Memo {
+                let memo_outputs = subscription.node.outputs.borrow();
+                outputs.extend(
+                    memo_outputs
+                        .iter()
+                        .filter_map(Weak::upgrade)
+                        .map(|output| (output, node_rank)),
+                );
+            }
+            if !runtimes.iter().any(|r| r.ptr_eq(runtime)) {
+                runtimes.push(runtime.clone());
+            }
+            next = subscription.next.get();
+        }
+    }
+
+    /// Returns the number of subscriptions that are linked into the list.
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.len.get()
+    }
+}
+
+impl Default for Subscribers {
+    fn default() -> Self {
+        Self {
+            id: SignalId(NEXT_SIGNAL_ID.fetch_add(1, Ordering::Relaxed)),
+            // The output signal of a memo is created by the memo.
+            rank: Cell::new(running_memo_rank()),
+            first: Cell::new(core::ptr::null()),
+            len: Cell::new(0),
+        }
+    }
+}
+
+impl Drop for Subscribers {
+    fn drop(&mut self) {
+        debug::signal_dropped(self.id);
+        // Detach all the subscriptions so that they do not access the list once it is dropped.
+        let mut next = self.first.replace(core::ptr::null());
+        // SAFETY: Subscriptions in the list are alive. See `Subscription::unlink`.
+        while let Some(subscription) = unsafe { next.as_ref() } {
+            next = subscription.next.replace(core::ptr::null());
+            subscription.prev.set(core::ptr::null());
+            subscription.list.set(core::ptr::null());
+        }
+    }
+}
 
 /// A struct for managing subscriptions to signals.
+///
+/// The subscriber list is reference-counted so that effects can hold weak references to the
+/// emitters they depend on and safely unsubscribe even if the signal has since been dropped.
+///
+/// Every signal is built on top of an emitter, but an emitter can also be used on its own to make
+/// any data source trackable, e.g. the state of a game loop or data that is fed in over FFI. Call
+/// [`track`](Self::track) whenever the data is read and [`trigger`](Self::trigger) whenever it
+/// changes. Memos and effects that read the data then rerun like for any other signal.
+///
+/// # Example
+/// ```
+/// # use sycamore_reactive::*;
+/// # use std::cell::Cell;
+/// struct Sensor {
+///     value: Cell<f64>,
+///     emitter: SignalEmitter,
+/// }
+///
+/// impl Sensor {
+///     fn read(&self) -> f64 {
+///         self.emitter.track();
+///         self.value.get()
+///     }
+///
+///     fn feed(&self, value: f64) {
+///         self.value.set(value);
+///         self.emitter.trigger();
+///     }
+/// }
+///
+/// # create_scope_immediate(|ctx| {
+/// let sensor = ctx.create_ref(Sensor { value: Cell::new(0.0), emitter: SignalEmitter::new() });
+/// let doubled = ctx.create_memo(|| sensor.read() * 2.0);
+/// sensor.feed(1.5);
+/// assert_eq!(*doubled.get(), 3.0);
+/// # });
+/// ```
 #[derive(Default)]
-pub struct SignalEmitter(RefCell<IndexMap<EffectCallbackPtr, WeakEffectCallback>>);
+pub struct SignalEmitter(pub(crate) Rc<Subscribers>);
 
 impl SignalEmitter {
-    /// Adds a callback to the subscriber list. If the callback is already a subscriber, does
-    /// nothing.
-    pub(crate) fn subscribe(&self, cb: WeakEffectCallback) {
-        self.0.borrow_mut().insert(cb.as_ptr(), cb);
+    /// Creates a new emitter without any subscribers. The emitter gets a new [`SignalId`].
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    /// Removes a callback from the subscriber list. If the callback is not a subscriber, does
-    /// nothing.
-    pub(crate) fn unsubscribe(&self, cb: EffectCallbackPtr) {
-        self.0.borrow_mut().remove(&cb);
+    /// Returns the [`SignalId`] of the signal that this emitter belongs to.
+    pub fn id(&self) -> SignalId {
+        self.0.id
     }
 
-    /// Track the current signal in the effect scope.
+    /// Returns a weak reference to the subscriber list.
+    pub(crate) fn downgrade(&self) -> Weak<Subscribers> {
+        Rc::downgrade(&self.0)
+    }
+
+    /// Track the current signal in the effect scope. This does nothing outside of a memo or an
+    /// effect.
     pub fn track(&self) {
-        EFFECTS.with(|effects| {
-            if let Some(last) = effects.borrow().last() {
-                // SAFETY: See guarantee on EffectState within EFFECTS.
-                let last = unsafe { &mut **last };
-                // SAFETY: `last` necessarily lasts longer than self.
-                last.add_dependency(unsafe { std::mem::transmute(self) });
-            }
-        });
+        let runtime = Runtime::current();
+        // Nothing is ever subscribed in a server side rendering runtime.
+        if runtime.is_ssr() {
+            return;
+        }
+        let effects = runtime.effects().borrow();
+        if let Some(last) = effects.last() {
+            // SAFETY: See guarantee on EffectState within the effect stack of the runtime.
+            let last = unsafe { &mut **last };
+            last.add_dependency(self);
+        }
     }
 
     /// Calls all the subscribers without modifying the state.
     /// This can be useful when using patterns such as inner mutability where the state updated will
     /// not be automatically triggered. In the general case, however, it is preferable to use
     /// [`Signal::set()`] instead.
+    ///
+    /// Subscribers are not called directly but are queued and run by the scheduler. Memos are
+    /// recomputed before effects, after the memos they depend on. Effects are run in the order in
+    /// which they were created (see [`EffectId::set_order_hint`] to override this order). This
+    /// ensures that outer effects re-execute before inner effects, preventing inner effects from
+    /// running twice. Inside a [`batch`], the subscribers are only called at the end of the batch.
+    ///
+    /// Each subscriber is queued on the [`Runtime`] it was created in.
+    ///
+    /// This is the same as [`trigger`](Self::trigger).
     pub fn trigger_subscribers(&self) {
-        // Clone subscribers to prevent modifying list when calling callbacks.
-        let subscribers = self.0.borrow().clone();
-        // Subscriber order is reversed because effects attach subscribers at the end of the
-        // effect scope. This will ensure that outer effects re-execute before inner effects,
-        // preventing inner effects from running twice.
-        for subscriber in subscribers.values().rev() {
-            // subscriber might have already been destroyed in the case of nested effects
-            if let Some(callback) = subscriber.upgrade() {
-                // Call the callback.
-                callback.borrow_mut()();
-            }
+        debug::signal_triggered(self.0.id);
+        let rank = self.0.rank.get().max(running_memo_rank());
+        self.0.rank.set(rank);
+        add_running_memo_output(self);
+        let mut runtimes = SmallVec::new();
+        let mut outputs = SmallVec::new();
+        self.0
+            .schedule_subscribers(NodeState::Dirty, rank, &mut outputs, &mut runtimes);
+        // The nodes that depend on the subscribers through memos are marked as maybe dirty. This
+        // uses a stack instead of recursion so that long chains of memos do not overflow it.
+        while let Some((output, rank)) = outputs.pop() {
+            output.schedule_subscribers(NodeState::MaybeDirty, rank, &mut outputs, &mut runtimes);
+        }
+        // The subscriptions can be unlinked by the flushes, so they are only run afterwards.
+        for runtime in runtimes {
+            runtime.scheduler().flush();
         }
     }
+
+    /// Notifies the subscribers that the data behind the emitter changed. See
+    /// [`trigger_subscribers`](Self::trigger_subscribers).
+    pub fn trigger(&self) {
+        self.trigger_subscribers();
+    }
+}
+
+impl Debug for SignalEmitter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("SignalEmitter").field(&self.id()).finish()
+    }
 }
 
+/// The equality function of a signal. See [`Scope::create_signal_with_eq`].
+pub(crate) type EqFn<'a, T> = Box<dyn Fn(&T, &T) -> bool + 'a>;
+
 /// A read-only [`Signal`].
-pub struct ReadSignal<T> {
+pub struct ReadSignal<T: ?Sized> {
     value: RefCell<Rc<T>>,
-    emitter: SignalEmitter,
+    pub(crate) emitter: SignalEmitter,
+    /// The equality function of a signal created with
+    /// [`create_signal_with_eq`](Scope::create_signal_with_eq). Setting a value that is equal to
+    /// the current value does not notify the subscribers.
+    ///
+    /// # Safety
+    /// The function does not actually have the `'static` lifetime. It lives as long as the scope
+    /// that the signal is allocated on.
+    eq: Option<EqFn<'static, T>>,
 }
 
-impl<T> ReadSignal<T> {
+impl<T: ?Sized> ReadSignal<T> {
     /// Get the current value of the state. When called inside a reactive scope, calling this will
     /// add itself to the scope's dependencies.
     ///
@@ -108,28 +373,141 @@ impl<T> ReadSignal<T> {
         self.value.borrow().clone()
     }
 
-    /// Creates a mapped [`ReadSignal`]. This is equivalent to using
-    /// [`create_memo`](Scope::create_memo).
+    /// Returns a closure that calls [`get`](Self::get). This allows passing the signal directly
+    /// where a function is expected instead of writing `|| signal.get()`.
+    ///
+    /// Implementing [`Fn`] for signals is not possible on stable Rust, so this adapter is used
+    /// instead.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # use std::rc::Rc;
+    /// fn render(value: impl Fn() -> Rc<i32>) -> String {
+    ///     value().to_string()
+    /// }
+    ///
+    /// # create_scope_immediate(|ctx| {
+    /// let count = ctx.create_signal(1);
+    /// assert_eq!(render(count.callable()), "1");
+    /// # });
+    /// ```
+    pub fn callable(&self) -> impl Fn() -> Rc<T> + Copy + '_ {
+        move || self.get()
+    }
+
+    /// Creates a [`Derived`] signal that maps the value of this signal with `f`. The derived
+    /// signal does not need a [`Scope`] and is recomputed every time that it is read. Use
+    /// [`Derived::memo`] to memoize it.
     ///
     /// # Example
     /// ```rust
     /// # use sycamore_reactive::*;
     /// # create_scope_immediate(|ctx| {
     /// let state = ctx.create_signal(1);
-    /// let double = state.map(&ctx, |&x| x * 2);
-    /// assert_eq!(*double.get(), 2);
+    /// let double = state.map(|&x| x * 2);
+    /// assert_eq!(double.get(), 2);
     ///
     /// state.set(2);
-    /// assert_eq!(*double.get(), 4);
+    /// assert_eq!(double.get(), 4);
+    ///
+    /// // Maps can be chained.
+    /// let text = state.map(|&x| x * 2).map(|x| x.to_string());
+    /// assert_eq!(text.get(), "4");
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn map<'a, U>(&'a self, f: impl Fn(&T) -> U + 'a) -> Derived<impl Fn() -> U + 'a> {
+        Derived::new(move || f(&self.get()))
+    }
+
+    /// Creates a mapped signal like [`map`](Self::map), except that it does not follow this
+    /// signal. The mapped signal is derived from the current value of this signal and is only
+    /// derived again from the latest value when the returned [`Invalidator`] is invalidated. See
+    /// [`create_memo_untracked`](Scope::create_memo_untracked).
+    ///
+    /// # Example
+    /// ```rust
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let saved = ctx.create_signal(1);
+    /// let (edited, reset) = saved.map_untracked(ctx, |&x| x * 10);
+    ///
+    /// saved.set(2);
+    /// assert_eq!(*edited.get(), 10);
+    /// reset.invalidate();
+    /// assert_eq!(*edited.get(), 20);
     /// # });
     /// ```
     #[must_use]
-    pub fn map<'a, U>(
+    pub fn map_untracked<'a, U: 'a>(
         &'a self,
         ctx: ScopeRef<'a>,
         mut f: impl FnMut(&T) -> U + 'a,
-    ) -> &'a ReadSignal<U> {
-        ctx.create_memo(move || f(&self.get()))
+    ) -> (&'a Memo<'a, U>, Invalidator) {
+        ctx.create_memo_untracked(move || f(&self.get()))
+    }
+
+    /// Creates a [`Derived`] signal that combines the values of two signals into a tuple.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let a = ctx.create_signal(1);
+    /// let b = ctx.create_signal("a");
+    /// let zipped = a.zip(b);
+    /// assert_eq!(*zipped.get().0, 1);
+    ///
+    /// b.set("b");
+    /// assert_eq!(*zipped.get().1, "b");
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn zip<'a, U>(
+        &'a self,
+        other: &'a ReadSignal<U>,
+    ) -> Derived<impl Fn() -> (Rc<T>, Rc<U>) + Copy + 'a> {
+        Derived::new(move || (self.get(), other.get()))
+    }
+
+    /// Creates a [`ReadSignal`] that starts with the current value of this signal and is only
+    /// updated to the latest value of this signal when `trigger` is updated. Updates to this
+    /// signal alone are not propagated.
+    ///
+    /// This is useful for "apply on submit" forms where the value of an input should only be used
+    /// once the form is submitted.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let input = ctx.create_signal("");
+    /// let submit = ctx.create_signal(());
+    /// let query = input.sample_when(ctx, submit);
+    ///
+    /// input.set("sycamore");
+    /// assert_eq!(*query.get(), "");
+    ///
+    /// submit.set(());
+    /// assert_eq!(*query.get(), "sycamore");
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn sample_when<'a, U>(
+        &'a self,
+        ctx: ScopeRef<'a>,
+        trigger: &'a ReadSignal<U>,
+    ) -> &'a ReadSignal<T> {
+        let sampled = ctx.create_ref(Signal::new_rc(self.get_untracked()));
+        let mut initial = true;
+        ctx.create_node(NodeKind::Memo, move || {
+            trigger.track();
+            if !core::mem::take(&mut initial) {
+                sampled.set_rc(self.get_untracked());
+            }
+        });
+        sampled
     }
 
     /// When called inside a reactive scope, calling this will add itself to the scope's
@@ -139,10 +517,173 @@ impl<T> ReadSignal<T> {
     pub fn track(&self) {
         self.emitter.track();
     }
+
+    /// Returns the [`SignalEmitter`] of the signal. This can be used to make a
+    /// [`RawNode`] depend on the signal.
+    pub fn emitter(&self) -> &SignalEmitter {
+        &self.emitter
+    }
+
+    /// Returns the [`SignalId`] of the signal.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::collections::HashMap;
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let a = ctx.create_signal(0);
+    /// let b = ctx.create_signal(0);
+    /// assert_ne!(a.id(), b.id());
+    ///
+    /// let mut names = HashMap::new();
+    /// names.insert(a.id(), "a");
+    /// assert_eq!(names[&a.id()], "a");
+    /// # });
+    /// ```
+    pub fn id(&self) -> SignalId {
+        self.emitter.id()
+    }
+}
+
+impl<S> ReadSignal<S> {
+    /// Flattens a signal of signals into a [`Derived`] signal of the inner value, which tracks
+    /// both the outer signal and the current inner signal.
+    ///
+    /// The inner value is not cloned. Instead, the flattened signal shares the `Rc` of the inner
+    /// signal.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let a = ctx.create_signal(1);
+    /// let b = ctx.create_signal(2);
+    /// let current = ctx.create_signal(a);
+    /// let flattened = current.flatten();
+    /// assert_eq!(*flattened.get(), 1);
+    ///
+    /// a.set(3);
+    /// assert_eq!(*flattened.get(), 3);
+    ///
+    /// current.set(b);
+    /// assert_eq!(*flattened.get(), 2);
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn flatten<'a, T>(&'a self) -> Derived<impl Fn() -> Rc<T> + Copy + 'a>
+    where
+        S: AsRef<ReadSignal<T>>,
+    {
+        Derived::new(move || AsRef::<ReadSignal<T>>::as_ref(&*self.get()).get())
+    }
+}
+
+impl<T> ReadSignal<T> {
+    /// Creates a [`Derived`] signal from a function that returns a signal, and flattens it. This
+    /// is equivalent to calling [`map`](Self::map) followed by [`Derived::flatten`].
+    ///
+    /// # Example
+    /// ```rust
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// #[derive(Clone, Copy)]
+    /// struct Counter<'a> {
+    ///     count: &'a Signal<i32>,
+    /// }
+    ///
+    /// let a = Counter { count: ctx.create_signal(1) };
+    /// let b = Counter { count: ctx.create_signal(2) };
+    /// let selected = ctx.create_signal(a);
+    /// let count = selected.map_flat(|counter| counter.count);
+    /// assert_eq!(*count.get(), 1);
+    ///
+    /// selected.set(b);
+    /// assert_eq!(*count.get(), 2);
+    ///
+    /// b.count.set(3);
+    /// assert_eq!(*count.get(), 3);
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn map_flat<'a, U, S: AsRef<ReadSignal<U>>>(
+        &'a self,
+        f: impl Fn(&T) -> S + 'a,
+    ) -> Derived<impl Fn() -> Rc<U> + 'a> {
+        Derived::new(move || f(&self.get()).as_ref().get())
+    }
+}
+
+impl<T: Clone> ReadSignal<Option<T>> {
+    /// Creates a [`ReadSignal`] that contains the value of the signal if it is `Some`, or the
+    /// result of calling `f` if it is `None`.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let data = ctx.create_signal(None);
+    /// let text = data.unwrap_or_else_signal(ctx, || "Loading...");
+    /// assert_eq!(*text.get(), "Loading...");
+    ///
+    /// data.set(Some("Hello World!"));
+    /// assert_eq!(*text.get(), "Hello World!");
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn unwrap_or_else_signal<'a>(
+        &'a self,
+        ctx: ScopeRef<'a>,
+        mut f: impl FnMut() -> T + 'a,
+    ) -> &'a ReadSignal<T>
+    where
+        T: 'a,
+    {
+        ctx.create_memo(move || self.get().as_ref().clone().unwrap_or_else(&mut f))
+    }
+}
+
+impl<T: Clone, E: Clone> ReadSignal<Result<T, E>> {
+    /// Creates a [`ReadSignal`] that contains the success value of the signal, or `None` if the
+    /// signal contains an error.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let result = ctx.create_signal(Ok::<i32, String>(1));
+    /// let data = result.ok_signal(ctx);
+    /// let error = result.err_signal(ctx);
+    /// assert_eq!(*data.get(), Some(1));
+    /// assert_eq!(*error.get(), None);
+    ///
+    /// result.set(Err("failed".to_string()));
+    /// assert_eq!(*data.get(), None);
+    /// assert_eq!(error.get().as_deref(), Some("failed"));
+    /// # });
+    /// ```
+    #[must_use]
+    pub fn ok_signal<'a>(&'a self, ctx: ScopeRef<'a>) -> &'a ReadSignal<Option<T>>
+    where
+        T: 'a,
+    {
+        ctx.create_memo(move || self.get().as_ref().as_ref().ok().cloned())
+    }
+
+    /// Creates a [`ReadSignal`] that contains the error value of the signal, or `None` if the
+    /// signal contains a success value.
+    ///
+    /// See [`ok_signal`](Self::ok_signal) for an example.
+    #[must_use]
+    pub fn err_signal<'a>(&'a self, ctx: ScopeRef<'a>) -> &'a ReadSignal<Option<E>>
+    where
+        E: 'a,
+    {
+        ctx.create_memo(move || self.get().as_ref().as_ref().err().cloned())
+    }
 }
 
 /// Reactive state that can be updated and subscribed to.
-pub struct Signal<T>(ReadSignal<T>);
+pub struct Signal<T: ?Sized>(ReadSignal<T>);
 
 impl<T> Signal<T> {
     /// Create a new [`Signal`] with the specified value.
@@ -150,12 +691,25 @@ impl<T> Signal<T> {
         Self(ReadSignal {
             value: RefCell::new(Rc::new(value)),
             emitter: Default::default(),
+            eq: None,
         })
     }
 
+    /// Create a new [`Signal`] with the specified value and equality function. The signal must be
+    /// allocated on a scope that does not outlive `eq`.
+    pub(crate) fn new_with_eq<'a>(value: T, eq: impl Fn(&T, &T) -> bool + 'a) -> Self {
+        let mut signal = Self::new(value);
+        let eq: EqFn<'a, T> = Box::new(eq);
+        // SAFETY: Only the lifetime is transmuted. See the safety comment on `ReadSignal::eq`.
+        signal.0.eq = Some(unsafe { core::mem::transmute::<EqFn<'a, T>, EqFn<'static, T>>(eq) });
+        signal
+    }
+
     /// Set the current value of the state.
     ///
-    /// This will notify and update any effects and memos that depend on this value.
+    /// This will notify and update any effects and memos that depend on this value. If the
+    /// signal was created with [`create_signal_with_eq`](Scope::create_signal_with_eq) and the
+    /// new value is equal to the current value, nothing happens.
     ///
     /// # Example
     /// ```
@@ -169,8 +723,7 @@ impl<T> Signal<T> {
     /// # });
     /// ```
     pub fn set(&self, value: T) {
-        *self.0.value.borrow_mut() = Rc::new(value);
-        self.0.emitter.trigger_subscribers();
+        self.set_rc(Rc::new(value));
     }
 
     /// Set the current value of the state _without_ triggering subscribers.
@@ -200,6 +753,47 @@ impl<T> Signal<T> {
     }
 }
 
+impl<T: ?Sized> Signal<T> {
+    /// Create a new [`Signal`] with the specified `Rc`-wrapped value.
+    pub(crate) fn new_rc(value: Rc<T>) -> Self {
+        Self(ReadSignal {
+            value: RefCell::new(value),
+            emitter: Default::default(),
+            eq: None,
+        })
+    }
+
+    /// Set the current value of the state to an already `Rc`-wrapped value.
+    pub(crate) fn set_rc(&self, value: Rc<T>) {
+        if let Some(eq) = &self.0.eq {
+            if eq(&self.0.value.borrow(), &value) {
+                return;
+            }
+        }
+        *self.0.value.borrow_mut() = value;
+        self.0.emitter.trigger_subscribers();
+    }
+}
+
+impl Signal<str> {
+    /// Sets the string of the signal to `value`. The string is copied into the allocation of the
+    /// current string if it has the same length and is not shared (i.e. no one kept the [`Rc`]
+    /// returned by [`get`](ReadSignal::get)), and into a new allocation otherwise.
+    pub(crate) fn set_str(&self, value: &str) {
+        {
+            let mut current = self.0.value.borrow_mut();
+            match Rc::get_mut(&mut current) {
+                Some(current) if current.len() == value.len() => {
+                    // SAFETY: The bytes of a `str` are replaced with the bytes of another `str`.
+                    unsafe { current.as_bytes_mut() }.copy_from_slice(value.as_bytes());
+                }
+                _ => *current = Rc::from(value),
+            }
+        }
+        self.0.emitter.trigger_subscribers();
+    }
+}
+
 impl<T: Default> Signal<T> {
     /// Take the current value out and replace it with the default value.
     ///
@@ -219,7 +813,7 @@ impl<T: Default> Signal<T> {
     }
 }
 
-impl<'a, T> Deref for Signal<T> {
+impl<'a, T: ?Sized> Deref for Signal<T> {
     type Target = ReadSignal<T>;
 
     fn deref(&self) -> &Self::Target {
@@ -227,6 +821,36 @@ impl<'a, T> Deref for Signal<T> {
     }
 }
 
+impl<T: ?Sized> AsRef<ReadSignal<T>> for ReadSignal<T> {
+    fn as_ref(&self) -> &ReadSignal<T> {
+        self
+    }
+}
+
+impl<T: ?Sized> AsRef<ReadSignal<T>> for Signal<T> {
+    fn as_ref(&self) -> &ReadSignal<T> {
+        self
+    }
+}
+
+impl<'a, T: ?Sized> From<&'a Signal<T>> for &'a ReadSignal<T> {
+    fn from(signal: &'a Signal<T>) -> Self {
+        signal
+    }
+}
+
+impl<'a, T> From<&'a RcSignal<T>> for &'a ReadSignal<T> {
+    fn from(signal: &'a RcSignal<T>) -> Self {
+        signal
+    }
+}
+
+impl<'a, T> From<&'a RcSignal<T>> for &'a Signal<T> {
+    fn from(signal: &'a RcSignal<T>) -> Self {
+        signal
+    }
+}
+
 /// A trait that is implemented for all [`ReadSignal`]s regardless of the type parameter.
 pub trait AnyReadSignal<'a> {
     /// Call the [`ReadSignal::track`] method.
@@ -237,12 +861,12 @@ impl<'a, T> AnyReadSignal<'a> for RcSignal<T> {
         self.deref().deref().track();
     }
 }
-impl<'a, T> AnyReadSignal<'a> for Signal<T> {
+impl<'a, T: ?Sized> AnyReadSignal<'a> for Signal<T> {
     fn track(&self) {
         self.deref().track();
     }
 }
-impl<'a, T> AnyReadSignal<'a> for ReadSignal<T> {
+impl<'a, T: ?Sized> AnyReadSignal<'a> for ReadSignal<T> {
     fn track(&self) {
         self.track();
     }
@@ -311,17 +935,17 @@ pub fn create_rc_signal<T>(value: T) -> RcSignal<T> {
 /* Display implementations */
 
 impl<T: Display> Display for RcSignal<T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         self.get().fmt(f)
     }
 }
-impl<T: Display> Display for Signal<T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+impl<T: Display + ?Sized> Display for Signal<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         self.get().fmt(f)
     }
 }
-impl<T: Display> Display for ReadSignal<T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+impl<T: Display + ?Sized> Display for ReadSignal<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         self.get().fmt(f)
     }
 }
@@ -329,17 +953,17 @@ impl<T: Display> Display for ReadSignal<T> {
 /* Debug implementations */
 
 impl<T: Debug> Debug for RcSignal<T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         f.debug_tuple("RcSignal").field(&self.get()).finish()
     }
 }
-impl<T: Debug> Debug for Signal<T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+impl<T: Debug + ?Sized> Debug for Signal<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         f.debug_tuple("Signal").field(&self.get()).finish()
     }
 }
-impl<T: Debug> Debug for ReadSignal<T> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+impl<T: Debug + ?Sized> Debug for ReadSignal<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         f.debug_tuple("ReadSignal").field(&self.get()).finish()
     }
 }
@@ -375,17 +999,17 @@ impl<T: Eq> Eq for Signal<T> {}
 impl<T: Eq> Eq for ReadSignal<T> {}
 
 impl<T: Hash> Hash for RcSignal<T> {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
         self.get_untracked().hash(state)
     }
 }
 impl<T: Hash> Hash for Signal<T> {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
         self.get_untracked().hash(state)
     }
 }
 impl<T: Hash> Hash for ReadSignal<T> {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
         self.get_untracked().hash(state)
     }
 }
@@ -415,6 +1039,77 @@ impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for RcSignal<T> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn signal_eq_suppresses_notifications() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal_eq(0);
+            let double = ctx.create_memo(|| *state.get() * 2);
+            let runs = ctx.create_signal(0);
+            ctx.create_effect(|| {
+                state.track();
+                runs.set(*runs.get_untracked() + 1);
+            });
+            assert_eq!(*runs.get(), 1);
+
+            state.set(0);
+            assert_eq!(*runs.get(), 1);
+
+            state.set(1);
+            assert_eq!(*runs.get(), 2);
+            assert_eq!(*double.get(), 2);
+        });
+    }
+
+    #[test]
+    fn signal_with_eq_can_borrow_from_scope() {
+        create_scope_immediate(|ctx| {
+            let comparisons = ctx.create_signal(0);
+            let state = ctx.create_signal_with_eq("a".to_string(), |a, b| {
+                comparisons.set(*comparisons.get_untracked() + 1);
+                a.eq_ignore_ascii_case(b)
+            });
+            state.set("A".to_string());
+            assert_eq!(*state.get(), "a");
+            assert_eq!(*comparisons.get(), 1);
+        });
+    }
+
+    #[test]
+    fn callable_is_tracked() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(1);
+            let get = state.callable();
+            let double = ctx.create_memo(move || *get() * 2);
+            state.set(2);
+            assert_eq!(*double.get(), 4);
+        });
+    }
+
+    #[test]
+    fn custom_source_with_emitter() {
+        struct Counter {
+            count: Cell<u32>,
+            emitter: SignalEmitter,
+        }
+
+        create_scope_immediate(|ctx| {
+            let counter = ctx.create_ref(Counter {
+                count: Cell::new(0),
+                emitter: SignalEmitter::new(),
+            });
+            let observed = ctx.create_signal(0);
+            ctx.create_effect(|| {
+                counter.emitter.track();
+                observed.set(counter.count.get());
+            });
+
+            counter.count.set(1);
+            assert_eq!(*observed.get(), 0);
+            counter.emitter.trigger();
+            assert_eq!(*observed.get(), 1);
+        });
+    }
+
     #[test]
     fn signal() {
         create_scope_immediate(|ctx| {
@@ -442,7 +1137,7 @@ mod tests {
     fn set_silent_signal() {
         create_scope_immediate(|ctx| {
             let state = ctx.create_signal(0);
-            let double = state.map(ctx, |&x| x * 2);
+            let double = state.map(|&x| x * 2).memo(ctx);
 
             assert_eq!(*double.get(), 0);
             state.set_silent(1);
@@ -466,11 +1161,233 @@ mod tests {
     fn map_signal() {
         create_scope_immediate(|ctx| {
             let state = ctx.create_signal(0);
-            let double = state.map(ctx, |&x| x * 2);
+            let double = state.map(|&x| x * 2);
 
-            assert_eq!(*double.get(), 0);
+            assert_eq!(double.get(), 0);
             state.set(1);
-            assert_eq!(*double.get(), 2);
+            assert_eq!(double.get(), 2);
+        });
+    }
+
+    #[test]
+    fn map_signal_chained() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(0);
+            let chained = state.map(|&x| x + 1).map(|&x| x * 2);
+            assert_eq!(chained.get(), 2);
+            state.set(1);
+            assert_eq!(chained.get(), 4);
+        });
+    }
+
+    #[test]
+    fn map_rc_signal() {
+        // No scope is needed.
+        let state = create_rc_signal(0);
+        let double = state.map(|&x| x * 2);
+        state.set(1);
+        assert_eq!(double.get(), 2);
+    }
+
+    #[test]
+    fn zip_signals() {
+        create_scope_immediate(|ctx| {
+            let a = ctx.create_signal(0);
+            let _ = ctx.create_child_scope(|ctx| {
+                let b = ctx.create_signal("a");
+                let zipped = a.zip(b);
+                assert_eq!(zipped.get(), (Rc::new(0), Rc::new("a")));
+
+                a.set(1);
+                b.set("b");
+                assert_eq!(zipped.get(), (Rc::new(1), Rc::new("b")));
+            });
+        });
+    }
+
+    #[test]
+    fn sample_when_trigger_fires() {
+        create_scope_immediate(|ctx| {
+            let value = ctx.create_signal(0);
+            let trigger = ctx.create_signal(());
+            let sampled = value.sample_when(ctx, trigger);
+            let runs = ctx.create_signal(0);
+            ctx.create_effect(|| {
+                sampled.track();
+                runs.set(*runs.get_untracked() + 1);
+            });
+            assert_eq!(*sampled.get(), 0);
+
+            value.set(1);
+            value.set(2);
+            assert_eq!(*sampled.get(), 0);
+            assert_eq!(*runs.get(), 1);
+
+            trigger.set(());
+            assert_eq!(*sampled.get(), 2);
+            assert_eq!(*runs.get(), 2);
+        });
+    }
+
+    #[test]
+    fn map_untracked_signal() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(1);
+            let (mapped, invalidator) = state.map_untracked(ctx, |&x| x * 2);
+            let runs = ctx.create_signal(0);
+            ctx.create_effect(|| {
+                mapped.track();
+                runs.set(*runs.get_untracked() + 1);
+            });
+
+            state.set(2);
+            assert_eq!(*mapped.get(), 2);
+            assert_eq!(*runs.get(), 1);
+
+            invalidator.invalidate();
+            assert_eq!(*mapped.get(), 4);
+            assert_eq!(*runs.get(), 2);
+
+            // The mapped signal still does not follow the signal after being invalidated.
+            state.set(3);
+            assert_eq!(*mapped.get(), 4);
+        });
+    }
+
+    #[test]
+    fn flatten_signal() {
+        create_scope_immediate(|ctx| {
+            let a = ctx.create_signal(0);
+            let b = ctx.create_signal(10);
+            let outer = ctx.create_signal(a);
+            let flattened = outer.flatten();
+            let value = ctx.create_signal(0);
+            let runs = ctx.create_signal(0);
+            ctx.create_effect(move || {
+                value.set(*flattened.get());
+                runs.set(*runs.get_untracked() + 1);
+            });
+            assert_eq!(*value.get(), 0);
+
+            a.set(1);
+            assert_eq!(*value.get(), 1);
+
+            outer.set(b);
+            assert_eq!(*value.get(), 10);
+
+            // `a` is no longer tracked.
+            a.set(2);
+            assert_eq!(*runs.get(), 3);
+            b.set(11);
+            assert_eq!(*value.get(), 11);
+        });
+    }
+
+    #[test]
+    fn flatten_shares_inner_rc() {
+        create_scope_immediate(|ctx| {
+            let inner = ctx.create_signal(String::from("hello"));
+            let outer = ctx.create_signal(inner);
+            let flattened = outer.flatten();
+            assert!(Rc::ptr_eq(&flattened.get(), &inner.get()));
+        });
+    }
+
+    #[test]
+    fn option_signal_helpers() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(None);
+            let value = state.unwrap_or_else_signal(ctx, || 0);
+            assert_eq!(*value.get(), 0);
+
+            state.set(Some(1));
+            assert_eq!(*value.get(), 1);
+        });
+    }
+
+    #[test]
+    fn result_signal_helpers() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(Ok::<_, &str>(1));
+            let ok = state.ok_signal(ctx);
+            let err = state.err_signal(ctx);
+            assert_eq!(*ok.get(), Some(1));
+            assert_eq!(*err.get(), None);
+
+            state.set(Err("error"));
+            assert_eq!(*ok.get(), None);
+            assert_eq!(*err.get(), Some("error"));
+        });
+    }
+
+    #[test]
+    fn effect_unsubscribes_on_dispose() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(0);
+            let disposer = ctx.create_child_scope(|ctx| {
+                ctx.create_effect(|| state.track());
+            });
+            assert_eq!(state.emitter.0.len(), 1);
+            disposer();
+            assert_eq!(state.emitter.0.len(), 0);
+        });
+    }
+
+    #[test]
+    fn effect_unsubscribes_from_untracked_dependencies() {
+        create_scope_immediate(|ctx| {
+            let condition = ctx.create_signal(true);
+            let state = ctx.create_signal(0);
+            ctx.create_effect(|| {
+                if *condition.get() {
+                    state.track();
+                }
+            });
+            assert_eq!(state.emitter.0.len(), 1);
+            condition.set(false);
+            assert_eq!(state.emitter.0.len(), 0);
+            assert_eq!(condition.emitter.0.len(), 1);
+        });
+    }
+
+    #[test]
+    fn effect_unsubscribes_from_middle_of_subscribers() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(0);
+            let order = ctx.create_signal(Vec::new());
+            let log = |i| order.set([order.get_untracked().as_slice(), &[i]].concat());
+            ctx.create_effect(move || {
+                state.track();
+                log(0);
+            });
+            let disposer = ctx.create_child_scope(|ctx| {
+                ctx.create_effect(move || {
+                    state.track();
+                    log(1);
+                });
+            });
+            ctx.create_effect(move || {
+                state.track();
+                log(2);
+            });
+            assert_eq!(state.emitter.0.len(), 3);
+
+            disposer();
+            assert_eq!(state.emitter.0.len(), 2);
+            order.set(Vec::new());
+            state.set(1);
+            assert_eq!(*order.get(), [0, 2]);
+        });
+    }
+
+    #[test]
+    fn signal_id() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(0);
+            let mapped = state.map(|&x| x).memo(ctx);
+            assert_ne!(state.id(), ReadSignal::id(mapped));
+            assert_eq!(state.id(), state.id());
+            assert_ne!(create_rc_signal(0).id(), create_rc_signal(0).id());
         });
     }
 
@@ -489,7 +1406,7 @@ mod tests {
     fn take_silent_signal() {
         create_scope_immediate(|ctx| {
             let state = ctx.create_signal(123);
-            let double = state.map(ctx, |&x| x * 2);
+            let double = state.map(|&x| x * 2).memo(ctx);
 
             // Do not trigger subscribers.
             state.take_silent();
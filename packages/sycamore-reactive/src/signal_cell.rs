@@ -0,0 +1,171 @@
+//! Signals that are mutated in place.
+
+use core::fmt;
+use core::ops::DerefMut;
+
+use crate::*;
+
+/// A signal that stores its value in a [`RefCell`] instead of an [`Rc`], created with
+/// [`create_signal_cell`](Scope::create_signal_cell).
+///
+/// The value is mutated in place with [`borrow_mut_notify`](Self::borrow_mut_notify) instead of
+/// being replaced. This avoids allocating a new [`Rc`] on every update of values that are changed
+/// often and only slightly, such as a large text buffer or a log that is appended to.
+///
+/// The value can only be accessed through a borrow, so a borrow must not be held while the value
+/// is mutated.
+pub struct SignalCell<T> {
+    value: RefCell<T>,
+    emitter: SignalEmitter,
+}
+
+impl<T> SignalCell<T> {
+    /// Borrows the value and tracks the signal as a dependency if inside an effect or memo.
+    ///
+    /// # Panics
+    /// Panics if the value is currently mutably borrowed.
+    pub fn borrow(&self) -> Ref<'_, T> {
+        self.emitter.track();
+        self.value.borrow()
+    }
+
+    /// Borrows the value without tracking the signal.
+    ///
+    /// # Panics
+    /// Panics if the value is currently mutably borrowed.
+    pub fn borrow_untracked(&self) -> Ref<'_, T> {
+        self.value.borrow()
+    }
+
+    /// Mutably borrows the value. The subscribers of the signal are notified when the returned
+    /// guard is dropped.
+    ///
+    /// # Panics
+    /// Panics if the value is currently borrowed.
+    pub fn borrow_mut_notify(&self) -> SignalCellMut<'_, T> {
+        SignalCellMut {
+            value: Some(self.value.borrow_mut()),
+            emitter: &self.emitter,
+        }
+    }
+
+    /// Replaces the value and notifies the subscribers of the signal.
+    pub fn set(&self, value: T) {
+        *self.borrow_mut_notify() = value;
+    }
+
+    /// Tracks the signal as a dependency without borrowing the value.
+    pub fn track(&self) {
+        self.emitter.track();
+    }
+
+    /// Returns the [`SignalEmitter`] of the signal.
+    pub fn emitter(&self) -> &SignalEmitter {
+        &self.emitter
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SignalCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SignalCell").field(&self.value).finish()
+    }
+}
+
+/// A mutable borrow of the value of a [`SignalCell`], created with
+/// [`borrow_mut_notify`](SignalCell::borrow_mut_notify). The subscribers of the signal are
+/// notified when the guard is dropped.
+pub struct SignalCellMut<'a, T> {
+    /// Always `Some` until dropped.
+    value: Option<RefMut<'a, T>>,
+    emitter: &'a SignalEmitter,
+}
+
+impl<T> Deref for SignalCellMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value.as_ref().unwrap()
+    }
+}
+
+impl<T> DerefMut for SignalCellMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.value.as_mut().unwrap()
+    }
+}
+
+impl<T> Drop for SignalCellMut<'_, T> {
+    fn drop(&mut self) {
+        // The value is released first so that the subscribers can read it.
+        drop(self.value.take());
+        self.emitter.trigger_subscribers();
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for SignalCellMut<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SignalCellMut").field(&**self).finish()
+    }
+}
+
+impl<'a> Scope<'a> {
+    /// Creates a [`SignalCell`] with the given initial value.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let log = ctx.create_signal_cell(String::new());
+    /// let len = ctx.create_memo(|| log.borrow().len());
+    ///
+    /// log.borrow_mut_notify().push_str("Hello");
+    /// assert_eq!(*len.get(), 5);
+    /// # });
+    /// ```
+    pub fn create_signal_cell<T: 'a>(&'a self, value: T) -> &'a SignalCell<T> {
+        self.create_ref(SignalCell {
+            value: RefCell::new(value),
+            emitter: SignalEmitter::default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signal_cell_notifies_once_guard_is_dropped() {
+        create_scope_immediate(|ctx| {
+            let buffer = ctx.create_signal_cell(Vec::new());
+            let observed = ctx.create_signal(Vec::new());
+            ctx.create_effect(|| observed.set(buffer.borrow().clone()));
+
+            {
+                let mut buffer = buffer.borrow_mut_notify();
+                buffer.push(1);
+                buffer.push(2);
+                // Not notified yet.
+                assert!(observed.get().is_empty());
+            }
+            assert_eq!(*observed.get(), [1, 2]);
+
+            buffer.set(vec![3]);
+            assert_eq!(*observed.get(), [3]);
+        });
+    }
+
+    #[test]
+    fn signal_cell_untracked() {
+        create_scope_immediate(|ctx| {
+            let cell = ctx.create_signal_cell(0);
+            let runs = ctx.create_signal(0);
+            ctx.create_effect(|| {
+                let _ = *cell.borrow_untracked();
+                runs.set(*runs.get_untracked() + 1);
+            });
+            cell.set(1);
+            assert_eq!(*runs.get(), 1);
+        });
+    }
+}
@@ -0,0 +1,215 @@
+//! Tracking pending asynchronous work in a subtree.
+
+use core::fmt;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+
+use crate::*;
+
+struct SuspenseInner {
+    /// Number of [`SuspenseGuard`]s that are alive.
+    pending: RcSignal<usize>,
+    /// Wakers of the [`Resolved`] futures that are waiting for `pending` to reach `0`.
+    wakers: RefCell<Vec<Waker>>,
+}
+
+/// The state of a suspense boundary, created with
+/// [`create_suspense_boundary`](Scope::create_suspense_boundary).
+///
+/// A suspense boundary is pending as long as one of the [`SuspenseGuard`]s created inside it is
+/// alive. Renderers use this to display a fallback while the subtree is loading or, when
+/// streaming, to know when the subtree is ready to be sent.
+#[derive(Clone)]
+pub struct SuspenseState(Rc<SuspenseInner>);
+
+impl SuspenseState {
+    fn new() -> Self {
+        Self(Rc::new(SuspenseInner {
+            pending: create_rc_signal(0),
+            wakers: Default::default(),
+        }))
+    }
+
+    /// Returns `true` if there is pending work inside the boundary. This method is reactive.
+    pub fn is_pending(&self) -> bool {
+        *self.0.pending.get() > 0
+    }
+
+    /// Returns `true` if there is pending work inside the boundary without tracking it.
+    pub fn is_pending_untracked(&self) -> bool {
+        *self.0.pending.get_untracked() > 0
+    }
+
+    /// Marks the boundary as pending until the returned guard is dropped.
+    pub fn suspend(&self) -> SuspenseGuard {
+        self.0.pending.set(*self.0.pending.get_untracked() + 1);
+        SuspenseGuard(Some(self.clone()))
+    }
+
+    /// Returns a future that completes once the boundary is no longer pending.
+    pub fn resolved(&self) -> Resolved {
+        Resolved(self.clone())
+    }
+
+    /// Polls whether the boundary is no longer pending. If it is still pending, the waker of `cx`
+    /// is woken once it is resolved.
+    pub fn poll_resolved(&self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.is_pending_untracked() {
+            self.0.wakers.borrow_mut().push(cx.waker().clone());
+            Poll::Pending
+        } else {
+            Poll::Ready(())
+        }
+    }
+}
+
+impl fmt::Debug for SuspenseState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SuspenseState")
+            .field("pending", &self.0.pending.get_untracked())
+            .finish()
+    }
+}
+
+/// Keeps a suspense boundary pending while it is alive. Created with
+/// [`suspend`](Scope::suspend).
+#[derive(Debug)]
+#[must_use = "the boundary is only pending while the guard is alive"]
+pub struct SuspenseGuard(Option<SuspenseState>);
+
+impl Drop for SuspenseGuard {
+    fn drop(&mut self) {
+        if let Some(state) = self.0.take() {
+            let pending = *state.0.pending.get_untracked() - 1;
+            state.0.pending.set(pending);
+            if pending == 0 {
+                for waker in state.0.wakers.take() {
+                    waker.wake();
+                }
+            }
+        }
+    }
+}
+
+/// Future returned by [`SuspenseState::resolved`].
+#[derive(Debug)]
+pub struct Resolved(SuspenseState);
+
+impl Future for Resolved {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        self.0.poll_resolved(cx)
+    }
+}
+
+impl<'a> Scope<'a> {
+    /// Creates a new suspense boundary in the current [`Scope`]. Calls to
+    /// [`suspend`](Self::suspend) in this scope or in child scopes mark this boundary as pending,
+    /// unless there is another boundary in between.
+    ///
+    /// # Panics
+    /// This method panics if a suspense boundary exists already in this scope.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let boundary = ctx.create_suspense_boundary();
+    /// assert!(!boundary.is_pending());
+    ///
+    /// let guard = ctx.suspend();
+    /// assert!(boundary.is_pending());
+    /// drop(guard);
+    /// assert!(!boundary.is_pending());
+    /// # });
+    /// ```
+    pub fn create_suspense_boundary(&'a self) -> SuspenseState {
+        let state = SuspenseState::new();
+        self.provide_context(state.clone());
+        state
+    }
+
+    /// Marks the nearest suspense boundary as pending until the returned guard is dropped. If
+    /// there is no suspense boundary, the guard does nothing.
+    pub fn suspend(&'a self) -> SuspenseGuard {
+        match self.try_use_context::<SuspenseState>() {
+            Some(state) => state.suspend(),
+            None => SuspenseGuard(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::task::Wake;
+
+    use super::*;
+
+    #[test]
+    fn suspend_without_boundary() {
+        create_scope_immediate(|ctx| {
+            let _guard = ctx.suspend();
+        });
+    }
+
+    #[test]
+    fn nearest_boundary_is_suspended() {
+        create_scope_immediate(|ctx| {
+            let outer = ctx.create_suspense_boundary();
+            let _ = ctx.create_child_scope(|ctx| {
+                let inner = ctx.create_suspense_boundary();
+                let guard = ctx.suspend();
+                assert!(inner.is_pending());
+                assert!(!outer.is_pending());
+                drop(guard);
+            });
+        });
+    }
+
+    #[test]
+    fn is_pending_is_reactive() {
+        create_scope_immediate(|ctx| {
+            let boundary = ctx.create_suspense_boundary();
+            let pending = ctx.create_memo({
+                let boundary = boundary.clone();
+                move || boundary.is_pending()
+            });
+            let guard = ctx.suspend();
+            assert!(*pending.get());
+            drop(guard);
+            assert!(!*pending.get());
+        });
+    }
+
+    #[test]
+    fn resolved_wakes_when_last_guard_is_dropped() {
+        struct CountWakes(AtomicUsize);
+        impl Wake for CountWakes {
+            fn wake(self: Arc<Self>) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        create_scope_immediate(|ctx| {
+            let boundary = ctx.create_suspense_boundary();
+            let wakes = Arc::new(CountWakes(AtomicUsize::new(0)));
+            let waker = Waker::from(Arc::clone(&wakes));
+            let mut cx = Context::from_waker(&waker);
+            let mut resolved = boundary.resolved();
+
+            let first = ctx.suspend();
+            let second = ctx.suspend();
+            assert!(Pin::new(&mut resolved).poll(&mut cx).is_pending());
+
+            drop(first);
+            assert_eq!(wakes.0.load(Ordering::SeqCst), 0);
+            drop(second);
+            assert_eq!(wakes.0.load(Ordering::SeqCst), 1);
+            assert!(Pin::new(&mut resolved).poll(&mut cx).is_ready());
+        });
+    }
+}
@@ -0,0 +1,270 @@
+//! Non-urgent updates.
+
+use core::fmt;
+
+use crate::*;
+
+/// A transition, created with [`create_transition`](Scope::create_transition).
+///
+/// The effects that are created after the transition in its scope or in child scopes are
+/// transition-aware. When signals are updated with [`start`](Self::start) (or
+/// [`start_transition`](Scope::start_transition)), the transition-aware effects that depend on
+/// them, directly or through memos and other effects, keep showing the previous state until the
+/// transition commits. Memos, the other effects and the transition-aware effects that are
+/// triggered by unrelated updates in the meantime are run right away as usual.
+///
+/// A transition commits once its suspense boundary is no longer pending, i.e. once the
+/// asynchronous work that was started by the updates (see [`suspend`](Scope::suspend)) is done.
+/// If nothing is suspended, the transition commits as soon as the updates are done.
+///
+/// Cloning the handle does not create a new transition.
+#[derive(Clone)]
+pub struct Transition {
+    /// The group of the transition-aware effects. They are deferred in this group and run when
+    /// the transition commits.
+    group: EffectGroup,
+    /// The effects of the group that were created before the transition are not
+    /// transition-aware.
+    created: EffectId,
+    runtime: Runtime,
+    pending: RcSignal<bool>,
+}
+
+impl Transition {
+    /// Runs `f` as part of the transition. The signal updates inside `f` are batched.
+    pub fn start(&self, f: impl FnOnce()) {
+        batch(|| {
+            // Effects that show whether the transition is pending are not deferred.
+            self.pending.set(true);
+            self.runtime
+                .scheduler()
+                .with_transition(Some(self.clone()), f);
+        });
+    }
+
+    /// Returns `true` if the effect with the given `id` of `group` is transition-aware, i.e. if
+    /// it waits for this transition to commit when it is triggered by the transition.
+    pub(crate) fn defers(&self, group: &EffectGroup, id: EffectId) -> bool {
+        self.group.ptr_eq(group) && id > self.created
+    }
+
+    /// Returns a signal that is `true` while the transition has not committed yet.
+    pub fn is_pending(&self) -> &ReadSignal<bool> {
+        &self.pending
+    }
+}
+
+impl fmt::Debug for Transition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Transition")
+            .field("pending", &self.pending.get_untracked())
+            .finish()
+    }
+}
+
+impl<'a> Scope<'a> {
+    /// Creates a new [`Transition`] in the current [`Scope`]. The transition uses the
+    /// [`EffectGroup`] and the suspense boundary (see
+    /// [`create_suspense_boundary`](Self::create_suspense_boundary)) of this scope, which are
+    /// created if they do not exist yet.
+    ///
+    /// # Panics
+    /// This method panics if a transition exists already in this scope.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let tab = ctx.create_signal("home");
+    /// let transition = ctx.create_transition();
+    /// let shown = ctx.create_signal("");
+    /// ctx.create_effect(|| shown.set(*tab.get()));
+    ///
+    /// let guard = ctx.suspend();
+    /// ctx.start_transition(|| tab.set("settings"));
+    /// // The new tab is still loading.
+    /// assert!(*transition.is_pending().get());
+    /// assert_eq!(*shown.get(), "home");
+    ///
+    /// drop(guard);
+    /// assert!(!*transition.is_pending().get());
+    /// assert_eq!(*shown.get(), "settings");
+    /// # });
+    /// ```
+    pub fn create_transition(&'a self) -> &'a Transition {
+        let pending = create_rc_signal(false);
+        let suspense = match self.own_context::<SuspenseState>() {
+            Some(suspense) => suspense.clone(),
+            None => self.create_suspense_boundary(),
+        };
+        let group = match self.own_context::<EffectGroup>() {
+            Some(group) => group.clone(),
+            None => self.create_effect_group(),
+        };
+        // The commit effect is created before the transition so that it is not transition-aware,
+        // and is run after the other effects so that the transition-aware effects are deferred
+        // before it runs them.
+        let commit = self.create_effect({
+            let pending = pending.clone();
+            let group = group.clone();
+            move || {
+                if *pending.get() && !suspense.is_pending() {
+                    // The effects that show whether the transition is pending are not deferred.
+                    self.runtime.scheduler().with_transition(None, || {
+                        pending.set(false);
+                        group.run_deferred();
+                    });
+                }
+            }
+        });
+        commit.set_order_hint(i32::MAX);
+
+        let transition = Transition {
+            group,
+            created: EffectId::next(),
+            runtime: self.runtime.clone(),
+            pending,
+        };
+        self.provide_context(transition.clone());
+        self.create_ref(transition)
+    }
+
+    /// Runs `f` as part of the nearest [`Transition`]. If there is no transition, `f` is simply
+    /// run inside of a [`batch`].
+    pub fn start_transition(&'a self, f: impl FnOnce()) {
+        match self.try_use_context::<Transition>() {
+            Some(transition) => transition.start(f),
+            None => batch(f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transition_commits_immediately_without_suspense() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(0);
+            let transition = ctx.create_transition();
+            let observed = ctx.create_signal(Vec::new());
+            ctx.create_effect(|| {
+                let value = (*state.get(), *transition.is_pending().get());
+                observed.set([observed.get_untracked().as_slice(), &[value]].concat());
+            });
+
+            ctx.start_transition(|| state.set(1));
+            assert_eq!(*observed.get(), [(0, false), (1, false)]);
+        });
+    }
+
+    #[test]
+    fn only_transition_aware_effects_are_deferred() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(0);
+            let urgent = ctx.create_signal(0);
+            ctx.create_effect(|| urgent.set(*state.get()));
+            let deferred = ctx.create_signal(0);
+            let _ = ctx.create_child_scope(|ctx| {
+                let transition = ctx.create_transition();
+                let double = ctx.create_memo(|| *state.get() * 2);
+                ctx.create_effect(|| deferred.set(*double.get()));
+
+                let guard = ctx.suspend();
+                transition.start(|| state.set(1));
+                assert_eq!(*urgent.get(), 1);
+                assert_eq!(*double.get(), 2);
+                assert_eq!(*deferred.get(), 0);
+
+                drop(guard);
+                assert_eq!(*deferred.get(), 2);
+            });
+        });
+    }
+
+    #[test]
+    fn only_effects_that_depend_on_transition_are_deferred() {
+        create_scope_immediate(|ctx| {
+            let tab = ctx.create_signal(0);
+            let clock = ctx.create_signal(0);
+            let transition = ctx.create_transition();
+            let shown_tab = ctx.create_signal(0);
+            ctx.create_effect(|| shown_tab.set(*tab.get()));
+            let shown_clock = ctx.create_signal(0);
+            ctx.create_effect(|| shown_clock.set(*clock.get()));
+            let spinner = ctx.create_signal(false);
+            ctx.create_effect(|| spinner.set(*transition.is_pending().get()));
+
+            let guard = ctx.suspend();
+            transition.start(|| tab.set(1));
+            assert_eq!(*shown_tab.get(), 0);
+            assert!(*spinner.get());
+
+            // Unrelated updates are shown while the transition is pending.
+            clock.set(1);
+            assert_eq!(*shown_clock.get(), 1);
+
+            drop(guard);
+            assert_eq!(*shown_tab.get(), 1);
+            assert!(!*spinner.get());
+        });
+    }
+
+    #[test]
+    fn deferred_effect_waits_for_commit() {
+        create_scope_immediate(|ctx| {
+            let tab = ctx.create_signal(0);
+            let other = ctx.create_signal(0);
+            let transition = ctx.create_transition();
+            let shown = ctx.create_signal((0, 0));
+            ctx.create_effect(|| shown.set((*tab.get(), *other.get())));
+
+            let guard = ctx.suspend();
+            transition.start(|| tab.set(1));
+            // The effect does not show the new tab before the transition commits.
+            other.set(1);
+            assert_eq!(*shown.get(), (0, 0));
+
+            drop(guard);
+            assert_eq!(*shown.get(), (1, 1));
+        });
+    }
+
+    #[test]
+    fn transition_reuses_effect_group_of_scope() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(0);
+            let group = ctx.create_effect_group();
+            let before = ctx.create_signal(0);
+            ctx.create_effect(|| before.set(*state.get()));
+            let transition = ctx.create_transition();
+            let after = ctx.create_signal(0);
+            ctx.create_effect(|| after.set(*state.get()));
+
+            let guard = ctx.suspend();
+            transition.start(|| state.set(1));
+            // Only the effects created after the transition are transition-aware.
+            assert_eq!(*before.get(), 1);
+            assert_eq!(*after.get(), 0);
+
+            // Committing does not resume a paused group.
+            group.pause();
+            drop(guard);
+            assert_eq!(*after.get(), 0);
+            group.resume();
+            assert_eq!(*after.get(), 1);
+        });
+    }
+
+    #[test]
+    fn start_transition_without_transition() {
+        create_scope_immediate(|ctx| {
+            let state = ctx.create_signal(0);
+            let mirror = ctx.create_signal(0);
+            ctx.create_effect(|| mirror.set(*state.get()));
+            ctx.start_transition(|| state.set(1));
+            assert_eq!(*mirror.get(), 1);
+        });
+    }
+}
@@ -0,0 +1,117 @@
+//! Signals without a value.
+
+use core::fmt;
+
+use crate::*;
+
+/// A signal without a value that is only used to rerun its subscribers, created with
+/// [`create_trigger`](Scope::create_trigger). This replaces the `create_signal(())` pattern
+/// without storing a value.
+///
+/// Notifications are coalesced: the subscribers are queued like for any other signal, so a
+/// subscriber that is notified several times inside a [`batch`] or while the scheduler is running
+/// (e.g. by an effect) is only run once, after the last notification.
+pub struct Trigger {
+    emitter: SignalEmitter,
+}
+
+impl Trigger {
+    /// Tracks the trigger as a dependency if inside an effect or memo.
+    pub fn track(&self) {
+        self.emitter.track();
+    }
+
+    /// Reruns the effects and memos that track the trigger.
+    pub fn notify(&self) {
+        self.emitter.trigger_subscribers();
+    }
+
+    /// Returns the [`SignalId`] of the trigger.
+    pub fn id(&self) -> SignalId {
+        self.emitter.id()
+    }
+}
+
+impl<'a> AnyReadSignal<'a> for Trigger {
+    fn track(&self) {
+        self.track();
+    }
+}
+
+impl fmt::Debug for Trigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Trigger").field(&self.id()).finish()
+    }
+}
+
+impl<'a> Scope<'a> {
+    /// Creates a [`Trigger`].
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let refresh = ctx.create_trigger();
+    /// let fetches = ctx.create_signal(0);
+    /// ctx.create_effect(|| {
+    ///     refresh.track();
+    ///     fetches.set(*fetches.get_untracked() + 1);
+    /// });
+    ///
+    /// batch(|| {
+    ///     refresh.notify();
+    ///     refresh.notify();
+    /// });
+    /// assert_eq!(*fetches.get(), 2); // The initial run and a single run for the batch.
+    /// # });
+    /// ```
+    pub fn create_trigger(&'a self) -> &'a Trigger {
+        self.create_ref(Trigger {
+            emitter: SignalEmitter::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trigger_reruns_subscribers() {
+        create_scope_immediate(|ctx| {
+            let trigger = ctx.create_trigger();
+            let runs = ctx.create_signal(0);
+            ctx.create_effect(|| {
+                trigger.track();
+                runs.set(*runs.get_untracked() + 1);
+            });
+
+            trigger.notify();
+            trigger.notify();
+            assert_eq!(*runs.get(), 3);
+        });
+    }
+
+    #[test]
+    fn notifications_from_effect_are_coalesced() {
+        create_scope_immediate(|ctx| {
+            let source = ctx.create_signal(0);
+            let trigger = ctx.create_trigger();
+            let runs = ctx.create_signal(0);
+            ctx.create_effect(|| {
+                if *source.get() > 0 {
+                    trigger.notify();
+                    trigger.notify();
+                    trigger.notify();
+                }
+            });
+            ctx.create_effect(|| {
+                trigger.track();
+                runs.set(*runs.get_untracked() + 1);
+            });
+
+            source.set(1);
+            assert_eq!(*runs.get(), 2);
+        });
+    }
+}
@@ -0,0 +1,158 @@
+//! Validating signals.
+
+use crate::*;
+
+/// Combinators for validators, i.e. functions that return the error of a value, if any.
+///
+/// This trait is implemented for all such functions.
+///
+/// # Example
+/// ```
+/// # use sycamore_reactive::*;
+/// let not_empty = |name: &String| if name.is_empty() { Err("required") } else { Ok(()) };
+/// let short = |name: &String| if name.len() > 8 { Err("too long") } else { Ok(()) };
+/// let validate = not_empty.and(short).map_err(str::to_uppercase);
+/// assert_eq!(validate(&String::new()), Err("REQUIRED".to_string()));
+/// assert_eq!(validate(&"Sycamore".to_string()), Ok(()));
+/// ```
+pub trait ValidatorExt<T: ?Sized, E>: Fn(&T) -> Result<(), E> + Sized {
+    /// Returns a validator that checks `self` and then `other`. The first error is returned.
+    fn and(self, other: impl Fn(&T) -> Result<(), E>) -> impl Fn(&T) -> Result<(), E> {
+        move |value| {
+            self(value)?;
+            other(value)
+        }
+    }
+
+    /// Returns a validator that converts the errors of `self` with `f`.
+    fn map_err<F>(self, f: impl Fn(E) -> F) -> impl Fn(&T) -> Result<(), F> {
+        move |value| self(value).map_err(&f)
+    }
+}
+
+impl<T: ?Sized, E, V: Fn(&T) -> Result<(), E>> ValidatorExt<T, E> for V {}
+
+/// A signal that holds the error of a validation, such as the signals that are returned by
+/// [`validate`](Scope::validate). See [`all_valid`](Scope::all_valid).
+pub trait ErrorSignal {
+    /// Returns `true` if the signal holds an error. This method is reactive.
+    fn has_error(&self) -> bool;
+}
+
+impl<E> ErrorSignal for ReadSignal<Option<E>> {
+    fn has_error(&self) -> bool {
+        self.get().is_some()
+    }
+}
+
+impl<E> ErrorSignal for Signal<Option<E>> {
+    fn has_error(&self) -> bool {
+        self.get().is_some()
+    }
+}
+
+impl<'a> Scope<'a> {
+    /// Returns a signal that holds the error of the value of `signal`, if any. `validator` is
+    /// called whenever the value changes, and also when the signals that it uses change.
+    ///
+    /// Validators can be combined with [`ValidatorExt`].
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let age = ctx.create_signal(12);
+    /// let error = ctx.validate(age, |age| if *age < 18 { Err("too young") } else { Ok(()) });
+    /// assert_eq!(*error.get(), Some("too young"));
+    ///
+    /// age.set(18);
+    /// assert_eq!(*error.get(), None);
+    /// # });
+    /// ```
+    pub fn validate<T: 'a, E: 'a>(
+        &'a self,
+        signal: &'a ReadSignal<T>,
+        validator: impl Fn(&T) -> Result<(), E> + 'a,
+    ) -> &'a ReadSignal<Option<E>> {
+        self.create_memo(move || validator(&signal.get()).err())
+    }
+
+    /// Returns a signal that is `true` if none of the `errors` signals holds an error.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let name = ctx.create_signal(String::new());
+    /// let age = ctx.create_signal(30);
+    /// let name_error = ctx.validate(name, |name| if name.is_empty() { Err(()) } else { Ok(()) });
+    /// let age_error = ctx.validate(age, |age| if *age > 150 { Err("unlikely") } else { Ok(()) });
+    /// let valid = ctx.all_valid([name_error, age_error]);
+    /// assert!(!*valid.get());
+    ///
+    /// name.set("Ferris".to_string());
+    /// assert!(*valid.get());
+    /// # });
+    /// ```
+    pub fn all_valid<const N: usize>(
+        &'a self,
+        errors: [&'a (dyn ErrorSignal + 'a); N],
+    ) -> &'a ReadSignal<bool> {
+        self.create_selector(move || errors.iter().all(|error| !error.has_error()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn min_len(len: usize) -> impl Fn(&str) -> Result<(), String> {
+        move |value| {
+            if value.len() < len {
+                Err(format!("must be at least {len} characters"))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    #[test]
+    fn combined_validators_return_first_error() {
+        let validator = min_len(3).and(|value: &str| {
+            if value.chars().all(char::is_alphanumeric) {
+                Ok(())
+            } else {
+                Err("must be alphanumeric".to_string())
+            }
+        });
+        assert_eq!(
+            validator("a!"),
+            Err("must be at least 3 characters".to_string())
+        );
+        assert_eq!(validator("ab!"), Err("must be alphanumeric".to_string()));
+        assert_eq!(validator("abc"), Ok(()));
+    }
+
+    #[test]
+    fn validation_tracks_other_signals() {
+        create_scope_immediate(|ctx| {
+            let password = ctx.create_signal("secret");
+            let confirm = ctx.create_signal("secret");
+            let mismatch = ctx.validate(confirm, |confirm| {
+                if confirm == &*password.get() {
+                    Ok(())
+                } else {
+                    Err(())
+                }
+            });
+            let long = ctx.validate(password, |password| min_len(8)(password));
+            let valid = ctx.all_valid([mismatch, long]);
+            assert!(!*valid.get());
+
+            password.set("correct horse");
+            assert_eq!(*mismatch.get(), Some(()));
+            confirm.set("correct horse");
+            assert!(*valid.get());
+        });
+    }
+}
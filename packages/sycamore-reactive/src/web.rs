@@ -0,0 +1,213 @@
+//! Reactive primitives for browser APIs.
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{BroadcastChannel, Event, EventTarget, MessageEvent, StorageEvent};
+
+use crate::*;
+
+/// The size of the browser window in CSS pixels, as returned by
+/// [`create_window_size`](Scope::create_window_size).
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct WindowSize {
+    /// The value of `window.innerWidth`.
+    pub width: f64,
+    /// The value of `window.innerHeight`.
+    pub height: f64,
+}
+
+impl<'a> Scope<'a> {
+    /// Creates a [`ReadSignal`] that is `true` whenever the document matches the media query
+    /// `query`, e.g. `"(max-width: 600px)"`.
+    ///
+    /// If the query is invalid or if there is no `window` (e.g. during SSR), the signal is always
+    /// `false`. The listener on the media query is removed when the [`Scope`] is disposed.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let is_mobile = ctx.create_media_query("(max-width: 600px)");
+    /// ctx.create_effect(|| println!("mobile layout: {}", is_mobile.get()));
+    /// # });
+    /// ```
+    pub fn create_media_query(&'a self, query: &str) -> &'a ReadSignal<bool> {
+        let list = web_sys::window().and_then(|window| window.match_media(query).ok().flatten());
+        let (matches, invalidator) = self.create_memo_with_invalidate({
+            let list = list.clone();
+            move || list.as_ref().is_some_and(|list| list.matches())
+        });
+        if let Some(list) = list {
+            self.add_invalidating_listener(list.into(), "change", invalidator);
+        }
+        matches
+    }
+
+    /// Creates a [`ReadSignal`] that tracks the size of the browser window.
+    ///
+    /// If there is no `window` (e.g. during SSR), the size is always zero. The `resize` listener is
+    /// removed when the [`Scope`] is disposed.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let size = ctx.create_window_size();
+    /// ctx.create_effect(|| println!("window width: {}", size.get().width));
+    /// # });
+    /// ```
+    pub fn create_window_size(&'a self) -> &'a ReadSignal<WindowSize> {
+        let window = web_sys::window();
+        let (size, invalidator) = self.create_memo_with_invalidate({
+            let window = window.clone();
+            move || {
+                let dimension = |value: Result<JsValue, JsValue>| {
+                    value
+                        .ok()
+                        .and_then(|value| value.as_f64())
+                        .unwrap_or_default()
+                };
+                window
+                    .as_ref()
+                    .map(|window| WindowSize {
+                        width: dimension(window.inner_width()),
+                        height: dimension(window.inner_height()),
+                    })
+                    .unwrap_or_default()
+            }
+        });
+        if let Some(window) = window {
+            self.add_invalidating_listener(window.into(), "resize", invalidator);
+        }
+        size
+    }
+
+    /// Creates a new [`Signal`] that is synced across the browser tabs (and windows) of the same
+    /// origin under `key`.
+    ///
+    /// Every time the signal is updated, the new value is serialized and sent to the other tabs
+    /// with a `BroadcastChannel`. If `BroadcastChannel` is not supported, the value is written to
+    /// `localStorage` instead and received through `storage` events. The signals of the other
+    /// tabs are then updated with the new value. Values that cannot be deserialized are ignored.
+    ///
+    /// The signal starts with `initial`. To also restore the value when a tab is opened, use
+    /// [`create_persistent_signal`](Self::create_persistent_signal) instead. The listener is
+    /// removed when the [`Scope`] is disposed.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let dark_mode = ctx.create_broadcast_signal("dark-mode", false);
+    /// // Toggling the theme in one tab toggles it in all the other tabs.
+    /// dark_mode.set(true);
+    /// # });
+    /// ```
+    pub fn create_broadcast_signal<T>(&'a self, key: impl Into<String>, initial: T) -> &'a Signal<T>
+    where
+        T: Serialize + DeserializeOwned + 'a,
+    {
+        let key = alloc::format!("sycamore-reactive:{}", key.into());
+        let signal = self.create_signal(initial);
+        let channel = BroadcastChannel::new(&key).ok();
+        // Set while a value from another tab is applied so that it is not sent back.
+        let remote = self.create_ref(Cell::new(false));
+
+        // The listener cannot borrow from the scope, so the values are handed over to a node in
+        // the scope. Only the latest value is kept.
+        let inbox = Rc::new(RefCell::new(None::<String>));
+        let received = Rc::new(SignalEmitter::new());
+        self.create_effect({
+            let inbox = Rc::clone(&inbox);
+            let received = Rc::clone(&received);
+            move || {
+                received.track();
+                let value = inbox.borrow_mut().take();
+                if let Some(Ok(value)) = value.map(|value| serde_json::from_str(&value)) {
+                    remote.set(true);
+                    signal.set(value);
+                }
+            }
+        });
+        let mut initial = true;
+        self.create_effect({
+            let channel = channel.clone();
+            let key = key.clone();
+            move || {
+                let value = signal.get();
+                if core::mem::take(&mut initial) || remote.replace(false) {
+                    return;
+                }
+                let Ok(serialized) = serde_json::to_string(&*value) else {
+                    return;
+                };
+                match &channel {
+                    Some(channel) => {
+                        let _ = channel.post_message(&JsValue::from_str(&serialized));
+                    }
+                    None => LocalStorage.set(&key, &serialized),
+                }
+            }
+        });
+
+        let listener = Closure::wrap(Box::new(move |event: Event| {
+            let value = if let Some(event) = event.dyn_ref::<MessageEvent>() {
+                event.data().as_string()
+            } else if let Some(event) = event.dyn_ref::<StorageEvent>() {
+                event
+                    .new_value()
+                    .filter(|_| event.key().as_deref() == Some(key.as_str()))
+            } else {
+                None
+            };
+            if let Some(value) = value {
+                *inbox.borrow_mut() = Some(value);
+                received.trigger();
+            }
+        }) as Box<dyn Fn(Event)>);
+        match channel {
+            Some(channel) => {
+                self.add_event_listener(channel.clone().into(), "message", listener);
+                self.on_cleanup(move || channel.close());
+            }
+            None => {
+                if let Some(window) = web_sys::window() {
+                    self.add_event_listener(window.into(), "storage", listener);
+                }
+            }
+        }
+        signal
+    }
+
+    /// Invalidates a memo whenever `event` is fired on `target`, until the [`Scope`] is disposed.
+    fn add_invalidating_listener(
+        &self,
+        target: EventTarget,
+        event: &'static str,
+        invalidator: Invalidator,
+    ) {
+        let closure =
+            Closure::wrap(Box::new(move |_| invalidator.invalidate()) as Box<dyn Fn(Event)>);
+        self.add_event_listener(target, event, closure);
+    }
+
+    /// Calls `closure` whenever `event` is fired on `target`, until the [`Scope`] is disposed.
+    fn add_event_listener(
+        &self,
+        target: EventTarget,
+        event: &'static str,
+        closure: Closure<dyn Fn(Event)>,
+    ) {
+        if target
+            .add_event_listener_with_callback(event, closure.as_ref().unchecked_ref())
+            .is_ok()
+        {
+            self.on_cleanup(move || {
+                let _ = target
+                    .remove_event_listener_with_callback(event, closure.as_ref().unchecked_ref());
+            });
+        }
+    }
+}
@@ -0,0 +1,380 @@
+//! Mirroring signals between the main thread and web workers.
+
+use alloc::collections::VecDeque;
+use core::fmt;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{DedicatedWorkerGlobalScope, EventTarget, MessageEvent, Worker};
+
+use crate::*;
+
+/// A channel that a [`SignalSync`] sends its frames through.
+///
+/// [`Worker`] (on the main thread) and [`DedicatedWorkerGlobalScope`] (inside the worker) send the
+/// frames with `postMessage`. Other channels (e.g. a `MessagePort` or an in-process queue in
+/// tests) can implement this trait themselves and pass the received frames to
+/// [`SignalSync::receive`].
+pub trait SyncTransport {
+    /// Sends a frame to the other side.
+    fn post(&self, frame: &str);
+}
+
+impl SyncTransport for Worker {
+    fn post(&self, frame: &str) {
+        let _ = self.post_message(&JsValue::from_str(frame));
+    }
+}
+
+impl SyncTransport for DedicatedWorkerGlobalScope {
+    fn post(&self, frame: &str) {
+        let _ = self.post_message(&JsValue::from_str(frame));
+    }
+}
+
+/// The version of a value for last-write-wins: a Lamport clock and the replica that wrote the
+/// value. The replica breaks ties between concurrent writes.
+type Stamp = (u64, u32);
+
+struct SyncedSignal<'a> {
+    stamp: Cell<Stamp>,
+    /// Set while a value that was received is being applied so that it is not sent back.
+    remote: Cell<bool>,
+    /// Deserializes a received value and sets the signal. Returns `false` if the value could not
+    /// be deserialized.
+    apply: Box<dyn Fn(Value) -> bool + 'a>,
+}
+
+/// Mirrors named signals with another [`SignalSync`], usually across the main thread and a web
+/// worker. Created with [`create_signal_sync`](Scope::create_signal_sync) or
+/// [`create_worker_sync`](Scope::create_worker_sync).
+///
+/// Values are serialized to JSON with `serde`. The updates of the signals that are registered
+/// with [`sync`](Self::sync) are collected and sent together in one frame once all the effects
+/// have run, so updating several signals in a [`batch`] only sends one message. The initial values
+/// are not sent, only the updates.
+///
+/// Conflicts are resolved with last-write-wins: every update is stamped with a logical clock, and
+/// an update that is older than the current value of the signal is ignored. Concurrent updates
+/// are ordered by the replica id, so both sides always end up with the same value.
+pub struct SignalSync<'a> {
+    ctx: ScopeRef<'a>,
+    replica: u32,
+    clock: Cell<u64>,
+    transport: Box<dyn SyncTransport + 'a>,
+    signals: RefCell<HashMap<String, Rc<SyncedSignal<'a>>>>,
+    /// The updates that were not sent yet, at most one per signal.
+    outbox: RefCell<Vec<(String, Stamp, Value)>>,
+    /// Triggers the node that sends the outbox.
+    pending: SignalEmitter,
+}
+
+impl<'a> SignalSync<'a> {
+    /// Mirrors `signal` with the signal that has the same `name` on the other side.
+    ///
+    /// # Panics
+    /// Panics if a signal was already registered with `name`.
+    pub fn sync<T>(&'a self, name: &str, signal: &'a Signal<T>)
+    where
+        T: Serialize + DeserializeOwned + 'a,
+    {
+        let synced = Rc::new(SyncedSignal {
+            stamp: Cell::new((0, self.replica)),
+            remote: Cell::new(false),
+            apply: Box::new(move |value| match serde_json::from_value(value) {
+                Ok(value) => {
+                    signal.set(value);
+                    true
+                }
+                Err(_) => false,
+            }),
+        });
+        let previous = self
+            .signals
+            .borrow_mut()
+            .insert(name.to_string(), Rc::clone(&synced));
+        assert!(previous.is_none(), "signal `{name}` is already synced");
+
+        let name = name.to_string();
+        let mut initial = true;
+        self.ctx.create_effect(move || {
+            let value = signal.get();
+            if core::mem::take(&mut initial) || synced.remote.replace(false) {
+                return;
+            }
+            let Ok(value) = serde_json::to_value(&*value) else {
+                return;
+            };
+            self.clock.set(self.clock.get() + 1);
+            let stamp = (self.clock.get(), self.replica);
+            synced.stamp.set(stamp);
+            let mut outbox = self.outbox.borrow_mut();
+            outbox.retain(|(pending, _, _)| *pending != name);
+            outbox.push((name.clone(), stamp, value));
+            drop(outbox);
+            self.pending.trigger();
+        });
+    }
+
+    /// Applies a frame that was sent by the other side. Frames that are malformed and values that
+    /// cannot be deserialized are ignored.
+    ///
+    /// A frame is a JSON array of updates, each of which is an array of the name of the signal,
+    /// the clock, the replica and the value.
+    pub fn receive(&self, frame: &str) {
+        let Ok(Value::Array(updates)) = serde_json::from_str::<Value>(frame) else {
+            return;
+        };
+        batch(|| {
+            for update in updates {
+                let Value::Array(update) = update else {
+                    continue;
+                };
+                let Ok([Value::String(name), clock, replica, value]) =
+                    <[Value; 4]>::try_from(update)
+                else {
+                    continue;
+                };
+                let (Some(clock), Some(replica)) = (clock.as_u64(), replica.as_u64()) else {
+                    continue;
+                };
+                let stamp = (clock, replica as u32);
+                self.clock.set(self.clock.get().max(clock));
+
+                let synced = self.signals.borrow().get(&name).cloned();
+                let Some(synced) = synced else {
+                    continue;
+                };
+                if stamp > synced.stamp.get() {
+                    synced.remote.set(true);
+                    if (synced.apply)(value) {
+                        synced.stamp.set(stamp);
+                    } else {
+                        synced.remote.set(false);
+                    }
+                }
+            }
+        });
+    }
+
+    /// Sends the pending updates in one frame.
+    fn send(&self) {
+        let outbox = core::mem::take(&mut *self.outbox.borrow_mut());
+        if outbox.is_empty() {
+            return;
+        }
+        let updates: Vec<_> = outbox
+            .into_iter()
+            .map(|(name, (clock, replica), value)| {
+                Value::Array(vec![name.into(), clock.into(), replica.into(), value])
+            })
+            .collect();
+        self.transport.post(&Value::Array(updates).to_string());
+    }
+}
+
+impl fmt::Debug for SignalSync<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SignalSync")
+            .field("replica", &self.replica)
+            .field("signals", &self.signals.borrow().len())
+            .finish()
+    }
+}
+
+impl<'a> Scope<'a> {
+    /// Creates a new [`SignalSync`] that sends its frames through `transport`. The frames that are
+    /// received from the other side must be passed to [`SignalSync::receive`].
+    ///
+    /// `replica` must be different on the two sides, e.g. `0` on the main thread and `1` in the
+    /// worker.
+    pub fn create_signal_sync(
+        &'a self,
+        replica: u32,
+        transport: impl SyncTransport + 'a,
+    ) -> &'a SignalSync<'a> {
+        let sync = self.create_ref(SignalSync {
+            ctx: self,
+            replica,
+            clock: Cell::new(0),
+            transport: Box::new(transport),
+            signals: Default::default(),
+            outbox: Default::default(),
+            pending: SignalEmitter::new(),
+        });
+        let sender = self.create_effect(|| {
+            sync.pending.track();
+            sync.send();
+        });
+        // The frame is only sent once all the other effects have run.
+        sender.set_order_hint(i32::MAX);
+        sync
+    }
+
+    /// Creates a new [`SignalSync`] that exchanges frames with `postMessage` through `port`, which
+    /// is either the [`Worker`] on the main thread or the [`DedicatedWorkerGlobalScope`] inside the
+    /// worker. See [`create_signal_sync`](Self::create_signal_sync).
+    ///
+    /// The `message` listener is removed when the [`Scope`] is disposed.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use sycamore_reactive::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let worker = web_sys::Worker::new("worker.js").unwrap();
+    /// let sync = ctx.create_worker_sync(0, worker);
+    /// let query = ctx.create_signal(String::new());
+    /// let results = ctx.create_signal(Vec::<String>::new());
+    /// // The worker syncs the same names and computes the results off the main thread.
+    /// sync.sync("query", query);
+    /// sync.sync("results", results);
+    /// # });
+    /// ```
+    pub fn create_worker_sync<P>(&'a self, replica: u32, port: P) -> &'a SignalSync<'a>
+    where
+        P: SyncTransport + AsRef<EventTarget> + Clone + 'a,
+    {
+        let target: EventTarget = port.as_ref().clone();
+        let sync = self.create_signal_sync(replica, port);
+
+        // The listener cannot borrow from the scope, so the frames are handed over to a node in
+        // the scope.
+        let inbox = Rc::new(RefCell::new(VecDeque::<String>::new()));
+        let received = Rc::new(SignalEmitter::new());
+        self.create_effect({
+            let inbox = Rc::clone(&inbox);
+            let received = Rc::clone(&received);
+            move || {
+                received.track();
+                loop {
+                    let frame = inbox.borrow_mut().pop_front();
+                    let Some(frame) = frame else { break };
+                    sync.receive(&frame);
+                }
+            }
+        });
+        let closure = Closure::wrap(Box::new(move |event: MessageEvent| {
+            if let Some(frame) = event.data().as_string() {
+                inbox.borrow_mut().push_back(frame);
+                received.trigger();
+            }
+        }) as Box<dyn Fn(MessageEvent)>);
+        if target
+            .add_event_listener_with_callback("message", closure.as_ref().unchecked_ref())
+            .is_ok()
+        {
+            self.on_cleanup(move || {
+                let _ = target.remove_event_listener_with_callback(
+                    "message",
+                    closure.as_ref().unchecked_ref(),
+                );
+            });
+        }
+        sync
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct Frames(Rc<RefCell<Vec<String>>>);
+
+    impl SyncTransport for Frames {
+        fn post(&self, frame: &str) {
+            self.0.borrow_mut().push(frame.to_string());
+        }
+    }
+
+    impl Frames {
+        fn deliver(&self, to: &SignalSync) {
+            let frames = core::mem::take(&mut *self.0.borrow_mut());
+            for frame in frames {
+                to.receive(&frame);
+            }
+        }
+    }
+
+    #[test]
+    fn updates_are_batched_into_one_frame() {
+        create_scope_immediate(|ctx| {
+            let frames = Frames::default();
+            let main = ctx.create_signal_sync(0, frames.clone());
+            let a = ctx.create_signal(0);
+            let b = ctx.create_signal(String::new());
+            main.sync("a", a);
+            main.sync("b", b);
+            assert!(frames.0.borrow().is_empty());
+
+            batch(|| {
+                a.set(1);
+                a.set(2);
+                b.set("hello".to_string());
+            });
+            assert_eq!(*frames.0.borrow(), [r#"[["a",1,0,2],["b",2,0,"hello"]]"#]);
+        });
+    }
+
+    #[test]
+    fn received_updates_are_applied_without_echo() {
+        create_scope_immediate(|ctx| {
+            let to_worker = Frames::default();
+            let to_main = Frames::default();
+            let main = ctx.create_signal_sync(0, to_worker.clone());
+            let worker = ctx.create_signal_sync(1, to_main.clone());
+            let main_input = ctx.create_signal(0);
+            let worker_input = ctx.create_signal(0);
+            let main_output = ctx.create_signal(0);
+            let worker_output = ctx.create_signal(0);
+            // The worker computes the output from the input.
+            ctx.create_effect(|| worker_output.set(*worker_input.get() * 2));
+            main.sync("input", main_input);
+            main.sync("output", main_output);
+            worker.sync("input", worker_input);
+            worker.sync("output", worker_output);
+
+            main_input.set(21);
+            to_worker.deliver(worker);
+            assert_eq!(*worker_input.get(), 21);
+            assert_eq!(to_main.0.borrow().len(), 1);
+            to_main.deliver(main);
+            assert_eq!(*main_output.get(), 42);
+            // Nothing is sent back.
+            assert!(to_worker.0.borrow().is_empty());
+        });
+    }
+
+    #[test]
+    fn last_write_wins() {
+        create_scope_immediate(|ctx| {
+            let to_worker = Frames::default();
+            let to_main = Frames::default();
+            let main = ctx.create_signal_sync(0, to_worker.clone());
+            let worker = ctx.create_signal_sync(1, to_main.clone());
+            let main_state = ctx.create_signal(0);
+            let worker_state = ctx.create_signal(0);
+            main.sync("state", main_state);
+            worker.sync("state", worker_state);
+
+            // Concurrent updates with the same clock are won by the higher replica.
+            main_state.set(1);
+            worker_state.set(2);
+            to_worker.deliver(worker);
+            to_main.deliver(main);
+            assert_eq!(*main_state.get(), 2);
+            assert_eq!(*worker_state.get(), 2);
+
+            // A stale update is ignored.
+            main.receive(r#"[["state",1,1,3]]"#);
+            assert_eq!(*main_state.get(), 2);
+            // Malformed frames are ignored.
+            main.receive("{");
+            main.receive(r#"[["state","x",1,3]]"#);
+            assert_eq!(*main_state.get(), 2);
+        });
+    }
+}
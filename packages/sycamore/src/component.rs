@@ -1,5 +1,7 @@
 //! Utilities for components and component properties.
 
+use std::fmt;
+
 use crate::generic_node::GenericNode;
 use crate::reactive::*;
 use crate::view::View;
@@ -20,6 +22,58 @@ pub fn component_scope<G: GenericNode>(f: impl FnOnce() -> View<G>) -> View<G> {
     }
 }
 
+/// The children of a component, e.g. `p { "Hello" }` in `view! { ctx, Layout { p { "Hello" } } }`.
+///
+/// Components accept children with a `children` prop. The view of the children is only created
+/// when the component calls [`call`](Self::call), which allows the component to create it inside
+/// of its own scope or context.
+///
+/// # Example
+/// ```
+/// # use sycamore::prelude::*;
+/// #[derive(Prop)]
+/// struct LayoutProps<'a, G: GenericNode> {
+///     children: Children<'a, G>,
+/// }
+///
+/// #[component]
+/// fn Layout<'a, G: Html>(ctx: ScopeRef<'a>, props: LayoutProps<'a, G>) -> View<G> {
+///     let children = props.children.call(ctx);
+///     view! { ctx, main { (children) } }
+/// }
+///
+/// # fn app<G: Html>(ctx: ScopeRef) -> View<G> {
+/// view! { ctx, Layout { p { "Hello" } } }
+/// # }
+/// ```
+pub struct Children<'a, G: GenericNode> {
+    f: Box<dyn FnOnce(ScopeRef<'a>) -> View<G> + 'a>,
+}
+
+impl<'a, G: GenericNode> Children<'a, G> {
+    /// Creates children whose view is created with `f`.
+    pub fn new(f: impl FnOnce(ScopeRef<'a>) -> View<G> + 'a) -> Self {
+        Self { f: Box::new(f) }
+    }
+
+    /// Creates the view of the children in `ctx`.
+    pub fn call(self, ctx: ScopeRef<'a>) -> View<G> {
+        (self.f)(ctx)
+    }
+}
+
+impl<'a, G: GenericNode> From<View<G>> for Children<'a, G> {
+    fn from(view: View<G>) -> Self {
+        Self::new(move |_| view)
+    }
+}
+
+impl<G: GenericNode> fmt::Debug for Children<'_, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Children").finish_non_exhaustive()
+    }
+}
+
 /// A trait that is implemented automatically by the [`Prop`](crate::Prop) derive macro.
 pub trait Prop {
     type Builder;
@@ -0,0 +1,99 @@
+//! Easing functions for animations, such as the ones created with
+//! [`create_tweened_signal`](crate::motion::ScopeCreateTweened::create_tweened_signal).
+//!
+//! Each function maps the progress of an animation, from `0.0` to `1.0`, to the eased progress.
+//! The eased progress is `0.0` at the start and `1.0` at the end of the animation but can be
+//! outside of that range in between (e.g. for [`back_out`]).
+
+use std::f64::consts::PI;
+
+/// No easing.
+pub fn linear(t: f64) -> f64 {
+    t
+}
+
+/// Quadratic easing that starts slowly.
+pub fn quad_in(t: f64) -> f64 {
+    t * t
+}
+
+/// Quadratic easing that ends slowly.
+pub fn quad_out(t: f64) -> f64 {
+    1.0 - quad_in(1.0 - t)
+}
+
+/// Quadratic easing that starts and ends slowly.
+pub fn quad_inout(t: f64) -> f64 {
+    if t < 0.5 {
+        2.0 * t * t
+    } else {
+        1.0 - 2.0 * (1.0 - t) * (1.0 - t)
+    }
+}
+
+/// Cubic easing that starts slowly.
+pub fn cubic_in(t: f64) -> f64 {
+    t * t * t
+}
+
+/// Cubic easing that ends slowly.
+pub fn cubic_out(t: f64) -> f64 {
+    1.0 - cubic_in(1.0 - t)
+}
+
+/// Cubic easing that starts and ends slowly.
+pub fn cubic_inout(t: f64) -> f64 {
+    if t < 0.5 {
+        4.0 * t * t * t
+    } else {
+        1.0 - 4.0 * (1.0 - t) * (1.0 - t) * (1.0 - t)
+    }
+}
+
+/// Sinusoidal easing that starts slowly.
+pub fn sine_in(t: f64) -> f64 {
+    1.0 - (t * PI / 2.0).cos()
+}
+
+/// Sinusoidal easing that ends slowly.
+pub fn sine_out(t: f64) -> f64 {
+    (t * PI / 2.0).sin()
+}
+
+/// Sinusoidal easing that starts and ends slowly.
+pub fn sine_inout(t: f64) -> f64 {
+    (1.0 - (t * PI).cos()) / 2.0
+}
+
+/// Easing that overshoots the target slightly before settling.
+pub fn back_out(t: f64) -> f64 {
+    const C1: f64 = 1.70158;
+    const C3: f64 = C1 + 1.0;
+    1.0 + C3 * (t - 1.0).powi(3) + C1 * (t - 1.0).powi(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn easings_start_at_0_and_end_at_1() {
+        let easings: [fn(f64) -> f64; 11] = [
+            linear,
+            quad_in,
+            quad_out,
+            quad_inout,
+            cubic_in,
+            cubic_out,
+            cubic_inout,
+            sine_in,
+            sine_out,
+            sine_inout,
+            back_out,
+        ];
+        for easing in easings {
+            assert!(easing(0.0).abs() < 1e-9);
+            assert!((easing(1.0) - 1.0).abs() < 1e-9);
+        }
+    }
+}
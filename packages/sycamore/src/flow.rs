@@ -1,6 +1,11 @@
 use std::hash::Hash;
 
+#[cfg(feature = "dom")]
+use wasm_bindgen::UnwrapThrowExt;
+
 use crate::prelude::*;
+#[cfg(feature = "dom")]
+use crate::utils::render::insert;
 
 /// Props for [`Keyed`].
 #[derive(Prop)]
@@ -8,7 +13,7 @@ pub struct KeyedProps<'a, T, F, G: GenericNode, K, Key>
 where
     F: Fn(BoundedScopeRef<'_, 'a>, T) -> View<G> + 'a,
     K: Fn(&T) -> Key + 'a,
-    Key: Clone + Hash + Eq,
+    Key: Clone + Hash + Eq + 'a,
     T: Clone + PartialEq,
 {
     pub iterable: &'a ReadSignal<Vec<T>>,
@@ -29,7 +34,7 @@ pub fn Keyed<'a, G: GenericNode, T, F, K, Key>(
 where
     F: Fn(BoundedScopeRef<'_, 'a>, T) -> View<G> + 'a,
     K: Fn(&T) -> Key + 'a,
-    Key: Clone + Hash + Eq,
+    Key: Clone + Hash + Eq + 'a,
     T: Clone + Eq,
 {
     let KeyedProps {
@@ -38,8 +43,7 @@ where
         key,
     } = props;
 
-    let mapped = ctx.map_keyed(iterable, view, key);
-    View::new_dyn(ctx, || View::new_fragment(mapped.get().as_ref().clone()))
+    ctx.map_keyed_output(iterable, view, key)
 }
 
 /// Props for [`Indexed`].
@@ -56,7 +60,8 @@ where
 /// [`View`]s. Using this will minimize re-renders instead of re-rendering every single
 /// node on every state change.
 ///
-/// For keyed iteration, see [`Keyed`].
+/// For keyed iteration, see [`Keyed`]. To also pass the index of each item to `view`, see
+/// [`Enumerated`].
 #[component]
 pub fn Indexed<'a, G: GenericNode, T, F>(
     ctx: ScopeRef<'a>,
@@ -68,6 +73,102 @@ where
 {
     let IndexedProps { iterable, view } = props;
 
-    let mapped = ctx.map_indexed(iterable, view);
-    View::new_dyn(ctx, || View::new_fragment(mapped.get().as_ref().clone()))
+    ctx.map_indexed_output(iterable, view)
+}
+
+/// Props for [`Enumerated`].
+#[derive(Prop)]
+pub struct EnumeratedProps<'a, G: GenericNode, T, F>
+where
+    F: Fn(BoundedScopeRef<'_, 'a>, T, usize) -> View<G> + 'a,
+{
+    pub iterable: &'a ReadSignal<Vec<T>>,
+    pub view: F,
+}
+
+/// Like [`Indexed`], but `view` also receives the index of each item, e.g. to render numbered
+/// lists.
+///
+/// Since the index is part of the rendered item, all the items after an inserted or removed item
+/// are rendered again.
+#[component]
+pub fn Enumerated<'a, G: GenericNode, T, F>(
+    ctx: ScopeRef<'a>,
+    props: EnumeratedProps<'a, G, T, F>,
+) -> View<G>
+where
+    T: Clone + PartialEq + 'a,
+    F: Fn(BoundedScopeRef<'_, 'a>, T, usize) -> View<G> + 'a,
+{
+    let EnumeratedProps { iterable, view } = props;
+
+    let enumerated = iterable
+        .map(|items| items.iter().cloned().enumerate().collect::<Vec<_>>())
+        .memo(ctx);
+    ctx.map_indexed_output(enumerated, move |ctx, (index, item)| view(ctx, item, index))
+}
+
+/// Props for [`Portal`].
+#[cfg(feature = "dom")]
+#[derive(Prop, Debug)]
+pub struct PortalProps<'a, G: GenericNode> {
+    pub children: Children<'a, G>,
+    /// A CSS selector for the element under which the children are rendered.
+    pub selector: &'a str,
+}
+
+/// Renders its children at the end of the element matching `selector`, e.g. a modal root outside
+/// of the app, instead of in place.
+///
+/// The children are still owned by the scope of the [`Portal`], which means that they can use the
+/// contexts of their parent components and that they are removed from the element when the scope
+/// is disposed. Nothing is rendered outside of the browser.
+///
+/// _This API requires the following crate features to be activated: `dom`_
+#[cfg(feature = "dom")]
+#[component]
+pub fn Portal<'a, G: Html>(ctx: ScopeRef<'a>, props: PortalProps<'a, G>) -> View<G> {
+    let PortalProps { children, selector } = props;
+
+    if G::IS_BROWSER {
+        let parent = web_sys::window()
+            .unwrap_throw()
+            .document()
+            .unwrap_throw()
+            .query_selector(selector)
+            .unwrap_throw()
+            .unwrap_or_else(|| panic!("no element matches the portal selector `{}`", selector));
+        let parent = node_from_web_sys::<G>(parent.into());
+
+        // Insert the children before a marker so that they stay together if the element has other
+        // children.
+        let marker = G::marker();
+        parent.append_child(&marker);
+        let view = children.call(ctx);
+        insert(ctx, &parent, view.clone(), None, Some(&marker), true);
+
+        ctx.on_cleanup(move || {
+            for node in view.flatten() {
+                parent.remove_child(&node);
+            }
+            parent.remove_child(&marker);
+        });
+    }
+
+    View::empty()
+}
+
+/// Wraps a DOM node in `G`, which must be a [`DomNode`] or a
+/// [`HydrateNode`](crate::generic_node::HydrateNode).
+#[cfg(feature = "dom")]
+fn node_from_web_sys<G: GenericNode>(node: web_sys::Node) -> G {
+    #[cfg(feature = "experimental-hydrate")]
+    if std::any::TypeId::of::<G>() == std::any::TypeId::of::<HydrateNode>() {
+        let node: Box<dyn std::any::Any> = Box::new(HydrateNode::from_web_sys(node));
+        return *node.downcast().unwrap();
+    }
+    let node: Box<dyn std::any::Any> = Box::new(DomNode::from_web_sys(node));
+    *node
+        .downcast()
+        .expect("Portal can only be used with DomNode or HydrateNode")
 }
@@ -1,14 +1,71 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fmt;
 use std::future::Future;
+use std::hash::Hash;
+use std::pin::Pin;
+use std::rc::Rc;
+#[cfg(feature = "ssr")]
+use std::task::Context;
+use std::task::{Poll, Waker};
 
 use wasm_bindgen_futures::spawn_local;
 
 use crate::prelude::*;
 
 pub trait ScopeFuturesExt<'a> {
+    /// Runs the future `f` and returns a signal that is set to its output once it completes.
+    ///
+    /// The nearest suspense boundary (see [`Suspense`](crate::suspense::Suspense)) is pending
+    /// until the future completes.
     fn create_resource<U, F>(&'a self, f: F) -> RcSignal<Option<U>>
     where
         U: 'static,
         F: Future<Output = U> + 'static;
+
+    /// Creates a resource that loads the output of `key` with `client`. The resource is refetched
+    /// whenever the signals that are used by `key` change, and whenever its key is invalidated
+    /// (see [`FetchClient::invalidate`]).
+    ///
+    /// The signal keeps the previous output while a new key is loading. The nearest suspense
+    /// boundary is pending while the output of a key is loading. The request is aborted if the
+    /// resource no longer needs it, e.g. because the scope is disposed.
+    fn create_fetch_resource<F: Fetcher>(
+        &'a self,
+        client: &FetchClient<F>,
+        key: impl FnMut() -> F::Key + 'a,
+    ) -> RcSignal<Option<Rc<F::Output>>>;
+
+    /// Applies `update` to the value of `resource` right away and runs `mutation` in the
+    /// background, e.g. to send the change to the server. Returns a signal that is set to the
+    /// outcome of the mutation once it completes.
+    ///
+    /// If the mutation succeeds, the resource is set to its output so that it matches the server.
+    /// If it fails and `rollback_on_err` is `true`, the previous value is restored, unless the
+    /// resource was updated in the meantime. If the resource is not loaded yet, `update` is not
+    /// called.
+    fn mutate_optimistic<U, E, F>(
+        &'a self,
+        resource: &RcSignal<Option<U>>,
+        update: impl FnOnce(&U) -> U,
+        mutation: F,
+        rollback_on_err: bool,
+    ) -> RcSignal<Option<Result<(), E>>>
+    where
+        U: Clone + 'static,
+        E: 'static,
+        F: Future<Output = Result<U, E>> + 'static;
+
+    /// Like [`create_resource`](Self::create_resource), but the output of the future is
+    /// transferred from SSR to the client. If the output is restored on the client, the future is
+    /// not run at all.
+    ///
+    /// See [`create_hydrated_signal`](crate::utils::hydrate::data::ScopeCreateHydratedSignal).
+    #[cfg(all(feature = "serde", feature = "experimental-hydrate"))]
+    fn create_hydrated_resource<U, F>(&'a self, f: F) -> RcSignal<Option<U>>
+    where
+        U: serde::Serialize + serde::de::DeserializeOwned + 'static,
+        F: Future<Output = U> + 'static;
 }
 
 impl<'a> ScopeFuturesExt<'a> for Scope<'a> {
@@ -18,14 +75,543 @@ impl<'a> ScopeFuturesExt<'a> for Scope<'a> {
         F: Future<Output = U> + 'static,
     {
         let signal = create_rc_signal(None);
+        spawn_resource(self, signal.clone(), f);
+        signal
+    }
+
+    fn create_fetch_resource<F: Fetcher>(
+        &'a self,
+        client: &FetchClient<F>,
+        mut key: impl FnMut() -> F::Key + 'a,
+    ) -> RcSignal<Option<Rc<F::Output>>> {
+        let signal = create_rc_signal(None);
+        let id = client.0.next_id.get();
+        client.0.next_id.set(id + 1);
+        // The key that the resource is waiting for.
+        let current = self.create_ref(RefCell::new(None::<F::Key>));
 
-        spawn_local({
+        self.create_effect({
+            let client = client.clone();
             let signal = signal.clone();
+            move || {
+                client.0.invalidated.track();
+                let key = key();
+                untrack(|| {
+                    let previous = current.borrow_mut().replace(key.clone());
+                    if let Some(previous) = previous.filter(|previous| *previous != key) {
+                        client.leave(id, &previous);
+                    }
+                    client.request(self, id, &key, &signal);
+                });
+            }
+        });
+        let client = client.clone();
+        self.on_cleanup(move || {
+            if let Some(key) = current.borrow_mut().take() {
+                client.leave(id, &key);
+            }
+        });
+        signal
+    }
+
+    fn mutate_optimistic<U, E, F>(
+        &'a self,
+        resource: &RcSignal<Option<U>>,
+        update: impl FnOnce(&U) -> U,
+        mutation: F,
+        rollback_on_err: bool,
+    ) -> RcSignal<Option<Result<(), E>>>
+    where
+        U: Clone + 'static,
+        E: 'static,
+        F: Future<Output = Result<U, E>> + 'static,
+    {
+        let previous = resource.get_untracked();
+        if let Some(value) = previous.as_ref() {
+            resource.set(Some(update(value)));
+        }
+        // Used to find out whether the resource was updated while the mutation was running.
+        let optimistic = resource.get_untracked();
+
+        let outcome = create_rc_signal(None);
+        spawn(self, {
+            let resource = resource.clone();
+            let outcome = outcome.clone();
             async move {
-                signal.set(Some(f.await));
+                match mutation.await {
+                    Ok(value) => {
+                        resource.set(Some(value));
+                        outcome.set(Some(Ok(())));
+                    }
+                    Err(err) => {
+                        if rollback_on_err && Rc::ptr_eq(&resource.get_untracked(), &optimistic) {
+                            resource.set((*previous).clone());
+                        }
+                        outcome.set(Some(Err(err)));
+                    }
+                }
             }
         });
+        outcome
+    }
 
+    #[cfg(all(feature = "serde", feature = "experimental-hydrate"))]
+    fn create_hydrated_resource<U, F>(&'a self, f: F) -> RcSignal<Option<U>>
+    where
+        U: serde::Serialize + serde::de::DeserializeOwned + 'static,
+        F: Future<Output = U> + 'static,
+    {
+        let signal = crate::utils::hydrate::data::create_hydrated_rc_signal(self, || None);
+        if signal.get_untracked().is_none() {
+            spawn_resource(self, signal.clone(), f);
+        }
         signal
     }
 }
+
+/// Runs the future `f` and sets `signal` to its output once it completes.
+fn spawn_resource<U, F>(ctx: ScopeRef<'_>, signal: RcSignal<Option<U>>, f: F)
+where
+    U: 'static,
+    F: Future<Output = U> + 'static,
+{
+    let guard = ctx.suspend();
+    spawn(ctx, async move {
+        signal.set(Some(f.await));
+        drop(guard);
+    });
+}
+
+/// Spawns `task` on the [`TaskQueue`] of the renderer, or on the browser event loop if there is
+/// none.
+fn spawn(ctx: ScopeRef<'_>, task: impl Future<Output = ()> + 'static) {
+    match ctx.try_use_context::<TaskQueue>() {
+        Some(tasks) => tasks.push(task),
+        None => spawn_local(task),
+    }
+}
+
+/// The future returned by [`Fetcher::fetch`].
+pub type FetchFuture<T> = Pin<Box<dyn Future<Output = T>>>;
+
+/// A data source that is used by resources created with
+/// [`create_fetch_resource`](ScopeFuturesExt::create_fetch_resource), e.g. an HTTP or GraphQL
+/// client.
+///
+/// The [`FetchClient`] that wraps the fetcher takes care of caching, deduplicating and aborting
+/// requests, so a fetcher only needs to load the data for a key.
+pub trait Fetcher: 'static {
+    /// The cache key of a request, e.g. the URL or the query and its variables.
+    type Key: Clone + Eq + Hash + 'static;
+    /// The data that is loaded.
+    type Output: 'static;
+
+    /// Loads the data for `key`.
+    ///
+    /// `abort` is aborted when no resource needs the data anymore, e.g. because the scopes of the
+    /// resources were disposed or because their keys changed. The returned future is dropped
+    /// without being polled further in that case. Fetchers that can cancel the underlying request
+    /// (e.g. with an `AbortController`) can do so with [`AbortToken::on_abort`].
+    fn fetch(&self, key: &Self::Key, abort: AbortToken) -> FetchFuture<Self::Output>;
+}
+
+#[derive(Default)]
+struct AbortState {
+    aborted: Cell<bool>,
+    waker: RefCell<Option<Waker>>,
+    callbacks: RefCell<Vec<Box<dyn FnOnce()>>>,
+}
+
+/// Signals that a request made by a [`Fetcher`] is no longer needed.
+#[derive(Clone, Default)]
+pub struct AbortToken(Rc<AbortState>);
+
+impl AbortToken {
+    /// Returns `true` if the request was aborted.
+    pub fn is_aborted(&self) -> bool {
+        self.0.aborted.get()
+    }
+
+    /// Calls `f` when the request is aborted, or right away if it was aborted already.
+    pub fn on_abort(&self, f: impl FnOnce() + 'static) {
+        if self.is_aborted() {
+            f();
+        } else {
+            self.0.callbacks.borrow_mut().push(Box::new(f));
+        }
+    }
+
+    fn abort(&self) {
+        if self.0.aborted.replace(true) {
+            return;
+        }
+        if let Some(waker) = self.0.waker.take() {
+            waker.wake();
+        }
+        for callback in self.0.callbacks.take() {
+            callback();
+        }
+    }
+
+    /// Runs `f` until it completes or until the token is aborted.
+    async fn run<T>(self, mut f: FetchFuture<T>) -> Option<T> {
+        std::future::poll_fn(move |cx| {
+            if self.is_aborted() {
+                return Poll::Ready(None);
+            }
+            *self.0.waker.borrow_mut() = Some(cx.waker().clone());
+            f.as_mut().poll(cx).map(Some)
+        })
+        .await
+    }
+}
+
+impl fmt::Debug for AbortToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AbortToken")
+            .field(&self.is_aborted())
+            .finish()
+    }
+}
+
+/// A resource that is waiting for the output of a request.
+struct Waiter<T> {
+    id: u64,
+    signal: RcSignal<Option<Rc<T>>>,
+    /// Keeps the suspense boundary of the resource pending until the output arrives.
+    _guard: SuspenseGuard,
+}
+
+/// A request that is in flight.
+struct InFlight<T> {
+    abort: AbortToken,
+    waiters: Vec<Waiter<T>>,
+}
+
+struct ClientState<F: Fetcher> {
+    fetcher: F,
+    next_id: Cell<u64>,
+    cache: RefCell<HashMap<F::Key, Rc<F::Output>>>,
+    in_flight: RefCell<HashMap<F::Key, InFlight<F::Output>>>,
+    /// Triggered when cache entries are invalidated so that the resources refetch them.
+    invalidated: SignalEmitter,
+}
+
+/// Caches and deduplicates the requests of a [`Fetcher`] for the resources created with
+/// [`create_fetch_resource`](ScopeFuturesExt::create_fetch_resource).
+///
+/// - The output of each key is cached, so resources with the same key share it.
+/// - Resources that need the same key while it is loading wait for the same request.
+/// - A request is aborted once no resource is waiting for it anymore.
+///
+/// Cloning the client does not create a new cache.
+pub struct FetchClient<F: Fetcher>(Rc<ClientState<F>>);
+
+impl<F: Fetcher> FetchClient<F> {
+    /// Creates a new client with an empty cache.
+    pub fn new(fetcher: F) -> Self {
+        Self(Rc::new(ClientState {
+            fetcher,
+            next_id: Cell::new(0),
+            cache: Default::default(),
+            in_flight: Default::default(),
+            invalidated: SignalEmitter::new(),
+        }))
+    }
+
+    /// Returns the cached output of `key`, if any.
+    pub fn cached(&self, key: &F::Key) -> Option<Rc<F::Output>> {
+        self.0.cache.borrow().get(key).cloned()
+    }
+
+    /// Removes `key` from the cache. The resources that use `key` fetch it again.
+    pub fn invalidate(&self, key: &F::Key) {
+        if self.0.cache.borrow_mut().remove(key).is_some() {
+            self.0.invalidated.trigger();
+        }
+    }
+
+    /// Removes all the keys from the cache. All the resources fetch their keys again.
+    pub fn invalidate_all(&self) {
+        self.0.cache.borrow_mut().clear();
+        self.0.invalidated.trigger();
+    }
+
+    /// Sets `signal` to the output of `key`, fetching it if it is not cached.
+    fn request(
+        &self,
+        ctx: ScopeRef<'_>,
+        id: u64,
+        key: &F::Key,
+        signal: &RcSignal<Option<Rc<F::Output>>>,
+    ) {
+        if let Some(output) = self.cached(key) {
+            let current = signal.get_untracked();
+            if !matches!(&*current, Some(current) if Rc::ptr_eq(current, &output)) {
+                signal.set(Some(output));
+            }
+            return;
+        }
+        let waiter = Waiter {
+            id,
+            signal: signal.clone(),
+            _guard: ctx.suspend(),
+        };
+        let mut in_flight = self.0.in_flight.borrow_mut();
+        if let Some(request) = in_flight.get_mut(key) {
+            if !request.waiters.iter().any(|waiter| waiter.id == id) {
+                request.waiters.push(waiter);
+            }
+            return;
+        }
+        let abort = AbortToken::default();
+        in_flight.insert(
+            key.clone(),
+            InFlight {
+                abort: abort.clone(),
+                waiters: vec![waiter],
+            },
+        );
+        drop(in_flight);
+
+        let future = self.0.fetcher.fetch(key, abort.clone());
+        let client = self.clone();
+        let key = key.clone();
+        spawn(ctx, async move {
+            let Some(output) = abort.run(future).await else {
+                return;
+            };
+            let output = Rc::new(output);
+            client
+                .0
+                .cache
+                .borrow_mut()
+                .insert(key.clone(), Rc::clone(&output));
+            let request = client.0.in_flight.borrow_mut().remove(&key);
+            for waiter in request.map(|request| request.waiters).unwrap_or_default() {
+                waiter.signal.set(Some(Rc::clone(&output)));
+            }
+        });
+    }
+
+    /// Stops waiting for `key`. The request is aborted if nothing else is waiting for it.
+    fn leave(&self, id: u64, key: &F::Key) {
+        let mut in_flight = self.0.in_flight.borrow_mut();
+        let Some(request) = in_flight.get_mut(key) else {
+            return;
+        };
+        request.waiters.retain(|waiter| waiter.id != id);
+        if request.waiters.is_empty() {
+            let request = in_flight.remove(key).unwrap();
+            drop(in_flight);
+            request.abort.abort();
+        }
+    }
+}
+
+impl<F: Fetcher> Clone for FetchClient<F> {
+    fn clone(&self) -> Self {
+        Self(Rc::clone(&self.0))
+    }
+}
+
+impl<F: Fetcher> fmt::Debug for FetchClient<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FetchClient")
+            .field("cached", &self.0.cache.borrow().len())
+            .field("in_flight", &self.0.in_flight.borrow().len())
+            .finish()
+    }
+}
+
+type Task = Pin<Box<dyn Future<Output = ()>>>;
+
+/// Futures that are polled by the renderer instead of being spawned on the browser event loop.
+/// This is used when rendering on the server, where there is no event loop.
+#[derive(Clone, Default)]
+pub(crate) struct TaskQueue(Rc<RefCell<Vec<Task>>>);
+
+impl TaskQueue {
+    fn push(&self, task: impl Future<Output = ()> + 'static) {
+        self.0.borrow_mut().push(Box::pin(task));
+    }
+
+    /// Polls all the tasks once, including the tasks that are pushed while polling, and removes
+    /// the tasks that are complete.
+    #[cfg(feature = "ssr")]
+    pub(crate) fn poll(&self, cx: &mut Context<'_>) {
+        let mut pending = Vec::new();
+        loop {
+            let mut tasks = self.0.take();
+            if tasks.is_empty() {
+                break;
+            }
+            tasks.retain_mut(|task| task.as_mut().poll(cx).is_pending());
+            pending.append(&mut tasks);
+        }
+        *self.0.borrow_mut() = pending;
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+
+    /// Completes on the second poll.
+    struct YieldOnce(bool);
+
+    impl Future for YieldOnce {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+            if std::mem::replace(&mut self.0, true) {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        }
+    }
+
+    #[derive(Default)]
+    struct TimesTen {
+        fetched: Rc<RefCell<Vec<u32>>>,
+        aborted: Rc<RefCell<Vec<u32>>>,
+    }
+
+    impl Fetcher for TimesTen {
+        type Key = u32;
+        type Output = u32;
+
+        fn fetch(&self, key: &u32, abort: AbortToken) -> FetchFuture<u32> {
+            let key = *key;
+            self.fetched.borrow_mut().push(key);
+            let aborted = Rc::clone(&self.aborted);
+            abort.on_abort(move || aborted.borrow_mut().push(key));
+            Box::pin(async move {
+                YieldOnce(false).await;
+                key * 10
+            })
+        }
+    }
+
+    /// Polls the tasks until the fetches complete.
+    fn run(tasks: &TaskQueue) {
+        for _ in 0..2 {
+            tasks.poll(&mut Context::from_waker(Waker::noop()));
+        }
+    }
+
+    #[test]
+    fn fetch_resources_share_requests_and_cache() {
+        create_scope_immediate(|ctx| {
+            let tasks = TaskQueue::default();
+            ctx.provide_context(tasks.clone());
+            let fetcher = TimesTen::default();
+            let fetched = Rc::clone(&fetcher.fetched);
+            let client = FetchClient::new(fetcher);
+
+            let a = ctx.create_fetch_resource(&client, || 1);
+            let b = ctx.create_fetch_resource(&client, || 1);
+            assert_eq!(*fetched.borrow(), [1]);
+            assert!(a.get().is_none());
+            run(&tasks);
+            assert_eq!(a.get().as_deref(), Some(&10));
+            assert_eq!(b.get().as_deref(), Some(&10));
+
+            // The output is cached.
+            let c = ctx.create_fetch_resource(&client, || 1);
+            assert_eq!(c.get().as_deref(), Some(&10));
+            assert_eq!(*fetched.borrow(), [1]);
+
+            client.invalidate(&1);
+            assert_eq!(*fetched.borrow(), [1, 1]);
+            run(&tasks);
+            assert_eq!(a.get().as_deref(), Some(&10));
+        });
+    }
+
+    #[test]
+    fn fetch_resource_aborts_unneeded_requests() {
+        create_scope_immediate(|ctx| {
+            let tasks = TaskQueue::default();
+            ctx.provide_context(tasks.clone());
+            let fetcher = TimesTen::default();
+            let aborted = Rc::clone(&fetcher.aborted);
+            let client = FetchClient::new(fetcher);
+
+            let key = ctx.create_signal(1);
+            let resource = ctx.create_fetch_resource(&client, || *key.get());
+            key.set(2);
+            assert_eq!(*aborted.borrow(), [1]);
+            run(&tasks);
+            assert_eq!(resource.get().as_deref(), Some(&20));
+            assert!(client.cached(&1).is_none());
+
+            let disposer = ctx.create_child_scope(|ctx| {
+                let _ = ctx.create_fetch_resource(&client, || 3);
+            });
+            disposer();
+            assert_eq!(*aborted.borrow(), [1, 3]);
+        });
+    }
+
+    #[test]
+    fn mutate_optimistic_reconciles_or_rolls_back() {
+        create_scope_immediate(|ctx| {
+            let tasks = TaskQueue::default();
+            ctx.provide_context(tasks.clone());
+            let todos = create_rc_signal(Some(vec!["a"]));
+
+            let outcome = ctx.mutate_optimistic(
+                &todos,
+                |todos| [todos.as_slice(), &["b"]].concat(),
+                async {
+                    YieldOnce(false).await;
+                    Ok::<_, ()>(vec!["a", "B"])
+                },
+                true,
+            );
+            assert_eq!(todos.get().as_deref(), Some(&["a", "b"][..]));
+            assert!(outcome.get().is_none());
+            run(&tasks);
+            assert_eq!(todos.get().as_deref(), Some(&["a", "B"][..]));
+            assert_eq!(*outcome.get(), Some(Ok(())));
+
+            let outcome = ctx.mutate_optimistic(
+                &todos,
+                |_| Vec::new(),
+                async {
+                    YieldOnce(false).await;
+                    Err("offline")
+                },
+                true,
+            );
+            assert_eq!(todos.get().as_deref(), Some(&[][..]));
+            run(&tasks);
+            assert_eq!(todos.get().as_deref(), Some(&["a", "B"][..]));
+            assert_eq!(*outcome.get(), Some(Err("offline")));
+        });
+    }
+
+    #[test]
+    fn mutate_optimistic_keeps_newer_values() {
+        create_scope_immediate(|ctx| {
+            let tasks = TaskQueue::default();
+            ctx.provide_context(tasks.clone());
+            let count = create_rc_signal(Some(0));
+
+            let _ = ctx.mutate_optimistic(
+                &count,
+                |count| count + 1,
+                async {
+                    YieldOnce(false).await;
+                    Err::<i32, _>(())
+                },
+                true,
+            );
+            count.set(Some(5));
+            run(&tasks);
+            assert_eq!(*count.get(), Some(5));
+        });
+    }
+}
@@ -1,14 +1,15 @@
 //! Rendering backend for the DOM.
 
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
 use std::fmt;
 use std::hash::{Hash, Hasher};
+use std::rc::Rc;
 
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::{intern, JsCast};
-use web_sys::{Comment, Element, Node, Text};
+use web_sys::{AddEventListenerOptions, Comment, Element, Event, HtmlElement, Node, Text};
 
-use crate::generic_node::{GenericNode, Html};
+use crate::generic_node::{EventOptions, GenericNode, Html};
 use crate::reactive::*;
 use crate::utils::render::insert;
 use crate::view::View;
@@ -79,6 +80,39 @@ impl DomNode {
             node,
         }
     }
+
+    /// Adds `handler` as a listener of the event `name`. The listener is removed when `ctx` is
+    /// disposed.
+    fn add_event_listener<'a>(
+        &self,
+        ctx: ScopeRef<'a>,
+        name: &str,
+        options: Option<&AddEventListenerOptions>,
+        handler: Box<dyn Fn(Event) + 'a>,
+    ) {
+        // SAFETY: extend lifetime because the closure is dropped when the ctx is disposed,
+        // preventing the handler from ever being accessed after its lifetime.
+        let handler: Box<dyn Fn(Event) + 'static> = unsafe { std::mem::transmute(handler) };
+        let closure = Closure::wrap(handler);
+        let callback = closure.as_ref().unchecked_ref();
+        match options {
+            Some(options) => self
+                .node
+                .add_event_listener_with_callback_and_add_event_listener_options(
+                    intern(name),
+                    callback,
+                    options,
+                ),
+            None => self
+                .node
+                .add_event_listener_with_callback(intern(name), callback),
+        }
+        .unwrap_throw();
+
+        ctx.on_cleanup(move || {
+            drop(closure);
+        });
+    }
 }
 
 impl PartialEq for DomNode {
@@ -146,6 +180,18 @@ impl GenericNode for DomNode {
         }
     }
 
+    fn element_ns(tag: &str, namespace: &str) -> Self {
+        let node = document()
+            .create_element_ns(Some(intern(namespace)), intern(tag))
+            .unwrap_throw()
+            .dyn_into()
+            .unwrap_throw();
+        DomNode {
+            id: Default::default(),
+            node,
+        }
+    }
+
     fn text_node(text: &str) -> Self {
         let node = document().create_text_node(text).into();
         DomNode {
@@ -196,6 +242,22 @@ impl GenericNode for DomNode {
             .unwrap_throw();
     }
 
+    fn set_style_property(&self, name: &str, value: &str) {
+        self.node
+            .unchecked_ref::<HtmlElement>()
+            .style()
+            .set_property(intern(name), value)
+            .unwrap_throw();
+    }
+
+    fn remove_style_property(&self, name: &str) {
+        self.node
+            .unchecked_ref::<HtmlElement>()
+            .style()
+            .remove_property(intern(name))
+            .unwrap_throw();
+    }
+
     fn set_property(&self, name: &str, value: &JsValue) {
         assert!(js_sys::Reflect::set(&self.node, &name.into(), value).unwrap_throw());
     }
@@ -255,18 +317,28 @@ impl GenericNode for DomNode {
     }
 
     fn event<'a>(&self, ctx: ScopeRef<'a>, name: &str, handler: Box<dyn Fn(Self::EventType) + 'a>) {
-        // SAFETY: extend lifetime because the closure is dropped when the ctx is disposed,
-        // preventing the handler from ever being accessed after its lifetime.
-        let handler: Box<dyn Fn(Self::EventType) + 'static> =
-            unsafe { std::mem::transmute(handler) };
-        let closure = Closure::wrap(handler);
-        self.node
-            .add_event_listener_with_callback(intern(name), closure.as_ref().unchecked_ref())
-            .unwrap_throw();
-
-        ctx.on_cleanup(move || {
-            drop(closure);
+        self.add_event_listener(ctx, name, None, handler);
+    }
+
+    fn typed_event<'a, E: JsCast + 'static>(
+        &self,
+        ctx: ScopeRef<'a>,
+        name: &str,
+        options: EventOptions,
+        handler: Box<dyn Fn(E) + 'a>,
+    ) {
+        let mut listener_options = AddEventListenerOptions::new();
+        listener_options.once(options.once).passive(options.passive);
+        let handler = Box::new(move |event: Event| {
+            if options.prevent_default {
+                event.prevent_default();
+            }
+            if options.stop_propagation {
+                event.stop_propagation();
+            }
+            handler(event.unchecked_into());
         });
+        self.add_event_listener(ctx, name, Some(&listener_options), handler);
     }
 
     fn update_inner_text(&self, text: &str) {
@@ -293,20 +365,75 @@ impl Html for DomNode {
 /// Alias for [`render_to`] with `parent` being the `<body>` tag.
 ///
 /// _This API requires the following crate features to be activated: `dom`_
-pub fn render(view: impl FnOnce(ScopeRef<'_>) -> View<DomNode>) {
+pub fn render<'a>(view: impl FnOnce(ScopeRef<'_>) -> View<DomNode> + 'a) -> RenderHandle<'a> {
     let window = web_sys::window().unwrap_throw();
     let document = window.document().unwrap_throw();
 
-    render_to(view, &document.body().unwrap_throw());
+    render_to(view, &document.body().unwrap_throw())
 }
 
 /// Render a [`View`] under a `parent` node.
 /// For rendering under the `<body>` tag, use [`render`] instead.
 ///
+/// The returned [`RenderHandle`] can be used to unmount the view. If it is dropped instead, the
+/// view stays mounted forever.
+///
+/// _This API requires the following crate features to be activated: `dom`_
+pub fn render_to<'a>(
+    view: impl FnOnce(ScopeRef<'_>) -> View<DomNode> + 'a,
+    parent: &Node,
+) -> RenderHandle<'a> {
+    let parent = DomNode::from_web_sys(parent.clone());
+    let rendered = Rc::new(RefCell::new(View::empty()));
+    let disposer = create_scope({
+        let parent = parent.clone();
+        let rendered = Rc::clone(&rendered);
+        move |ctx| {
+            let view = view(ctx);
+            *rendered.borrow_mut() = view.clone();
+            insert(ctx, &parent, view, None, None, false);
+        }
+    });
+    RenderHandle {
+        disposer: Box::new(disposer),
+        parent,
+        rendered,
+    }
+}
+
+/// A handle to a [`View`] rendered with [`render`] or [`render_to`].
+///
+/// Call [`unmount`](Self::unmount) to dispose the root scope of the view and remove its nodes from
+/// the DOM. Dropping the handle without calling `unmount` intentionally leaks the root scope,
+/// keeping the view mounted.
+///
 /// _This API requires the following crate features to be activated: `dom`_
-pub fn render_to(view: impl FnOnce(ScopeRef<'_>) -> View<DomNode>, parent: &Node) {
-    // Do not call the destructor function, effectively leaking the scope.
-    let _ = render_get_scope(view, parent);
+pub struct RenderHandle<'a> {
+    disposer: Box<dyn FnOnce() + 'a>,
+    parent: DomNode,
+    rendered: Rc<RefCell<View<DomNode>>>,
+}
+
+impl RenderHandle<'_> {
+    /// Disposes the root scope of the rendered view and removes the nodes of the view from the
+    /// DOM.
+    pub fn unmount(self) {
+        (self.disposer)();
+        let rendered = self.rendered.take();
+        for node in rendered.flatten() {
+            if node.parent_node().as_ref() == Some(&self.parent) {
+                self.parent.remove_child(&node);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for RenderHandle<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RenderHandle")
+            .field("parent", &self.parent)
+            .finish_non_exhaustive()
+    }
 }
 
 /// Render a [`View`] under a `parent` node, in a way that can be cleaned up.
@@ -7,7 +7,7 @@ use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use web_sys::Node;
 
-use crate::generic_node::{DomNode, GenericNode, Html};
+use crate::generic_node::{DomNode, EventOptions, GenericNode, Html};
 use crate::reactive::*;
 use crate::utils::hydrate::web::get_next_element;
 use crate::utils::hydrate::{hydration_completed, with_hydration_context};
@@ -97,6 +97,21 @@ impl GenericNode for HydrateNode {
         }
     }
 
+    /// When hydrating, instead of creating a new node, this will attempt to hydrate an existing
+    /// node.
+    fn element_ns(tag: &str, namespace: &str) -> Self {
+        let el = get_next_element();
+        if let Some(el) = el {
+            Self {
+                node: DomNode::from_web_sys(el.into()),
+            }
+        } else {
+            Self {
+                node: DomNode::element_ns(tag, namespace),
+            }
+        }
+    }
+
     /// When hydrating, instead of creating a new node, this will attempt to hydrate an existing
     /// node.
     fn text_node(text: &str) -> Self {
@@ -145,6 +160,16 @@ impl GenericNode for HydrateNode {
         self.node.remove_class(class);
     }
 
+    #[inline]
+    fn set_style_property(&self, name: &str, value: &str) {
+        self.node.set_style_property(name, value);
+    }
+
+    #[inline]
+    fn remove_style_property(&self, name: &str) {
+        self.node.remove_style_property(name);
+    }
+
     #[inline]
     fn set_property(&self, name: &str, value: &JsValue) {
         self.node.set_property(name, value);
@@ -209,6 +234,17 @@ impl GenericNode for HydrateNode {
         self.node.event(ctx, name, handler);
     }
 
+    #[inline]
+    fn typed_event<'a, E: JsCast + 'static>(
+        &self,
+        ctx: ScopeRef<'a>,
+        name: &str,
+        options: EventOptions,
+        handler: Box<dyn Fn(E) + 'a>,
+    ) {
+        self.node.typed_event(ctx, name, options, handler);
+    }
+
     #[inline]
     fn update_inner_text(&self, text: &str) {
         self.node.update_inner_text(text);
@@ -267,6 +303,9 @@ pub fn hydrate_get_scope<'a>(
     parent: &'a Node,
 ) -> impl FnOnce() + 'a {
     create_scope(|ctx| {
+        // Restore the data transferred from SSR before any signal or effect is created.
+        #[cfg(feature = "serde")]
+        ctx.provide_context(crate::utils::hydrate::data::HydrationData::from_document());
         insert(
             ctx,
             &HydrateNode::from_web_sys(parent.clone()),
@@ -22,6 +22,20 @@ pub use hydrate_dom::*;
 #[cfg(feature = "ssr")]
 pub use ssr_node::*;
 
+/// Options for [`GenericNode::typed_event`]. Set with modifiers of the `on:` directive of the
+/// `view!` macro, e.g. `on:click|prevent_default|once`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EventOptions {
+    /// Call `preventDefault()` on the event before calling the handler.
+    pub prevent_default: bool,
+    /// Call `stopPropagation()` on the event before calling the handler.
+    pub stop_propagation: bool,
+    /// Remove the event listener after it is called once.
+    pub once: bool,
+    /// Tell the browser that the handler does not call `preventDefault()`.
+    pub passive: bool,
+}
+
 /// Abstraction over a rendering backend.
 ///
 /// You would probably use this trait as a trait bound when you want to accept any rendering
@@ -55,6 +69,15 @@ pub trait GenericNode: fmt::Debug + Clone + PartialEq + Eq + Hash + 'static {
     /// Create a new element node.
     fn element(tag: &str) -> Self;
 
+    /// Create a new element node in the namespace `namespace`, e.g. a SVG element. The `view!`
+    /// macro uses this for elements inside of `svg` and `math` elements.
+    ///
+    /// By default, this creates an element without a namespace with [`GenericNode::element`].
+    fn element_ns(tag: &str, namespace: &str) -> Self {
+        let _ = namespace;
+        Self::element(tag)
+    }
+
     /// Create a new text node.
     fn text_node(text: &str) -> Self;
 
@@ -87,6 +110,24 @@ pub trait GenericNode: fmt::Debug + Clone + PartialEq + Eq + Hash + 'static {
 
     fn remove_class(&self, class: &str);
 
+    /// Sets the CSS property `name` of the inline style of a node.
+    ///
+    /// By default, this sets the `style` attribute to only this property, which overwrites the
+    /// other properties of the inline style. Backends that can update a single property should
+    /// override this.
+    fn set_style_property(&self, name: &str, value: &str) {
+        self.set_attribute("style", &format!("{name}: {value};"));
+    }
+
+    /// Removes the CSS property `name` from the inline style of a node.
+    ///
+    /// By default, this removes the `style` attribute, like
+    /// [`set_style_property`](GenericNode::set_style_property) which only keeps one property.
+    fn remove_style_property(&self, name: &str) {
+        let _ = name;
+        self.remove_attribute("style");
+    }
+
     /// Sets a property on a node.
     fn set_property(&self, name: &str, value: &JsValue);
 
@@ -125,6 +166,22 @@ pub trait GenericNode: fmt::Debug + Clone + PartialEq + Eq + Hash + 'static {
     /// Add a event handler to the event `name`.
     fn event<'a>(&self, ctx: ScopeRef<'a>, name: &str, handler: Box<dyn Fn(Self::EventType) + 'a>);
 
+    /// Add a event handler to the event `name` that receives the event as `E`, e.g. a
+    /// [`MouseEvent`](web_sys::MouseEvent) for a `click` event. This is used by the `on:`
+    /// directive of the `view!` macro.
+    ///
+    /// By default, the handler is not attached, which is right for backends that never dispatch
+    /// events, such as [`SsrNode`]. Backends that dispatch events must override this.
+    fn typed_event<'a, E: JsCast + 'static>(
+        &self,
+        ctx: ScopeRef<'a>,
+        name: &str,
+        options: EventOptions,
+        handler: Box<dyn Fn(E) + 'a>,
+    ) {
+        let _ = (ctx, name, options, handler);
+    }
+
     /// Update inner text of the node. If the node has elements, all the elements are replaced with
     /// a new text node.
     fn update_inner_text(&self, text: &str);
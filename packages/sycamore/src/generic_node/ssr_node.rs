@@ -3,15 +3,20 @@
 use std::cell::RefCell;
 use std::collections::HashSet;
 use std::hash::{Hash, Hasher};
-use std::iter::FromIterator;
 use std::rc::{Rc, Weak};
 
 use indexmap::map::IndexMap;
 use once_cell::sync::Lazy;
 use wasm_bindgen::prelude::*;
 
+#[cfg(feature = "futures")]
+use crate::futures::TaskQueue;
 use crate::generic_node::{GenericNode, Html};
 use crate::reactive::*;
+#[cfg(feature = "futures")]
+use crate::suspense::StreamingBoundaries;
+#[cfg(feature = "serde")]
+use crate::utils::hydrate::data::HydrationData;
 use crate::utils::hydrate::{get_next_id, with_hydration_context};
 use crate::view::View;
 
@@ -119,6 +124,54 @@ impl SsrNode {
     }
 }
 
+/// Splits the value of a `style` attribute into its properties. Semicolons inside of quotes or
+/// parentheses, e.g. in `url("a;b")`, do not end a property.
+fn style_properties(style: Option<&String>) -> Vec<(&str, &str)> {
+    let Some(style) = style else {
+        return Vec::new();
+    };
+    let mut declarations = Vec::new();
+    let mut start = 0;
+    let mut quote = None;
+    let mut depth = 0usize;
+    let mut escaped = false;
+    for (i, c) in style.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match (c, quote) {
+            ('\\', _) => escaped = true,
+            (c, Some(q)) if c == q => quote = None,
+            (_, Some(_)) => {}
+            ('"' | '\'', None) => quote = Some(c),
+            ('(', None) => depth += 1,
+            (')', None) => depth = depth.saturating_sub(1),
+            (';', None) if depth == 0 => {
+                declarations.push(&style[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    declarations.push(&style[start..]);
+    declarations
+        .into_iter()
+        .filter_map(|property| property.split_once(':'))
+        .map(|(name, value)| (name.trim(), value.trim()))
+        .collect()
+}
+
+/// Joins properties that were split with [`style_properties`] into the value of a `style`
+/// attribute.
+fn join_style_properties(properties: &[(&str, &str)]) -> String {
+    properties
+        .iter()
+        .map(|(name, value)| format!("{name}: {value};"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 impl GenericNode for SsrNode {
     /// Although [`SsrNode`] is intended to be used on the server-side instead of in the browser,
     /// the event type is still [`web_sys::Event`] because it must support the same API as
@@ -171,12 +224,13 @@ impl GenericNode for SsrNode {
         let classes = attributes.get_mut("class");
 
         if let Some(classes) = classes {
-            // Make sure classes are unique.
-            let mut class_set = HashSet::<_>::from_iter(classes.split(' '));
-
-            class_set.insert(class);
-
-            *classes = class_set.drain().collect::<Vec<_>>().join(" ");
+            // Make sure classes are unique. The order is kept so that the output is deterministic.
+            if !classes.split(' ').any(|c| c == class) {
+                if !classes.is_empty() {
+                    classes.push(' ');
+                }
+                classes.push_str(class);
+            }
         } else {
             attributes.insert("class".to_string(), class.to_owned());
         }
@@ -188,12 +242,34 @@ impl GenericNode for SsrNode {
         let classes = attributes.get_mut("class");
 
         if let Some(classes) = classes {
-            // Make sure classes are unique.
-            let mut class_set = HashSet::<_>::from_iter(classes.split(' '));
+            *classes = classes
+                .split(' ')
+                .filter(|c| *c != class)
+                .collect::<Vec<_>>()
+                .join(" ");
+        }
+    }
 
-            class_set.remove(class);
+    fn set_style_property(&self, name: &str, value: &str) {
+        let mut element = self.unwrap_element().borrow_mut();
+        let mut properties = style_properties(element.attributes.get("style"));
+        match properties.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, v)) => *v = value,
+            None => properties.push((name, value)),
+        }
+        let style = join_style_properties(&properties);
+        element.attributes.insert("style".to_string(), style);
+    }
 
-            *classes = class_set.drain().collect::<Vec<_>>().join(" ");
+    fn remove_style_property(&self, name: &str) {
+        let mut element = self.unwrap_element().borrow_mut();
+        let mut properties = style_properties(element.attributes.get("style"));
+        properties.retain(|(n, _)| *n != name);
+        let style = join_style_properties(&properties);
+        if style.is_empty() {
+            element.attributes.remove("style");
+        } else {
+            element.attributes.insert("style".to_string(), style);
         }
     }
 
@@ -438,18 +514,178 @@ impl WriteToString for RawText {
 /// Render a [`View`] into a static [`String`]. Useful
 /// for rendering to a string on the server side.
 ///
+/// For views that are only rendered once, see [`render_to_string_static`].
+///
 /// _This API requires the following crate features to be activated: `ssr`_
 pub fn render_to_string(view: impl FnOnce(ScopeRef<'_>) -> View<SsrNode>) -> String {
     let mut ret = String::new();
-    create_scope_immediate(|ctx| {
-        let v = with_hydration_context(|| view(ctx));
+    create_scope_immediate(|ctx| render_view(ctx, view, &mut ret));
+
+    ret
+}
+
+/// Render a [`View`] into a static [`String`], like [`render_to_string`], in a scope created with
+/// [`create_ssr_scope`]. Effects are only run once and do not track the signals that they use, so
+/// updating a signal from the view afterwards, e.g. from a spawned task, does not update anything.
+///
+/// _This API requires the following crate features to be activated: `ssr`_
+pub fn render_to_string_static(view: impl FnOnce(ScopeRef<'_>) -> View<SsrNode>) -> String {
+    let mut ret = String::new();
+    let disposer = create_ssr_scope(|ctx| render_view(ctx, view, &mut ret));
+    disposer();
+
+    ret
+}
+
+/// Renders `view` in `ctx` and appends the HTML to `ret`.
+fn render_view<'a>(
+    ctx: ScopeRef<'a>,
+    view: impl FnOnce(ScopeRef<'a>) -> View<SsrNode>,
+    ret: &mut String,
+) {
+    #[cfg(feature = "serde")]
+    let data = HydrationData::collect();
+    #[cfg(feature = "serde")]
+    ctx.provide_context(data.clone());
+    let v = with_hydration_context(|| view(ctx));
+
+    for node in v.flatten() {
+        node.write_to_string(ret);
+    }
+    #[cfg(feature = "serde")]
+    data.write_to_string(ret);
+}
+
+/// Script that replaces the fallback of a suspense boundary with its content once the content has
+/// been streamed.
+#[cfg(feature = "futures")]
+const SWAP_SUSPENSE_SCRIPT: &str = "(function(i){\
+var t=document.getElementById(\"sycamore-suspense-content-\"+i);\
+document.getElementById(\"sycamore-suspense-\"+i).replaceWith(t.content);\
+t.remove()})";
 
-        for node in v.flatten() {
-            node.write_to_string(&mut ret);
+/// Render a [`View`] into a stream of HTML chunks. Useful for sending a page to the client before
+/// all its data has loaded.
+///
+/// The first chunk is the shell, where every [`Suspense`](crate::suspense::Suspense) that is
+/// still pending displays its fallback. Each following chunk contains the content of a suspense
+/// boundary that has resolved, along with an inline script that swaps it with the fallback. With
+/// the `serde` feature, the last chunk contains the data that is transferred to the client.
+/// Resources created with [`create_resource`](crate::futures::ScopeFuturesExt::create_resource)
+/// are polled by the stream itself, so any executor can be used to drive the stream.
+///
+/// _This API requires the following crate features to be activated: `ssr`, `futures`_
+#[cfg(feature = "futures")]
+pub fn render_to_string_stream(
+    view: impl FnOnce(ScopeRef<'_>) -> View<SsrNode> + 'static,
+) -> impl futures_core::Stream<Item = String> {
+    let tasks = TaskQueue::default();
+    let boundaries = StreamingBoundaries::<SsrNode>::default();
+    #[cfg(feature = "serde")]
+    let data = HydrationData::collect();
+    let shell = Rc::new(RefCell::new(String::new()));
+
+    let disposer = create_scope({
+        let tasks = tasks.clone();
+        let boundaries = boundaries.clone();
+        #[cfg(feature = "serde")]
+        let data = data.clone();
+        let shell = Rc::clone(&shell);
+        move |ctx| {
+            ctx.provide_context(tasks);
+            ctx.provide_context(boundaries);
+            #[cfg(feature = "serde")]
+            ctx.provide_context(data);
+            let v = with_hydration_context(|| view(ctx));
+
+            for node in v.flatten() {
+                node.write_to_string(&mut shell.borrow_mut());
+            }
         }
     });
 
-    ret
+    RenderStream {
+        shell: Some(shell.take()),
+        tasks,
+        boundaries,
+        #[cfg(feature = "serde")]
+        data: Some(data),
+        disposer: Some(Box::new(disposer)),
+    }
+}
+
+/// Stream returned by [`render_to_string_stream`].
+#[cfg(feature = "futures")]
+struct RenderStream {
+    shell: Option<String>,
+    tasks: TaskQueue,
+    boundaries: StreamingBoundaries<SsrNode>,
+    /// The data transferred to the client, sent in the last chunk once all the boundaries have
+    /// resolved.
+    #[cfg(feature = "serde")]
+    data: Option<HydrationData>,
+    /// Disposes the scope of the rendered view once the stream is complete or dropped.
+    disposer: Option<Box<dyn FnOnce()>>,
+}
+
+#[cfg(feature = "futures")]
+impl futures_core::Stream for RenderStream {
+    type Item = String;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<String>> {
+        use std::task::Poll;
+
+        if let Some(shell) = self.shell.take() {
+            return Poll::Ready(Some(shell));
+        }
+
+        self.tasks.poll(cx);
+        if let Some(boundary) = self.boundaries.take_resolved() {
+            let mut chunk = format!(
+                "<template id=\"sycamore-suspense-content-{}\">",
+                boundary.id
+            );
+            for node in boundary.view.flatten() {
+                node.write_to_string(&mut chunk);
+            }
+            chunk.push_str("</template><script>");
+            chunk.push_str(SWAP_SUSPENSE_SCRIPT);
+            chunk.push_str(&format!("({})</script>", boundary.id));
+            return Poll::Ready(Some(chunk));
+        }
+
+        if self.boundaries.is_empty() {
+            #[cfg(feature = "serde")]
+            if let Some(data) = self.data.take() {
+                let mut chunk = String::new();
+                data.write_to_string(&mut chunk);
+                if !chunk.is_empty() {
+                    return Poll::Ready(Some(chunk));
+                }
+            }
+            if let Some(disposer) = self.disposer.take() {
+                disposer();
+            }
+            return Poll::Ready(None);
+        }
+        // Boundaries can also be suspended by work that is not driven by the stream.
+        for state in self.boundaries.pending_states() {
+            let _ = state.poll_resolved(cx);
+        }
+        Poll::Pending
+    }
+}
+
+#[cfg(feature = "futures")]
+impl Drop for RenderStream {
+    fn drop(&mut self) {
+        if let Some(disposer) = self.disposer.take() {
+            disposer();
+        }
+    }
 }
 
 #[cfg(test)]
@@ -467,6 +703,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn style_properties_ignore_semicolons_in_quotes_and_parentheses() {
+        let style = r#"background: url("a;b.png"); content: ';'; color: red"#.to_string();
+        assert_eq!(
+            style_properties(Some(&style)),
+            [
+                ("background", r#"url("a;b.png")"#),
+                ("content", "';'"),
+                ("color", "red")
+            ]
+        );
+    }
+
     #[test]
     fn render_escaped_text() {
         assert_eq!(
@@ -547,4 +796,137 @@ mod tests {
         // node.first_child should be p2
         assert_eq!(node.first_child().as_ref(), Some(&p2));
     }
+
+    /// Collects the chunks of a stream, polling it with a waker that does nothing.
+    #[cfg(feature = "futures")]
+    fn collect_stream(stream: impl futures_core::Stream<Item = String>) -> Vec<String> {
+        use std::task::{Context, Poll, Waker};
+
+        let mut stream = Box::pin(stream);
+        let mut cx = Context::from_waker(Waker::noop());
+        let mut chunks = Vec::new();
+        loop {
+            match stream.as_mut().poll_next(&mut cx) {
+                Poll::Ready(Some(chunk)) => chunks.push(chunk),
+                Poll::Ready(None) => return chunks,
+                Poll::Pending => {}
+            }
+        }
+    }
+
+    /// A future that is pending the first `n` times it is polled.
+    #[cfg(feature = "futures")]
+    struct Delay(usize);
+
+    #[cfg(feature = "futures")]
+    impl std::future::Future for Delay {
+        type Output = ();
+
+        fn poll(
+            mut self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<()> {
+            if self.0 == 0 {
+                std::task::Poll::Ready(())
+            } else {
+                self.0 -= 1;
+                std::task::Poll::Pending
+            }
+        }
+    }
+
+    #[cfg(feature = "futures")]
+    #[test]
+    fn render_to_string_stream_without_suspense() {
+        let chunks = collect_stream(render_to_string_stream(|ctx| view! { ctx, p { "Hello" } }));
+        assert_eq!(chunks, ["<p data-hk=\"0.0\">Hello</p>"]);
+    }
+
+    #[cfg(feature = "futures")]
+    #[test]
+    fn render_to_string_stream_with_suspense() {
+        use crate::futures::ScopeFuturesExt;
+
+        let chunks = collect_stream(render_to_string_stream(|ctx| {
+            view! { ctx,
+                Suspense {
+                    fallback: view! { ctx, "Loading..." },
+                    view: |ctx| {
+                        let data = ctx.create_resource(async {
+                            Delay(2).await;
+                            "Loaded"
+                        });
+                        view! { ctx, p { (data.get().unwrap_or_default()) } }
+                    },
+                }
+            }
+        }));
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[0].contains("Loading..."));
+        assert!(chunks[0].contains("id=\"sycamore-suspense-0\""));
+        assert!(!chunks[0].contains("Loaded"));
+        assert!(chunks[1].starts_with("<template id=\"sycamore-suspense-content-0\">"));
+        assert!(chunks[1].contains("Loaded"));
+        assert!(chunks[1].ends_with("(0)</script>"));
+    }
+
+    #[cfg(feature = "futures")]
+    #[test]
+    fn render_to_string_stream_nested_suspense() {
+        use crate::futures::ScopeFuturesExt;
+
+        let chunks = collect_stream(render_to_string_stream(|ctx| {
+            view! { ctx,
+                Suspense {
+                    fallback: view! { ctx, "Loading outer" },
+                    view: |ctx| {
+                        let ctx = *ctx;
+                        let outer = ctx.create_resource(Delay(3));
+                        let inner = view! { ctx,
+                            Suspense {
+                                fallback: view! { ctx, "Loading inner" },
+                                view: |ctx| {
+                                    let inner = ctx.create_resource(Delay(1));
+                                    view! { ctx, (format!("inner {:?}", inner.get())) }
+                                },
+                            }
+                        };
+                        view! { ctx, (format!("outer {:?}", outer.get())) (inner) }
+                    },
+                }
+            }
+        }));
+        // The inner boundary resolves first but is only sent after the outer boundary.
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks[1].contains("sycamore-suspense-content-0"));
+        assert!(chunks[1].contains("outer Some(())"));
+        assert!(chunks[1].contains("Loading inner"));
+        assert!(chunks[2].contains("sycamore-suspense-content-1"));
+        assert!(chunks[2].contains("inner Some(())"));
+    }
+
+    #[cfg(all(feature = "futures", feature = "serde"))]
+    #[test]
+    fn render_to_string_stream_sends_hydration_data_last() {
+        use crate::futures::ScopeFuturesExt;
+
+        let chunks = collect_stream(render_to_string_stream(|ctx| {
+            view! { ctx,
+                Suspense {
+                    fallback: view! { ctx, "Loading..." },
+                    view: |ctx| {
+                        let data = ctx.create_hydrated_resource(async {
+                            Delay(1).await;
+                            42
+                        });
+                        view! { ctx, (format!("{:?}", data.get())) }
+                    },
+                }
+            }
+        }));
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks[1].contains("Some(42)"));
+        assert!(chunks[2].starts_with("<script type=\"application/json\""));
+        assert!(chunks[2].contains("42"));
+    }
 }
@@ -13,11 +13,13 @@
 //!   `experimental-builder-agnostic`.
 //! - `experimental-hydrate` - Enables client-side hydration support.
 //! - `futures` - Enables wrappers around `wasm-bindgen-futures` to make it easier to extend a
-//!   reactive scope into an `async` function.
+//!   reactive scope into an `async` function. Together with `ssr`, also enables streaming SSR with
+//!   `render_to_string_stream`.
 //! - `ssr` - Enables rendering templates to static strings (useful for Server Side Rendering /
 //!   Pre-rendering).
 //! - `serde` - Enables serializing and deserializing `Signal`s and other wrapper types using
-//!   `serde`.
+//!   `serde`. Together with `experimental-hydrate`, also enables transferring signals from SSR to
+//!   the client with `create_hydrated_signal`.
 //! - `wasm-bindgen-interning` (_default_) - Enables interning for `wasm-bindgen` strings. This
 //!   improves performance at a slight cost in binary size. If you want to minimize the size of the
 //!   result `.wasm` binary, you might want to disable this.
@@ -36,12 +38,14 @@ extern crate self as sycamore;
 #[cfg(feature = "experimental-builder-agnostic")]
 pub mod builder;
 pub mod component;
+pub mod easing;
 pub mod flow;
 #[cfg(feature = "futures")]
 pub mod futures;
 pub mod generic_node;
 pub mod motion;
 pub mod noderef;
+pub mod suspense;
 pub mod utils;
 pub mod view;
 
@@ -49,12 +53,14 @@ pub mod reactive {
     pub use sycamore_reactive::*;
 }
 
+#[cfg(all(feature = "ssr", feature = "futures"))]
+pub use crate::generic_node::render_to_string_stream;
 #[cfg(feature = "ssr")]
-pub use crate::generic_node::render_to_string;
+pub use crate::generic_node::{render_to_string, render_to_string_static};
 #[cfg(all(feature = "dom", feature = "experimental-hydrate"))]
 pub use generic_node::{hydrate, hydrate_get_scope, hydrate_to};
 #[cfg(feature = "dom")]
-pub use generic_node::{render, render_get_scope, render_to};
+pub use generic_node::{render, render_get_scope, render_to, RenderHandle};
 
 pub use sycamore_macro::*;
 
@@ -67,10 +73,15 @@ pub mod prelude {
     #[cfg(feature = "ssr")]
     pub use crate::generic_node::SsrNode;
 
+    pub use crate::component::Children;
     pub use crate::flow::*;
     pub use crate::generic_node::{GenericNode, Html};
+    pub use crate::motion::{ScopeCreateRaf, ScopeCreateTweened};
     pub use crate::noderef::{NodeRef, ScopeCreateNodeRef};
     pub use crate::reactive::*;
+    pub use crate::suspense::{Suspense, SuspenseProps};
+    #[cfg(all(feature = "serde", feature = "experimental-hydrate"))]
+    pub use crate::utils::hydrate::data::ScopeCreateHydratedSignal;
     pub use crate::view::View;
 
     pub use sycamore_macro::*;
@@ -81,5 +92,8 @@ pub mod prelude {
 pub mod rt {
     pub use js_sys::Reflect;
     pub use wasm_bindgen::{intern, JsCast, JsValue};
-    pub use web_sys::Event;
+    pub use web_sys::{
+        DragEvent, Event, FocusEvent, InputEvent, KeyboardEvent, MouseEvent, PointerEvent,
+        TouchEvent, WheelEvent,
+    };
 }
@@ -1,57 +1,463 @@
-// TODO: implement create_raf
-
-// //! Utilities for smooth transitions and animations.
-
-// use std::cell::RefCell;
-// use std::rc::Rc;
-
-// use wasm_bindgen::{prelude::*, JsCast};
-
-// use crate::reactive::*;
-
-// pub trait ScopeCreateRaf<'a> {
-//     fn create_raf(
-//         &'a self,
-//         f: impl FnMut() + 'a,
-//     ) -> (&'a ReadSignal<bool>, &'a dyn Fn(), &'a dyn Fn());
-// }
-
-// impl<'a> ScopeCreateRaf<'a> for Scope<'a> {
-//     fn create_raf(
-//         &'a self,
-//         f: impl FnMut() + 'a,
-//     ) -> (&'a ReadSignal<bool>, &'a dyn Fn(), &'a dyn Fn()) {
-//         let running = self.create_signal(true);
-//         let start = self.create_ref(|| running.set(true));
-//         let stop = self.create_ref(|| running.set(false));
-
-//         if cfg!(target_arch = "wasm32") {
-//             // Only run on wasm32 architecture.
-//             let boxed: Box<dyn FnMut() + 'a> = Box::new(f);
-//             // SAFETY: We are only transmuting the lifetime from 'a to 'static which is safe
-// because             // the closure will not be accessed once the enclosing Scope is disposed.
-//             let extended: Box<dyn FnMut() + 'static> = unsafe { std::mem::transmute(boxed) };
-//             let extended = RefCell::new(extended);
-//             let scope_status = self.use_scope_status();
-
-//             let f = Rc::new(RefCell::new(None::<Closure<dyn Fn()>>));
-//             let g = Rc::clone(&f);
-
-//             *g.borrow_mut() = Some(Closure::wrap(Box::new(move || {
-//                 if *scope_status.get() {
-//                     // Verified that scope is still valid. We can access `extended` in here.
-//                     extended.borrow_mut()();
-//                     // Request the next raf frame.
-//                     web_sys::window()
-//                         .unwrap_throw()
-//                         .request_animation_frame(
-//                             f.borrow().as_ref().unwrap_throw().as_ref().unchecked_ref(),
-//                         )
-//                         .unwrap_throw();
-//                 }
-//             })));
-//         }
-
-//         (running, start, stop)
-//     }
-// }
+//! Utilities for smooth transitions and animations.
+
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::ops::Deref;
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::reactive::*;
+
+type FrameCallback = Rc<dyn Fn(f64)>;
+
+/// The callbacks that are run on every animation frame, shared by all the running
+/// [`create_raf`](ScopeCreateRaf::create_raf) loops on the current thread.
+#[derive(Default)]
+struct AnimationFrame {
+    next_id: Cell<usize>,
+    callbacks: RefCell<BTreeMap<usize, FrameCallback>>,
+    /// Whether a call to `requestAnimationFrame` is pending.
+    requested: Cell<bool>,
+    /// Whether the tests run the frames with `run_frame` instead of the browser.
+    #[cfg(test)]
+    simulated: Cell<bool>,
+}
+
+thread_local! {
+    static ANIMATION_FRAME: AnimationFrame = Default::default();
+}
+
+/// Runs `f` with the timestamp of every animation frame until [`remove_frame_callback`] is called
+/// with the returned id.
+fn add_frame_callback(f: FrameCallback) -> usize {
+    let id = ANIMATION_FRAME.with(|frame| {
+        let id = frame.next_id.get();
+        frame.next_id.set(id + 1);
+        frame.callbacks.borrow_mut().insert(id, f);
+        id
+    });
+    request_frame();
+    id
+}
+
+fn remove_frame_callback(id: usize) {
+    ANIMATION_FRAME.with(|frame| frame.callbacks.borrow_mut().remove(&id));
+}
+
+/// Returns `true` if animation frames are available, i.e. on `wasm32` in a browser window.
+/// Otherwise, no frames are ever run and animations complete immediately.
+fn has_animation_frames() -> bool {
+    #[cfg(test)]
+    if ANIMATION_FRAME.with(|frame| frame.simulated.get()) {
+        return true;
+    }
+    #[cfg(target_arch = "wasm32")]
+    return web_sys::window().is_some();
+    #[cfg(not(target_arch = "wasm32"))]
+    false
+}
+
+/// Requests an animation frame if none is pending. Animation frames are only available on
+/// `wasm32`.
+fn request_frame() {
+    let requested = ANIMATION_FRAME.with(|frame| frame.requested.replace(true));
+    #[cfg(target_arch = "wasm32")]
+    if !requested {
+        use wasm_bindgen::prelude::*;
+        use wasm_bindgen::JsCast;
+
+        thread_local! {
+            static RUN_FRAME: Closure<dyn Fn(f64)> =
+                Closure::wrap(Box::new(run_frame) as Box<dyn Fn(f64)>);
+        }
+
+        RUN_FRAME.with(|run_frame| {
+            web_sys::window()
+                .unwrap_throw()
+                .request_animation_frame(run_frame.as_ref().unchecked_ref())
+                .unwrap_throw();
+        });
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    let _ = requested;
+}
+
+/// Runs all the frame callbacks with `timestamp`. The callbacks are batched so that memos and
+/// effects that depend on several animations (e.g. tweens) only run once per frame.
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+fn run_frame(timestamp: f64) {
+    let callbacks: Vec<_> = ANIMATION_FRAME.with(|frame| {
+        frame.requested.set(false);
+        frame.callbacks.borrow().values().cloned().collect()
+    });
+    batch(|| {
+        for callback in callbacks {
+            callback(timestamp);
+        }
+    });
+    if ANIMATION_FRAME.with(|frame| !frame.callbacks.borrow().is_empty()) {
+        request_frame();
+    }
+}
+
+/// Extension trait for [`Scope`] adding the [`create_raf`](ScopeCreateRaf::create_raf) method.
+pub trait ScopeCreateRaf<'a> {
+    /// Creates a [`ReadSignal`] that is updated with the timestamp of every animation frame (see
+    /// `requestAnimationFrame`) while it is running. Also returns functions to start and stop the
+    /// loop. The loop is initially stopped and is stopped automatically when the [`Scope`] is
+    /// disposed.
+    ///
+    /// All the running loops are updated in a single [`batch`] on every frame, so memos and
+    /// effects that depend on several loops only run once per frame.
+    ///
+    /// Animation frames are only available on `wasm32`. On other targets, the signal is never
+    /// updated.
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore::prelude::*;
+    /// # create_scope_immediate(|ctx| {
+    /// let (timestamp, start, stop) = ctx.create_raf();
+    /// ctx.create_effect(|| println!("frame at {}ms", timestamp.get()));
+    /// start();
+    /// // ...
+    /// stop();
+    /// # });
+    /// ```
+    fn create_raf(&'a self) -> (&'a ReadSignal<f64>, &'a dyn Fn(), &'a dyn Fn());
+}
+
+impl<'a> ScopeCreateRaf<'a> for Scope<'a> {
+    fn create_raf(&'a self) -> (&'a ReadSignal<f64>, &'a dyn Fn(), &'a dyn Fn()) {
+        let timestamp: &'a RcSignal<f64> = self.create_ref(create_rc_signal(0.0));
+        let id = self.create_ref(Cell::new(None));
+
+        let start: &'a dyn Fn() = self.create_ref(move || {
+            if id.get().is_none() {
+                let timestamp = timestamp.clone();
+                id.set(Some(add_frame_callback(Rc::new(move |t| timestamp.set(t)))));
+            }
+        });
+        let stop: &'a dyn Fn() = self.create_ref(move || {
+            if let Some(id) = id.take() {
+                remove_frame_callback(id);
+            }
+        });
+        self.on_cleanup(stop);
+
+        (timestamp, start, stop)
+    }
+}
+
+/// Types that can be interpolated, e.g. to animate them with
+/// [`create_tweened_signal`](ScopeCreateTweened::create_tweened_signal).
+///
+/// Implemented for floats and integers, and element-wise for arrays, `Vec`s and tuples. This covers
+/// vectors (e.g. `[f64; 2]`) and colors (e.g. `[u8; 4]` for RGBA).
+pub trait Lerp {
+    /// Interpolates between `self` (at `scalar == 0.0`) and `other` (at `scalar == 1.0`).
+    fn lerp(&self, other: &Self, scalar: f64) -> Self;
+}
+
+macro_rules! impl_lerp_for_float {
+    ($($f:ty),*) => {
+        $(
+            impl Lerp for $f {
+                fn lerp(&self, other: &Self, scalar: f64) -> Self {
+                    self + (other - self) * scalar as $f
+                }
+            }
+        )*
+    };
+}
+
+impl_lerp_for_float!(f32, f64);
+
+macro_rules! impl_lerp_for_int {
+    ($($i:ty),*) => {
+        $(
+            impl Lerp for $i {
+                fn lerp(&self, other: &Self, scalar: f64) -> Self {
+                    (*self as f64).lerp(&(*other as f64), scalar).round() as $i
+                }
+            }
+        )*
+    };
+}
+
+impl_lerp_for_int!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+impl<T: Lerp, const N: usize> Lerp for [T; N] {
+    fn lerp(&self, other: &Self, scalar: f64) -> Self {
+        std::array::from_fn(|i| self[i].lerp(&other[i], scalar))
+    }
+}
+
+impl<T: Lerp + Clone> Lerp for Vec<T> {
+    /// Interpolates element-wise. If the lengths differ, the extra elements of `other` are used
+    /// as is.
+    fn lerp(&self, other: &Self, scalar: f64) -> Self {
+        other
+            .iter()
+            .enumerate()
+            .map(|(i, b)| match self.get(i) {
+                Some(a) => a.lerp(b, scalar),
+                None => b.clone(),
+            })
+            .collect()
+    }
+}
+
+macro_rules! impl_lerp_for_tuple {
+    ($($T:ident $i:tt),*) => {
+        impl<$($T: Lerp),*> Lerp for ($($T,)*) {
+            fn lerp(&self, other: &Self, scalar: f64) -> Self {
+                ($(self.$i.lerp(&other.$i, scalar),)*)
+            }
+        }
+    };
+}
+
+impl_lerp_for_tuple!(A 0, B 1);
+impl_lerp_for_tuple!(A 0, B 1, C 2);
+impl_lerp_for_tuple!(A 0, B 1, C 2, D 3);
+
+/// A running animation of a [`Tweened`] signal.
+struct Tween<T> {
+    from: T,
+    to: T,
+    /// The timestamp of the first frame of the animation.
+    start: Option<f64>,
+}
+
+/// A signal whose value is animated towards new values. Created with
+/// [`create_tweened_signal`](ScopeCreateTweened::create_tweened_signal).
+///
+/// Dereferences to a [`ReadSignal`] of the current animated value.
+pub struct Tweened<'a, T> {
+    value: &'a Signal<T>,
+    tween: RefCell<Option<Tween<T>>>,
+    tweening: &'a Signal<bool>,
+    start: &'a dyn Fn(),
+    stop: &'a dyn Fn(),
+}
+
+impl<'a, T: Lerp + Clone + 'a> Tweened<'a, T> {
+    /// Animates the value from its current value to `target`. If the value is already being
+    /// animated, the animation is restarted from the current animated value.
+    ///
+    /// Without animation frames (e.g. on the server), the value is set to `target` immediately.
+    pub fn set(&self, target: T) {
+        if !has_animation_frames() {
+            self.set_immediate(target);
+            return;
+        }
+        *self.tween.borrow_mut() = Some(Tween {
+            from: (*self.value.get_untracked()).clone(),
+            to: target,
+            start: None,
+        });
+        self.tweening.set(true);
+        (self.start)();
+    }
+
+    /// Sets the value to `value` immediately, stopping the animation if there is one.
+    pub fn set_immediate(&self, value: T) {
+        self.tween.take();
+        (self.stop)();
+        self.tweening.set(false);
+        self.value.set(value);
+    }
+
+    /// Returns `true` while the value is being animated. This method is reactive.
+    pub fn is_tweening(&self) -> bool {
+        *self.tweening.get()
+    }
+}
+
+impl<'a, T> Deref for Tweened<'a, T> {
+    type Target = ReadSignal<T>;
+
+    fn deref(&self) -> &Self::Target {
+        self.value
+    }
+}
+
+/// Extension trait for [`Scope`] adding the
+/// [`create_tweened_signal`](ScopeCreateTweened::create_tweened_signal) method.
+pub trait ScopeCreateTweened<'a> {
+    /// Creates a signal whose value is animated over `duration` every time it is
+    /// [set](Tweened::set), using the [`easing`](crate::easing) function to shape the animation.
+    /// The value is updated once per animation frame (see
+    /// [`create_raf`](ScopeCreateRaf::create_raf)). Where there are no animation frames (i.e. when
+    /// not running in a browser), the value jumps to the target immediately instead.
+    ///
+    /// # Example
+    /// ```
+    /// # use std::time::Duration;
+    /// # use sycamore::prelude::*;
+    /// # use sycamore::easing;
+    /// # create_scope_immediate(|ctx| {
+    /// let progress = ctx.create_tweened_signal(0.0, Duration::from_millis(250), easing::quad_out);
+    /// progress.set(100.0); // Animates from 0.0 to 100.0 over 250ms.
+    /// let width = ctx.create_memo(|| format!("{}%", progress.get()));
+    /// # });
+    /// ```
+    fn create_tweened_signal<T: Lerp + Clone + 'a>(
+        &'a self,
+        initial: T,
+        duration: Duration,
+        easing: impl Fn(f64) -> f64 + 'a,
+    ) -> &'a Tweened<'a, T>;
+}
+
+impl<'a> ScopeCreateTweened<'a> for Scope<'a> {
+    fn create_tweened_signal<T: Lerp + Clone + 'a>(
+        &'a self,
+        initial: T,
+        duration: Duration,
+        easing: impl Fn(f64) -> f64 + 'a,
+    ) -> &'a Tweened<'a, T> {
+        let duration = duration.as_secs_f64() * 1000.0;
+        let (timestamp, start, stop) = self.create_raf();
+        let tweened = self.create_ref(Tweened {
+            value: self.create_signal(initial),
+            tween: RefCell::new(None),
+            tweening: self.create_signal(false),
+            start,
+            stop,
+        });
+
+        self.create_effect(move || {
+            let now = *timestamp.get();
+            let mut tween = tweened.tween.borrow_mut();
+            let next = match &mut *tween {
+                Some(Tween { from, to, start }) => {
+                    let start = *start.get_or_insert(now);
+                    let progress = if duration > 0.0 {
+                        ((now - start) / duration).min(1.0)
+                    } else {
+                        1.0
+                    };
+                    if progress < 1.0 {
+                        from.lerp(to, easing(progress))
+                    } else {
+                        let to = to.clone();
+                        *tween = None;
+                        stop();
+                        tweened.tweening.set(false);
+                        to
+                    }
+                }
+                None => return,
+            };
+            drop(tween);
+            tweened.value.set(next);
+        });
+
+        tweened
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs the frames with `run_frame` in the tests on the current thread.
+    fn simulate_frames() {
+        ANIMATION_FRAME.with(|frame| frame.simulated.set(true));
+    }
+
+    #[test]
+    fn raf_updates_while_running() {
+        create_scope_immediate(|ctx| {
+            let (timestamp, start, stop) = ctx.create_raf();
+            run_frame(1.0);
+            assert_eq!(*timestamp.get(), 0.0);
+
+            start();
+            start();
+            run_frame(2.0);
+            assert_eq!(*timestamp.get(), 2.0);
+
+            stop();
+            run_frame(3.0);
+            assert_eq!(*timestamp.get(), 2.0);
+        });
+    }
+
+    #[test]
+    fn raf_loops_are_batched_per_frame() {
+        create_scope_immediate(|ctx| {
+            let (a, start_a, _) = ctx.create_raf();
+            let (b, start_b, _) = ctx.create_raf();
+            let runs = ctx.create_signal(0);
+            ctx.create_effect(|| {
+                a.track();
+                b.track();
+                runs.set(*runs.get_untracked() + 1);
+            });
+            start_a();
+            start_b();
+            run_frame(1.0);
+            assert_eq!(*runs.get(), 2);
+        });
+    }
+
+    #[test]
+    fn raf_stops_on_dispose() {
+        create_scope_immediate(|ctx| {
+            let (_, start, _) = ctx.create_raf();
+            start();
+        });
+        assert!(ANIMATION_FRAME.with(|frame| frame.callbacks.borrow().is_empty()));
+    }
+
+    #[test]
+    fn lerp() {
+        assert_eq!(0.0.lerp(&10.0, 0.25), 2.5);
+        assert_eq!(0u8.lerp(&255, 0.5), 128);
+        assert_eq!([0.0, 10.0].lerp(&[10.0, 0.0], 0.5), [5.0, 5.0]);
+        assert_eq!((0, 0.0).lerp(&(10, 1.0), 0.5), (5, 0.5));
+        assert_eq!(vec![0, 0].lerp(&vec![10, 10, 10], 0.5), [5, 5, 10]);
+    }
+
+    #[test]
+    fn tweened_signal() {
+        simulate_frames();
+        create_scope_immediate(|ctx| {
+            let tweened =
+                ctx.create_tweened_signal(0.0, Duration::from_millis(100), crate::easing::linear);
+            assert_eq!(*tweened.get(), 0.0);
+            assert!(!tweened.is_tweening());
+
+            tweened.set(10.0);
+            assert!(tweened.is_tweening());
+            run_frame(1000.0);
+            assert_eq!(*tweened.get(), 0.0);
+            run_frame(1050.0);
+            assert_eq!(*tweened.get(), 5.0);
+            run_frame(1200.0);
+            assert_eq!(*tweened.get(), 10.0);
+            assert!(!tweened.is_tweening());
+            assert!(ANIMATION_FRAME.with(|frame| frame.callbacks.borrow().is_empty()));
+
+            tweened.set(20.0);
+            tweened.set_immediate(30.0);
+            run_frame(1300.0);
+            assert_eq!(*tweened.get(), 30.0);
+        });
+    }
+
+    #[test]
+    fn tweened_signal_without_animation_frames() {
+        create_scope_immediate(|ctx| {
+            let tweened =
+                ctx.create_tweened_signal(0.0, Duration::from_millis(100), crate::easing::linear);
+            tweened.set(10.0);
+            assert_eq!(*tweened.get(), 10.0);
+            assert!(!tweened.is_tweening());
+            assert!(ANIMATION_FRAME.with(|frame| frame.callbacks.borrow().is_empty()));
+        });
+    }
+}
@@ -85,6 +85,20 @@ impl<G: GenericNode> fmt::Debug for NodeRef<G> {
 /// Extension trait for [`Scope`] adding the `create_node_ref` method.
 pub trait ScopeCreateNodeRef<'a> {
     /// Create a new [`NodeRef`] on the current [`Scope`].
+    ///
+    /// The [`NodeRef`] is set when it is passed to the `ref` attribute of an element in the
+    /// `view!` macro and lives as long as the [`Scope`].
+    ///
+    /// # Example
+    /// ```
+    /// # use sycamore::prelude::*;
+    /// # fn Component<G: Html>(ctx: ScopeRef) -> View<G> {
+    /// let node_ref = ctx.create_node_ref();
+    /// let view = view! { ctx, div(ref=node_ref) };
+    /// let node: G = node_ref.get();
+    /// # view
+    /// # }
+    /// ```
     fn create_node_ref<G: GenericNode>(&'a self) -> &'a NodeRef<G>;
 }
 
@@ -0,0 +1,167 @@
+//! Displaying a fallback while a part of the view is loading.
+
+#[cfg(all(feature = "ssr", feature = "futures"))]
+use std::cell::RefCell;
+use std::marker::PhantomData;
+#[cfg(all(feature = "ssr", feature = "futures"))]
+use std::rc::Rc;
+
+use crate::prelude::*;
+
+/// Props for [`Suspense`].
+#[derive(Prop)]
+pub struct SuspenseProps<'a, G: GenericNode, F>
+where
+    F: FnOnce(BoundedScopeRef<'_, 'a>) -> View<G> + 'a,
+{
+    /// The view that is displayed while `view` is pending.
+    pub fallback: View<G>,
+    /// Creates the view that is suspended. It is called once with a child scope that is the
+    /// suspense boundary: the resources and other pending work created inside of it are waited
+    /// for before the view is displayed.
+    pub view: F,
+    #[prop(default)]
+    _marker: PhantomData<&'a ()>,
+}
+
+/// Displays `fallback` while there is pending work inside `view`, such as a resource that is
+/// loading.
+///
+/// `view` is rendered inside a new suspense boundary (see
+/// [`create_suspense_boundary`](Scope::create_suspense_boundary)). When streaming SSR with
+/// `render_to_string_stream`, the fallback is sent first and `view` is sent in a later chunk once
+/// it is ready.
+#[component]
+pub fn Suspense<'a, G: GenericNode, F>(ctx: ScopeRef<'a>, props: SuspenseProps<'a, G, F>) -> View<G>
+where
+    F: FnOnce(BoundedScopeRef<'_, 'a>) -> View<G> + 'a,
+{
+    let SuspenseProps { fallback, view, .. } = props;
+
+    #[cfg(all(feature = "ssr", feature = "futures"))]
+    let streaming = ctx
+        .try_use_context::<StreamingBoundaries<G>>()
+        .map(|boundaries| {
+            let parent = ctx.try_use_context::<StreamedBoundaryId>().map(|id| id.0);
+            (boundaries.clone(), boundaries.next_id(), parent)
+        });
+
+    let mut state = None;
+    let mut content = None;
+    let _ = ctx.create_child_scope(|ctx| {
+        state = Some(ctx.create_suspense_boundary());
+        #[cfg(all(feature = "ssr", feature = "futures"))]
+        if let Some((_, id, _)) = &streaming {
+            ctx.provide_context(StreamedBoundaryId(*id));
+        }
+        content = Some(view(ctx));
+    });
+    let (state, content) = (state.unwrap(), content.unwrap());
+
+    #[cfg(all(feature = "ssr", feature = "futures"))]
+    if let Some((boundaries, id, parent)) = streaming {
+        if state.is_pending_untracked() {
+            boundaries.0.borrow_mut().pending.push(StreamedBoundary {
+                id,
+                parent,
+                state,
+                view: content,
+            });
+            let wrapper = G::element("sycamore-suspense");
+            wrapper.set_attribute("id", &format!("sycamore-suspense-{}", id));
+            for node in fallback.flatten() {
+                wrapper.append_child(&node);
+            }
+            return View::new_node(wrapper);
+        }
+    }
+
+    View::new_dyn(ctx, move || {
+        if state.is_pending() {
+            fallback.clone()
+        } else {
+            content.clone()
+        }
+    })
+}
+
+/// A suspense boundary that was still pending when the shell was rendered while streaming.
+#[cfg(all(feature = "ssr", feature = "futures"))]
+pub(crate) struct StreamedBoundary<G: GenericNode> {
+    pub id: usize,
+    /// The id of the enclosing boundary. The content of this boundary can only be sent once the
+    /// content of the enclosing boundary has been sent.
+    pub parent: Option<usize>,
+    pub state: SuspenseState,
+    pub view: View<G>,
+}
+
+#[cfg(all(feature = "ssr", feature = "futures"))]
+pub(crate) struct StreamingBoundariesInner<G: GenericNode> {
+    next_id: usize,
+    pending: Vec<StreamedBoundary<G>>,
+}
+
+/// The suspense boundaries that are rendered while streaming. Provided as a context by
+/// `render_to_string_stream`.
+#[cfg(all(feature = "ssr", feature = "futures"))]
+pub(crate) struct StreamingBoundaries<G: GenericNode>(Rc<RefCell<StreamingBoundariesInner<G>>>);
+
+#[cfg(all(feature = "ssr", feature = "futures"))]
+impl<G: GenericNode> StreamingBoundaries<G> {
+    fn next_id(&self) -> usize {
+        let mut inner = self.0.borrow_mut();
+        inner.next_id += 1;
+        inner.next_id - 1
+    }
+
+    /// Returns `true` if all the boundaries have been sent.
+    pub fn is_empty(&self) -> bool {
+        self.0.borrow().pending.is_empty()
+    }
+
+    /// Removes and returns a boundary that is no longer pending and whose enclosing boundary has
+    /// been sent.
+    pub fn take_resolved(&self) -> Option<StreamedBoundary<G>> {
+        let mut inner = self.0.borrow_mut();
+        let pending = &inner.pending;
+        let index = pending.iter().position(|boundary| {
+            !boundary.state.is_pending_untracked()
+                && !pending
+                    .iter()
+                    .any(|other| Some(other.id) == boundary.parent)
+        })?;
+        Some(inner.pending.remove(index))
+    }
+
+    /// Returns the states of the boundaries that have not been sent yet.
+    pub fn pending_states(&self) -> Vec<SuspenseState> {
+        self.0
+            .borrow()
+            .pending
+            .iter()
+            .map(|boundary| boundary.state.clone())
+            .collect()
+    }
+}
+
+#[cfg(all(feature = "ssr", feature = "futures"))]
+impl<G: GenericNode> Clone for StreamingBoundaries<G> {
+    fn clone(&self) -> Self {
+        Self(Rc::clone(&self.0))
+    }
+}
+
+#[cfg(all(feature = "ssr", feature = "futures"))]
+impl<G: GenericNode> Default for StreamingBoundaries<G> {
+    fn default() -> Self {
+        Self(Rc::new(RefCell::new(StreamingBoundariesInner {
+            next_id: 0,
+            pending: Vec::new(),
+        })))
+    }
+}
+
+/// The id of the streamed boundary that encloses the current scope.
+#[cfg(all(feature = "ssr", feature = "futures"))]
+struct StreamedBoundaryId(usize);
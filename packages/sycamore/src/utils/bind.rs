@@ -0,0 +1,61 @@
+//! Utilities for the `bind:` directive of the `view!` macro.
+
+use std::any::Any;
+
+use js_sys::Reflect;
+use wasm_bindgen::{JsValue, UnwrapThrowExt};
+
+use crate::generic_node::GenericNode;
+
+/// Returns the items of an array-like JS collection, such as an `HTMLCollection`.
+fn collection_items(collection: &JsValue) -> impl Iterator<Item = JsValue> + '_ {
+    let len = Reflect::get(collection, &"length".into())
+        .unwrap_throw()
+        .as_f64()
+        .unwrap_or_default() as u32;
+    (0..len).map(move |i| Reflect::get_u32(collection, i).unwrap_throw())
+}
+
+/// Returns the value of an `<option>` element.
+fn option_value(option: &JsValue) -> Option<String> {
+    Reflect::get(option, &"value".into()).ok()?.as_string()
+}
+
+/// Returns the values of the selected `<option>`s of a `<select>` element.
+pub fn selected_values(select: &JsValue) -> Vec<String> {
+    let options = Reflect::get(select, &"selectedOptions".into()).unwrap_throw();
+    collection_items(&options)
+        .filter_map(|option| option_value(&option))
+        .collect()
+}
+
+/// Selects exactly the `<option>`s of the `<select>` element `select` whose values are in
+/// `values`.
+///
+/// Does nothing if `select` is not backed by a DOM node.
+pub fn set_selected_values<G: GenericNode>(select: &G, values: &[String]) {
+    let select = match dom_node(select) {
+        Some(select) => select,
+        None => return,
+    };
+    let options = Reflect::get(&select, &"options".into()).unwrap_throw();
+    for option in collection_items(&options) {
+        let selected = option_value(&option).is_some_and(|value| values.contains(&value));
+        Reflect::set(&option, &"selected".into(), &selected.into()).unwrap_throw();
+    }
+}
+
+/// Returns the underlying DOM node of `node`, if any.
+#[cfg_attr(not(feature = "dom"), allow(unused_variables))]
+fn dom_node<G: GenericNode>(node: &G) -> Option<JsValue> {
+    let node = node as &dyn Any;
+    #[cfg(feature = "dom")]
+    if let Some(node) = node.downcast_ref::<crate::generic_node::DomNode>() {
+        return Some(node.inner_element().into());
+    }
+    #[cfg(all(feature = "dom", feature = "experimental-hydrate"))]
+    if let Some(node) = node.downcast_ref::<crate::generic_node::HydrateNode>() {
+        return Some(node.inner_element().into());
+    }
+    None
+}
@@ -0,0 +1,270 @@
+//! Data that is created during SSR and restored on the client during hydration.
+//!
+//! Each piece of data gets a hydration key that is generated in the same way as the `data-hk`
+//! attribute of elements. Since the same components are rendered in the same order on the server
+//! and on the client, the keys match on both sides. The data is serialized as JSON into a
+//! `<script>` element at the end of the HTML and read back by `hydrate` before the view is
+//! rendered.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use super::get_next_data_key;
+use crate::reactive::*;
+
+/// The `id` of the `<script>` element that contains the serialized hydration data.
+pub const HYDRATION_DATA_ELEMENT_ID: &str = "__sycamore_hydration_data";
+
+type Serializer = Box<dyn Fn() -> serde_json::Value>;
+
+#[derive(Default)]
+struct HydrationDataInner {
+    /// Whether the data is being collected during SSR.
+    collecting: bool,
+    /// Serializers for the data that was created during SSR, keyed by hydration key.
+    serializers: Vec<(String, Serializer)>,
+    /// The data that was restored from the HTML, keyed by hydration key.
+    restored: HashMap<String, serde_json::Value>,
+}
+
+/// The data that is transferred from SSR to the client. Provided as a context by the SSR renderers
+/// and by `hydrate`.
+#[derive(Clone, Default)]
+pub struct HydrationData(Rc<RefCell<HydrationDataInner>>);
+
+impl HydrationData {
+    /// Creates an empty [`HydrationData`] that collects the data created during SSR.
+    pub fn collect() -> Self {
+        Self(Rc::new(RefCell::new(HydrationDataInner {
+            collecting: true,
+            ..Default::default()
+        })))
+    }
+
+    /// Creates a [`HydrationData`] from the JSON that was written by
+    /// [`write_to_string`](Self::write_to_string). Invalid JSON is treated as empty.
+    pub fn from_json(json: &str) -> Self {
+        Self(Rc::new(RefCell::new(HydrationDataInner {
+            restored: serde_json::from_str(json).unwrap_or_default(),
+            ..Default::default()
+        })))
+    }
+
+    /// Reads the [`HydrationData`] from the `<script>` element written during SSR, if any.
+    #[cfg(feature = "dom")]
+    pub fn from_document() -> Self {
+        web_sys::window()
+            .and_then(|window| window.document())
+            .and_then(|document| document.get_element_by_id(HYDRATION_DATA_ELEMENT_ID))
+            .and_then(|element| element.text_content())
+            .map(|json| Self::from_json(&json))
+            .unwrap_or_default()
+    }
+
+    /// Writes the collected data to `s` as a `<script>` element. Does nothing if no data was
+    /// collected.
+    pub fn write_to_string(&self, s: &mut String) {
+        let inner = self.0.borrow();
+        if inner.serializers.is_empty() {
+            return;
+        }
+        let data: serde_json::Map<_, _> = inner
+            .serializers
+            .iter()
+            .map(|(key, serialize)| (key.clone(), serialize()))
+            .collect();
+        // Escape `</` so that the data cannot close the `<script>` element.
+        let json = serde_json::Value::Object(data)
+            .to_string()
+            .replace("</", "<\\/");
+        s.push_str("<script type=\"application/json\" id=\"");
+        s.push_str(HYDRATION_DATA_ELEMENT_ID);
+        s.push_str("\">");
+        s.push_str(&json);
+        s.push_str("</script>");
+    }
+
+    /// Takes the restored value with the hydration key `key`, if any.
+    fn take_restored<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let value = self.0.borrow_mut().restored.remove(key)?;
+        serde_json::from_value(value).ok()
+    }
+
+    /// Registers `signal` to be serialized with the hydration key `key`, if collecting.
+    fn register<T: Serialize + 'static>(&self, key: String, signal: RcSignal<T>) {
+        let mut inner = self.0.borrow_mut();
+        if inner.collecting {
+            inner.serializers.push((
+                key,
+                Box::new(move || {
+                    serde_json::to_value(&*signal.get_untracked()).unwrap_or_default()
+                }),
+            ));
+        }
+    }
+}
+
+/// Creates an [`RcSignal`] whose value is transferred from SSR to the client. On the client, the
+/// value is restored from the HTML if possible instead of being computed with `f`.
+pub(crate) fn create_hydrated_rc_signal<T>(ctx: ScopeRef<'_>, f: impl FnOnce() -> T) -> RcSignal<T>
+where
+    T: Serialize + DeserializeOwned + 'static,
+{
+    match get_next_data_key() {
+        Some(key) => create_keyed_hydrated_rc_signal(ctx, key, f),
+        None => create_rc_signal(f()),
+    }
+}
+
+/// Like [`create_hydrated_rc_signal`] but with an explicit hydration key.
+fn create_keyed_hydrated_rc_signal<T>(
+    ctx: ScopeRef<'_>,
+    key: String,
+    f: impl FnOnce() -> T,
+) -> RcSignal<T>
+where
+    T: Serialize + DeserializeOwned + 'static,
+{
+    let data = match ctx.try_use_context::<HydrationData>() {
+        Some(data) => data,
+        None => return create_rc_signal(f()),
+    };
+    let signal = create_rc_signal(data.take_restored(&key).unwrap_or_else(f));
+    data.register(key, signal.clone());
+    signal
+}
+
+/// Extension trait for [`Scope`] adding the `create_hydrated_signal` method.
+pub trait ScopeCreateHydratedSignal<'a> {
+    /// Create a new [`Signal`] whose value is serialized into the HTML during SSR and restored on
+    /// the client before any effects are run. `f` is only called if there is no value to restore.
+    ///
+    /// The signal is serialized when the HTML is written, so updates that happen during SSR
+    /// (e.g. when a resource resolves while streaming) are transferred as well.
+    fn create_hydrated_signal<T>(&'a self, f: impl FnOnce() -> T) -> &'a Signal<T>
+    where
+        T: Serialize + DeserializeOwned + 'static;
+
+    /// Like [`create_hydrated_signal`](Self::create_hydrated_signal) but the value is transferred
+    /// with the stable hydration key `key` instead of a key that depends on the position of the
+    /// component. This allows the signal to be restored even if the component is rendered in a
+    /// different place on the client, or outside of a hydration context.
+    ///
+    /// `key` must be unique within the page. The signal is initialized with `default` if there is
+    /// no value to restore and behaves like any other signal afterwards.
+    fn create_signal_hydrated<T>(&'a self, key: &str, default: T) -> &'a Signal<T>
+    where
+        T: Serialize + DeserializeOwned + 'static;
+}
+
+impl<'a> ScopeCreateHydratedSignal<'a> for Scope<'a> {
+    fn create_hydrated_signal<T>(&'a self, f: impl FnOnce() -> T) -> &'a Signal<T>
+    where
+        T: Serialize + DeserializeOwned + 'static,
+    {
+        let signal: &'a RcSignal<T> = self.create_ref(create_hydrated_rc_signal(self, f));
+        signal
+    }
+
+    fn create_signal_hydrated<T>(&'a self, key: &str, default: T) -> &'a Signal<T>
+    where
+        T: Serialize + DeserializeOwned + 'static,
+    {
+        // Explicit keys are prefixed so that they cannot clash with the generated keys.
+        let key = format!("key:{key}");
+        let signal: &'a RcSignal<T> =
+            self.create_ref(create_keyed_hydrated_rc_signal(self, key, || default));
+        signal
+    }
+}
+
+#[cfg(all(test, feature = "ssr"))]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use crate::render_to_string;
+
+    #[component]
+    fn Counter<G: Html>(ctx: ScopeRef, initial: i32) -> View<G> {
+        let count = ctx.create_hydrated_signal(|| initial);
+        view! { ctx, p { (count.get()) } }
+    }
+
+    /// Extracts the JSON from the `<script>` element written by [`HydrationData`].
+    fn extract_json(html: &str) -> &str {
+        let start = html.find(HYDRATION_DATA_ELEMENT_ID).unwrap() + HYDRATION_DATA_ELEMENT_ID.len();
+        &html[start + "\">".len()..html.len() - "</script>".len()]
+    }
+
+    #[test]
+    fn hydrated_signal_is_serialized() {
+        let html = render_to_string(|ctx| view! { ctx, Counter(1) Counter(2) });
+        assert!(html.ends_with(
+            "<script type=\"application/json\" id=\"__sycamore_hydration_data\">\
+            {\"1.0\":1,\"2.0\":2}</script>"
+        ));
+    }
+
+    #[test]
+    fn no_script_without_hydrated_signals() {
+        let html = render_to_string(|ctx| view! { ctx, p { "Hello" } });
+        assert!(!html.contains("<script"));
+    }
+
+    #[test]
+    fn hydrated_signal_is_restored() {
+        let html = render_to_string(|ctx| view! { ctx, Counter(1) Counter(2) });
+        let data = HydrationData::from_json(extract_json(&html));
+
+        // Render the same components again with the restored data, as the client would.
+        let restored = render_to_string(|ctx| {
+            let mut view = None;
+            let _ = ctx.create_child_scope(|ctx| {
+                ctx.provide_context(data);
+                view = Some(view! { ctx, Counter(3) Counter(4) });
+            });
+            view.unwrap()
+        });
+        assert!(restored.contains(">1</p>"));
+        assert!(restored.contains(">2</p>"));
+        assert!(!restored.contains("<script"));
+    }
+
+    #[test]
+    fn keyed_hydrated_signal_is_restored() {
+        let html = render_to_string(|ctx| {
+            let name = ctx.create_signal_hydrated("name", "server".to_string());
+            view! { ctx, Counter(1) p { (name.get()) } }
+        });
+        assert!(html.contains("\"key:name\":\"server\""));
+        let data = HydrationData::from_json(extract_json(&html));
+
+        // The key does not depend on the position of the signal.
+        let restored = render_to_string(|ctx| {
+            let mut view = None;
+            let _ = ctx.create_child_scope(|ctx| {
+                ctx.provide_context(data);
+                let name = ctx.create_signal_hydrated("name", "client".to_string());
+                let missing = ctx.create_signal_hydrated("missing", 0);
+                assert_eq!(*missing.get(), 0);
+                name.set(format!("{} and client", name.get()));
+                view = Some(view! { ctx, p { (name.get()) } });
+            });
+            view.unwrap()
+        });
+        assert!(restored.contains(">server and client</p>"));
+    }
+
+    #[test]
+    fn serialized_data_cannot_close_script() {
+        let html = render_to_string(|ctx| {
+            let text = ctx.create_hydrated_signal(|| "</script>".to_string());
+            view! { ctx, (text.get()) }
+        });
+        assert!(html.contains("\"<\\/script>\""));
+    }
+}
@@ -1,5 +1,7 @@
 //! Hydration support for Sycamore.
 
+#[cfg(feature = "serde")]
+pub mod data;
 pub mod web;
 
 use std::cell::RefCell;
@@ -47,6 +49,21 @@ pub fn get_current_id() -> Option<(usize, usize)> {
     })
 }
 
+/// Returns the hydration key for the next piece of data that is transferred from SSR to the client.
+/// Data keys are counted separately from the keys of nodes.
+///
+/// If hydration context does not exist, returns `None`.
+pub fn get_next_data_key() -> Option<String> {
+    HYDRATION_CONTEXT.with(|context| {
+        let mut context = context.borrow_mut();
+        context.as_mut().map(|reg| {
+            let id = reg.current_data_id;
+            reg.current_data_id += 1;
+            format!("{}.{}", reg.current_component_id, id)
+        })
+    })
+}
+
 /// Returns `true` if hydration has completed.
 pub fn hydration_completed() -> bool {
     HYDRATION_CONTEXT.with(|context| context.borrow().is_none())
@@ -63,6 +80,7 @@ where
     HYDRATION_CONTEXT.with(|context| {
         if context.borrow().is_some() {
             let prev_id;
+            let prev_data_id;
             let prev_component_id;
             {
                 let mut context = context.borrow_mut();
@@ -70,14 +88,17 @@ where
                 // Store previous state to restore after component.
                 prev_component_id = context.current_component_id;
                 prev_id = context.current_id;
+                prev_data_id = context.current_data_id;
 
                 context.current_component_id = context.next_component_id;
                 context.next_component_id += 1;
                 context.current_id = 0; // Reset current_id to 0.
+                context.current_data_id = 0;
             }
             let r = f();
             context.borrow_mut().as_mut().unwrap().current_component_id = prev_component_id;
             context.borrow_mut().as_mut().unwrap().current_id = prev_id;
+            context.borrow_mut().as_mut().unwrap().current_data_id = prev_data_id;
             r
         } else {
             f()
@@ -89,6 +110,7 @@ where
 #[derive(Debug, Clone, Copy)]
 pub struct HydrationRegistry {
     pub current_id: usize,
+    pub current_data_id: usize,
     pub current_component_id: usize,
     pub next_component_id: usize,
 }
@@ -97,6 +119,7 @@ impl HydrationRegistry {
     pub fn new() -> Self {
         Self {
             current_id: 0,
+            current_data_id: 0,
             current_component_id: 0,
             next_component_id: 1,
         }
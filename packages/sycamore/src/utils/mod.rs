@@ -1,3 +1,4 @@
+pub mod bind;
 #[cfg(feature = "experimental-hydrate")]
 pub mod hydrate;
 pub mod render;
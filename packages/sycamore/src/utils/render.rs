@@ -1,5 +1,7 @@
 //! Utilities for rendering nodes.
 
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::rc::Rc;
 
 use ahash::AHashMap;
@@ -9,6 +11,74 @@ use crate::generic_node::GenericNode;
 use crate::reactive::*;
 use crate::view::{View, ViewType};
 
+/// The value of a CSS property that is set with the `style:` attribute of the `view!` macro.
+/// `None` removes the property.
+pub trait StyleValue {
+    /// Returns the value of the property, or `None` if the property should be removed.
+    fn style_value(&self) -> Option<Cow<'_, str>>;
+}
+
+impl StyleValue for str {
+    fn style_value(&self) -> Option<Cow<'_, str>> {
+        Some(Cow::Borrowed(self))
+    }
+}
+
+impl StyleValue for String {
+    fn style_value(&self) -> Option<Cow<'_, str>> {
+        Some(Cow::Borrowed(self))
+    }
+}
+
+impl StyleValue for Cow<'_, str> {
+    fn style_value(&self) -> Option<Cow<'_, str>> {
+        Some(Cow::Borrowed(self))
+    }
+}
+
+impl<T: StyleValue + ?Sized> StyleValue for &T {
+    fn style_value(&self) -> Option<Cow<'_, str>> {
+        (**self).style_value()
+    }
+}
+
+impl<T: StyleValue + ?Sized> StyleValue for Rc<T> {
+    fn style_value(&self) -> Option<Cow<'_, str>> {
+        (**self).style_value()
+    }
+}
+
+impl<T: StyleValue> StyleValue for Option<T> {
+    fn style_value(&self) -> Option<Cow<'_, str>> {
+        self.as_ref().and_then(StyleValue::style_value)
+    }
+}
+
+macro_rules! impl_style_value_to_string {
+    ($($t: ty),*) => {
+        $(
+            impl StyleValue for $t {
+                fn style_value(&self) -> Option<Cow<'_, str>> {
+                    Some(Cow::Owned(self.to_string()))
+                }
+            }
+        )*
+    };
+}
+
+impl_style_value_to_string!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64, char
+);
+
+/// Sets the CSS property `name` of the inline style of `el` to `value`, or removes the property
+/// if `value` is `None`. This is used by the `style:` attribute of the `view!` macro.
+pub fn set_style_property<G: GenericNode>(el: &G, name: &str, value: &impl StyleValue) {
+    match value.style_value() {
+        Some(value) => el.set_style_property(name, &value),
+        None => el.remove_style_property(name),
+    }
+}
+
 /// Insert a [`GenericNode`] under `parent` at the specified `marker`. If `initial` is `Some(_)`,
 /// `initial` will be replaced with the new inserted node.
 ///
@@ -188,6 +258,33 @@ pub fn append_nodes<G: GenericNode>(parent: &G, fragment: Vec<G>, marker: Option
     }
 }
 
+/// Sets the attributes of `el` from the reactive map `attrs` of attribute names to values.
+///
+/// When `attrs` is updated, only the attributes that were added, changed or removed are updated
+/// on `el`. This is the utility behind the `..attrs` syntax in the `view!` macro.
+pub fn spread_attributes<'a, G: GenericNode>(
+    ctx: ScopeRef<'a>,
+    el: &G,
+    attrs: &'a ReadSignal<HashMap<String, String>>,
+) {
+    let el = el.clone();
+    let mut prev = Rc::new(HashMap::<String, String>::new());
+    ctx.create_effect(move || {
+        let next = attrs.get();
+        for name in prev.keys() {
+            if !next.contains_key(name) {
+                el.remove_attribute(name);
+            }
+        }
+        for (name, value) in next.iter() {
+            if prev.get(name) != Some(value) {
+                el.set_attribute(name, value);
+            }
+        }
+        prev = next;
+    });
+}
+
 /// Normalizes a `Vec<Template<G>>` into a `Vec<G>`.
 ///
 /// Returns whether the normalized `Vec<G>` is dynamic (and should be rendered in an effect).
@@ -36,15 +36,7 @@ impl<G: GenericNode> View<G> {
 
     /// Create a new [`View`] from a [`FnMut`].
     pub fn new_dyn<'a>(ctx: ScopeRef<'a>, f: impl FnMut() -> View<G> + 'a) -> Self {
-        let memo = ctx.create_memo(f);
-        let signal = create_rc_signal(memo.get().as_ref().clone());
-        ctx.create_effect({
-            let signal = signal.clone();
-            move || signal.set(memo.get().as_ref().clone()) // TODO: get rid of double clone
-        });
-        Self {
-            inner: ViewType::Dyn(signal),
-        }
+        ctx.create_dyn_output(f)
     }
 
     /// Create a new [`View`] from a `Vec` of [`GenericNode`]s.
@@ -122,6 +114,18 @@ impl<G: GenericNode> View<G> {
     }
 }
 
+impl<G: GenericNode> ReactiveOutput for View<G> {
+    fn from_dyn(signal: RcSignal<Self>) -> Self {
+        Self {
+            inner: ViewType::Dyn(signal),
+        }
+    }
+
+    fn from_list(list: Vec<Self>) -> Self {
+        Self::new_fragment(list)
+    }
+}
+
 impl<G: GenericNode> Default for View<G> {
     fn default() -> Self {
         Self::empty()
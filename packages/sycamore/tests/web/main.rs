@@ -8,13 +8,13 @@ pub mod cleanup;
 pub mod hydrate;
 pub mod indexed;
 pub mod keyed;
-// pub mod portal;
+pub mod portal;
 pub mod reconcile;
 pub mod render;
 
 use sycamore::prelude::*;
 use wasm_bindgen_test::*;
-use web_sys::{Document, Element, HtmlElement, Node, Window};
+use web_sys::{Document, Element, Event, HtmlElement, Node, Window};
 
 wasm_bindgen_test_configure!(run_in_browser);
 
@@ -66,6 +66,31 @@ fn empty_template() {
     );
 }
 
+#[wasm_bindgen_test]
+fn render_handle_unmount() {
+    let test_container = test_container();
+    let disposed = std::rc::Rc::new(std::cell::Cell::new(false));
+
+    for _ in 0..2 {
+        disposed.set(false);
+        let handle = sycamore::render_to(
+            {
+                let disposed = std::rc::Rc::clone(&disposed);
+                move |ctx| {
+                    ctx.on_cleanup(move || disposed.set(true));
+                    view! { ctx, p { "Hello" } "World" }
+                }
+            },
+            &test_container,
+        );
+        assert_eq!(test_container.inner_html(), "<p>Hello</p>World");
+
+        handle.unmount();
+        assert!(disposed.get());
+        assert_eq!(test_container.inner_html(), "");
+    }
+}
+
 #[wasm_bindgen_test]
 fn hello_world() {
     sycamore::render_to(
@@ -328,38 +353,98 @@ fn reactive_attribute() {
 }
 
 #[wasm_bindgen_test]
-#[ignore]
 fn two_way_bind_to_props() {
-    // create_scope_immediate(|ctx| {
-    //     let value = ctx.create_signal(String::new());
-    //     let value2 = value.clone();
-
-    //     sycamore::render_to(
-    //         || {
-    //             cloned!((value) => view! {
-    //                 input(bind:value=value)
-    //                 p { (value2.get()) }
-    //             })
-    //         },
-    //         &test_container(),
-    //     );
-
-    //     let input = document()
-    //         .query_selector("input")
-    //         .unwrap()
-    //         .unwrap()
-    //         .unchecked_into::<HtmlInputElement>();
-
-    //     value.set("abc".to_string());
-    //     assert_eq!(
-    //         js_sys::Reflect::get(&input, &"value".into()).unwrap(),
-    //         "abc"
-    //     );
-
-    //     js_sys::Reflect::set(&input, &"value".into(), &"def".into()).unwrap();
-    //     input.dispatch_event(&Event::new("input").unwrap()).unwrap();
-    //     assert_eq!(value.get().as_str(), "def");
-    // });
+    create_scope_immediate(|ctx| {
+        let value = ctx.create_signal(String::new());
+        let node = view! { ctx,
+            input(bind:value=value)
+            p { (value.get()) }
+        };
+
+        sycamore::render_to(|_| node, &test_container());
+
+        let input = document().query_selector("input").unwrap().unwrap();
+
+        value.set("abc".to_string());
+        assert_eq!(
+            js_sys::Reflect::get(&input, &"value".into()).unwrap(),
+            "abc"
+        );
+
+        js_sys::Reflect::set(&input, &"value".into(), &"def".into()).unwrap();
+        input.dispatch_event(&Event::new("input").unwrap()).unwrap();
+        assert_eq!(value.get().as_str(), "def");
+    });
+}
+
+#[wasm_bindgen_test]
+fn two_way_bind_to_number() {
+    create_scope_immediate(|ctx| {
+        let value = ctx.create_signal(1.0);
+        let node = view! { ctx,
+            input(type="number", bind:valueAsNumber=value)
+        };
+
+        sycamore::render_to(|_| node, &test_container());
+
+        let input = document().query_selector("input").unwrap().unwrap();
+        assert_eq!(js_sys::Reflect::get(&input, &"value".into()).unwrap(), "1");
+
+        js_sys::Reflect::set(&input, &"value".into(), &"2.5".into()).unwrap();
+        input.dispatch_event(&Event::new("input").unwrap()).unwrap();
+        assert_eq!(*value.get(), 2.5);
+    });
+}
+
+#[wasm_bindgen_test]
+fn event_modifiers() {
+    create_scope_immediate(|ctx| {
+        let clicks = ctx.create_signal(0);
+        let node = view! { ctx,
+            button(on:click|prevent_default|once=|event: sycamore::rt::MouseEvent| {
+                assert!(event.default_prevented());
+                clicks.set(*clicks.get() + 1);
+            })
+        };
+
+        sycamore::render_to(|_| node, &test_container());
+
+        let button = document().query_selector("button").unwrap().unwrap();
+        let button = sycamore::rt::JsCast::unchecked_into::<HtmlElement>(button);
+        button.click();
+        button.click();
+        assert_eq!(*clicks.get(), 1);
+    });
+}
+
+#[wasm_bindgen_test]
+fn svg_and_mathml_namespaces() {
+    create_scope_immediate(|ctx| {
+        let node = view! { ctx,
+            svg {
+                path(d="M 0 0 L 10 10")
+                foreignObject { p { "Hello" } }
+            }
+            math { mi { "x" } }
+            div(xmlns="http://www.w3.org/2000/svg")
+        };
+
+        sycamore::render_to(|_| node, &test_container());
+
+        let namespace = |selector: &str| {
+            document()
+                .query_selector(selector)
+                .unwrap()
+                .unwrap()
+                .namespace_uri()
+                .unwrap()
+        };
+        assert_eq!(namespace("svg"), "http://www.w3.org/2000/svg");
+        assert_eq!(namespace("path"), "http://www.w3.org/2000/svg");
+        assert_eq!(namespace("p"), "http://www.w3.org/1999/xhtml");
+        assert_eq!(namespace("mi"), "http://www.w3.org/1998/Math/MathML");
+        assert_eq!(namespace("div"), "http://www.w3.org/2000/svg");
+    });
 }
 
 #[wasm_bindgen_test]
@@ -1,8 +1,3 @@
-use std::cell::RefCell;
-use std::rc::Rc;
-
-use sycamore::portal::{Portal, PortalProps};
-
 use super::*;
 
 #[wasm_bindgen_test]
@@ -16,31 +11,35 @@ fn test_portal() {
     let el2 = document().create_element("div").unwrap();
     test_container.append_child(&el2).unwrap();
 
-    let portal = Signal::new(None);
-    let portal_root = Rc::new(RefCell::new(None));
-
-    sycamore::render_to(
-        cloned!((portal, portal_root) => move || {
-            let root = create_scope(cloned!((portal) => move || {
-                portal.set(Some(view! {
-                    Portal(PortalProps {
-                        children: view! { "Hello World!" },
-                        selector: "#portal-target",
-                    })
-                }));
-            }));
-            *portal_root.borrow_mut() = Some(root);
-            view! {
-                (portal.get().as_ref().clone().unwrap_or_default())
-            }
-        }),
-        &el2,
-    );
-
-    assert_eq!(el.inner_html(), "Hello World!");
-
-    // Destroying the portal should remove the portal from the DOM.
-    drop(portal_root.take());
-
-    assert_eq!(el.inner_html(), "");
+    create_scope_immediate(|ctx| {
+        let show = ctx.create_signal(true);
+        ctx.provide_context(String::from("Hello World!"));
+
+        let node = view! { ctx,
+            (if *show.get() {
+                view! { ctx,
+                    Portal(selector="#portal-target") {
+                        PortalContent()
+                    }
+                }
+            } else {
+                View::empty()
+            })
+        };
+        sycamore::render_to(|_| node, &el2);
+
+        assert_eq!(el.text_content().unwrap(), "Hello World!");
+        assert_eq!(el2.text_content().unwrap(), "");
+
+        // Disposing the scope of the portal should remove its children from the DOM.
+        show.set(false);
+        assert_eq!(el.inner_html(), "");
+    });
+}
+
+/// Reads the context provided by the parent of the portal.
+#[component]
+fn PortalContent<G: Html>(ctx: ScopeRef, _: ()) -> View<G> {
+    let text = ctx.use_context::<String>().clone();
+    view! { ctx, p { (text) } }
 }
@@ -0,0 +1,115 @@
+#!/usr/bin/env bash
+# Checks that the size of the release binaries of the examples does not grow beyond the baseline
+# in `scripts/wasm-size-baseline.txt` by more than the budget.
+#
+# Usage:
+#   scripts/check-wasm-size.sh           # Compare the sizes with the baseline.
+#   scripts/check-wasm-size.sh --update  # Record the current sizes as the new baseline.
+#
+# Two kinds of sizes are measured, selected with `SIZE_TARGET`:
+# * `wasm`: the size of the wasm binary after `wasm-bindgen` and `wasm-opt -Oz`. Requires the
+#   `wasm32-unknown-unknown` target, `wasm-bindgen` (wasm-bindgen-cli, same version as the
+#   `wasm-bindgen` dependency) and `wasm-opt` (binaryen). If `twiggy` is installed, the largest
+#   items of the binaries that exceed the budget are printed.
+# * `native`: the size of the `.text` section of the release binary for the host, which is a proxy
+#   for the amount of monomorphized code that only needs `size` and `nm` (binutils). The largest
+#   symbols of the binaries that exceed the budget are printed.
+# By default, `wasm` is used if the `wasm32-unknown-unknown` target is installed and `native`
+# otherwise. The baseline has separate entries for both.
+#
+# The budget is the allowed growth in percent, `1` by default. Set `WASM_SIZE_BUDGET` to change it.
+
+set -euo pipefail
+
+cd "$(dirname "$0")/.."
+
+BASELINE=scripts/wasm-size-baseline.txt
+BUDGET=${WASM_SIZE_BUDGET:-1}
+EXAMPLES=(hello-world counter todomvc js-framework-benchmark)
+OUT_DIR=target/wasm-size
+
+if [[ -z "${SIZE_TARGET:-}" ]]; then
+    if rustup target list --installed 2>/dev/null | grep -q '^wasm32-unknown-unknown$'; then
+        SIZE_TARGET=wasm
+    else
+        SIZE_TARGET=native
+    fi
+fi
+if [[ "$SIZE_TARGET" != wasm && "$SIZE_TARGET" != native ]]; then
+    echo "unknown SIZE_TARGET \`$SIZE_TARGET\`, expected \`wasm\` or \`native\`" >&2
+    exit 2
+fi
+echo "Measuring $SIZE_TARGET sizes"
+
+update=false
+if [[ "${1:-}" == "--update" ]]; then
+    update=true
+fi
+
+mkdir -p "$OUT_DIR"
+sizes=()
+failed=false
+
+# Prints the size of `example` in bytes.
+measure() {
+    local example=$1
+    if [[ "$SIZE_TARGET" == wasm ]]; then
+        cargo build --release --target wasm32-unknown-unknown -p "$example" >&2
+        wasm-bindgen --target web --no-typescript --out-name "$example" \
+            --out-dir "$OUT_DIR/$example" "target/wasm32-unknown-unknown/release/$example.wasm" >&2
+        wasm-opt -Oz -o "$OUT_DIR/$example.wasm" "$OUT_DIR/$example/${example}_bg.wasm" >&2
+        wc -c <"$OUT_DIR/$example.wasm" | tr -d ' '
+    else
+        cargo build --release -p "$example" >&2
+        size -A "target/release/$example" | awk '$1 == ".text" { print $2 }'
+    fi
+}
+
+# Prints the largest items of `example`.
+print_largest() {
+    local example=$1
+    if [[ "$SIZE_TARGET" == wasm ]]; then
+        if command -v twiggy >/dev/null; then
+            twiggy top -n 20 "$OUT_DIR/$example.wasm"
+        fi
+    else
+        nm --size-sort --reverse-sort --print-size --demangle "target/release/$example" | head -n 20
+    fi
+}
+
+for example in "${EXAMPLES[@]}"; do
+    size=$(measure "$example")
+    sizes+=("$SIZE_TARGET $example $size")
+
+    if $update; then
+        echo "$example: $size bytes"
+        continue
+    fi
+    baseline=$(awk -v target="$SIZE_TARGET" -v example="$example" \
+        '$1 == target && $2 == example { print $3 }' "$BASELINE")
+    if [[ -z "$baseline" ]]; then
+        echo "$example: $size bytes, no $SIZE_TARGET baseline (run with --update to record one)"
+        failed=true
+        continue
+    fi
+    limit=$((baseline + baseline * BUDGET / 100))
+    echo "$example: $size bytes (baseline $baseline bytes, limit $limit bytes)"
+    if ((size > limit)); then
+        echo "$example: exceeds the size budget by $((size - limit)) bytes"
+        print_largest "$example"
+        failed=true
+    fi
+done
+
+if $update; then
+    {
+        grep '^#' "$BASELINE"
+        # Keep the entries of the other target.
+        grep -v '^#' "$BASELINE" | awk -v target="$SIZE_TARGET" '$1 != target' || true
+        printf '%s\n' "${sizes[@]}"
+    } >"$BASELINE.tmp"
+    mv "$BASELINE.tmp" "$BASELINE"
+    echo "Updated $BASELINE"
+elif $failed; then
+    exit 1
+fi